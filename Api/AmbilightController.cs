@@ -219,6 +219,7 @@ public class AmbilightController : ControllerBase
             int extractionProgress = 0;
             ulong extractionFramesCurrent = 0;
             ulong extractionFramesTotal = 0;
+            List<AmbilightPictureArea>? pictureAreas = null;
             
             if (entryPoint?.Storage != null)
             {
@@ -229,6 +230,7 @@ public class AmbilightController : ControllerBase
                     extractionProgress = ambiItem.ExtractionProgress;
                     extractionFramesCurrent = ambiItem.ExtractionFramesCurrent;
                     extractionFramesTotal = ambiItem.ExtractionFramesTotal;
+                    pictureAreas = ambiItem.PictureAreas;
                     
                     // Debug logging
                     if (extractionStatus == "extracting")
@@ -246,17 +248,36 @@ public class AmbilightController : ControllerBase
                 System.Diagnostics.Debug.WriteLine("[Ambilight] API Status: Storage not available");
             }
 
+            bool hasBinary = System.IO.File.Exists(binPath);
+            var binaryError = AmbilightBinaryError.None;
+            string binaryErrorMessage = string.Empty;
+            double? coveredSeconds = null;
+            if (hasBinary)
+            {
+                binaryError = AmbilightBinary.Validate(binPath, out binaryErrorMessage);
+                if (AmbilightBinary.TryReadExtent(binPath, out _, out var lastFrameSeconds, out _))
+                {
+                    coveredSeconds = lastFrameSeconds;
+                }
+            }
+
             var status = new AmbilightStatusResponse
             {
                 ItemId = guid,
                 ItemName = item.Name,
-                HasBinary = System.IO.File.Exists(binPath),
+                HasBinary = hasBinary,
                 BinaryPath = binPath,
-                BinarySize = System.IO.File.Exists(binPath) ? new FileInfo(binPath).Length : 0,
+                BinarySize = hasBinary ? new FileInfo(binPath).Length : 0,
+                BinaryError = binaryError == AmbilightBinaryError.None ? null : binaryError.ToString(),
+                BinaryErrorCode = (int)binaryError,
+                BinaryErrorMessage = binaryError == AmbilightBinaryError.None ? null : binaryErrorMessage,
+                BinaryCoveredSeconds = coveredSeconds,
+                RuntimeSeconds = item.RunTimeTicks is long ticks && ticks > 0 ? ticks / 10_000_000.0 : null,
                 ExtractionStatus = extractionStatus,
                 ExtractionProgress = extractionProgress,
                 ExtractionFramesCurrent = extractionFramesCurrent,
-                ExtractionFramesTotal = extractionFramesTotal
+                ExtractionFramesTotal = extractionFramesTotal,
+                PictureAreas = pictureAreas
             };
 
             return Ok(status);
@@ -387,6 +408,696 @@ public class AmbilightController : ControllerBase
         }
     }
 
+    /// <summary>
+    /// Extracts a quick, approximate binary of the first minutes of an item at a low frame rate, to check LED counts,
+    /// orientation and colors on new hardware before a full extraction. It replaces the item's binary until the next
+    /// scheduled extraction; items with a complete binary are refused (delete it first).
+    /// </summary>
+    /// <param name="itemId">The item ID.</param>
+    /// <param name="minutes">Minutes from the start of the video to extract.</param>
+    /// <param name="fps">Frames per second to extract at.</param>
+    /// <returns>Extraction result.</returns>
+    [HttpPost("Preview/{itemId}")]
+    [ProducesResponseType(StatusCodes.Status202Accepted)]
+    [ProducesResponseType(StatusCodes.Status400BadRequest)]
+    [ProducesResponseType(StatusCodes.Status409Conflict)]
+    public ActionResult<AmbilightExtractResponse> PreviewItem([FromRoute, Required] string itemId, [FromQuery] double minutes = 2.0, [FromQuery] double fps = 10.0)
+    {
+        try
+        {
+            if (string.IsNullOrWhiteSpace(itemId) || !Guid.TryParse(itemId, out var guid))
+            {
+                return BadRequest(new { error = "Invalid item ID", itemId });
+            }
+
+            if (minutes <= 0 || minutes > 60 || fps <= 0 || fps > 60)
+            {
+                return BadRequest(new { error = "minutes must be in (0, 60] and fps in (0, 60]", minutes, fps });
+            }
+
+            var storage = AmbilightEntryPoint.Instance?.Storage;
+            var extractor = AmbilightEntryPoint.Instance?.Extractor;
+            if (storage == null || extractor == null)
+            {
+                return StatusCode(500, new { error = "Ambilight service not running" });
+            }
+
+            var item = storage.GetItem(guid.ToString("N"));
+            if (item == null || string.IsNullOrEmpty(item.FilePath))
+            {
+                return BadRequest(new { error = "Item not found or has no file path", itemId });
+            }
+
+            if (item.ExtractionStatus == "completed" && storage.BinaryIsValid(item.Id))
+            {
+                return Conflict(new { error = "Item already has a complete binary; delete it first to preview", itemName = item.Name });
+            }
+
+            _ = Task.Run(async () => await extractor.RunPreviewForItemAsync(item, minutes, fps, CancellationToken.None));
+
+            return Accepted(new AmbilightExtractResponse
+            {
+                ItemId = guid,
+                ItemName = item.Name,
+                Message = "Preview extraction started in background"
+            });
+        }
+        catch (Exception ex)
+        {
+            return StatusCode(500, new {
+                error = ex.Message,
+                type = ex.GetType().Name,
+                stackTrace = ex.StackTrace
+            });
+        }
+    }
+
+    /// <summary>
+    /// Grabs the frame at <paramref name="timestamp"/> from an item, tries several zone depth / edge weight
+    /// combinations, writes a PPM preview for each and returns a suggested configuration.
+    /// </summary>
+    /// <param name="itemId">The item ID.</param>
+    /// <param name="timestamp">Position of a representative frame, in seconds.</param>
+    /// <returns>Scored candidates and the suggested zone depth factor and edge weight.</returns>
+    [HttpPost("Tune/{itemId}")]
+    [ProducesResponseType(StatusCodes.Status200OK)]
+    [ProducesResponseType(StatusCodes.Status400BadRequest)]
+    public async Task<ActionResult<AmbilightTuneReport>> TuneItem([FromRoute, Required] string itemId, [FromQuery, Required] double timestamp)
+    {
+        try
+        {
+            if (string.IsNullOrWhiteSpace(itemId) || !Guid.TryParse(itemId, out var guid))
+            {
+                return BadRequest(new { error = "Invalid item ID", itemId });
+            }
+
+            var libraryManager = GetLibraryManager();
+            if (libraryManager == null)
+            {
+                return StatusCode(500, new { error = "LibraryManager not available" });
+            }
+
+            var item = libraryManager.GetItemById(guid);
+            if (item == null || string.IsNullOrEmpty(item.Path))
+            {
+                return BadRequest(new { error = "Item not found or has no file path", itemId });
+            }
+
+            var extractor = AmbilightEntryPoint.Instance?.ExtractorCore;
+            if (extractor == null)
+            {
+                return StatusCode(500, new { error = "Ambilight service not running" });
+            }
+
+            var config = Plugin.Instance?.Configuration;
+            var dataFolder = string.IsNullOrWhiteSpace(config?.AmbilightDataFolder) ? "/data/ambilight" : config!.AmbilightDataFolder.Trim();
+            var previewFolder = Path.Combine(dataFolder, "tune", guid.ToString("N"));
+
+            var report = await extractor.TuneFromFrameAsync(item.Path, timestamp, previewFolder, HttpContext.RequestAborted);
+            if (report == null)
+            {
+                return BadRequest(new { error = "Could not decode a frame at that timestamp", itemName = item.Name, timestamp });
+            }
+
+            return Ok(report);
+        }
+        catch (Exception ex)
+        {
+            return StatusCode(500, new
+            {
+                error = ex.Message,
+                type = ex.GetType().Name,
+                stackTrace = ex.StackTrace
+            });
+        }
+    }
+
+    /// <summary>
+    /// Checks an item's binary against its video: header, checksum, timestamp order, coverage of the video's duration and
+    /// the settings it was extracted with, and with <paramref name="spotCheck"/> a few frames recomputed from the video.
+    /// </summary>
+    /// <param name="itemId">The item ID.</param>
+    /// <param name="spotCheck">Also compare a few frames with the video (decodes them, so it takes a few seconds).</param>
+    /// <returns>A verdict ("ok", "stale" or "corrupt") with the problems found.</returns>
+    [HttpGet("Verify/{itemId}")]
+    [ProducesResponseType(StatusCodes.Status200OK)]
+    [ProducesResponseType(StatusCodes.Status400BadRequest)]
+    [ProducesResponseType(StatusCodes.Status404NotFound)]
+    public async Task<ActionResult<AmbilightVerifyReport>> VerifyItem([FromRoute, Required] string itemId, [FromQuery] bool spotCheck = false)
+    {
+        try
+        {
+            if (string.IsNullOrWhiteSpace(itemId) || !Guid.TryParse(itemId, out var guid))
+            {
+                return BadRequest(new { error = "Invalid item ID", itemId });
+            }
+
+            var storage = AmbilightEntryPoint.Instance?.Storage;
+            var extractor = AmbilightEntryPoint.Instance?.Extractor;
+            if (storage == null || extractor == null)
+            {
+                return StatusCode(500, new { error = "Ambilight service not running" });
+            }
+
+            var item = storage.GetItem(guid.ToString("N"));
+            if (item == null || string.IsNullOrEmpty(item.FilePath))
+            {
+                return BadRequest(new { error = "Item not found or has no file path", itemId });
+            }
+
+            if (!System.IO.File.Exists(storage.GetBinaryPath(item.Id)))
+            {
+                return NotFound(new { error = "Item has no binary", itemId });
+            }
+
+            return Ok(await extractor.VerifyItemAsync(item, spotCheck, HttpContext.RequestAborted));
+        }
+        catch (Exception ex)
+        {
+            return StatusCode(500, new
+            {
+                error = ex.Message,
+                type = ex.GetType().Name,
+                stackTrace = ex.StackTrace
+            });
+        }
+    }
+
+    /// <summary>
+    /// Predicts an item's extraction with the current settings without running it: resolution, frame count, binary
+    /// size and, from a short benchmark, the time it takes.
+    /// </summary>
+    /// <param name="itemId">The item ID.</param>
+    /// <param name="benchmark">Time a few hundred frames through the extraction pipeline (takes a few seconds).</param>
+    /// <returns>The estimate.</returns>
+    [HttpGet("Estimate/{itemId}")]
+    [ProducesResponseType(StatusCodes.Status200OK)]
+    [ProducesResponseType(StatusCodes.Status400BadRequest)]
+    public async Task<ActionResult<AmbilightEstimate>> EstimateItem([FromRoute, Required] string itemId, [FromQuery] bool benchmark = true)
+    {
+        try
+        {
+            if (string.IsNullOrWhiteSpace(itemId) || !Guid.TryParse(itemId, out var guid))
+            {
+                return BadRequest(new { error = "Invalid item ID", itemId });
+            }
+
+            var storage = AmbilightEntryPoint.Instance?.Storage;
+            var extractorCore = AmbilightEntryPoint.Instance?.ExtractorCore;
+            if (storage == null || extractorCore == null)
+            {
+                return StatusCode(500, new { error = "Ambilight service not running" });
+            }
+
+            var item = storage.GetItem(guid.ToString("N"));
+            if (item == null || string.IsNullOrEmpty(item.FilePath))
+            {
+                return BadRequest(new { error = "Item not found or has no file path", itemId });
+            }
+
+            return Ok(await extractorCore.EstimateAsync(item.FilePath, benchmark, HttpContext.RequestAborted));
+        }
+        catch (Exception ex)
+        {
+            return StatusCode(500, new
+            {
+                error = ex.Message,
+                type = ex.GetType().Name,
+                stackTrace = ex.StackTrace
+            });
+        }
+    }
+
+    /// <summary>
+    /// Predicts the frame count and binary size of every item waiting for extraction, to plan disk space before
+    /// extracting a library. Only probes the files.
+    /// </summary>
+    /// <returns>Per-item estimates and their totals.</returns>
+    [HttpGet("Estimate/Pending")]
+    [ProducesResponseType(StatusCodes.Status200OK)]
+    public async Task<ActionResult<AmbilightLibraryEstimate>> EstimatePending()
+    {
+        try
+        {
+            var extractor = AmbilightEntryPoint.Instance?.Extractor;
+            if (extractor == null)
+            {
+                return StatusCode(500, new { error = "Ambilight service not running" });
+            }
+
+            return Ok(await extractor.EstimatePendingAsync(HttpContext.RequestAborted));
+        }
+        catch (Exception ex)
+        {
+            return StatusCode(500, new
+            {
+                error = ex.Message,
+                type = ex.GetType().Name,
+                stackTrace = ex.StackTrace
+            });
+        }
+    }
+
+    /// <summary>
+    /// Gets the state of running players: media position, frame index, effective sync lead and send statistics.
+    /// </summary>
+    /// <param name="sessionId">Optional session id filter.</param>
+    /// <returns>One entry per running player (one per WLED target).</returns>
+    [HttpGet("Playback/Status")]
+    [ProducesResponseType(StatusCodes.Status200OK)]
+    public ActionResult<List<AmbilightPlayerStatus>> GetPlaybackStatus([FromQuery] string? sessionId = null)
+    {
+        var playback = AmbilightEntryPoint.Instance?.Playback;
+        if (playback == null)
+        {
+            return StatusCode(500, new { error = "Ambilight service not running" });
+        }
+
+        return Ok(playback.GetStatus(sessionId));
+    }
+
+    /// <summary>
+    /// Adjusts a tuning parameter on running players (e.g. name=brightness&amp;value=40).
+    /// The change is applied on the next frame without restarting playback.
+    /// </summary>
+    /// <param name="name">Parameter name: brightness, saturation, smooth, gamma, gamma_red, gamma_green, gamma_blue, red_boost, green_boost, blue_boost, min_brightness, color_temp, decay or av_offset_ms.</param>
+    /// <param name="value">New value.</param>
+    /// <param name="sessionId">Optional session id; when omitted all active sessions are updated.</param>
+    /// <returns>Number of players updated.</returns>
+    [HttpPost("Playback/Set")]
+    [ProducesResponseType(StatusCodes.Status200OK)]
+    [ProducesResponseType(StatusCodes.Status400BadRequest)]
+    public ActionResult<AmbilightSetParameterResponse> SetPlaybackParameter(
+        [FromQuery, Required] string name,
+        [FromQuery, Required] double value,
+        [FromQuery] string? sessionId = null)
+    {
+        var playback = AmbilightEntryPoint.Instance?.Playback;
+        if (playback == null)
+        {
+            return StatusCode(500, new { error = "Ambilight service not running" });
+        }
+
+        var updated = playback.SetParameter(sessionId, name, value);
+        if (updated < 0)
+        {
+            return BadRequest(new { error = "Unknown parameter", name, allowed = AmbilightInProcessPlayer.TunableParameters });
+        }
+
+        return Ok(new AmbilightSetParameterResponse
+        {
+            Name = name,
+            Value = value,
+            PlayersUpdated = updated
+        });
+    }
+
+    /// <summary>
+    /// Sends a control command to running players: <c>pause</c>, <c>resume</c>, <c>seek</c> (needs
+    /// <see cref="AmbilightPlaybackCommand.Seconds"/>), <c>chapter</c> (seek to the start of
+    /// <see cref="AmbilightPlaybackCommand.Chapter"/>), <c>rate</c> (needs <see cref="AmbilightPlaybackCommand.Rate"/>),
+    /// <c>unload</c> or <c>reload</c> (apply saved device mapping changes to every session without stopping).
+    /// </summary>
+    /// <param name="command">The command.</param>
+    /// <returns>Number of players the command was sent to.</returns>
+    [HttpPost("Playback/Command")]
+    [ProducesResponseType(StatusCodes.Status200OK)]
+    [ProducesResponseType(StatusCodes.Status400BadRequest)]
+    public ActionResult<AmbilightPlaybackCommandResponse> SendPlaybackCommand([FromBody, Required] AmbilightPlaybackCommand command)
+    {
+        var playback = AmbilightEntryPoint.Instance?.Playback;
+        if (playback == null)
+        {
+            return StatusCode(500, new { error = "Ambilight service not running" });
+        }
+
+        int affected;
+        switch ((command.Command ?? string.Empty).Trim().ToLowerInvariant())
+        {
+            case "pause":
+                affected = playback.SetPaused(command.SessionId, true);
+                break;
+            case "resume":
+                affected = playback.SetPaused(command.SessionId, false);
+                break;
+            case "seek":
+                if (command.Seconds is not { } seconds || !double.IsFinite(seconds))
+                {
+                    return BadRequest(new { error = "seek requires Seconds" });
+                }
+                affected = playback.Seek(command.SessionId, seconds);
+                break;
+            case "chapter":
+                if (command.Chapter is not { } chapter || chapter < 1)
+                {
+                    return BadRequest(new { error = "chapter requires Chapter (1 or more)" });
+                }
+                affected = playback.SeekChapter(command.SessionId, chapter);
+                break;
+            case "rate":
+                affected = command.Rate is { } rate ? playback.SetPlaybackRate(command.SessionId, rate) : -1;
+                if (affected < 0)
+                {
+                    return BadRequest(new
+                    {
+                        error = FormattableString.Invariant($"rate requires Rate between {AmbilightInProcessPlayer.MinPlaybackRate} and {AmbilightInProcessPlayer.MaxPlaybackRate}")
+                    });
+                }
+                break;
+            case "unload":
+                affected = playback.Unload(command.SessionId);
+                break;
+            case "reload":
+                affected = playback.ReloadConfiguration();
+                break;
+            default:
+                return BadRequest(new { error = "Unknown command", command = command.Command, allowed = new[] { "pause", "resume", "seek", "chapter", "rate", "unload", "reload" } });
+        }
+
+        return Ok(new AmbilightPlaybackCommandResponse
+        {
+            Command = command.Command,
+            PlayersAffected = affected
+        });
+    }
+
+    /// <summary>
+    /// Queues an item to play right after the current one on running players, crossfading between them
+    /// instead of going dark.
+    /// </summary>
+    /// <param name="itemId">The item to play next.</param>
+    /// <param name="sessionId">Optional session id; all sessions when omitted.</param>
+    /// <returns>Number of players that queued the item.</returns>
+    [HttpPost("Playback/Next")]
+    [ProducesResponseType(StatusCodes.Status200OK)]
+    [ProducesResponseType(StatusCodes.Status400BadRequest)]
+    public ActionResult<AmbilightQueueNextResponse> QueueNext(
+        [FromQuery, Required] string itemId,
+        [FromQuery] string? sessionId = null)
+    {
+        if (string.IsNullOrWhiteSpace(itemId) || !Guid.TryParse(itemId, out var guid))
+        {
+            return BadRequest(new { error = "Invalid item ID", itemId });
+        }
+
+        var entryPoint = AmbilightEntryPoint.Instance;
+        if (entryPoint?.Playback == null || entryPoint.Storage == null)
+        {
+            return StatusCode(500, new { error = "Ambilight service not running" });
+        }
+
+        var binPath = entryPoint.Storage.ResolvePlaybackSource(guid.ToString("N"));
+        if (binPath == null)
+        {
+            return BadRequest(new { error = "No ambilight binary for item", itemId });
+        }
+
+        return Ok(new AmbilightQueueNextResponse
+        {
+            ItemId = guid,
+            PlayersQueued = entryPoint.Playback.QueueNext(sessionId, binPath)
+        });
+    }
+
+    /// <summary>
+    /// Follows an external SMPTE timecode (LTC from a sound card or MIDI Timecode, decoded by a bridge that posts
+    /// here every frame or so), for players outside Jellyfin. The first report for a source starts players for
+    /// the device's mappings; later reports keep them locked to the timecode. Set <c>Stop</c> to end it.
+    /// </summary>
+    /// <param name="request">The timecode report.</param>
+    /// <returns>The media position the timecode maps to and the number of players following it.</returns>
+    [HttpPost("Timecode")]
+    [ProducesResponseType(StatusCodes.Status200OK)]
+    [ProducesResponseType(StatusCodes.Status400BadRequest)]
+    public ActionResult<AmbilightTimecodeResponse> ReportTimecode([FromBody, Required] AmbilightTimecodeRequest request)
+    {
+        var entryPoint = AmbilightEntryPoint.Instance;
+        if (entryPoint?.Playback == null || entryPoint.Storage == null)
+        {
+            return StatusCode(500, new { error = "Ambilight service not running" });
+        }
+
+        if (string.IsNullOrWhiteSpace(request.Device))
+        {
+            return BadRequest(new { error = "Device is required" });
+        }
+
+        var source = string.IsNullOrWhiteSpace(request.Source) ? request.Device.Trim() : request.Source.Trim();
+        if (request.Stop)
+        {
+            entryPoint.Playback.StopTimecode(source);
+            return Ok(new AmbilightTimecodeResponse { Source = source });
+        }
+
+        if (!AmbilightTimecode.TryParse(request.Timecode, request.Fps, out var seconds))
+        {
+            return BadRequest(new { error = "Invalid timecode", timecode = request.Timecode, fps = request.Fps });
+        }
+
+        double start = 0.0;
+        if (!string.IsNullOrWhiteSpace(request.StartTimecode) && !AmbilightTimecode.TryParse(request.StartTimecode, request.Fps, out start))
+        {
+            return BadRequest(new { error = "Invalid start timecode", timecode = request.StartTimecode, fps = request.Fps });
+        }
+
+        if (!Guid.TryParse(request.ItemId, out var guid))
+        {
+            return BadRequest(new { error = "Invalid item ID", itemId = request.ItemId });
+        }
+
+        var binPath = entryPoint.Storage.ResolvePlaybackSource(guid.ToString("N"));
+        if (binPath == null)
+        {
+            return BadRequest(new { error = "No ambilight binary for item", itemId = request.ItemId });
+        }
+
+        var position = Math.Max(0.0, seconds - start);
+        return Ok(new AmbilightTimecodeResponse
+        {
+            Source = source,
+            PositionSeconds = position,
+            Players = entryPoint.Playback.OnTimecode(source, request.Device.Trim(), binPath, position, request.Paused)
+        });
+    }
+
+    /// <summary>
+    /// Returns the device mappings (WLED host, LED layout, protocol, A/V offset) that apply to a client,
+    /// matched exactly like playback sessions. An external player can call this with the Jellyfin device name
+    /// of whichever client starts playing and use the result as its profile.
+    /// </summary>
+    /// <param name="device">Jellyfin device name, e.g. "Living Room TV".</param>
+    /// <param name="deviceId">Optional Jellyfin device id, tried before the name.</param>
+    /// <returns>The matching mappings; empty when the device has no ambilight.</returns>
+    [HttpGet("Profile")]
+    [ProducesResponseType(StatusCodes.Status200OK)]
+    [ProducesResponseType(StatusCodes.Status400BadRequest)]
+    public ActionResult<List<DeviceMapping>> GetDeviceProfile([FromQuery] string? device = null, [FromQuery] string? deviceId = null)
+    {
+        if (string.IsNullOrWhiteSpace(device) && string.IsNullOrWhiteSpace(deviceId))
+        {
+            return BadRequest(new { error = "device or deviceId is required" });
+        }
+
+        var playback = AmbilightEntryPoint.Instance?.Playback;
+        if (playback == null)
+        {
+            return StatusCode(500, new { error = "Ambilight service not running" });
+        }
+
+        return Ok(playback.ResolveWledTargets(deviceId, device));
+    }
+
+    /// <summary>
+    /// Lights the WLED targets of a device with the identify pattern (top red, right green, bottom blue, left white,
+    /// then a dot running clockwise from the first top LED), to check input position, direction and LED counts
+    /// before watching anything. Targets that are playing are skipped.
+    /// </summary>
+    /// <param name="device">Jellyfin device name of the mapping.</param>
+    /// <param name="host">Optional WLED host, to identify only one of the device's targets.</param>
+    /// <returns>The targets the pattern was sent to.</returns>
+    [HttpPost("Identify")]
+    [ProducesResponseType(StatusCodes.Status200OK)]
+    [ProducesResponseType(StatusCodes.Status400BadRequest)]
+    public ActionResult<AmbilightIdentifyResponse> Identify([FromQuery, Required] string device, [FromQuery] string? host = null)
+    {
+        if (string.IsNullOrWhiteSpace(device))
+        {
+            return BadRequest(new { error = "device is required" });
+        }
+
+        var playback = AmbilightEntryPoint.Instance?.Playback;
+        if (playback == null)
+        {
+            return StatusCode(500, new { error = "Ambilight service not running" });
+        }
+
+        var targets = playback.ResolveWledTargets(null, device)
+            .Where(m => string.IsNullOrWhiteSpace(host) || string.Equals(m.Host.Trim(), host.Trim(), StringComparison.OrdinalIgnoreCase))
+            .ToList();
+        if (targets.Count == 0)
+        {
+            return BadRequest(new { error = "No device mapping matches", device, host });
+        }
+
+        var response = new AmbilightIdentifyResponse { Device = device };
+        foreach (var mapping in targets)
+        {
+            var target = $"{mapping.Host.Trim()}:{mapping.Port}";
+            if (playback.Identify(mapping))
+            {
+                response.Targets.Add(target);
+            }
+            else
+            {
+                response.Busy.Add(target);
+            }
+        }
+
+        return Ok(response);
+    }
+
+    /// <summary>
+    /// Drives the calibration wizard: plays a static <c>white</c> (white balance) or <c>ramp</c> (gamma) pattern on
+    /// one of a device's WLED targets through the normal color pipeline. Adjust it live with <c>Playback/Set</c> and
+    /// the returned session id; repeat the call every few seconds as a heartbeat, and end it with <c>stop</c>.
+    /// </summary>
+    /// <param name="device">Jellyfin device name of the mapping.</param>
+    /// <param name="host">Optional WLED host; the device's first target when omitted.</param>
+    /// <param name="pattern">white, ramp or stop.</param>
+    /// <returns>The calibration session.</returns>
+    [HttpPost("Calibrate")]
+    [ProducesResponseType(StatusCodes.Status200OK)]
+    [ProducesResponseType(StatusCodes.Status400BadRequest)]
+    public ActionResult<AmbilightCalibrateResponse> Calibrate(
+        [FromQuery, Required] string device,
+        [FromQuery] string? host = null,
+        [FromQuery] string pattern = "white")
+    {
+        pattern = (pattern ?? string.Empty).Trim().ToLowerInvariant();
+        if (pattern is not ("white" or "ramp" or "stop"))
+        {
+            return BadRequest(new { error = "Unknown calibration pattern", pattern, allowed = new[] { "white", "ramp", "stop" } });
+        }
+
+        var playback = AmbilightEntryPoint.Instance?.Playback;
+        if (playback == null)
+        {
+            return StatusCode(500, new { error = "Ambilight service not running" });
+        }
+
+        var mapping = playback.ResolveWledTargets(null, device)
+            .FirstOrDefault(m => string.IsNullOrWhiteSpace(host) || string.Equals(m.Host.Trim(), host.Trim(), StringComparison.OrdinalIgnoreCase));
+        if (mapping == null)
+        {
+            return BadRequest(new { error = "No device mapping matches", device, host });
+        }
+
+        if (pattern == "stop")
+        {
+            playback.StopCalibration(mapping);
+            return Ok(new AmbilightCalibrateResponse { Pattern = pattern, Target = $"{mapping.Host.Trim()}:{mapping.Port}" });
+        }
+
+        var sessionId = playback.StartCalibration(mapping, pattern);
+        if (sessionId == null)
+        {
+            return StatusCode(500, new { error = "Could not start the calibration pattern" });
+        }
+
+        return Ok(new AmbilightCalibrateResponse
+        {
+            SessionId = sessionId,
+            Pattern = pattern,
+            Target = $"{mapping.Host.Trim()}:{mapping.Port}"
+        });
+    }
+
+    /// <summary>
+    /// Generates a small synthetic AMb2 file (solid colors, a rotating gradient or a single-LED chase)
+    /// with exact timestamps, for testing playback and LED layout without extracting real media.
+    /// When <paramref name="itemId"/> is given the fixture replaces that item's binary, so playing the item
+    /// drives the LEDs with the pattern; otherwise the file is returned as a download.
+    /// </summary>
+    /// <param name="pattern">solid, gradient or chase.</param>
+    /// <param name="seconds">Duration of the fixture.</param>
+    /// <param name="fps">Frame rate of the fixture.</param>
+    /// <param name="format">Payload format: 0 = RGB, 1 = RGBW, 2 = RGB16, 3 = RGBW16.</param>
+    /// <param name="itemId">Optional item whose binary is replaced by the fixture.</param>
+    /// <returns>The fixture file, or 204 when it was installed for an item.</returns>
+    [HttpPost("Fixture")]
+    [ProducesResponseType(StatusCodes.Status200OK)]
+    [ProducesResponseType(StatusCodes.Status204NoContent)]
+    [ProducesResponseType(StatusCodes.Status400BadRequest)]
+    public ActionResult GenerateFixture(
+        [FromQuery] string pattern = "solid",
+        [FromQuery] double seconds = 10.0,
+        [FromQuery] double fps = 24.0,
+        [FromQuery] byte format = 0,
+        [FromQuery] string? itemId = null)
+    {
+        try
+        {
+            if (seconds <= 0 || seconds > 3600 || fps <= 0 || fps > 240)
+            {
+                return BadRequest(new { error = "seconds must be in (0, 3600] and fps in (0, 240]", seconds, fps });
+            }
+
+            Guid guid = Guid.Empty;
+            if (!string.IsNullOrWhiteSpace(itemId) && !Guid.TryParse(itemId, out guid))
+            {
+                return BadRequest(new { error = "Invalid item ID", itemId });
+            }
+
+            var config = Plugin.Instance?.Configuration ?? new PluginConfiguration();
+            byte[] bytes;
+            try
+            {
+                bytes = AmbilightFixtureGenerator.Generate(
+                    pattern,
+                    seconds,
+                    fps,
+                    (ushort)Math.Max(0, config.AmbilightTopLedCount),
+                    (ushort)Math.Max(0, config.AmbilightBottomLedCount),
+                    (ushort)Math.Max(0, config.AmbilightLeftLedCount),
+                    (ushort)Math.Max(0, config.AmbilightRightLedCount),
+                    format);
+            }
+            catch (ArgumentException ex)
+            {
+                return BadRequest(new { error = ex.Message, patterns = AmbilightFixtureGenerator.Patterns });
+            }
+
+            var fileName = $"fixture-{pattern.Trim().ToLowerInvariant()}-fmt{format}.bin";
+            if (guid == Guid.Empty)
+            {
+                return File(bytes, "application/octet-stream", fileName);
+            }
+
+            var dataFolder = string.IsNullOrWhiteSpace(config.AmbilightDataFolder) ? "/data/ambilight" : config.AmbilightDataFolder.Trim();
+            Directory.CreateDirectory(dataFolder);
+            var binPath = Path.Combine(dataFolder, guid.ToString("N") + ".bin");
+            var tempPath = binPath + ".tmp";
+
+            // The fixture has no extraction parameters or chapters; the replaced binary's would describe the wrong file.
+            System.IO.File.Delete(binPath + AmbilightStorageService.ParametersExtension);
+            System.IO.File.Delete(binPath + AmbilightStorageService.ChaptersExtension);
+            System.IO.File.WriteAllBytes(tempPath, bytes);
+            System.IO.File.Move(tempPath, binPath, overwrite: true);
+            AmbilightStorageService.WriteChecksum(binPath, bytes);
+
+            return NoContent();
+        }
+        catch (Exception ex)
+        {
+            return StatusCode(500, new
+            {
+                error = ex.Message,
+                type = ex.GetType().Name,
+                stackTrace = ex.StackTrace
+            });
+        }
+    }
+
     /// <summary>
     /// Deletes the ambilight binary for a specific item so it can be re-extracted.
     /// </summary>
@@ -407,9 +1118,17 @@ public class AmbilightController : ControllerBase
             var dataFolder = string.IsNullOrWhiteSpace(config?.AmbilightDataFolder) ? "/data/ambilight" : config!.AmbilightDataFolder.Trim();
             var binPath = Path.Combine(dataFolder, guid.ToString("N") + ".bin");
 
-            if (System.IO.File.Exists(binPath))
+            var storage = AmbilightEntryPoint.Instance?.Storage;
+            if (storage != null)
+            {
+                storage.DeleteBinary(guid.ToString("N"));
+            }
+            else
             {
                 System.IO.File.Delete(binPath);
+                System.IO.File.Delete(binPath + ".sha256");
+                System.IO.File.Delete(binPath + AmbilightStorageService.ParametersExtension);
+                System.IO.File.Delete(binPath + AmbilightStorageService.ChaptersExtension);
             }
 
             // We intentionally do not touch metadata here; the extractor service will
@@ -428,6 +1147,67 @@ public class AmbilightController : ControllerBase
     }
 }
 
+public class AmbilightSetParameterResponse
+{
+    public string? Name { get; set; }
+    public double Value { get; set; }
+    public int PlayersUpdated { get; set; }
+}
+
+public class AmbilightPlaybackCommand
+{
+    public string? Command { get; set; } // pause, resume, seek, chapter, rate, unload, reload
+    public double? Seconds { get; set; } // seek target (media position)
+    public int? Chapter { get; set; } // chapter target, 1-based
+    public double? Rate { get; set; } // playback speed for rate, e.g. 1.5
+    public string? SessionId { get; set; } // all sessions when empty
+}
+
+public class AmbilightPlaybackCommandResponse
+{
+    public string? Command { get; set; }
+    public int PlayersAffected { get; set; }
+}
+
+public class AmbilightIdentifyResponse
+{
+    public string Device { get; set; } = string.Empty;
+    public List<string> Targets { get; set; } = new(); // host:port the pattern is shown on
+    public List<string> Busy { get; set; } = new(); // skipped because a player is playing on them
+}
+
+public class AmbilightCalibrateResponse
+{
+    public string? SessionId { get; set; } // pass to Playback/Set; empty after stop
+    public string Pattern { get; set; } = string.Empty;
+    public string Target { get; set; } = string.Empty; // host:port
+}
+
+public class AmbilightQueueNextResponse
+{
+    public Guid ItemId { get; set; }
+    public int PlayersQueued { get; set; }
+}
+
+public class AmbilightTimecodeRequest
+{
+    public string? Source { get; set; } // identifies the bridge; defaults to Device
+    public string Device { get; set; } = string.Empty; // device mapping name to drive
+    public string? ItemId { get; set; }
+    public string? Timecode { get; set; } // HH:MM:SS:FF, or HH:MM:SS;FF for drop-frame
+    public double Fps { get; set; } = 24.0; // e.g. 23.976, 25, 29.97
+    public string? StartTimecode { get; set; } // timecode of the first frame of the item, e.g. 01:00:00:00
+    public bool Paused { get; set; } // timecode is holding still
+    public bool Stop { get; set; }
+}
+
+public class AmbilightTimecodeResponse
+{
+    public string Source { get; set; } = string.Empty;
+    public double PositionSeconds { get; set; }
+    public int Players { get; set; }
+}
+
 public class AmbilightStatusResponse
 {
     public Guid ItemId { get; set; }
@@ -435,10 +1215,28 @@ public class AmbilightStatusResponse
     public bool HasBinary { get; set; }
     public string? BinaryPath { get; set; }
     public long BinarySize { get; set; }
+
+    /// <summary>
+    /// Why the binary cannot be played (an <see cref="AmbilightBinaryError"/> name such as "BadMagic"), or null when it is valid
+    /// or missing. <see cref="BinaryErrorCode"/> is the same as a number (0 = valid).
+    /// </summary>
+    public string? BinaryError { get; set; }
+    public int BinaryErrorCode { get; set; }
+    public string? BinaryErrorMessage { get; set; }
+
+    /// <summary>Timestamp of the binary's last whole frame in seconds; below <see cref="RuntimeSeconds"/> for a truncated binary.</summary>
+    public double? BinaryCoveredSeconds { get; set; }
+    public double? RuntimeSeconds { get; set; }
     public string? ExtractionStatus { get; set; }
     public int ExtractionProgress { get; set; }
     public ulong ExtractionFramesCurrent { get; set; }
     public ulong ExtractionFramesTotal { get; set; }
+
+    /// <summary>
+    /// Where the picture sat inside black bars over time, as fractions of the frame, when they were tracked during
+    /// extraction; a film that changes aspect ratio has one entry per change.
+    /// </summary>
+    public List<AmbilightPictureArea>? PictureAreas { get; set; }
 }
 
 public class AmbilightExtractResponse
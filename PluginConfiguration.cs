@@ -19,7 +19,175 @@ namespace Jellyfin.Plugin.Ambilight
         // Extraction
         public string ExtractionPriority { get; set; } = "newest_first";
         public bool ExtractNewlyAddedItems { get; set; } = true;
-        public string HardwareAcceleration { get; set; } = "auto"; // "auto", "none", "vaapi", "qsv", "cuda", "videotoolbox"
+        public string HardwareAcceleration { get; set; } = "auto"; // "auto", "none", "vaapi", "nvdec" ("cuda"), "v4l2m2m", "qsv", "videotoolbox"
+
+        /// <summary>
+        /// Device for hardware decoding: the VAAPI render node (default <c>/dev/dri/renderD128</c>) or the CUDA GPU
+        /// index for NVDEC (default 0). Empty = default.
+        /// </summary>
+        public string HardwareAccelerationDevice { get; set; } = string.Empty;
+
+        /// <summary>
+        /// "inprocess" decodes with ffmpeg and averages zones in C#; "external" runs the Rust extractor with
+        /// <c>--zones-only</c> for decode and zone averaging, while the plugin still writes the file and tracks progress.
+        /// </summary>
+        public string ExtractorBackend { get; set; } = "inprocess";
+
+        /// <summary>
+        /// Zone color algorithm: "best" weights edges and the zone center over every pixel, "balanced" does the same
+        /// over every second pixel, "fast" skips edge detection and takes a sampled mean.
+        /// </summary>
+        public string ExtractionQuality { get; set; } = "best";
+
+        /// <summary>
+        /// How each zone's pixels are reduced to its color: "edge" (edge and center weighted mean), "mean", "dominant"
+        /// (most common color) or "median". <see cref="LibraryColorModes"/> can override it per library.
+        /// </summary>
+        public string ExtractionColorMode { get; set; } = "edge";
+
+        /// <summary>
+        /// Per-library overrides of <see cref="ExtractionColorMode"/>. Only affects newly extracted binaries.
+        /// </summary>
+        public List<LibraryColorMode> LibraryColorModes { get; set; } = new();
+
+        /// <summary>
+        /// Weight the pixels that stand out in each zone: "contrast" (far from the zone's average brightness), "motion"
+        /// (changed since the previous frame) or "off". Replaces the edge weighting of the edge and mean color modes.
+        /// </summary>
+        public string ExtractionSaliency { get; set; } = "off";
+
+        /// <summary>
+        /// Flag the frames where a new shot starts in the binary, so players can skip smoothing on cuts. Needs a player
+        /// that reads frame info; older ones reject these files.
+        /// </summary>
+        public bool SceneCutFlags { get; set; } = false;
+
+        /// <summary>
+        /// Average change of all LED channels from one frame to the next, in percent of full range, that counts as a cut.
+        /// </summary>
+        public int SceneCutThreshold { get; set; } = 25;
+
+        /// <summary>
+        /// Store each frame's average LED luminance in the binary, so players take it from the file instead of working it
+        /// out every frame. Needs a player that reads frame info; older ones reject these files.
+        /// </summary>
+        public bool FrameLuminance { get; set; } = false;
+
+        /// <summary>
+        /// Decode the audio in an extra pass and store a loudness envelope (10 values a second) with the frames, for the
+        /// player's <see cref="AmbilightAudioPulse"/>. Needs a player that reads frame info; older ones reject these files.
+        /// </summary>
+        public bool AudioEnergy { get; set; } = false;
+
+        /// <summary>
+        /// Look for the end credits (mostly black frames with light text) in an extra pass over the end of the video
+        /// and flag their frames, for the player's <see cref="AmbilightCreditsMode"/>. Needs a player that reads frame
+        /// info; older ones reject these files.
+        /// </summary>
+        public bool CreditsDetection { get; set; } = false;
+
+        /// <summary>
+        /// Tone down heavy color grades scene by scene: each scene's average color is pulled towards neutral by
+        /// <see cref="SceneWhiteBalanceStrength"/>, baked into the extracted colors. Scenes end at scene cuts.
+        /// </summary>
+        public bool SceneWhiteBalance { get; set; } = false;
+
+        /// <summary>
+        /// Share of the correction to neutral that is applied, in percent (0-100).
+        /// </summary>
+        public int SceneWhiteBalanceStrength { get; set; } = 50;
+
+        /// <summary>
+        /// Where the in-process extractor computes zone colors: "cpu", or "opencl" to run the zone reduction on the
+        /// first OpenCL GPU (falls back to the CPU when there is none).
+        /// </summary>
+        public string ExtractionCompute { get; set; } = "cpu";
+
+        /// <summary>
+        /// Height in pixels frames are scaled to for zone extraction; the width follows at 16:9 (default 180 = 320x180).
+        /// Higher finds finer edges in thin zones at the cost of extraction time.
+        /// </summary>
+        public int AnalysisHeight { get; set; } = 180;
+
+        /// <summary>
+        /// Deinterlace filter for interlaced sources (DVD and TV rips): "bwdif", "yadif", or "off". Only streams whose
+        /// field order says interlaced are filtered, and only their frames flagged as interlaced.
+        /// </summary>
+        public string ExtractionDeinterlace { get; set; } = "bwdif";
+
+        /// <summary>
+        /// Index of the video stream to extract (as ffprobe numbers streams), or -1 to pick the main video automatically:
+        /// cover art and still-image streams are skipped, then the highest resolution, longest and default stream wins.
+        /// </summary>
+        public int VideoStreamIndex { get; set; } = -1;
+
+        /// <summary>
+        /// Videos that decode to less than this many seconds are marked not extractable instead of producing a tiny binary.
+        /// </summary>
+        public double MinExtractDurationSeconds { get; set; } = 5.0;
+
+        /// <summary>
+        /// Threads the in-process extractor spreads zone extraction over, a batch of frames at a time
+        /// (0 = half the CPU cores, 1 = single-threaded).
+        /// </summary>
+        public int ExtractionThreads { get; set; } = 0;
+
+        /// <summary>
+        /// Threads ffmpeg may use to decode during extraction (0 = ffmpeg's default, about one per core).
+        /// </summary>
+        public int ExtractionDecoderThreads { get; set; } = 0;
+
+        /// <summary>
+        /// Average share of the whole machine's CPU an extraction may use, decoder included, in percent
+        /// (0 or 100 = no limit). Reading frames pauses while extraction is over budget.
+        /// </summary>
+        public int ExtractionCpuLimitPercent { get; set; } = 0;
+
+        /// <summary>
+        /// Time segments the in-process extractor splits a video into and decodes in parallel, one ffmpeg each
+        /// (1 = off). Segments are at least two minutes long, so short videos use fewer.
+        /// </summary>
+        public int ExtractionSegments { get; set; } = 1;
+
+        /// <summary>
+        /// Checkpoint single-pass in-process extractions every 30 seconds, so one that is killed part way (container
+        /// restart, OOM) continues from the last checkpoint instead of frame 0.
+        /// </summary>
+        public bool ExtractionResume { get; set; } = true;
+
+        /// <summary>
+        /// Leave a binary alone when its parameters sidecar shows it was extracted from the same file (size and
+        /// modification time) with the same settings, and it still matches its checksum. Manual extractions always run.
+        /// </summary>
+        public bool SkipUpToDateExtractions { get; set; } = true;
+
+        /// <summary>
+        /// Store the frames of new binaries zstd-compressed, in blocks the seek index points at. Players decompress
+        /// them as they read; older ones reject these files.
+        /// </summary>
+        public bool CompressBinaries { get; set; } = false;
+
+        /// <summary>
+        /// Base URL (WebDAV or any HTTP endpoint accepting PUT) that finished binaries and their checksum are uploaded to,
+        /// for when the server that plays them is not the one extracting (empty = off).
+        /// </summary>
+        public string UploadUrl { get; set; } = string.Empty;
+
+        /// <summary>
+        /// Value of the Authorization header sent with uploads, e.g. "Bearer ..." or "Basic ..." (empty = none).
+        /// </summary>
+        public string UploadAuthorization { get; set; } = string.Empty;
+
+        /// <summary>
+        /// Base URL that binaries are read from, with HTTP range requests, when an item has no valid local binary,
+        /// e.g. the store extraction uploads to (empty = local data folder only).
+        /// </summary>
+        public string RemoteBinaryUrl { get; set; } = string.Empty;
+
+        /// <summary>
+        /// Value of the Authorization header sent when reading from <see cref="RemoteBinaryUrl"/> (empty = none).
+        /// </summary>
+        public string RemoteBinaryAuthorization { get; set; } = string.Empty;
         
         // WLED Device Mappings
         public string DeviceMatchField { get; set; } = "DeviceName";
@@ -31,17 +199,178 @@ namespace Jellyfin.Plugin.Ambilight
         public int AmbilightLeftLedCount { get; set; } = 49;
         public int AmbilightRightLedCount { get; set; } = 49;
         public bool AmbilightRgbw { get; set; } = false;
-        
+
+        /// <summary>
+        /// Depth of each sampled edge band as a multiple of the LED spacing (clamped to 12% of the frame).
+        /// </summary>
+        public double AmbilightZoneDepthFactor { get; set; } = 2.0;
+
+        /// <summary>
+        /// Depth of the top edge band in percent of the picture height; 0 uses <see cref="AmbilightZoneDepthFactor"/>.
+        /// The right, bottom and left bands work the same way (percent of the width for left and right).
+        /// </summary>
+        public double AmbilightBandDepthPercentTop { get; set; } = 0;
+        public double AmbilightBandDepthPercentRight { get; set; } = 0;
+        public double AmbilightBandDepthPercentBottom { get; set; } = 0;
+        public double AmbilightBandDepthPercentLeft { get; set; } = 0;
+
+        /// <summary>
+        /// How corners are extracted: "share" (the ends of adjacent sides both cover the corner), "dedicated" (the
+        /// sides stop short and each corner gets <see cref="AmbilightCornerZones"/> zones of its own) or "blend" (the two
+        /// LEDs meeting at a corner get the average of their colors). Stored in the binary's header.
+        /// </summary>
+        public string AmbilightCorners { get; set; } = "share";
+
+        /// <summary>
+        /// Zones per corner with dedicated corners (1-4).
+        /// </summary>
+        public int AmbilightCornerZones { get; set; } = 1;
+
+        /// <summary>
+        /// Share of edge-detection weighting vs. center weighting when averaging a zone (0 = center only, 1 = edges only).
+        /// </summary>
+        public double AmbilightEdgeWeight { get; set; } = 0.7;
+
+        /// <summary>
+        /// Look for letterbox and pillarbox bars before extracting and lay the zones out on the picture inside them,
+        /// so the LEDs facing the bars do not stay dark.
+        /// </summary>
+        public bool DetectBlackBars { get; set; } = true;
+
+        /// <summary>
+        /// Keeps hardcoded subtitles and lower thirds out of the bottom zones under the bottom-center region: "shrink"
+        /// samples only a thin strip along the bottom edge there, "exclude" fills those LEDs in from their neighbors,
+        /// "off" samples them like the rest.
+        /// </summary>
+        public string SubtitleGuard { get; set; } = "off";
+
+        /// <summary>
+        /// Width of the bottom-center subtitle region as a percentage of the picture width.
+        /// </summary>
+        public int SubtitleGuardWidthPercent { get; set; } = 60;
+
+        /// <summary>
+        /// How HDR10 (PQ) sources are brought to SDR before zone averaging: "bt2390" rolls off only the highlights,
+        /// "hable" applies a filmic curve over the whole range, "off" reads PQ values as if they were SDR.
+        /// </summary>
+        public string HdrToneMapping { get; set; } = "bt2390";
+
+        /// <summary>
+        /// Peak luminance in nits HDR10 sources are tone mapped from (0 = MaxCLL or mastering peak from the stream, else 1000).
+        /// </summary>
+        public int HdrPeakNits { get; set; } = 0;
+
+        /// <summary>
+        /// How much of the tone mapping curve is applied, in percent: 100 = the full curve, 0 = plain clipping at SDR
+        /// white, which is brighter but loses highlight detail.
+        /// </summary>
+        public int HdrToneMapStrength { get; set; } = 100;
+
+        /// <summary>
+        /// Convert sources with BT.2020 primaries (UHD remuxes) to BT.709 before zone averaging, so their LED colors match
+        /// the 1080p release instead of coming out oversaturated.
+        /// </summary>
+        public bool ConvertWideGamut { get; set; } = true;
+
+        /// <summary>
+        /// Measure each film's overall brightness in an analysis pass before extracting, and lift the zone colors of
+        /// dark films so their LEDs stay at usable levels. Films that are already bright enough are left as they are.
+        /// </summary>
+        public bool BrightnessNormalization { get; set; } = false;
+
+        /// <summary>
+        /// Largest factor <see cref="BrightnessNormalization"/> brightens the darkest parts of a film by.
+        /// </summary>
+        public double BrightnessNormalizationMaxGain { get; set; } = 3.0;
+
         // Ambilight Visual Settings (global preferences)
         public double AmbilightSyncLeadSeconds { get; set; } = 0.2;
+
+        /// <summary>
+        /// Probe the round trip to each WLED target at startup and every minute, and add the measured one-way
+        /// latency to the sync lead.
+        /// </summary>
+        public bool AutoLatencyCompensation { get; set; } = false;
+
+        /// <summary>
+        /// Dim the LEDs once the output has been essentially unchanged for this many minutes (0 = off).
+        /// Full brightness returns on the next real change.
+        /// </summary>
+        public double StaticSceneFadeMinutes { get; set; } = 0.0;
+
+        /// <summary>
+        /// Brightness (percent of normal) a static scene fades down to.
+        /// </summary>
+        public double StaticSceneFadeBrightness { get; set; } = 30.0;
+
+        /// <summary>
+        /// What the LEDs show while playback is paused: "hold" (last frame), "dim" (last frame at
+        /// <see cref="PauseDimPercent"/>), "fade" (to black over <see cref="PauseFadeSeconds"/>) or
+        /// "breathe" (the frame's average color, slowly pulsing up to <see cref="PauseDimPercent"/>).
+        /// </summary>
+        public string PauseMode { get; set; } = "hold";
+
+        public double PauseDimPercent { get; set; } = 40.0;
+
+        public double PauseFadeSeconds { get; set; } = 3.0;
+
+        /// <summary>
+        /// Brightness cap (percent of normal) between <see cref="NightStart"/> and <see cref="NightEnd"/>
+        /// (local server time, "HH:mm"; the window may span midnight). 100 = no night schedule.
+        /// </summary>
+        public double NightBrightness { get; set; } = 100.0;
+
+        public string NightStart { get; set; } = "22:00";
+
+        public string NightEnd { get; set; } = "07:00";
         /// <summary>
         /// Smoothing window in seconds for temporal blending between frames.
         /// Set to 0 to disable smoothing entirely.
         /// Higher values = smoother but more lag; lower values = more responsive but can flicker.
         /// </summary>
         public double AmbilightSmoothSeconds { get; set; } = 0.12;
+
+        /// <summary>
+        /// Smoothing window in seconds used while an LED gets darker, so brightening can follow
+        /// <see cref="AmbilightSmoothSeconds"/> (fast attack) while fades to black stay gentle. 0 = same as the smoothing window.
+        /// </summary>
+        public double AmbilightDecaySeconds { get; set; } = 0.0;
+
+        /// <summary>
+        /// Jump straight to the new colors on frames the extractor flagged as scene cuts instead of smoothing into them.
+        /// </summary>
+        public bool AmbilightSceneCutReset { get; set; } = true;
+
+        /// <summary>
+        /// How much brighter (percent) the LEDs get at the loudest moments of a binary with an audio energy track. 0 = off.
+        /// </summary>
+        public int AmbilightAudioPulse { get; set; } = 0;
+
+        /// <summary>
+        /// What the LEDs do during end credits flagged by the extractor: "off" (play them as extracted), "dim" (at
+        /// <see cref="AmbilightCreditsBrightness"/>) or "ambient" (the average colors of the last minutes before the
+        /// credits, at that brightness).
+        /// </summary>
+        public string AmbilightCreditsMode { get; set; } = "dim";
+
+        /// <summary>
+        /// Brightness during end credits, in percent.
+        /// </summary>
+        public double AmbilightCreditsBrightness { get; set; } = 30.0;
+
+        /// <summary>
+        /// How stored zones are mapped to a device with a different LED count: "linear" interpolates along each side
+        /// with the corners aligned (no banding when upscaling), "nearest" repeats the closest zone.
+        /// </summary>
+        public string AmbilightResampleMode { get; set; } = "linear";
         public double AmbilightGamma { get; set; } = 2.2;
         public double AmbilightSaturation { get; set; } = 1.0;
+
+        /// <summary>
+        /// Do the saturation adjustment and temporal smoothing in OKLab instead of raw RGB, so fades and
+        /// transitions keep their hue (no red→brown shift) at a small CPU cost.
+        /// </summary>
+        public bool AmbilightPerceptualColor { get; set; } = false;
         public double AmbilightBrightnessTarget { get; set; } = 60.0;
         
         public double AmbilightGammaRed { get; set; } = 1.0;
@@ -54,11 +383,144 @@ namespace Jellyfin.Plugin.Ambilight
         
         public double AmbilightMinLedBrightness { get; set; } = 0.0;
 
+        /// <summary>
+        /// White point of the output in kelvin. 6500 = neutral; lower values warm the LEDs (e.g. to match a
+        /// cool-white diffuser), higher values cool them (e.g. for warm-white LED housings).
+        /// </summary>
+        public double AmbilightColorTemperatureK { get; set; } = 6500.0;
+
+        /// <summary>
+        /// Keep each session's players (and their sockets) alive between items and switch them to the
+        /// next binary instead of starting new ones. Idle players exit after 30 minutes or when the session ends.
+        /// </summary>
+        public bool ResidentPlayers { get; set; } = true;
+
+        /// <summary>
+        /// When an episode starts, load the next episode's binary into memory in the background.
+        /// </summary>
+        public bool PrecacheNextEpisode { get; set; } = false;
+
+        /// <summary>
+        /// Read frames from the binary as playback goes (local files memory-mapped, remote ones with a small read-ahead)
+        /// instead of loading the whole file into memory first, for low-memory servers. Disables the binary cache and
+        /// next-episode pre-caching.
+        /// </summary>
+        public bool StreamBinaries { get; set; } = false;
+
+        /// <summary>
+        /// Play local binaries that end mid-frame (interrupted extraction or copy) up to where they stop, and mark the
+        /// item for re-extraction. Off = such binaries are treated as missing until re-extracted.
+        /// </summary>
+        public bool PlayTruncatedBinaries { get; set; } = true;
+
+        /// <summary>
+        /// Crossfade in seconds when a resident player switches straight to the next item (0 = hard cut).
+        /// </summary>
+        public double GaplessCrossfadeSeconds { get; set; } = 1.0;
+
+        /// <summary>
+        /// Fade up from black when an item starts (unless it crossfades from the previous one) and after a seek (0 = instant).
+        /// </summary>
+        public double FadeInSeconds { get; set; } = 1.0;
+
+        /// <summary>
+        /// Fade the last frame out to black when playback stops, instead of blanking at once (0 = instant).
+        /// </summary>
+        public double FadeOutSeconds { get; set; } = 1.0;
+
+        /// <summary>
+        /// Easing of the fade-in/out envelope: "linear", "smooth" (ease in and out) or "perceptual"
+        /// (quadratic, which looks even to the eye because LED output is linear light).
+        /// </summary>
+        public string FadeCurve { get; set; } = "smooth";
+
+        /// <summary>
+        /// When an item finishes and nothing follows it, keep a resident player showing the average colors of the
+        /// item's last minutes, drifting slowly, until the next item starts or the player is stopped.
+        /// </summary>
+        public bool PostPlaybackAmbient { get; set; } = false;
+
+        /// <summary>
+        /// Brightness of the post-playback ambient light, in percent of the averaged colors.
+        /// </summary>
+        public double PostPlaybackAmbientBrightness { get; set; } = 60.0;
+
+        /// <summary>
+        /// Go dark after the post-playback ambient light has been on this long (0 = until stopped).
+        /// </summary>
+        public double PostPlaybackAmbientMinutes { get; set; } = 10.0;
+
+        /// <summary>
+        /// Compare Jellyfin's progress reports with the player clock and slew frame timing (±2%) to stay in sync on long items.
+        /// </summary>
+        public bool DriftCorrection { get; set; } = true;
+
+        /// <summary>
+        /// What players do when a playing session sends no progress for <see cref="HeartbeatTimeoutSeconds"/>:
+        /// "freewheel" (keep playing), "hold" (freeze the current frame) or "fade" (fade out). They resync when progress resumes.
+        /// </summary>
+        public string HeartbeatLossAction { get; set; } = "freewheel";
+
+        public double HeartbeatTimeoutSeconds { get; set; } = 30.0;
+
+        /// <summary>
+        /// Schedule every frame against the time playback (or a seek) was reported rather than when the player got to it,
+        /// so all targets of a session stay in phase and binary load time is compensated.
+        /// </summary>
+        public bool PhaseLockPlayers { get; set; } = false;
+
+        /// <summary>
+        /// When a WLED target reports fewer LEDs than its mapping configures, scale all four sides down to fit
+        /// instead of letting WLED cut off the end of the frame.
+        /// </summary>
+        public bool ClampToDeviceLedCount { get; set; } = true;
+
+        /// <summary>
+        /// Re-send the last frame at least this often (seconds) while nothing new is sent, e.g. paused or between
+        /// sparse frames, so WLED's realtime mode does not time out and fall back to its effect (0 = off).
+        /// </summary>
+        public double KeepAliveSeconds { get; set; } = 2.0;
+
+        /// <summary>
+        /// Minimum spacing between two packets to one WLED target, in milliseconds (0 = no pacing).
+        /// Frames that are already late when the player gets to them are dropped rather than sent in a burst.
+        /// </summary>
+        public double MinPacketIntervalMs { get; set; } = 10.0;
+
+        /// <summary>
+        /// DSCP code point (0-63) for the LED packets, so busy Wi-Fi can prioritize them (0 = unmarked).
+        /// 46 (EF) maps to the WMM voice queue, 34 (AF41) to video.
+        /// </summary>
+        public int UdpDscp { get; set; } = 0;
+
+        /// <summary>
+        /// When a playing player has sent nothing for this many seconds (stalled disk, CPU starvation), blank or dim
+        /// the LEDs until it recovers, instead of leaving them frozen (0 = off). Keep it above <see cref="KeepAliveSeconds"/>.
+        /// </summary>
+        public double WatchdogSeconds { get; set; } = 5.0;
+
+        /// <summary>
+        /// What the stall watchdog does: "blank" (LEDs off) or "dim" (last colors at 20%).
+        /// </summary>
+        public string WatchdogAction { get; set; } = "blank";
+
+        /// <summary>
+        /// Write each player's end-of-item statistics (frames sent/dropped, scheduling error, processing time)
+        /// as JSON to the reports folder in the data folder. The summary is always logged.
+        /// </summary>
+        public bool WriteRunReports { get; set; } = false;
+
         /// <summary>
         /// Libraries (by Id) that should be excluded from extraction.
         /// </summary>
         public List<string> ExcludedLibraryIds { get; set; } = new();
 
+        /// <summary>
+        /// Per-library gains applied to extracted colors, to compensate for sources with a systematic tint
+        /// (e.g. slightly green web rips). Only affects newly extracted binaries.
+        /// </summary>
+        public List<ExtractionWhiteBalance> ExtractionWhiteBalances { get; set; } = new();
+
         /// <summary>
         /// Folder where ambilight binary files are stored. Filenames are {ItemId}.bin.
         /// </summary>
@@ -84,5 +546,91 @@ namespace Jellyfin.Plugin.Ambilight
         public int LeftLedCount { get; set; } = 49;
         public int RightLedCount { get; set; } = 49;
         public int InputPosition { get; set; } = 0;
+
+        /// <summary>
+        /// Output framing: "raw" (WLED UDP raw, port 19446), "drgb" (WLED realtime / ESPHome wled effect, port 21324),
+        /// "e131" (sACN / ESPHome e131 effect, port 5568) or "adalight".
+        /// </summary>
+        public string Protocol { get; set; } = "raw";
+
+        /// <summary>
+        /// Display processing delay in milliseconds. Positive values delay the LEDs (TV lags the picture),
+        /// negative values advance them. Applied on top of the global sync lead.
+        /// </summary>
+        public int AvOffsetMs { get; set; } = 0;
+
+        /// <summary>
+        /// Optional path to a 3D LUT (<c>.cube</c>) applied to every LED color before sending, e.g. from a camera
+        /// calibration of this strip. Empty = no LUT.
+        /// </summary>
+        public string LutPath { get; set; } = string.Empty;
+
+        /// <summary>
+        /// Optional path to a per-LED gain map for this strip (see <see cref="Services.AmbilightLedCalibration"/>),
+        /// applied to the physical LEDs right before sending. Empty = no calibration.
+        /// </summary>
+        public string CalibrationPath { get; set; } = string.Empty;
+
+        /// <summary>
+        /// Sends RGB binaries to an RGBW strip by deriving the white channel at playback: "off", "min" (common minimum),
+        /// "luma" (gray component's share of luminance) or "calibrated" (uses <see cref="RgbwWhitePoint"/>).
+        /// Ignored for binaries extracted as RGBW.
+        /// </summary>
+        public string RgbwConversion { get; set; } = "off";
+
+        /// <summary>
+        /// Color of the strip's white LED as "r,g,b" (0–255), e.g. "255,200,150" for warm white. Used by the "calibrated" conversion.
+        /// </summary>
+        public string RgbwWhitePoint { get; set; } = "255,255,255";
+
+        /// <summary>
+        /// Unlit LEDs hidden at the corners, kept dark instead of stretching the picture into them: one number for
+        /// every corner, or "top-right,bottom-right,bottom-left,top-left" in strip order starting from the top side. Empty = none.
+        /// </summary>
+        public string CornerGapLeds { get; set; } = string.Empty;
+
+        /// <summary>
+        /// First strip LED this mapping plays onto, so the picture covers only a segment of a longer strip and the
+        /// LEDs before it keep their own effect. The segment is as long as the mapping's LEDs. WLED realtime ("drgb") only.
+        /// </summary>
+        public int SegmentStart { get; set; }
+
+        /// <summary>
+        /// Strip runs counter-clockwise (top right to left, then down the left side) when seen from the front.
+        /// </summary>
+        public bool Reverse { get; set; }
+
+        /// <summary>
+        /// Per-device color profile, overriding the global Lightning Tuning values for this mapping only; null = use the global value.
+        /// Live overrides from the Playback/Set endpoint still take precedence.
+        /// </summary>
+        public double? BrightnessTarget { get; set; }
+
+        public double? Gamma { get; set; }
+
+        public double? Saturation { get; set; }
+
+        public double? ColorTemperatureK { get; set; }
+    }
+
+    public class LibraryColorMode
+    {
+        public string LibraryId { get; set; } = string.Empty;
+
+        public string ColorMode { get; set; } = "edge";
+    }
+
+    public class ExtractionWhiteBalance
+    {
+        /// <summary>
+        /// Library the gains apply to. Empty = every library without its own entry.
+        /// </summary>
+        public string LibraryId { get; set; } = string.Empty;
+
+        // Per-side "r,g,b" multipliers, e.g. "1,0.94,1" to pull back a green cast.
+        public string TopGains { get; set; } = "1,1,1";
+        public string RightGains { get; set; } = "1,1,1";
+        public string BottomGains { get; set; } = "1,1,1";
+        public string LeftGains { get; set; } = "1,1,1";
     }
 }
@@ -16,6 +16,7 @@ using Jellyfin.Plugin.Ambilight.Services;
 using MediaBrowser.Common.Configuration;
 using MediaBrowser.Controller.Library;
 using MediaBrowser.Controller.Session;
+using MediaBrowser.Model.Plugins;
 using MediaBrowser.Model.Session;
 using Microsoft.Extensions.Hosting;
 using Microsoft.Extensions.Logging;
@@ -39,6 +40,7 @@ public class AmbilightEntryPoint : IHostedService
 
     private AmbilightStorageService? _storage;
     private AmbilightExtractorService? _extractor;
+    private AmbilightInProcessExtractor? _extractorCore;
     private AmbilightPlaybackService? _playback;
 
     private CancellationTokenSource? _cts;
@@ -47,6 +49,12 @@ public class AmbilightEntryPoint : IHostedService
     
     public AmbilightStorageService? Storage => _storage;
 
+    public AmbilightPlaybackService? Playback => _playback;
+
+    public AmbilightInProcessExtractor? ExtractorCore => _extractorCore;
+
+    public AmbilightExtractorService? Extractor => _extractor;
+
     public AmbilightEntryPoint(
         ILogger<AmbilightEntryPoint> logger,
         ILibraryManager libraryManager,
@@ -74,8 +82,9 @@ public class AmbilightEntryPoint : IHostedService
         var extractorCoreLogger = loggerFactory.CreateLogger<AmbilightInProcessExtractor>();
 
         _storage = new AmbilightStorageService(storageLogger, _config);
-        var extractorCore = new AmbilightInProcessExtractor(extractorCoreLogger, _config);
-        _extractor = new AmbilightExtractorService(extractorLogger, _libraryManager, _storage, _config, extractorCore);
+        var binaries = new EmbeddedBinariesResolver(_appPaths, _config, extractorCoreLogger);
+        _extractorCore = new AmbilightInProcessExtractor(extractorCoreLogger, _config, binaries);
+        _extractor = new AmbilightExtractorService(extractorLogger, _libraryManager, _storage, _config, _extractorCore);
         _playback = new AmbilightPlaybackService(playbackLogger, _sessionManager, _libraryManager, _storage, _config);
 
         _cts = new CancellationTokenSource();
@@ -84,6 +93,13 @@ public class AmbilightEntryPoint : IHostedService
         _sessionManager.PlaybackStart += OnPlaybackStart;
         _sessionManager.PlaybackStopped += OnPlaybackStopped;
         _sessionManager.PlaybackProgress += OnPlaybackProgress;
+        _sessionManager.SessionEnded += OnSessionEnded;
+
+        // Saved device mapping changes apply to running sessions without stopping playback
+        if (Plugin.Instance != null)
+        {
+            Plugin.Instance.ConfigurationChanged += OnConfigurationChanged;
+        }
 
         // Subscribe to library scan events instead of polling
         _libraryManager.ItemAdded += OnItemAdded;
@@ -105,6 +121,11 @@ public class AmbilightEntryPoint : IHostedService
         _sessionManager.PlaybackStart -= OnPlaybackStart;
         _sessionManager.PlaybackStopped -= OnPlaybackStopped;
         _sessionManager.PlaybackProgress -= OnPlaybackProgress;
+        _sessionManager.SessionEnded -= OnSessionEnded;
+        if (Plugin.Instance != null)
+        {
+            Plugin.Instance.ConfigurationChanged -= OnConfigurationChanged;
+        }
         
         _libraryManager.ItemAdded -= OnItemAdded;
         _libraryManager.ItemUpdated -= OnItemUpdated;
@@ -162,8 +183,8 @@ public class AmbilightEntryPoint : IHostedService
 
             _storage.SaveOrUpdateItem(ambiItem);
 
-            // Run extraction
-            await _extractor.RunExtractorForItemAsync(ambiItem, _cts?.Token ?? CancellationToken.None);
+            // Run extraction; asked for by hand, so an up-to-date binary is extracted again too.
+            await _extractor.RunExtractorForItemAsync(ambiItem, _cts?.Token ?? CancellationToken.None, force: true);
         }
         catch (Exception ex)
         {
@@ -237,8 +258,8 @@ public class AmbilightEntryPoint : IHostedService
                     _storage.SaveOrUpdateItem(ambiItem);
                 }
                 
-                // Check if extraction is needed (binary doesn't exist)
-                if (!_storage.BinaryExists(itemIdStr))
+                // Check if extraction is needed (binary missing, corrupt or partially copied)
+                if (!_storage.BinaryIsValid(itemIdStr))
                 {
                     if (_config.Debug)
                     {
@@ -284,7 +305,7 @@ public class AmbilightEntryPoint : IHostedService
             var binPath = _storage.GetBinaryPath(itemIdStr);
             if (File.Exists(binPath))
             {
-                File.Delete(binPath);
+                _storage.DeleteBinary(itemIdStr);
                 _logger.LogInformation("[Ambilight] Deleted binary file for removed item: {ItemName} ({Path})", itemName, binPath);
             }
             else
@@ -360,6 +381,42 @@ public class AmbilightEntryPoint : IHostedService
         _playback.OnPlaybackProgress(e.Session, info);
     }
 
+    private void OnSessionEnded(object? sender, SessionEventArgs e)
+    {
+        if (_playback == null || e.SessionInfo == null)
+        {
+            return;
+        }
+
+        _playback.OnSessionEnded(e.SessionInfo.Id);
+    }
+
+    private void OnConfigurationChanged(object? sender, BasePluginConfiguration e)
+    {
+        var playback = _playback;
+        if (playback == null)
+        {
+            return;
+        }
+
+        // Off the save request: stopping a removed target waits for its player to exit.
+        _ = Task.Run(() =>
+        {
+            try
+            {
+                int changed = playback.ReloadConfiguration();
+                if (changed > 0)
+                {
+                    _logger.LogInformation("[Ambilight] Configuration reloaded; {Count} player(s) updated", changed);
+                }
+            }
+            catch (Exception ex)
+            {
+                _logger.LogError(ex, "[Ambilight] Failed to apply the saved configuration to running players");
+            }
+        });
+    }
+
     /// <summary>
     /// Gets the library ID for an item by walking up the parent chain.
     /// Returns the ID in "N" format (without dashes) for consistent comparison.
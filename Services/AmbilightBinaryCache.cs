@@ -0,0 +1,1132 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (C) 2026 Jellyfin Ambilight Contributors
+// This file is part of Jellyfin Ambilight Plugin.
+// Jellyfin Ambilight Plugin is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+using System;
+using System.Buffers.Binary;
+using System.Collections.Generic;
+using System.IO;
+using System.IO.MemoryMappedFiles;
+using System.Linq;
+using System.Text;
+using System.Threading;
+using System.Threading.Tasks;
+using Microsoft.Extensions.Logging;
+using ZstdSharp;
+
+namespace Jellyfin.Plugin.Ambilight.Services;
+
+/// <summary>
+/// Keeps recently used AMb2 and AMb3 binaries parsed in memory so a resident player can switch items
+/// without re-reading the file, and so the next episode can be loaded ahead of time.
+/// Entries are keyed by path and invalidated when the file's size or write time changes. Remote binaries have neither
+/// to check, so they are loaded afresh every time instead of cached.
+/// </summary>
+public sealed class AmbilightBinaryCache
+{
+    // Current item plus one pre-cached item; a feature film is tens of MB once parsed.
+    private const int MaxEntries = 2;
+
+    private readonly ILogger _logger;
+    private readonly object _lock = new();
+    private readonly Dictionary<string, CacheEntry> _entries = new(StringComparer.Ordinal);
+
+    public AmbilightBinaryCache(ILogger logger)
+    {
+        _logger = logger;
+    }
+
+    /// <summary>
+    /// Returns the parsed binary, loading it (or joining an in-flight pre-cache) when needed.
+    /// Throws <see cref="InvalidDataException"/> for files that are not valid AMb2 or AMb3.
+    /// </summary>
+    public async Task<AmbilightBinary> GetAsync(string path, CancellationToken cancellationToken)
+    {
+        if (AmbilightHttpRangeStream.IsRemote(path))
+        {
+            return await Task.Run(() => AmbilightBinary.Load(path, cancellationToken), cancellationToken).ConfigureAwait(false);
+        }
+
+        var load = GetOrStartLoad(path);
+        try
+        {
+            return await load.WaitAsync(cancellationToken).ConfigureAwait(false);
+        }
+        catch (Exception ex) when (ex is not OperationCanceledException)
+        {
+            Invalidate(path);
+            throw;
+        }
+    }
+
+    /// <summary>
+    /// Starts loading <paramref name="path"/> in the background if it is not cached yet.
+    /// </summary>
+    public void Precache(string path)
+    {
+        if (Plugin.Instance?.Configuration?.StreamBinaries == true)
+        {
+            // Streaming players read from the file as they go; loading it whole here would defeat the point.
+            return;
+        }
+
+        if (AmbilightHttpRangeStream.IsRemote(path) || !File.Exists(path))
+        {
+            return;
+        }
+
+        var load = GetOrStartLoad(path);
+        _ = load.ContinueWith(
+            t =>
+            {
+                if (t.IsFaulted)
+                {
+                    _logger.LogWarning(t.Exception?.GetBaseException(), "[Ambilight] Pre-cache failed for {Path}", path);
+                    Invalidate(path);
+                }
+                else
+                {
+                    _logger.LogDebug("[Ambilight] Pre-cached {Path} ({Frames} frames)", path, t.Result.FrameCount);
+                }
+            },
+            TaskScheduler.Default);
+    }
+
+    public void Invalidate(string path)
+    {
+        lock (_lock)
+        {
+            _entries.Remove(path);
+        }
+    }
+
+    private Task<AmbilightBinary> GetOrStartLoad(string path)
+    {
+        var info = new FileInfo(path);
+        var length = info.Exists ? info.Length : -1;
+        var writeTime = info.Exists ? info.LastWriteTimeUtc : DateTime.MinValue;
+
+        lock (_lock)
+        {
+            if (_entries.TryGetValue(path, out var entry) && entry.Length == length && entry.WriteTimeUtc == writeTime)
+            {
+                entry.LastUsedUtc = DateTime.UtcNow;
+                return entry.Load;
+            }
+
+            while (_entries.Count >= MaxEntries)
+            {
+                var oldest = _entries.OrderBy(e => e.Value.LastUsedUtc).First().Key;
+                _entries.Remove(oldest);
+            }
+
+            entry = new CacheEntry
+            {
+                Length = length,
+                WriteTimeUtc = writeTime,
+                LastUsedUtc = DateTime.UtcNow,
+                Load = Task.Run(() => AmbilightBinary.Load(path, CancellationToken.None))
+            };
+            _entries[path] = entry;
+            return entry.Load;
+        }
+    }
+
+    private sealed class CacheEntry
+    {
+        public long Length { get; init; }
+        public DateTime WriteTimeUtc { get; init; }
+        public DateTime LastUsedUtc { get; set; }
+        public Task<AmbilightBinary> Load { get; init; } = null!;
+    }
+}
+
+/// <summary>
+/// An AMb2 or AMb3 file parsed into memory: header fields plus per-frame timestamps and payloads.
+/// AMb3 is AMb2 (same header but for the magic, same records) followed by a seek index, see <see cref="IndexStride"/>;
+/// its records may be stored zstd-compressed, see <see cref="IndexFlagZstd"/>.
+/// Payload formats: 0 = RGB, 1 = RGBW (8 bits per channel), 2 = RGB16, 3 = RGBW16 (little-endian u16 per channel).
+/// The format byte's upper bits say how the corners were extracted (see <see cref="Corners"/>) and whether timestamps
+/// carry frame info (see <see cref="FlagFrameInfo"/>); 0 for older files.
+/// Binaries opened with <see cref="OpenStreaming"/> read payloads from the file on demand instead (local files are
+/// memory-mapped and indexed by offset); those hold the file open until disposed and are meant for a single reader.
+/// </summary>
+public sealed class AmbilightBinary : IDisposable
+{
+    public const byte FormatRgb = 0;
+    public const byte FormatRgbw = 1;
+    public const byte FormatRgb16 = 2;
+    public const byte FormatRgbw16 = 3;
+
+    /// <summary>Corners (format byte bits 4-5): the ends of adjacent sides both cover the corner.</summary>
+    public const byte CornersShared = 0;
+
+    /// <summary>
+    /// Corners (format byte bits 4-5): the sides stop short of the corners, which have zones of their own after the
+    /// four sides (top-right, bottom-right, bottom-left, top-left); bits 6-7 hold the zones per corner minus one.
+    /// </summary>
+    public const byte CornersDedicated = 1;
+
+    /// <summary>Corners (format byte bits 4-5): the two LEDs meeting at a corner carry the average of their colors.</summary>
+    public const byte CornersBlended = 2;
+
+    /// <summary>Most zones a dedicated corner can have (bits 6-7 of the format byte).</summary>
+    public const int MaxCornerZones = 4;
+
+    /// <summary>
+    /// Format byte bit 3: the upper 24 bits of every record's timestamp hold frame info (see <see cref="FrameInfo"/>),
+    /// leaving <see cref="TimestampBits"/> bits, about 12 days, for the microseconds.
+    /// </summary>
+    public const byte FlagFrameInfo = 0x08;
+
+    public const int TimestampBits = 40;
+    private const ulong TimestampMask = (1UL << TimestampBits) - 1;
+
+    /// <summary>Frame info flag (info bit 16, timestamp bit 56): the frame is the first of a new shot.</summary>
+    public const uint FrameSceneCut = 1u << 16;
+
+    /// <summary>Frame info flag (info bit 17): info bits 0-7 hold the frame's mean BT.709 luma (0-255).</summary>
+    public const uint FrameLuminance = 1u << 17;
+
+    /// <summary>
+    /// Frame info flag (info bit 18): info bits 8-15 hold the audio energy around the frame (0-255, 255 at the film's
+    /// loud moments).
+    /// </summary>
+    public const uint FrameAudioEnergy = 1u << 18;
+
+    /// <summary>Frame info flag (info bit 19): the frame is part of the end credits.</summary>
+    public const uint FrameCredits = 1u << 19;
+
+    /// <summary>Magic (4) + fps (4) + four side counts (8) + format (1).</summary>
+    public const int HeaderSize = 17;
+
+    /// <summary>
+    /// AMb3 seek index: after the last record, one entry (timestamp µs, byte offset of the record; two u64) for every
+    /// <see cref="IndexStride"/>th frame from frame 0, then the <see cref="IndexFooterSize"/>-byte footer.
+    /// </summary>
+    public const int IndexStride = 256;
+
+    /// <summary>Index offset (8) + entry count (4) + stride (4) + flags (4) + "AMbI" (4).</summary>
+    public const int IndexFooterSize = 24;
+
+    /// <summary>
+    /// AMb3 footer flag: the records are stored as one zstd frame per index entry, each holding that entry's
+    /// <see cref="IndexStride"/> records (fewer for the last) as they would be stored uncompressed. Index offsets
+    /// point at the frames.
+    /// </summary>
+    public const uint IndexFlagZstd = 1;
+
+    // Sanity limit for the header's LED counts. Far more than any strip a realtime protocol can drive at video frame
+    // rates, so anything above it is a corrupt or foreign header rather than a real layout.
+    private const int MaxLedCount = 16384;
+
+    public string Path { get; private init; } = string.Empty;
+
+    /// <summary>2 or 3, from the magic.</summary>
+    public int Version { get; private init; } = 2;
+
+    /// <summary>True for AMb3 files whose seek index was read; seeks then look at one stride of frames.</summary>
+    public bool HasIndex => _indexTimestamps != null;
+
+    /// <summary>True for AMb3 files with zstd-compressed records (see <see cref="IndexFlagZstd"/>).</summary>
+    public bool IsCompressed { get; private set; }
+    public double Fps { get; private set; }
+    public int TopCount { get; private init; }
+    public int BottomCount { get; private init; }
+    public int LeftCount { get; private init; }
+    public int RightCount { get; private init; }
+    public byte Format { get; private init; }
+    public byte Corners { get; private init; }
+    public bool HasFrameInfo { get; private init; }
+
+    /// <summary>Zones per corner stored after the sides; 0 unless <see cref="Corners"/> is <see cref="CornersDedicated"/>.</summary>
+    public int CornerZones { get; private init; }
+    public bool Rgbw => Format == FormatRgbw || Format == FormatRgbw16;
+    public bool Is16Bit => Format == FormatRgb16 || Format == FormatRgbw16;
+    public int Channels => Rgbw ? 4 : 3;
+    public int BytesPerLed => Channels * (Is16Bit ? 2 : 1);
+    public int SideLedCount => TopCount + BottomCount + LeftCount + RightCount;
+    public int LedCount => SideLedCount + (4 * CornerZones);
+    public int FrameSize => LedCount * BytesPerLed;
+    public List<ulong> TimestampsUs { get; } = new();
+    private readonly List<uint> _frameInfo = new();
+    public List<byte[]> Frames { get; } = new();
+    public int FrameCount => IsStreaming ? _recordCount : Frames.Count;
+
+    /// <summary>
+    /// Bytes after the last whole frame, dropped when the file was read. Non-zero means the file was cut off mid-frame
+    /// (interrupted extraction or copy) and playback ends early.
+    /// </summary>
+    public long TrailingBytes { get; private set; }
+
+    /// <summary>True for binaries opened with <see cref="OpenStreaming"/>.</summary>
+    public bool IsStreaming => _source != null || _view != null;
+
+    // Streaming: records have a fixed size, so frame i's timestamp and payload are read on demand at
+    // HeaderSize + i * (8 + FrameSize) and nothing but the header is read up front. Local files are memory-mapped
+    // (the OS pages data in and out); remote ones are read through _source.
+    private int _recordCount;
+    private MemoryMappedFile? _map;
+    private MemoryMappedViewAccessor? _view;
+    private byte[] _payload = Array.Empty<byte>();
+
+    // Remote streaming reads a block of records at a time (about ReadAheadBytes), so sequential playback costs one
+    // read per block and a seek costs one read.
+    private const int ReadAheadBytes = 1024 * 1024;
+    private Stream? _source;
+    private byte[] _readAhead = Array.Empty<byte>();
+    private int _readAheadFirst = -1;
+    private int _readAheadCount;
+
+    // AMb3 seek index (null for AMb2 files and AMb3 files whose index is missing), and where the records end.
+    private ulong[]? _indexTimestamps;
+    private long[]? _indexOffsets;
+    private long _bodyEnd;
+
+    // Compressed files: the number of records (the last block has to be unpacked to know it), and the unpacked block
+    // that streaming reads last came from.
+    private long _compressedRecords;
+    private Decompressor? _decompressor;
+    private byte[] _block = Array.Empty<byte>();
+    private int _blockIndex = -1;
+
+    /// <summary>
+    /// Index of the first frame at or after <paramref name="seconds"/>, clamped to the last frame.
+    /// </summary>
+    public int FindFrameIndex(double seconds)
+    {
+        var targetUs = (ulong)(Math.Max(0.0, seconds) * 1_000_000.0);
+        if (_indexTimestamps != null)
+        {
+            // AMb3: the last index entry at or before the target brackets the frame to one stride.
+            int entry = Array.BinarySearch(_indexTimestamps, targetUs);
+            entry = entry >= 0 ? entry : Math.Max(0, ~entry - 1);
+            int lo = entry * IndexStride;
+            int hi = Math.Min(FrameCount, lo + IndexStride);
+            while (lo < hi)
+            {
+                int mid = lo + ((hi - lo) / 2);
+                if (TimestampUs(mid) < targetUs)
+                {
+                    lo = mid + 1;
+                }
+                else
+                {
+                    hi = mid;
+                }
+            }
+
+            return Math.Min(lo, FrameCount - 1);
+        }
+
+        if (IsStreaming)
+        {
+            // Timestamps are ascending. Start where the frame rate puts the target and widen until it is bracketed, so a
+            // seek reads one or two blocks near the target instead of the whole file.
+            int guess = (int)Math.Clamp(seconds * Fps, 0.0, _recordCount - 1);
+            int lo = guess;
+            int hi = guess + 1;
+            int step = 1;
+            while (lo > 0 && TimestampUs(lo) >= targetUs)
+            {
+                hi = lo;
+                lo = Math.Max(0, lo - step);
+                step *= 2;
+            }
+
+            step = 1;
+            while (hi < _recordCount && TimestampUs(hi) < targetUs)
+            {
+                lo = hi + 1;
+                hi = Math.Min(_recordCount, hi + step);
+                step *= 2;
+            }
+
+            while (lo < hi)
+            {
+                int mid = lo + ((hi - lo) / 2);
+                if (TimestampUs(mid) < targetUs)
+                {
+                    lo = mid + 1;
+                }
+                else
+                {
+                    hi = mid;
+                }
+            }
+
+            return Math.Min(lo, FrameCount - 1);
+        }
+
+        int index = 0;
+        while (index < TimestampsUs.Count && TimestampsUs[index] < targetUs)
+        {
+            index++;
+        }
+
+        return Math.Min(index, FrameCount - 1);
+    }
+
+    /// <summary>
+    /// Presentation time of frame <paramref name="index"/> in microseconds.
+    /// </summary>
+    public ulong TimestampUs(int index)
+    {
+        if (!IsStreaming)
+        {
+            return TimestampsUs[index];
+        }
+
+        return HasFrameInfo ? RawTimestamp(index) & TimestampMask : RawTimestamp(index);
+    }
+
+    /// <summary>
+    /// The extractor's notes on frame <paramref name="index"/>, 0 for files without them: flags such as
+    /// <see cref="FrameSceneCut"/> in bits 16-23.
+    /// </summary>
+    public uint FrameInfo(int index)
+    {
+        if (!HasFrameInfo)
+        {
+            return 0;
+        }
+
+        return IsStreaming ? (uint)(RawTimestamp(index) >> TimestampBits) : _frameInfo[index];
+    }
+
+    /// <summary>Whether the extractor flagged a scene cut at frame <paramref name="index"/>.</summary>
+    public bool IsSceneCut(int index) => (FrameInfo(index) & FrameSceneCut) != 0;
+
+    /// <summary>Whether the extractor found frame <paramref name="index"/> to be part of the end credits.</summary>
+    public bool IsCredits(int index) => (FrameInfo(index) & FrameCredits) != 0;
+
+    /// <summary>The mean luma of frame <paramref name="index"/> stored by the extractor, when there is one.</summary>
+    public bool TryGetLuminance(int index, out float luminance)
+    {
+        uint info = FrameInfo(index);
+        luminance = info & 0xFF;
+        return (info & FrameLuminance) != 0;
+    }
+
+    /// <summary>The audio energy around frame <paramref name="index"/> stored by the extractor, when there is one.</summary>
+    public bool TryGetAudioEnergy(int index, out float energy)
+    {
+        uint info = FrameInfo(index);
+        energy = (info >> 8) & 0xFF;
+        return (info & FrameAudioEnergy) != 0;
+    }
+
+    /// <summary>
+    /// Whether frame <paramref name="index"/> can be read without waiting on the network. Always true but for remote
+    /// streaming, where false means its block is being fetched in the background; ask again later.
+    /// </summary>
+    public bool IsFrameReady(int index)
+    {
+        if (_source is not AmbilightHttpRangeStream remote)
+        {
+            return true;
+        }
+
+        if (IsCompressed)
+        {
+            int block = index / IndexStride;
+            if (block == _blockIndex)
+            {
+                return true;
+            }
+
+            long start = _indexOffsets![block];
+            long end = block + 1 < _indexOffsets.Length ? _indexOffsets[block + 1] : _bodyEnd;
+            return remote.IsBuffered(start, (int)(end - start));
+        }
+
+        if (index >= _readAheadFirst && index < _readAheadFirst + _readAheadCount)
+        {
+            return true;
+        }
+
+        return remote.IsBuffered(RecordOffset(index), ReadAheadRecords(index) * (8 + FrameSize));
+    }
+
+    private ulong RawTimestamp(int index) => IsCompressed
+        ? BinaryPrimitives.ReadUInt64LittleEndian(ReadCompressedRecord(index))
+        : _view != null
+        ? _view.ReadUInt64(RecordOffset(index))
+        : BinaryPrimitives.ReadUInt64LittleEndian(ReadRecord(index));
+
+    /// <summary>
+    /// Decodes frame <paramref name="index"/> into <paramref name="dest"/> as <see cref="LedCount"/> x <see cref="Channels"/>
+    /// values on a 0–255 scale (fractional for 16-bit payloads).
+    /// </summary>
+    public void DecodeFrame(int index, float[] dest)
+    {
+        ReadOnlySpan<byte> payload = !IsStreaming ? Frames[index]
+            : IsCompressed ? ReadCompressedRecord(index).Slice(8, FrameSize)
+            : _view != null ? ReadMappedPayload(index)
+            : ReadRecord(index).Slice(8, FrameSize);
+        int values = Math.Min(dest.Length, LedCount * Channels);
+        if (Is16Bit)
+        {
+            for (int i = 0; i < values; i++)
+            {
+                dest[i] = BinaryPrimitives.ReadUInt16LittleEndian(payload.Slice(i * 2, 2)) / 257.0f;
+            }
+        }
+        else
+        {
+            for (int i = 0; i < values; i++)
+            {
+                dest[i] = payload[i];
+            }
+        }
+    }
+
+    /// <summary>
+    /// Cheap integrity check that reads only the header: known format and a file length that is a whole
+    /// number of frames. Catches truncated or partially copied files without a checksum.
+    /// </summary>
+    public static bool HasValidLayout(string path) => Validate(path, out _) == AmbilightBinaryError.None;
+
+    /// <summary>
+    /// Checks the header, file length and (for AMb3) seek index of a local file and says exactly what is wrong with it,
+    /// for logs and the status API: <paramref name="message"/> describes the problem (empty when the file is valid).
+    /// </summary>
+    public static AmbilightBinaryError Validate(string path, out string message)
+    {
+        try
+        {
+            using var fs = File.OpenRead(path);
+            using var reader = new BinaryReader(fs);
+            var layout = ReadHeader(reader, path);
+            layout.ReadIndex(reader);
+
+            long body = layout._bodyEnd - HeaderSize;
+            long record = 8L + layout.FrameSize;
+            if (layout.IsCompressed ? layout._compressedRecords == 0 : body == 0)
+            {
+                message = $"{path} has a header but no frames";
+                return AmbilightBinaryError.NoFrames;
+            }
+
+            if (!layout.IsCompressed && body % record != 0)
+            {
+                message = $"{path} has {body} bytes of frames, not a whole number of {record}-byte frames for {layout.LedCount} LEDs "
+                    + $"in format {layout.Format} ({body / record} frames and {body % record} bytes left over)";
+                return AmbilightBinaryError.FrameSizeMismatch;
+            }
+
+            if (layout.Version == 3 && !layout.HasIndex)
+            {
+                message = $"{path} is an AMb3 file without a readable seek index at its end";
+                return AmbilightBinaryError.MissingIndex;
+            }
+
+            message = string.Empty;
+            return AmbilightBinaryError.None;
+        }
+        catch (AmbilightBinaryFormatException ex)
+        {
+            message = ex.Message;
+            return ex.Error;
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException)
+        {
+            message = $"Cannot read {path}: {ex.Message}";
+            return AmbilightBinaryError.Unreadable;
+        }
+    }
+
+    /// <summary>
+    /// Reads how much of the timeline a local AMb2 or AMb3 file covers without loading it: the number of whole frames, the
+    /// timestamp of the last one and the bytes left over after it. Returns false when the header is invalid or
+    /// there is no whole frame.
+    /// </summary>
+    public static bool TryReadExtent(string path, out int frameCount, out double lastFrameSeconds, out long trailingBytes)
+    {
+        frameCount = 0;
+        lastFrameSeconds = 0.0;
+        trailingBytes = 0;
+        try
+        {
+            using var fs = File.OpenRead(path);
+            using var reader = new BinaryReader(fs);
+            var layout = ReadHeader(reader, path);
+            layout.ReadIndex(reader);
+            long record = 8L + layout.FrameSize;
+            long records = layout.IsCompressed ? layout._compressedRecords : (layout._bodyEnd - HeaderSize) / record;
+            if (records == 0)
+            {
+                return false;
+            }
+
+            frameCount = (int)Math.Min(records, int.MaxValue);
+            ulong last;
+            if (layout.IsCompressed)
+            {
+                // The last block is still unpacked from reading the index.
+                last = BinaryPrimitives.ReadUInt64LittleEndian(layout._block.AsSpan((int)((records - 1) % IndexStride * record), 8));
+            }
+            else
+            {
+                fs.Position = HeaderSize + ((records - 1) * record);
+                last = reader.ReadUInt64();
+                trailingBytes = (layout._bodyEnd - HeaderSize) % record;
+            }
+
+            lastFrameSeconds = (last & (layout.HasFrameInfo ? TimestampMask : ulong.MaxValue)) / 1_000_000.0;
+            return true;
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException or InvalidDataException)
+        {
+            return false;
+        }
+    }
+
+    /// <summary>
+    /// Reads and validates an AMb2 or AMb3 file, local or an <c>http(s)://</c> URL (read with range requests).
+    /// For AMb3 the footer's flags say how the records are laid out: plain, or zstd blocks (<see cref="IndexFlagZstd"/>).
+    /// A truncated final frame is dropped.
+    /// </summary>
+    public static AmbilightBinary Load(string path, CancellationToken cancellationToken)
+    {
+        using Stream fs = OpenSource(path);
+        using var reader = new BinaryReader(fs);
+        var binary = ReadHeader(reader, path);
+        binary.ReadIndex(reader);
+
+        var frameSize = binary.FrameSize;
+        if (binary.IsCompressed)
+        {
+            int record = 8 + frameSize;
+            for (int block = 0; block < binary._indexOffsets!.Length && !cancellationToken.IsCancellationRequested; block++)
+            {
+                int length = binary.UnpackBlock(fs, block);
+                for (int offset = 0; offset + record <= length; offset += record)
+                {
+                    binary.AddFrame(BinaryPrimitives.ReadUInt64LittleEndian(binary._block.AsSpan(offset, 8)), binary._block.AsSpan(offset + 8, frameSize).ToArray());
+                }
+            }
+
+            binary.DisposeDecompressor();
+            binary.DeriveFps();
+            return binary;
+        }
+
+        while (fs.Position < binary._bodyEnd && !cancellationToken.IsCancellationRequested)
+        {
+            if (binary._bodyEnd - fs.Position < 8L + frameSize)
+            {
+                binary.TrailingBytes = binary._bodyEnd - fs.Position;
+                break;
+            }
+
+            var tsBytes = reader.ReadBytes(8);
+            binary.AddFrame(BitConverter.ToUInt64(tsBytes, 0), reader.ReadBytes(frameSize));
+        }
+
+        binary.DeriveFps();
+        return binary;
+    }
+
+    private void AddFrame(ulong timestamp, byte[] payload)
+    {
+        if (HasFrameInfo)
+        {
+            _frameInfo.Add((uint)(timestamp >> TimestampBits));
+            timestamp &= TimestampMask;
+        }
+
+        TimestampsUs.Add(timestamp);
+        Frames.Add(payload);
+    }
+
+    /// <summary>
+    /// Opens an AMb2 or AMb3 file, local or remote (read with range requests), for streaming: reads only the header and
+    /// leaves the frames in the file, so opening is instant and memory use no longer grows with the length of the
+    /// item. A truncated final frame is dropped. Dispose the binary to close the file.
+    /// </summary>
+    public static AmbilightBinary OpenStreaming(string path, CancellationToken cancellationToken)
+    {
+        if (!AmbilightHttpRangeStream.IsRemote(path))
+        {
+            return OpenMapped(path);
+        }
+
+        var fs = OpenSource(path);
+        try
+        {
+            AmbilightBinary binary;
+            using (var reader = new BinaryReader(fs, Encoding.UTF8, leaveOpen: true))
+            {
+                binary = ReadHeader(reader, path);
+                binary.ReadIndex(reader);
+            }
+
+            cancellationToken.ThrowIfCancellationRequested();
+            binary._recordCount = (int)Math.Min(binary.IsCompressed ? binary._compressedRecords : (binary._bodyEnd - HeaderSize) / (8L + binary.FrameSize), int.MaxValue);
+            binary.TrailingBytes = binary.IsCompressed ? 0 : (binary._bodyEnd - HeaderSize) % (8L + binary.FrameSize);
+            binary._source = fs;
+            binary.DeriveFps();
+            return binary;
+        }
+        catch
+        {
+            fs.Dispose();
+            throw;
+        }
+    }
+
+    public void Dispose()
+    {
+        _source?.Dispose();
+        _source = null;
+        _view?.Dispose();
+        _view = null;
+        _map?.Dispose();
+        _map = null;
+        DisposeDecompressor();
+    }
+
+    private void DisposeDecompressor()
+    {
+        _decompressor?.Dispose();
+        _decompressor = null;
+    }
+
+    private static AmbilightBinary OpenMapped(string path)
+    {
+        var fs = File.OpenRead(path);
+        try
+        {
+            AmbilightBinary binary;
+            using (var reader = new BinaryReader(fs, Encoding.UTF8, leaveOpen: true))
+            {
+                binary = ReadHeader(reader, path);
+                binary.ReadIndex(reader);
+            }
+
+            long count = binary.IsCompressed ? binary._compressedRecords : (binary._bodyEnd - HeaderSize) / (8L + binary.FrameSize);
+            binary._recordCount = (int)Math.Min(count, int.MaxValue);
+            binary.TrailingBytes = binary.IsCompressed ? 0 : (binary._bodyEnd - HeaderSize) % (8L + binary.FrameSize);
+            if (binary._recordCount > 0)
+            {
+                binary._map = MemoryMappedFile.CreateFromFile(fs, null, 0, MemoryMappedFileAccess.Read, HandleInheritability.None, leaveOpen: false);
+                binary._view = binary._map.CreateViewAccessor(0, 0, MemoryMappedFileAccess.Read);
+            }
+            else
+            {
+                fs.Dispose();
+            }
+
+            binary.DeriveFps();
+            return binary;
+        }
+        catch
+        {
+            fs.Dispose();
+            throw;
+        }
+    }
+
+    private static Stream OpenSource(string path)
+    {
+        return AmbilightHttpRangeStream.IsRemote(path)
+            ? AmbilightHttpRangeStream.Open(path, Plugin.Instance?.Configuration?.RemoteBinaryAuthorization)
+            : File.OpenRead(path);
+    }
+
+    /// <summary>
+    /// Reads and checks the 17-byte header. Throws <see cref="AmbilightBinaryFormatException"/> saying what is wrong.
+    /// </summary>
+    private static AmbilightBinary ReadHeader(BinaryReader reader, string path)
+    {
+        var header = reader.ReadBytes(HeaderSize);
+        if (header.Length >= 4 && !header.AsSpan(0, 4).SequenceEqual("AMb2"u8) && !header.AsSpan(0, 4).SequenceEqual("AMb3"u8))
+        {
+            throw new AmbilightBinaryFormatException(
+                AmbilightBinaryError.BadMagic,
+                $"{path} is not an AMb2 or AMb3 file (starts with {Convert.ToHexString(header, 0, 4)}, expected 414D6232 \"AMb2\" or 414D6233 \"AMb3\")");
+        }
+
+        if (header.Length < HeaderSize)
+        {
+            throw new AmbilightBinaryFormatException(
+                AmbilightBinaryError.TruncatedHeader,
+                $"{path} ends after {header.Length} bytes, inside the {HeaderSize}-byte AMb2 header");
+        }
+
+        double fps = BinaryPrimitives.ReadSingleLittleEndian(header.AsSpan(4, 4));
+        if (double.IsNaN(fps) || fps <= 0.001 || fps > 300.0)
+        {
+            fps = 0.0;
+        }
+
+        byte packed = header[16];
+        byte corners = (byte)((packed >> 4) & 0x03);
+        var binary = new AmbilightBinary
+        {
+            Path = path,
+            Version = header[3] == (byte)'3' ? 3 : 2,
+            TopCount = BinaryPrimitives.ReadUInt16LittleEndian(header.AsSpan(8, 2)),
+            BottomCount = BinaryPrimitives.ReadUInt16LittleEndian(header.AsSpan(10, 2)),
+            LeftCount = BinaryPrimitives.ReadUInt16LittleEndian(header.AsSpan(12, 2)),
+            RightCount = BinaryPrimitives.ReadUInt16LittleEndian(header.AsSpan(14, 2)),
+            Format = (byte)(packed & 0x07),
+            Corners = corners,
+            HasFrameInfo = (packed & FlagFrameInfo) != 0,
+            CornerZones = corners == CornersDedicated ? (packed >> 6) + 1 : 0,
+            Fps = fps
+        };
+
+        if (binary.Format > FormatRgbw16)
+        {
+            throw new AmbilightBinaryFormatException(
+                AmbilightBinaryError.UnsupportedFormat,
+                $"{path} has payload format {binary.Format}; known formats are 0 (RGB) to 3 (RGBW16)");
+        }
+
+        if (corners > CornersBlended || (corners != CornersDedicated && packed >> 6 != 0))
+        {
+            throw new AmbilightBinaryFormatException(
+                AmbilightBinaryError.UnsupportedFormat,
+                $"{path} has corner flags {packed >> 4} in its format byte; known ones are 0 (shared), 1 (dedicated, zones per corner in bits 6-7) and 2 (blended)");
+        }
+
+        if (binary.LedCount == 0 || binary.LedCount > MaxLedCount)
+        {
+            throw new AmbilightBinaryFormatException(
+                AmbilightBinaryError.ImpossibleLedCount,
+                $"{path} declares {binary.TopCount}/{binary.BottomCount}/{binary.LeftCount}/{binary.RightCount} LEDs "
+                + $"(top/bottom/left/right, {binary.LedCount} total); expected 1 to {MaxLedCount}");
+        }
+
+        return binary;
+    }
+
+    /// <summary>
+    /// Reads the seek index of an AMb3 file, leaving <see cref="HasIndex"/> false when there is none or it does not fit
+    /// the file (cut off, or not finished). Sets where the records end either way: before the index, or at the end of
+    /// the file.
+    /// </summary>
+    private void ReadIndex(BinaryReader reader)
+    {
+        var fs = reader.BaseStream;
+        _bodyEnd = fs.Length;
+        if (Version < 3 || fs.Length < HeaderSize + IndexFooterSize)
+        {
+            return;
+        }
+
+        // One read each for the footer and the entries, which matters for remote files.
+        fs.Position = fs.Length - IndexFooterSize;
+        var footer = reader.ReadBytes(IndexFooterSize).AsSpan();
+        long indexOffset = BinaryPrimitives.ReadInt64LittleEndian(footer.Slice(0, 8));
+        int entries = BinaryPrimitives.ReadInt32LittleEndian(footer.Slice(8, 4));
+        int stride = BinaryPrimitives.ReadInt32LittleEndian(footer.Slice(12, 4));
+        bool compressed = (BinaryPrimitives.ReadUInt32LittleEndian(footer.Slice(16, 4)) & IndexFlagZstd) != 0;
+        bool magic = footer.Slice(20, 4).SequenceEqual("AMbI"u8);
+        long record = 8L + FrameSize;
+        long records = (indexOffset - HeaderSize) / record;
+        bool fits = magic && stride == IndexStride && indexOffset >= HeaderSize && entries >= 0
+            && indexOffset + (16L * entries) + IndexFooterSize == fs.Length;
+        if (!fits || (!compressed && ((indexOffset - HeaderSize) % record != 0 || entries != (records + stride - 1) / stride)))
+        {
+            // Without the index, compressed records cannot be told apart; a zstd frame right after the header gives
+            // them away.
+            fs.Position = HeaderSize;
+            var first = reader.ReadBytes(4);
+            fs.Position = HeaderSize;
+            if (first.AsSpan().SequenceEqual(ZstdMagic))
+            {
+                throw new AmbilightBinaryFormatException(
+                    AmbilightBinaryError.MissingIndex,
+                    $"{Path} holds compressed frames but its seek index is missing or damaged (cut off before it was finished?)");
+            }
+
+            return;
+        }
+
+        fs.Position = indexOffset;
+        var index = reader.ReadBytes(16 * entries);
+        var timestamps = new ulong[entries];
+        var offsets = new long[entries];
+        for (int i = 0; i < entries; i++)
+        {
+            timestamps[i] = BinaryPrimitives.ReadUInt64LittleEndian(index.AsSpan(i * 16, 8));
+            offsets[i] = BinaryPrimitives.ReadInt64LittleEndian(index.AsSpan((i * 16) + 8, 8));
+        }
+
+        _indexTimestamps = timestamps;
+        _indexOffsets = offsets;
+        _bodyEnd = indexOffset;
+        if (compressed)
+        {
+            // Every block but the last holds a full stride; unpacking the last one gives the record count.
+            for (int i = 0; i < entries; i++)
+            {
+                if (offsets[i] < HeaderSize || offsets[i] >= indexOffset || (i > 0 && offsets[i] <= offsets[i - 1]))
+                {
+                    throw new AmbilightBinaryFormatException(
+                        AmbilightBinaryError.MissingIndex,
+                        $"{Path} has a seek index whose entry {i} points outside its compressed frames");
+                }
+            }
+
+            IsCompressed = true;
+            int last = entries > 0 ? UnpackBlock(fs, entries - 1) : 0;
+            if (last % record != 0 || last > IndexStride * record)
+            {
+                throw new AmbilightBinaryFormatException(
+                    AmbilightBinaryError.FrameSizeMismatch,
+                    $"{Path} has a last compressed block of {last} bytes, not a whole number of {record}-byte frames for {LedCount} LEDs in format {Format}");
+            }
+
+            _compressedRecords = entries > 0 ? ((long)(entries - 1) * IndexStride) + (last / record) : 0;
+        }
+
+        fs.Position = HeaderSize;
+    }
+
+    // The magic number every zstd frame starts with (0xFD2FB528, little-endian).
+    private static ReadOnlySpan<byte> ZstdMagic => new byte[] { 0x28, 0xB5, 0x2F, 0xFD };
+
+    /// <summary>
+    /// Unpacks compressed block <paramref name="block"/> into <c>_block</c>, read from the memory-mapped view or else
+    /// from <paramref name="source"/>, and returns its length. Throws <see cref="AmbilightBinaryFormatException"/> for a
+    /// block that does not decompress.
+    /// </summary>
+    private int UnpackBlock(Stream? source, int block)
+    {
+        long start = _indexOffsets![block];
+        long end = block + 1 < _indexOffsets.Length ? _indexOffsets[block + 1] : _bodyEnd;
+        var packed = new byte[end - start];
+        if (_view != null)
+        {
+            _view.ReadArray(start, packed, 0, packed.Length);
+        }
+        else
+        {
+            source!.Position = start;
+            source.ReadExactly(packed);
+        }
+
+        int size = IndexStride * (8 + FrameSize);
+        if (_block.Length < size)
+        {
+            _block = new byte[size];
+        }
+
+        try
+        {
+            _decompressor ??= new Decompressor();
+            int length = _decompressor.Unwrap(packed, _block);
+            _blockIndex = block;
+            return length;
+        }
+        catch (ZstdException ex)
+        {
+            _blockIndex = -1;
+            throw new AmbilightBinaryFormatException(AmbilightBinaryError.CorruptBlock, $"{Path}: compressed block {block} does not decompress ({ex.Message})");
+        }
+    }
+
+    private ReadOnlySpan<byte> ReadCompressedRecord(int index)
+    {
+        int block = index / IndexStride;
+        if (block != _blockIndex)
+        {
+            UnpackBlock(_source, block);
+        }
+
+        int record = 8 + FrameSize;
+        return _block.AsSpan((index % IndexStride) * record, record);
+    }
+
+    /// <summary>
+    /// Writes the zstd-compressed AMb3 form (see <see cref="IndexFlagZstd"/>) of the uncompressed records in
+    /// <paramref name="raw"/>, which has a header and no index yet, to <paramref name="destination"/>: the same header,
+    /// one compressed block per <see cref="IndexStride"/> records, then the seek index pointing at the blocks.
+    /// </summary>
+    public static void WriteCompressed(Stream raw, Stream destination, int level)
+    {
+        raw.Position = 0;
+        using var reader = new BinaryReader(raw, Encoding.UTF8, leaveOpen: true);
+        var layout = ReadHeader(reader, string.Empty);
+        raw.Position = 0;
+        destination.Write(reader.ReadBytes(HeaderSize));
+
+        int record = 8 + layout.FrameSize;
+        long records = (raw.Length - HeaderSize) / record;
+        int entries = (int)((records + IndexStride - 1) / IndexStride);
+        var index = new byte[(16 * entries) + IndexFooterSize];
+        var block = new byte[IndexStride * record];
+        using var compressor = new Compressor(level);
+        for (int i = 0; i < entries; i++)
+        {
+            int length = (int)Math.Min(IndexStride, records - ((long)i * IndexStride)) * record;
+            raw.Position = HeaderSize + ((long)i * IndexStride * record);
+            raw.ReadExactly(block, 0, length);
+            ulong timestamp = BinaryPrimitives.ReadUInt64LittleEndian(block) & (layout.HasFrameInfo ? TimestampMask : ulong.MaxValue);
+            BinaryPrimitives.WriteUInt64LittleEndian(index.AsSpan(i * 16, 8), timestamp);
+            BinaryPrimitives.WriteInt64LittleEndian(index.AsSpan((i * 16) + 8, 8), destination.Position);
+            destination.Write(compressor.Wrap(block.AsSpan(0, length)));
+        }
+
+        var footer = index.AsSpan(16 * entries);
+        BinaryPrimitives.WriteInt64LittleEndian(footer.Slice(0, 8), destination.Position);
+        BinaryPrimitives.WriteInt32LittleEndian(footer.Slice(8, 4), entries);
+        BinaryPrimitives.WriteInt32LittleEndian(footer.Slice(12, 4), IndexStride);
+        BinaryPrimitives.WriteUInt32LittleEndian(footer.Slice(16, 4), IndexFlagZstd);
+        "AMbI"u8.CopyTo(footer.Slice(20, 4));
+        destination.Write(index);
+    }
+
+    /// <summary>
+    /// The seek index and footer that turn the AMb2-style records of <paramref name="file"/> into an AMb3 file, to be
+    /// appended after the last record. Reads the header and every <see cref="IndexStride"/>th timestamp from the file
+    /// and leaves its position at the end.
+    /// </summary>
+    public static byte[] BuildSeekIndex(Stream file)
+    {
+        long end = file.Length;
+        file.Position = 0;
+        using var reader = new BinaryReader(file, Encoding.UTF8, leaveOpen: true);
+        var layout = ReadHeader(reader, string.Empty);
+        long record = 8L + layout.FrameSize;
+        long records = (end - HeaderSize) / record;
+        int entries = (int)((records + IndexStride - 1) / IndexStride);
+        var index = new byte[(16 * entries) + IndexFooterSize];
+        for (int i = 0; i < entries; i++)
+        {
+            long offset = HeaderSize + ((long)i * IndexStride * record);
+            file.Position = offset;
+            ulong timestamp = reader.ReadUInt64() & (layout.HasFrameInfo ? TimestampMask : ulong.MaxValue);
+            BinaryPrimitives.WriteUInt64LittleEndian(index.AsSpan(i * 16, 8), timestamp);
+            BinaryPrimitives.WriteInt64LittleEndian(index.AsSpan((i * 16) + 8, 8), offset);
+        }
+
+        var footer = index.AsSpan(16 * entries);
+        BinaryPrimitives.WriteInt64LittleEndian(footer.Slice(0, 8), HeaderSize + (records * record));
+        BinaryPrimitives.WriteInt32LittleEndian(footer.Slice(8, 4), entries);
+        BinaryPrimitives.WriteInt32LittleEndian(footer.Slice(12, 4), IndexStride);
+        "AMbI"u8.CopyTo(footer.Slice(20, 4));
+        file.Position = end;
+        return index;
+    }
+
+    private void DeriveFps()
+    {
+        if (Fps <= 0.0)
+        {
+            // Header fps missing or bogus: derive it from the first frame interval.
+            double dtUs = FrameCount >= 2 ? Math.Abs((double)TimestampUs(1) - TimestampUs(0)) : 0.0;
+            Fps = dtUs > 0.0 ? 1e6 / dtUs : 24.0;
+        }
+    }
+
+    /// <summary>
+    /// The header's format byte: <paramref name="format"/> in the low bits, <see cref="FlagFrameInfo"/> when the
+    /// timestamps carry frame info, and the corner handling and, for dedicated corners, the zones per corner above it.
+    /// </summary>
+    public static byte PackFormat(byte format, byte corners, int cornerZones, bool frameInfo = false)
+    {
+        int zones = corners == CornersDedicated ? Math.Clamp(cornerZones, 1, MaxCornerZones) - 1 : 0;
+        return (byte)(format | (frameInfo ? FlagFrameInfo : 0) | (corners << 4) | (zones << 6));
+    }
+
+    /// <summary>A record timestamp carrying <paramref name="info"/> (see <see cref="FrameInfo"/>) in its upper bits.</summary>
+    public static ulong PackTimestamp(ulong timestampUs, uint info) => (timestampUs & TimestampMask) | ((ulong)info << TimestampBits);
+
+    private long RecordOffset(int index) => _indexOffsets != null
+        ? _indexOffsets[index / IndexStride] + ((long)(index % IndexStride) * (8 + FrameSize))
+        : HeaderSize + ((long)index * (8 + FrameSize));
+
+    private ReadOnlySpan<byte> ReadMappedPayload(int index)
+    {
+        int frameSize = FrameSize;
+        if (_payload.Length != frameSize)
+        {
+            _payload = new byte[frameSize];
+        }
+
+        _view!.ReadArray(RecordOffset(index) + 8, _payload, 0, frameSize);
+        return _payload;
+    }
+
+    private ReadOnlySpan<byte> ReadRecord(int index)
+    {
+        int record = 8 + FrameSize;
+        if (index < _readAheadFirst || index >= _readAheadFirst + _readAheadCount)
+        {
+            int records = ReadAheadRecords(index);
+            if (_readAhead.Length < records * record)
+            {
+                _readAhead = new byte[records * record];
+            }
+
+            _source!.Position = RecordOffset(index);
+            _source.ReadExactly(_readAhead, 0, records * record);
+            _readAheadFirst = index;
+            _readAheadCount = records;
+        }
+
+        return _readAhead.AsSpan((index - _readAheadFirst) * record, record);
+    }
+
+    private int ReadAheadRecords(int index) => Math.Max(1, Math.Min(ReadAheadBytes / (8 + FrameSize), FrameCount - index));
+}
+
+/// <summary>
+/// What is wrong with an AMb2 or AMb3 file. The numeric values are stable, so scripts can match on them as well as on
+/// the names the status API reports.
+/// </summary>
+public enum AmbilightBinaryError
+{
+    /// <summary>The file is valid.</summary>
+    None = 0,
+
+    /// <summary>Shorter than the 17-byte header.</summary>
+    TruncatedHeader = 1,
+
+    /// <summary>Does not start with "AMb2" or "AMb3".</summary>
+    BadMagic = 2,
+
+    /// <summary>Payload format byte is not one of the known formats.</summary>
+    UnsupportedFormat = 3,
+
+    /// <summary>Side counts add up to no LEDs, or to more than any real strip.</summary>
+    ImpossibleLedCount = 4,
+
+    /// <summary>The bytes after the header are not a whole number of frames for the declared layout.</summary>
+    FrameSizeMismatch = 5,
+
+    /// <summary>A header and nothing else.</summary>
+    NoFrames = 6,
+
+    /// <summary>The file could not be read at all.</summary>
+    Unreadable = 7,
+
+    /// <summary>An AMb3 file whose records are whole but whose seek index is missing or does not fit them.</summary>
+    MissingIndex = 8,
+
+    /// <summary>A compressed block of an AMb3 file does not decompress.</summary>
+    CorruptBlock = 9
+}
+
+/// <summary>
+/// Thrown for AMb2 and AMb3 files with an invalid header; <see cref="Error"/> says which check failed.
+/// </summary>
+public sealed class AmbilightBinaryFormatException : InvalidDataException
+{
+    public AmbilightBinaryFormatException(AmbilightBinaryError error, string message)
+        : base(message)
+    {
+        Error = error;
+    }
+
+    public AmbilightBinaryError Error { get; }
+}
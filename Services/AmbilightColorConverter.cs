@@ -0,0 +1,205 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (C) 2026 Jellyfin Ambilight Contributors
+// This file is part of Jellyfin Ambilight Plugin.
+// Jellyfin Ambilight Plugin is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+using System;
+
+namespace Jellyfin.Plugin.Ambilight.Services;
+
+/// <summary>Curve that compresses HDR highlights into the SDR range.</summary>
+internal enum ToneMapCurve
+{
+    /// <summary>ITU-R BT.2390 EETF: the picture is kept as is up to the knee and only highlights roll off.</summary>
+    Bt2390,
+
+    /// <summary>Hable (Uncharted 2) filmic curve: a softer toe and shoulder over the whole range.</summary>
+    Hable,
+}
+
+/// <summary>
+/// Converts rgb48le frames of UHD sources to SDR BT.709 in place, before zone averaging, so they give the same LED
+/// colors as their 1080p counterparts. Decoders hand code values over unchanged, and read as SDR BT.709 they come
+/// out wrong in two ways:
+/// <list type="bullet">
+/// <item>HDR10 (SMPTE ST 2084 / PQ) looks dim and washed out. It is tone mapped: each pixel is decoded to nits, its
+/// brightest channel is mapped from the source peak down to SDR white (100 nits), and all channels are scaled by the
+/// same factor so hues stay put.</item>
+/// <item>BT.2020 primaries look oversaturated and hue-shifted. They are converted to BT.709 in linear light. Colors
+/// outside BT.709 are scaled down to fit rather than clipped per channel.</item>
+/// </list>
+/// The result is re-encoded with the BT.1886 gamma (2.4) that SDR sources are decoded with. Everything per code is
+/// precomputed into tables over 12-bit input levels, enough for 10- and 12-bit sources, and the output stays 16-bit
+/// so zone averaging still sees the shadow detail.
+/// </summary>
+internal sealed class AmbilightColorConverter
+{
+    // SDR reference white in nits.
+    private const double SdrWhiteNits = 100.0;
+
+    // BT.1886 display gamma.
+    private const double SdrGamma = 2.4;
+
+    // ST 2084 constants.
+    private const double M1 = 2610.0 / 16384.0;
+    private const double M2 = 2523.0 / 4096.0 * 128.0;
+    private const double C1 = 3424.0 / 4096.0;
+    private const double C2 = 2413.0 / 4096.0 * 32.0;
+    private const double C3 = 2392.0 / 4096.0 * 32.0;
+
+    // Linear BT.2020 RGB to linear BT.709 RGB (ITU-R BT.2087).
+    private static readonly double[] Bt2020To709 =
+    {
+        1.6605, -0.5876, -0.0728,
+        -0.1246, 1.1329, -0.0083,
+        -0.0182, -0.1006, 1.1187,
+    };
+
+    // Input samples are looked up by their top 12 bits.
+    private const int LevelShift = 4;
+    private const int Levels = 65536 >> LevelShift;
+
+    private const int EncodeSteps = 16384;
+
+    // Linear light of each input level relative to SDR white, and the factor its tone-mapped value scales by.
+    private readonly double[] _linear = new double[Levels];
+    private readonly double[] _gain = new double[Levels];
+
+    // Gamma-encoded 16-bit output, indexed by the square root of linear light 0..1 in EncodeSteps steps so the
+    // shadows, where the gamma curve is steepest, get most of the table.
+    private readonly ushort[] _encode = new ushort[EncodeSteps + 1];
+
+    private AmbilightColorConverter(ToneMapCurve? curve, double peakNits, double strength, bool bt2020)
+    {
+        Curve = curve;
+        PeakNits = peakNits;
+        Strength = strength;
+        Bt2020 = bt2020;
+
+        for (int code = 0; code < Levels; code++)
+        {
+            double e = code / (double)(Levels - 1);
+            if (curve is not { } tone)
+            {
+                _linear[code] = Math.Pow(e, SdrGamma);
+                _gain[code] = 1;
+                continue;
+            }
+
+            double nits = PqToNits(e);
+            double linear = nits / SdrWhiteNits;
+            double curved = tone == ToneMapCurve.Hable ? Hable(linear, peakNits / SdrWhiteNits) : Bt2390(nits, peakNits) / SdrWhiteNits;
+            double mapped = strength * curved + (1 - strength) * Math.Min(linear, 1.0);
+            _linear[code] = linear;
+            _gain[code] = linear > 0 ? Math.Min(mapped, 1.0) / linear : 0;
+        }
+
+        for (int i = 0; i <= EncodeSteps; i++)
+        {
+            double root = i / (double)EncodeSteps;
+            double v = Math.Pow(root * root, 1 / SdrGamma);
+            _encode[i] = (ushort)Math.Clamp((int)Math.Round(v * 65535), 0, 65535);
+        }
+    }
+
+    /// <summary>Tone mapping curve for PQ input, or null when the input is SDR.</summary>
+    public ToneMapCurve? Curve { get; }
+
+    public double PeakNits { get; }
+
+    public double Strength { get; }
+
+    /// <summary>True when the input has BT.2020 primaries and is converted to BT.709.</summary>
+    public bool Bt2020 { get; }
+
+    /// <summary>
+    /// A converter for PQ content mastered up to <paramref name="peakNits"/> when <paramref name="curve"/> is set (SDR
+    /// input otherwise), from BT.2020 primaries when <paramref name="bt2020"/> is set. <paramref name="strength"/>
+    /// (0..1) blends between plain clipping at SDR white (0) and the full curve (1).
+    /// </summary>
+    public static AmbilightColorConverter Create(ToneMapCurve? curve, double peakNits, double strength, bool bt2020)
+        => new(curve, Math.Clamp(peakNits, SdrWhiteNits, 10000.0), Math.Clamp(strength, 0.0, 1.0), bt2020);
+
+    /// <summary>Converts an rgb48le frame in place.</summary>
+    public void Apply(Span<ushort> frame)
+    {
+        var m = Bt2020To709;
+        for (int i = 0; i + 2 < frame.Length; i += 3)
+        {
+            int r = frame[i] >> LevelShift;
+            int g = frame[i + 1] >> LevelShift;
+            int b = frame[i + 2] >> LevelShift;
+
+            // Both transfers are monotonic, so the largest code is the brightest channel.
+            double gain = _gain[Math.Max(r, Math.Max(g, b))];
+            double lr = _linear[r] * gain;
+            double lg = _linear[g] * gain;
+            double lb = _linear[b] * gain;
+
+            if (Bt2020)
+            {
+                double r709 = Math.Max(0, m[0] * lr + m[1] * lg + m[2] * lb);
+                double g709 = Math.Max(0, m[3] * lr + m[4] * lg + m[5] * lb);
+                double b709 = Math.Max(0, m[6] * lr + m[7] * lg + m[8] * lb);
+                double peak = Math.Max(r709, Math.Max(g709, b709));
+                double scale = peak > 1 ? 1 / peak : 1;
+                lr = r709 * scale;
+                lg = g709 * scale;
+                lb = b709 * scale;
+            }
+
+            frame[i] = Encode(lr);
+            frame[i + 1] = Encode(lg);
+            frame[i + 2] = Encode(lb);
+        }
+    }
+
+    private ushort Encode(double linear) => _encode[Math.Min((int)(Math.Sqrt(linear) * EncodeSteps + 0.5), EncodeSteps)];
+
+    private static double PqToNits(double e)
+    {
+        double p = Math.Pow(e, 1 / M2);
+        return 10000.0 * Math.Pow(Math.Max(p - C1, 0) / (C2 - C3 * p), 1 / M1);
+    }
+
+    private static double NitsToPq(double nits)
+    {
+        double y = Math.Pow(Math.Clamp(nits / 10000.0, 0, 1), M1);
+        return Math.Pow((C1 + C2 * y) / (1 + C3 * y), M2);
+    }
+
+    /// <summary>BT.2390 EETF from [0, <paramref name="peakNits"/>] to [0, SDR white], in nits.</summary>
+    private static double Bt2390(double nits, double peakNits)
+    {
+        double sourcePeak = NitsToPq(peakNits);
+        double e1 = Math.Min(NitsToPq(nits) / sourcePeak, 1.0);
+        double maxLum = NitsToPq(SdrWhiteNits) / sourcePeak;
+        double knee = 1.5 * maxLum - 0.5;
+        double e2 = e1;
+        if (e1 > knee)
+        {
+            // Hermite spline from the knee up to the target peak.
+            double t = (e1 - knee) / (1 - knee);
+            double t2 = t * t;
+            double t3 = t2 * t;
+            e2 = (2 * t3 - 3 * t2 + 1) * knee + (t3 - 2 * t2 + t) * (1 - knee) + (-2 * t3 + 3 * t2) * maxLum;
+        }
+
+        return PqToNits(e2 * sourcePeak);
+    }
+
+    /// <summary>Hable's filmic curve for linear light relative to SDR white, scaled so <paramref name="peak"/> maps to 1.</summary>
+    private static double Hable(double linear, double peak)
+    {
+        static double F(double x)
+        {
+            const double a = 0.15, b = 0.50, c = 0.10, d = 0.20, e = 0.02, f = 0.30;
+            return (x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f) - e / f;
+        }
+
+        return F(linear) / F(peak);
+    }
+}
@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (C) 2026 Jellyfin Ambilight Contributors
+// This file is part of Jellyfin Ambilight Plugin.
+// Jellyfin Ambilight Plugin is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+using System;
+using System.Collections.Concurrent;
+using System.Collections.Generic;
+using System.Globalization;
+using System.IO;
+using Microsoft.Extensions.Logging;
+
+namespace Jellyfin.Plugin.Ambilight.Services;
+
+/// <summary>
+/// A 3D color lookup table in Adobe/Resolve <c>.cube</c> format, applied to each LED color with trilinear
+/// interpolation. Fixes casts that per-channel gamma cannot, e.g. from a camera calibration of the strip.
+/// </summary>
+public sealed class AmbilightCubeLut
+{
+    private const int MaxSize = 256;
+
+    private static readonly ConcurrentDictionary<string, (DateTime WriteTimeUtc, AmbilightCubeLut Lut)> Cache = new(StringComparer.Ordinal);
+
+    // Entries in file order: red varies fastest, then green, then blue. The domain is the input range the grid spans.
+    private readonly float[] _table;
+    private readonly int _size;
+    private readonly float[] _domainMin;
+    private readonly float[] _domainMax;
+
+    private AmbilightCubeLut(float[] table, int size, float[] domainMin, float[] domainMax)
+    {
+        _table = table;
+        _size = size;
+        _domainMin = domainMin;
+        _domainMax = domainMax;
+    }
+
+    public int Size => _size;
+
+    /// <summary>
+    /// Loads <paramref name="path"/>, reusing the parsed table while the file is unchanged. Returns null (and logs)
+    /// when the path is empty, missing or not a valid 3D <c>.cube</c> file, so playback continues uncalibrated.
+    /// </summary>
+    public static AmbilightCubeLut? TryLoad(string? path, ILogger logger)
+    {
+        if (string.IsNullOrWhiteSpace(path))
+        {
+            return null;
+        }
+
+        path = path.Trim();
+        try
+        {
+            var writeTime = File.GetLastWriteTimeUtc(path);
+            if (Cache.TryGetValue(path, out var cached) && cached.WriteTimeUtc == writeTime)
+            {
+                return cached.Lut;
+            }
+
+            var lut = Parse(File.ReadLines(path), path);
+            Cache[path] = (writeTime, lut);
+            logger.LogInformation("[Ambilight] Loaded {Size}³ color LUT from {Path}", lut.Size, path);
+            return lut;
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException or InvalidDataException)
+        {
+            logger.LogWarning("[Ambilight] Ignoring color LUT {Path}: {Message}", path, ex.Message);
+            return null;
+        }
+    }
+
+    /// <summary>
+    /// Parses the text of a <c>.cube</c> file. Throws <see cref="InvalidDataException"/> on errors.
+    /// </summary>
+    public static AmbilightCubeLut Parse(IEnumerable<string> lines, string name)
+    {
+        int size = 0;
+        float[]? table = null;
+        int count = 0;
+        var domainMin = new[] { 0f, 0f, 0f };
+        var domainMax = new[] { 1f, 1f, 1f };
+
+        foreach (var rawLine in lines)
+        {
+            var line = rawLine.Trim();
+            if (line.Length == 0 || line[0] == '#')
+            {
+                continue;
+            }
+
+            var parts = line.Split((char[]?)null, StringSplitOptions.RemoveEmptyEntries);
+            switch (parts[0].ToUpperInvariant())
+            {
+                case "TITLE":
+                    continue;
+                case "LUT_1D_SIZE":
+                    throw new InvalidDataException($"{name} is a 1D LUT; only 3D LUTs are supported");
+                case "LUT_3D_SIZE":
+                    if (parts.Length < 2 || !int.TryParse(parts[1], NumberStyles.Integer, CultureInfo.InvariantCulture, out size) || size < 2 || size > MaxSize)
+                    {
+                        throw new InvalidDataException($"Invalid LUT_3D_SIZE in {name}");
+                    }
+
+                    table = new float[size * size * size * 3];
+                    continue;
+                case "DOMAIN_MIN":
+                    ReadTriple(parts, domainMin, name);
+                    continue;
+                case "DOMAIN_MAX":
+                    ReadTriple(parts, domainMax, name);
+                    continue;
+            }
+
+            if (table == null)
+            {
+                throw new InvalidDataException($"Data before LUT_3D_SIZE in {name}");
+            }
+
+            if (count >= table.Length)
+            {
+                throw new InvalidDataException($"Too many entries in {name}");
+            }
+
+            var rgb = new float[3];
+            ReadTriple(parts, rgb, name, 0);
+            table[count++] = rgb[0];
+            table[count++] = rgb[1];
+            table[count++] = rgb[2];
+        }
+
+        if (table == null || count != table.Length)
+        {
+            throw new InvalidDataException($"{name} has {count / 3} entries, expected {size * size * size}");
+        }
+
+        for (int c = 0; c < 3; c++)
+        {
+            if (domainMax[c] <= domainMin[c])
+            {
+                throw new InvalidDataException($"Invalid DOMAIN_MIN/DOMAIN_MAX in {name}");
+            }
+        }
+
+        return new AmbilightCubeLut(table, size, domainMin, domainMax);
+    }
+
+    /// <summary>
+    /// Maps a color on the 0–255 scale through the LUT, in place. Fractional values are kept for dithering.
+    /// </summary>
+    public void Apply(ref float r, ref float g, ref float b)
+    {
+        int n = _size - 1;
+        float fr = GridPosition(r, 0) * n;
+        float fg = GridPosition(g, 1) * n;
+        float fb = GridPosition(b, 2) * n;
+        int r0 = Math.Min((int)fr, n - 1);
+        int g0 = Math.Min((int)fg, n - 1);
+        int b0 = Math.Min((int)fb, n - 1);
+        float dr = fr - r0;
+        float dg = fg - g0;
+        float db = fb - b0;
+
+        for (int c = 0; c < 3; c++)
+        {
+            float c00 = Lerp(At(r0, g0, b0, c), At(r0 + 1, g0, b0, c), dr);
+            float c10 = Lerp(At(r0, g0 + 1, b0, c), At(r0 + 1, g0 + 1, b0, c), dr);
+            float c01 = Lerp(At(r0, g0, b0 + 1, c), At(r0 + 1, g0, b0 + 1, c), dr);
+            float c11 = Lerp(At(r0, g0 + 1, b0 + 1, c), At(r0 + 1, g0 + 1, b0 + 1, c), dr);
+            float v = Lerp(Lerp(c00, c10, dg), Lerp(c01, c11, dg), db);
+            v = Math.Clamp(v, 0.0f, 1.0f) * 255.0f;
+            switch (c)
+            {
+                case 0: r = v; break;
+                case 1: g = v; break;
+                default: b = v; break;
+            }
+        }
+    }
+
+    private float GridPosition(float value, int channel)
+        => Math.Clamp(((value / 255.0f) - _domainMin[channel]) / (_domainMax[channel] - _domainMin[channel]), 0.0f, 1.0f);
+
+    private float At(int r, int g, int b, int channel) => _table[((((b * _size) + g) * _size) + r) * 3 + channel];
+
+    private static float Lerp(float a, float b, float t) => a + ((b - a) * t);
+
+    private static void ReadTriple(string[] parts, float[] dest, string name, int first = 1)
+    {
+        if (parts.Length < first + 3)
+        {
+            throw new InvalidDataException($"Expected three values in {name}: {string.Join(' ', parts)}");
+        }
+
+        for (int i = 0; i < 3; i++)
+        {
+            if (!float.TryParse(parts[first + i], NumberStyles.Float, CultureInfo.InvariantCulture, out dest[i]))
+            {
+                throw new InvalidDataException($"Invalid number '{parts[first + i]}' in {name}");
+            }
+        }
+    }
+}
@@ -187,7 +187,9 @@ public class AmbilightExtractorService
 
                 // Treat any item without a valid binary as pending, including previous failures.
                 // This allows the scheduled task to retry failed extractions explicitly.
-                if (_storage.BinaryExists(item.Id))
+                // Items the extractor rejected as not extractable are only retried on request; previews and the partial
+                // binaries of interrupted runs are replaced.
+                if (item.ExtractionStatus == "not_extractable" || (item.ExtractionStatus is not ("preview" or "partial") && _storage.BinaryIsValid(item.Id)))
                 {
                     continue;
                 }
@@ -234,9 +236,10 @@ public class AmbilightExtractorService
     }
 
     /// <summary>
-    /// Runs the in-process extractor for a single item.
+    /// Runs the in-process extractor for a single item. Unless <paramref name="force"/> is set, an existing binary that
+    /// is up to date with the file and settings is kept.
     /// </summary>
-    public async Task RunExtractorForItemAsync(AmbilightItem item, CancellationToken cancellationToken)
+    public async Task RunExtractorForItemAsync(AmbilightItem item, CancellationToken cancellationToken, bool force = false)
     {
         var binPath = _storage.GetBinaryPath(item.Id);
         
@@ -267,16 +270,42 @@ public class AmbilightExtractorService
                 _storage.UpdateExtractionProgress(item.Id, progress.current, progress.total);
             });
 
-            var ok = await _extractorCore.ExtractAsync(item.FilePath, binPath, cancellationToken, progressCallback).ConfigureAwait(false);
+            var pictureAreas = new List<AmbilightPictureArea>();
+            var result = await _extractorCore.ExtractAsync(item.FilePath, binPath, cancellationToken, progressCallback, SelectWhiteBalance(item), pictureAreas, force, SelectColorMode(item)).ConfigureAwait(false);
 
-            if (ok && File.Exists(binPath))
+            if (result == AmbilightExtractionResult.UpToDate)
             {
+                // Keeps the picture areas recorded when the binary was made.
                 item.ExtractionStatus = "completed";
                 item.ExtractionError = null;
+            }
+            else if (result == AmbilightExtractionResult.Partial)
+            {
+                // Plays up to where it stopped; the next run resumes it.
+                item.ExtractionStatus = "partial";
+                item.ExtractionError = null;
+                item.PictureAreas = pictureAreas;
+                _storage.ClearExtractionProgress(item.Id);
+                _logger.LogInformation("[Ambilight] Extraction of {ItemName} stopped part way; kept what was extracted", item.Name);
+            }
+            else if (result == AmbilightExtractionResult.NotExtractable)
+            {
+                item.ExtractionStatus = "not_extractable";
+                item.ExtractionError = $"No video stream besides cover art, or shorter than {_config.MinExtractDurationSeconds:F0}s";
+                _logger.LogInformation("[Ambilight] {ItemName} is not extractable; it will be skipped by scheduled runs", item.Name);
+            }
+            else if (result == AmbilightExtractionResult.Success && File.Exists(binPath))
+            {
+                item.ExtractionStatus = "completed";
+                item.ExtractionError = null;
+                item.PictureAreas = pictureAreas;
                 if (_config.Debug)
                 {
                     _logger.LogInformation("[Ambilight] Extraction completed for {ItemName}", item.Name);
                 }
+
+                // The local copy stays the source of truth; a failed upload is logged but not an extraction failure.
+                await AmbilightUploader.UploadAsync(binPath, _config, _logger, cancellationToken).ConfigureAwait(false);
             }
             else
             {
@@ -312,6 +341,57 @@ public class AmbilightExtractorService
         }
     }
 
+    /// <summary>
+    /// Writes a quick preview binary for an item in place of its binary (see
+    /// <see cref="AmbilightInProcessExtractor.ExtractPreviewAsync"/>) and marks it "preview", so the next scheduled
+    /// run replaces it with a full extraction.
+    /// </summary>
+    public async Task<AmbilightExtractionResult> RunPreviewForItemAsync(AmbilightItem item, double minutes, double fps, CancellationToken cancellationToken)
+    {
+        _logger.LogInformation("[Ambilight] Extracting a {Minutes:0.#} minute preview of {ItemName} at {Fps:0.#} fps", minutes, item.Name, fps);
+        var result = await _extractorCore.ExtractPreviewAsync(item.FilePath, _storage.GetBinaryPath(item.Id), minutes, fps, cancellationToken, whiteBalance: SelectWhiteBalance(item), colorMode: SelectColorMode(item)).ConfigureAwait(false);
+        if (result == AmbilightExtractionResult.Success)
+        {
+            item.ExtractionStatus = "preview";
+            item.ExtractionError = null;
+            item.PictureAreas = new();
+            _storage.SaveOrUpdateItem(item);
+        }
+        else
+        {
+            _logger.LogWarning("[Ambilight] Preview extraction failed for {ItemName}", item.Name);
+        }
+
+        return result;
+    }
+
+    /// <summary>
+    /// Verifies an item's binary against its video with the white balance and color mode its library extracts with (see
+    /// <see cref="AmbilightInProcessExtractor.VerifyAsync"/>).
+    /// </summary>
+    public Task<AmbilightVerifyReport> VerifyItemAsync(AmbilightItem item, bool spotCheck, CancellationToken cancellationToken)
+        => _extractorCore.VerifyAsync(item.FilePath, _storage.GetBinaryPath(item.Id), spotCheck, SelectWhiteBalance(item), cancellationToken, SelectColorMode(item));
+
+    /// <summary>
+    /// Predicts the binary size of every item waiting for extraction with the current settings (see
+    /// <see cref="AmbilightInProcessExtractor.EstimateAsync"/>; probes only, nothing is decoded).
+    /// </summary>
+    public async Task<AmbilightLibraryEstimate> EstimatePendingAsync(CancellationToken cancellationToken)
+    {
+        var total = new AmbilightLibraryEstimate();
+        foreach (var item in GetItemsNeedingExtraction())
+        {
+            var estimate = await _extractorCore.EstimateAsync(item.FilePath, false, cancellationToken).ConfigureAwait(false);
+            total.Items++;
+            total.Frames += estimate.Frames;
+            total.Bytes += estimate.Bytes;
+            total.VideoSeconds += estimate.VideoSeconds;
+            total.Estimates.Add(estimate);
+        }
+
+        return total;
+    }
+
     /// <summary>
     /// Gets the library ID for an item by walking up the parent chain.
     /// Returns the ID in "N" format (without dashes) for consistent comparison.
@@ -346,6 +426,33 @@ public class AmbilightExtractorService
         return item.ParentId != Guid.Empty ? item.ParentId.ToString("N") : null;
     }
 
+    /// <summary>
+    /// Picks the extraction white balance for the item's library, falling back to the entry without a library.
+    /// </summary>
+    private ExtractionWhiteBalance? SelectWhiteBalance(AmbilightItem item)
+    {
+        var profiles = _config.ExtractionWhiteBalances;
+        if (profiles == null || profiles.Count == 0)
+        {
+            return null;
+        }
+
+        var libraryId = string.IsNullOrEmpty(item.LibraryId) ? null : NormalizeLibraryId(item.LibraryId);
+        return profiles.FirstOrDefault(p => libraryId != null && !string.IsNullOrWhiteSpace(p.LibraryId) && NormalizeLibraryId(p.LibraryId.Trim()) == libraryId)
+            ?? profiles.FirstOrDefault(p => string.IsNullOrWhiteSpace(p.LibraryId));
+    }
+
+    /// <summary>
+    /// The zone color mode of the item's library, or null to use <see cref="PluginConfiguration.ExtractionColorMode"/>.
+    /// </summary>
+    private string? SelectColorMode(AmbilightItem item)
+    {
+        var libraryId = string.IsNullOrEmpty(item.LibraryId) ? null : NormalizeLibraryId(item.LibraryId);
+        return libraryId == null
+            ? null
+            : _config.LibraryColorModes?.FirstOrDefault(m => !string.IsNullOrWhiteSpace(m.LibraryId) && NormalizeLibraryId(m.LibraryId.Trim()) == libraryId)?.ColorMode;
+    }
+
     /// <summary>
     /// Normalizes a library ID for comparison by removing dashes and converting to lowercase.
     /// Handles both "D" format (with dashes) and "N" format (without dashes).
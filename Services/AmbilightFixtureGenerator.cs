@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (C) 2026 Jellyfin Ambilight Contributors
+// This file is part of Jellyfin Ambilight Plugin.
+// Jellyfin Ambilight Plugin is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+using System;
+using System.Collections.Generic;
+using System.IO;
+using System.Linq;
+
+namespace Jellyfin.Plugin.Ambilight.Services;
+
+/// <summary>
+/// Builds small synthetic AMb2 files with predictable content and exact timestamps, for testing playback,
+/// LED layout and sync without extracting real media.
+/// </summary>
+public static class AmbilightFixtureGenerator
+{
+    /// <summary>
+    /// Pattern names accepted by <see cref="Generate"/>:
+    /// <c>solid</c> cycles red, green, blue, white and black, one second each;
+    /// <c>gradient</c> is a hue ring around the strip that rotates once every 10 seconds;
+    /// <c>chase</c> lights one LED per frame, starting at the first top LED, to check orientation and input position;
+    /// <c>white</c> is plain white, for judging white balance;
+    /// <c>ramp</c> is a gray ramp of <see cref="RampSteps"/> bands from black to white around the strip, for judging gamma.
+    /// </summary>
+    public static readonly IReadOnlyList<string> Patterns = new[] { "solid", "gradient", "chase", "white", "ramp" };
+
+    private const int RampSteps = 8;
+
+    private static readonly (byte r, byte g, byte b)[] SolidCycle =
+    {
+        (255, 0, 0), (0, 255, 0), (0, 0, 255), (255, 255, 255), (0, 0, 0)
+    };
+
+    /// <summary>
+    /// Returns the bytes of an AMb2 file. Frame <c>i</c> has timestamp <c>i * 1e6 / fps</c> microseconds.
+    /// Throws <see cref="ArgumentException"/> for unknown patterns or formats.
+    /// </summary>
+    public static byte[] Generate(string pattern, double seconds, double fps, ushort top, ushort bottom, ushort left, ushort right, byte format)
+    {
+        pattern = (pattern ?? string.Empty).Trim().ToLowerInvariant();
+        if (!Patterns.Contains(pattern))
+        {
+            throw new ArgumentException($"Unknown fixture pattern '{pattern}'", nameof(pattern));
+        }
+
+        if (format > AmbilightBinary.FormatRgbw16)
+        {
+            throw new ArgumentException($"Unsupported AMb2 format {format}", nameof(format));
+        }
+
+        bool rgbw = format == AmbilightBinary.FormatRgbw || format == AmbilightBinary.FormatRgbw16;
+        bool wide = format >= AmbilightBinary.FormatRgb16;
+        int leds = top + bottom + left + right;
+        int frameCount = Math.Max(1, (int)Math.Round(seconds * fps));
+
+        using var ms = new MemoryStream();
+        using var writer = new BinaryWriter(ms);
+        writer.Write(new[] { (byte)'A', (byte)'M', (byte)'b', (byte)'2' });
+        writer.Write((float)fps);
+        writer.Write(top);
+        writer.Write(bottom);
+        writer.Write(left);
+        writer.Write(right);
+        writer.Write(format);
+
+        for (int frame = 0; frame < frameCount; frame++)
+        {
+            double t = frame / fps;
+            writer.Write((ulong)Math.Round(frame * 1_000_000.0 / fps));
+
+            for (int led = 0; led < leds; led++)
+            {
+                var (r, g, b) = pattern switch
+                {
+                    "solid" => SolidCycle[(int)t % SolidCycle.Length],
+                    "gradient" => Hue((((double)led / Math.Max(1, leds)) + (t / 10.0)) % 1.0),
+                    "white" => ((byte)255, (byte)255, (byte)255),
+                    "ramp" => Gray(led * RampSteps / Math.Max(1, leds)),
+                    _ => led == frame % Math.Max(1, leds) ? ((byte)255, (byte)255, (byte)255) : ((byte)0, (byte)0, (byte)0)
+                };
+
+                WriteChannel(writer, r, wide);
+                WriteChannel(writer, g, wide);
+                WriteChannel(writer, b, wide);
+                if (rgbw)
+                {
+                    WriteChannel(writer, Math.Min(r, Math.Min(g, b)), wide);
+                }
+            }
+        }
+
+        writer.Flush();
+        return ms.ToArray();
+    }
+
+    private static void WriteChannel(BinaryWriter writer, byte value, bool wide)
+    {
+        if (wide)
+        {
+            // 0-255 maps onto 0-65535 exactly (x * 257), matching how the player scales 16-bit values back.
+            writer.Write((ushort)(value * 257));
+        }
+        else
+        {
+            writer.Write(value);
+        }
+    }
+
+    private static (byte r, byte g, byte b) Gray(int step)
+    {
+        byte v = (byte)Math.Round(255.0 * step / (RampSteps - 1));
+        return (v, v, v);
+    }
+
+    private static (byte r, byte g, byte b) Hue(double h)
+    {
+        double x = h * 6.0;
+        int sector = (int)x % 6;
+        double f = x - Math.Floor(x);
+        byte up = (byte)Math.Round(f * 255.0);
+        byte down = (byte)Math.Round((1.0 - f) * 255.0);
+        return sector switch
+        {
+            0 => (255, up, 0),
+            1 => (down, 255, 0),
+            2 => (0, 255, up),
+            3 => (0, down, 255),
+            4 => (up, 0, 255),
+            _ => (255, 0, down)
+        };
+    }
+}
@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (C) 2026 Jellyfin Ambilight Contributors
+// This file is part of Jellyfin Ambilight Plugin.
+// Jellyfin Ambilight Plugin is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+using System;
+using System.Collections.Concurrent;
+
+namespace Jellyfin.Plugin.Ambilight.Services;
+
+/// <summary>
+/// <c>x^exponent</c> on [0, 1] from a linearly interpolated lookup table, replacing the per-channel
+/// <see cref="MathF.Pow(float, float)"/> calls of the player's color pipeline. Inputs are clamped to [0, 1]
+/// (NaN counts as 0). The first step above 0 is computed exactly, since curves with an exponent below 1
+/// rise too steeply there for interpolation to match near-black levels.
+/// </summary>
+public sealed class AmbilightGammaTable
+{
+    private const int Size = 1024;
+
+    // Adaptive output gamma changes a little every frame, so shared tables are keyed by the exponent rounded to
+    // 1/SharedStepsPerUnit; a step is under one output level. Its exponent stays within [1/3, 1], so that is at
+    // most about 90 tables of 4 KB each.
+    private const float SharedStepsPerUnit = 128.0f;
+    private static readonly ConcurrentDictionary<int, AmbilightGammaTable> SharedTables = new();
+
+    private readonly float[] _values = new float[Size + 1];
+
+    public AmbilightGammaTable(float exponent)
+    {
+        Exponent = exponent;
+        for (int i = 0; i <= Size; i++)
+        {
+            _values[i] = MathF.Pow(i / (float)Size, exponent);
+        }
+    }
+
+    public float Exponent { get; }
+
+    /// <summary>
+    /// Returns a table shared by all players for <paramref name="exponent"/> rounded to 1/128.
+    /// </summary>
+    public static AmbilightGammaTable Shared(float exponent)
+    {
+        int key = (int)MathF.Round(exponent * SharedStepsPerUnit);
+        return SharedTables.GetOrAdd(key, k => new AmbilightGammaTable(k / SharedStepsPerUnit));
+    }
+
+    public float Apply(float x)
+    {
+        if (!(x > 0.0f))
+        {
+            return _values[0];
+        }
+
+        if (x >= 1.0f)
+        {
+            return _values[Size];
+        }
+
+        float position = x * Size;
+        int index = (int)position;
+        if (index == 0)
+        {
+            return MathF.Pow(x, Exponent);
+        }
+
+        return _values[index] + ((_values[index + 1] - _values[index]) * (position - index));
+    }
+}
@@ -0,0 +1,307 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (C) 2026 Jellyfin Ambilight Contributors
+// This file is part of Jellyfin Ambilight Plugin.
+// Jellyfin Ambilight Plugin is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+using System;
+using System.IO;
+using System.Net;
+using System.Net.Http;
+using System.Net.Http.Headers;
+using System.Threading;
+using System.Threading.Tasks;
+
+namespace Jellyfin.Plugin.Ambilight.Services;
+
+/// <summary>
+/// Read-only, seekable stream over a file served by HTTP(S), fetched in chunks with <c>Range</c> requests.
+/// Lets <see cref="AmbilightBinary.Load"/> read binaries from a remote store without the data folder being mounted.
+/// A failed chunk is retried a few times before the read fails, so a dropped connection does not restart the whole download.
+/// Sequential reads fetch the next chunk in the background, and <see cref="IsBuffered"/> lets a reader that must not
+/// wait on the network (the playback loop) check first.
+/// </summary>
+public sealed class AmbilightHttpRangeStream : Stream
+{
+    private const int ChunkSize = 4 * 1024 * 1024;
+    private const int MaxAttempts = 3;
+    private static readonly TimeSpan RetryDelay = TimeSpan.FromMilliseconds(500);
+
+    private static readonly HttpClient Http = new() { Timeout = TimeSpan.FromSeconds(30) };
+
+    private readonly Uri _uri;
+    private readonly string? _authorization;
+    private readonly long _length;
+    private long _position;
+    private byte[] _chunk = Array.Empty<byte>();
+    private long _chunkStart = -1;
+
+    // Read-ahead: at most one chunk is fetched in the background at a time, and taken over by the reader once done.
+    // A chunk that continues the current one is joined to it, keeping up to a chunk before it for short look-backs.
+    private readonly CancellationTokenSource _prefetchCts = new();
+    private Task<byte[]>? _prefetch;
+    private long _prefetchStart = -1;
+
+    private AmbilightHttpRangeStream(Uri uri, string? authorization, long length)
+    {
+        _uri = uri;
+        _authorization = authorization;
+        _length = length;
+    }
+
+    public static bool IsRemote(string path)
+        => path.StartsWith("http://", StringComparison.OrdinalIgnoreCase) || path.StartsWith("https://", StringComparison.OrdinalIgnoreCase);
+
+    /// <summary>
+    /// Opens <paramref name="url"/>, learning its length from a one-byte range request. Throws
+    /// <see cref="FileNotFoundException"/> for 404 and <see cref="IOException"/> when the server does not support ranges.
+    /// </summary>
+    public static AmbilightHttpRangeStream Open(string url, string? authorization = null)
+    {
+        var uri = new Uri(url);
+        using var response = Send(uri, authorization, 0, 0);
+        if (response.StatusCode == HttpStatusCode.NotFound)
+        {
+            throw new FileNotFoundException($"Remote binary not found: {url}", url);
+        }
+
+        if (response.StatusCode != HttpStatusCode.PartialContent || response.Content.Headers.ContentRange?.Length is not { } length)
+        {
+            throw new IOException($"Server does not support range requests for {url} (HTTP {(int)response.StatusCode})");
+        }
+
+        return new AmbilightHttpRangeStream(uri, authorization, length);
+    }
+
+    public override bool CanRead => true;
+    public override bool CanSeek => true;
+    public override bool CanWrite => false;
+    public override long Length => _length;
+
+    public override long Position
+    {
+        get => _position;
+        set => _position = Math.Clamp(value, 0, _length);
+    }
+
+    public override int Read(byte[] buffer, int offset, int count)
+    {
+        if (_position >= _length || count == 0)
+        {
+            return 0;
+        }
+
+        if (!Covers(_position, _position + 1))
+        {
+            if (_prefetch != null && _position >= _prefetchStart && _position < _prefetchStart + ChunkSize)
+            {
+                // The chunk is on its way already; wait for it rather than asking for it twice.
+                ((IAsyncResult)_prefetch).AsyncWaitHandle.WaitOne();
+            }
+
+            if (_prefetch is { IsCompleted: true })
+            {
+                AdoptPrefetch();
+            }
+
+            if (!Covers(_position, _position + 1))
+            {
+                FetchChunk(_position);
+            }
+        }
+
+        int available = (int)(_chunkStart + _chunk.Length - _position);
+        int n = Math.Min(count, available);
+        Buffer.BlockCopy(_chunk, (int)(_position - _chunkStart), buffer, offset, n);
+        _position += n;
+
+        // Reading on towards the end of the chunk: fetch what follows it in the background.
+        long chunkEnd = _chunkStart + _chunk.Length;
+        if (_prefetch == null && chunkEnd < _length && chunkEnd - _position < ChunkSize / 2)
+        {
+            StartPrefetch(chunkEnd);
+        }
+
+        return n;
+    }
+
+    /// <summary>
+    /// True when the <paramref name="count"/> bytes at <paramref name="position"/> can be read without a network round
+    /// trip. Otherwise starts fetching them in the background, if nothing else is being fetched, and returns false.
+    /// </summary>
+    public bool IsBuffered(long position, int count)
+    {
+        long end = Math.Min(_length, position + count);
+        if (Covers(position, end))
+        {
+            return true;
+        }
+
+        if (_prefetch is { IsCompleted: true })
+        {
+            AdoptPrefetch();
+            if (Covers(position, end))
+            {
+                return true;
+            }
+        }
+
+        if (_prefetch == null)
+        {
+            // Continue the current chunk when the range starts in it, so the two are joined.
+            StartPrefetch(Covers(position, position + 1) ? _chunkStart + _chunk.Length : position);
+        }
+
+        return false;
+    }
+
+    public override long Seek(long offset, SeekOrigin origin)
+    {
+        Position = origin switch
+        {
+            SeekOrigin.Begin => offset,
+            SeekOrigin.Current => _position + offset,
+            _ => _length + offset
+        };
+        return _position;
+    }
+
+    public override void Flush()
+    {
+    }
+
+    public override void SetLength(long value) => throw new NotSupportedException();
+
+    public override void Write(byte[] buffer, int offset, int count) => throw new NotSupportedException();
+
+    protected override void Dispose(bool disposing)
+    {
+        if (disposing)
+        {
+            _prefetchCts.Cancel();
+            _prefetchCts.Dispose();
+        }
+
+        base.Dispose(disposing);
+    }
+
+    private bool Covers(long from, long to) => _chunkStart >= 0 && from >= _chunkStart && to <= _chunkStart + _chunk.Length;
+
+    private void StartPrefetch(long start)
+    {
+        var token = _prefetchCts.Token;
+        _prefetchStart = start;
+        _prefetch = Task.Run(() => FetchChunkAsync(start, token), token);
+    }
+
+    private void AdoptPrefetch()
+    {
+        var done = _prefetch!;
+        _prefetch = null;
+        if (!done.IsCompletedSuccessfully)
+        {
+            // Failed for good; the chunk is fetched again when it is needed.
+            _ = done.Exception;
+            return;
+        }
+
+        var data = done.Result;
+        if (_chunkStart >= 0 && _prefetchStart == _chunkStart + _chunk.Length)
+        {
+            long keepFrom = Math.Max(_chunkStart, _prefetchStart - ChunkSize);
+            int keep = (int)(_prefetchStart - keepFrom);
+            var joined = new byte[keep + data.Length];
+            Buffer.BlockCopy(_chunk, (int)(keepFrom - _chunkStart), joined, 0, keep);
+            Buffer.BlockCopy(data, 0, joined, keep, data.Length);
+            _chunk = joined;
+            _chunkStart = keepFrom;
+        }
+        else
+        {
+            _chunk = data;
+            _chunkStart = _prefetchStart;
+        }
+    }
+
+    private void FetchChunk(long start)
+    {
+        long end = Math.Min(_length, start + ChunkSize) - 1;
+        for (int attempt = 1; ; attempt++)
+        {
+            try
+            {
+                using var response = Send(_uri, _authorization, start, end);
+                if (response.StatusCode != HttpStatusCode.PartialContent)
+                {
+                    throw new IOException($"Range request for {_uri} returned HTTP {(int)response.StatusCode}");
+                }
+
+                using var body = response.Content.ReadAsStream();
+                using var ms = new MemoryStream((int)(end - start + 1));
+                body.CopyTo(ms);
+                _chunk = ms.ToArray();
+                _chunkStart = start;
+                if (_chunk.Length == 0)
+                {
+                    throw new IOException($"Empty range response for {_uri}");
+                }
+
+                return;
+            }
+            catch (Exception ex) when (attempt < MaxAttempts && ex is HttpRequestException or IOException or TaskCanceledException)
+            {
+                // Retry the chunk; the next attempt fails for real.
+            }
+        }
+    }
+
+    private async Task<byte[]> FetchChunkAsync(long start, CancellationToken cancellationToken)
+    {
+        long end = Math.Min(_length, start + ChunkSize) - 1;
+        for (int attempt = 1; ; attempt++)
+        {
+            try
+            {
+                using var request = CreateRequest(_uri, _authorization, start, end);
+                using var response = await Http.SendAsync(request, HttpCompletionOption.ResponseHeadersRead, cancellationToken).ConfigureAwait(false);
+                if (response.StatusCode != HttpStatusCode.PartialContent)
+                {
+                    throw new IOException($"Range request for {_uri} returned HTTP {(int)response.StatusCode}");
+                }
+
+                var data = await response.Content.ReadAsByteArrayAsync(cancellationToken).ConfigureAwait(false);
+                if (data.Length == 0)
+                {
+                    throw new IOException($"Empty range response for {_uri}");
+                }
+
+                return data;
+            }
+            catch (Exception ex) when (attempt < MaxAttempts && !cancellationToken.IsCancellationRequested && ex is HttpRequestException or IOException or TaskCanceledException)
+            {
+                // Nobody is waiting on this one, so back off before retrying.
+                await Task.Delay(RetryDelay * attempt, cancellationToken).ConfigureAwait(false);
+            }
+        }
+    }
+
+    private static HttpResponseMessage Send(Uri uri, string? authorization, long from, long to)
+    {
+        using var request = CreateRequest(uri, authorization, from, to);
+        return Http.Send(request, HttpCompletionOption.ResponseHeadersRead);
+    }
+
+    private static HttpRequestMessage CreateRequest(Uri uri, string? authorization, long from, long to)
+    {
+        var request = new HttpRequestMessage(HttpMethod.Get, uri);
+        request.Headers.Range = new RangeHeaderValue(from, to);
+        if (!string.IsNullOrWhiteSpace(authorization))
+        {
+            request.Headers.TryAddWithoutValidation("Authorization", authorization.Trim());
+        }
+
+        return request;
+    }
+}
@@ -7,10 +7,16 @@
 // (at your option) any later version.
 
 using System;
+using System.Buffers.Binary;
 using System.Collections.Generic;
 using System.Diagnostics;
 using System.Globalization;
 using System.IO;
+using System.Linq;
+using System.Runtime.InteropServices;
+using System.Security.Cryptography;
+using System.Text;
+using System.Text.Json;
 using System.Threading;
 using System.Threading.Tasks;
 using Microsoft.Extensions.Logging;
@@ -19,23 +25,220 @@ namespace Jellyfin.Plugin.Ambilight.Services;
 
 /// <summary>
 /// In-process implementation of the ambilight-extractor logic in C#.
-/// Produces AMb2 binary files compatible with the Rust player/C# player.
+/// Produces AMb3 binary files (AMb2 records followed by a seek index) for the C# player.
 /// </summary>
 public sealed class AmbilightInProcessExtractor
 {
     private readonly ILogger<AmbilightInProcessExtractor> _logger;
     private readonly PluginConfiguration _config;
     private readonly string _ffmpegPath;
+    private readonly EmbeddedBinariesResolver? _binaries;
 
-    // Modest working resolution – we don't need full 4K to compute edge colors.
-    private const int ExtractWidth = 320;
-    private const int ExtractHeight = 180;
+    // Modest working resolution – we don't need full 4K to compute edge colors. Tune previews always use it;
+    // extraction uses AnalysisWidth x AnalysisHeight, which default to it.
+    private const int PreviewWidth = 320;
+    private const int PreviewHeight = 180;
 
-    public AmbilightInProcessExtractor(ILogger<AmbilightInProcessExtractor> logger, PluginConfiguration config)
+    private const int OutputBufferSize = 1024 * 1024;
+
+    public AmbilightInProcessExtractor(ILogger<AmbilightInProcessExtractor> logger, PluginConfiguration config, EmbeddedBinariesResolver? binaries = null)
     {
         _logger = logger;
         _config = config;
         _ffmpegPath = ResolveFfmpegPath();
+        _binaries = binaries;
+    }
+
+    /// <summary>How many pixels of each zone are sampled, from <see cref="PluginConfiguration.ExtractionQuality"/>.</summary>
+    private enum ZoneQuality
+    {
+        /// <summary>Plain mean of every fourth pixel in each direction; no edge detection.</summary>
+        Fast,
+
+        /// <summary>Edge and center weighting over every second pixel in each direction.</summary>
+        Balanced,
+
+        /// <summary>Edge and center weighting over every pixel of the zone.</summary>
+        Best
+    }
+
+    /// <summary>Zone color algorithm from <see cref="PluginConfiguration.ExtractionQuality"/>; unknown values mean best.</summary>
+    private ZoneQuality Quality => (_config.ExtractionQuality ?? string.Empty).Trim().ToLowerInvariant() switch
+    {
+        "fast" => ZoneQuality.Fast,
+        "balanced" => ZoneQuality.Balanced,
+        _ => ZoneQuality.Best
+    };
+
+    /// <summary>How a zone's pixels are reduced to one color.</summary>
+    private enum ZoneColorMode
+    {
+        /// <summary>Edge and center weighted mean (plain mean at fast quality).</summary>
+        Edge,
+
+        /// <summary>Plain mean of the zone.</summary>
+        Mean,
+
+        /// <summary>Most common color: the mean of the fullest bin of a coarse RGB histogram.</summary>
+        Dominant,
+
+        /// <summary>Per-channel median.</summary>
+        Median
+    }
+
+    /// <summary>What makes a pixel stand out for <see cref="PluginConfiguration.ExtractionSaliency"/>.</summary>
+    private enum ZoneSaliency
+    {
+        Off,
+
+        /// <summary>Luma far from the zone's mean: a subject against a flat background.</summary>
+        Contrast,
+
+        /// <summary>Luma changed since the previous frame: what moves in front of a static background.</summary>
+        Motion
+    }
+
+    /// <summary>Saliency weighting from <see cref="PluginConfiguration.ExtractionSaliency"/>; unknown values mean off.</summary>
+    private ZoneSaliency Saliency => (_config.ExtractionSaliency ?? string.Empty).Trim().ToLowerInvariant() switch
+    {
+        "contrast" => ZoneSaliency.Contrast,
+        "motion" => ZoneSaliency.Motion,
+        _ => ZoneSaliency.Off
+    };
+
+    /// <summary>
+    /// Zone color mode from <paramref name="colorMode"/> (a library's override), else
+    /// <see cref="PluginConfiguration.ExtractionColorMode"/>; unknown values mean edge.
+    /// </summary>
+    private ZoneColorMode ColorModeFor(string? colorMode) => (string.IsNullOrWhiteSpace(colorMode) ? _config.ExtractionColorMode ?? string.Empty : colorMode).Trim().ToLowerInvariant() switch
+    {
+        "mean" => ZoneColorMode.Mean,
+        "dominant" => ZoneColorMode.Dominant,
+        "median" => ZoneColorMode.Median,
+        _ => ZoneColorMode.Edge
+    };
+
+    /// <summary>
+    /// Height frames are scaled to for zone extraction, from <see cref="PluginConfiguration.AnalysisHeight"/>
+    /// (default 180); the width follows at 16:9. ffmpeg scales and converts to RGB in one pass while decoding.
+    /// </summary>
+    private int AnalysisHeight => Math.Clamp(_config.AnalysisHeight > 0 ? _config.AnalysisHeight : PreviewHeight, 72, 1080) & ~1;
+
+    private int AnalysisWidth => (int)Math.Round(AnalysisHeight * 16 / 9.0 / 2) * 2;
+
+    /// <summary>
+    /// True when decode and zone averaging are delegated to the Rust extractor (<c>--zones-only</c>),
+    /// which streams zone colors back while this class keeps file writing, checksums and progress.
+    /// </summary>
+    private bool UseExternalBackend => string.Equals(_config.ExtractorBackend, "external", StringComparison.OrdinalIgnoreCase);
+
+    /// <summary>
+    /// Threads for zone extraction: <see cref="PluginConfiguration.ExtractionThreads"/>, or half the cores when it is 0,
+    /// leaving the rest for ffmpeg's decoding and for Jellyfin itself.
+    /// </summary>
+    private int ExtractionWorkers => _config.ExtractionThreads > 0
+        ? Math.Min(_config.ExtractionThreads, Environment.ProcessorCount)
+        : Math.Max(1, Environment.ProcessorCount / 2);
+
+    /// <summary>
+    /// Cores extraction may keep busy on average, from <see cref="PluginConfiguration.ExtractionCpuLimitPercent"/>
+    /// of the whole machine, or 0 for no limit.
+    /// </summary>
+    private double CpuBudgetCores => _config.ExtractionCpuLimitPercent is > 0 and < 100
+        ? _config.ExtractionCpuLimitPercent / 100.0 * Environment.ProcessorCount
+        : 0;
+
+    private string BuildZonesOnlyArguments(string videoPath, ushort top, ushort bottom, ushort left, ushort right, bool rgbw, string decoder, int? streamIndex, ZoneColorMode colorMode)
+    {
+        // The backend understands auto, vaapi, nvdec and v4l2m2m; anything else decodes in software there.
+        var hwaccel = decoder is "vaapi" or "nvdec" or "v4l2m2m" ? decoder : "none";
+        var args = string.Create(
+            CultureInfo.InvariantCulture,
+            $"--zones-only --input \"{videoPath}\" --top {top} --bottom {bottom} --left {left} --right {right} --depth-factor {_config.AmbilightZoneDepthFactor} --edge-weight {Math.Clamp(_config.AmbilightEdgeWeight, 0.0, 1.0)} --hwaccel {hwaccel} --quality {Quality.ToString().ToLowerInvariant()}");
+        if (AnalysisHeight != PreviewHeight)
+        {
+            args += $" --analysis-height {AnalysisHeight}";
+        }
+
+        if (_config.ExtractionThreads > 0)
+        {
+            args += $" --threads {_config.ExtractionThreads}";
+        }
+
+        if (_config.ExtractionDecoderThreads > 0)
+        {
+            args += $" --decoder-threads {_config.ExtractionDecoderThreads}";
+        }
+
+        if (streamIndex.HasValue)
+        {
+            args += $" --video-stream-index {streamIndex.Value}";
+        }
+
+        if (BandDepthPercent.IsSet)
+        {
+            args += $" --band-depth-percent {BandDepthPercent}";
+        }
+
+        if (Corners == AmbilightBinary.CornersDedicated)
+        {
+            args += $" --corners dedicated --corner-zones {CornerZones}";
+        }
+        else if (Corners == AmbilightBinary.CornersBlended)
+        {
+            args += " --corners blend";
+        }
+
+        if (colorMode != ZoneColorMode.Edge)
+        {
+            args += $" --color-mode {colorMode.ToString().ToLowerInvariant()}";
+        }
+
+        if (Saliency != ZoneSaliency.Off)
+        {
+            args += $" --saliency {Saliency.ToString().ToLowerInvariant()}";
+        }
+
+        return rgbw ? args + " --rgbw" : args;
+    }
+
+    /// <summary>
+    /// Reads one <c>--zones-only</c> record: a little-endian u32 byte length followed by that many bytes
+    /// of zone colors. Returns false at end of stream.
+    /// </summary>
+    private static async Task<bool> ReadZoneRecordAsync(Stream stream, byte[] lengthBuffer, byte[] zoneColors, CancellationToken cancellationToken)
+    {
+        if (!await ReadExactAsync(stream, lengthBuffer, cancellationToken).ConfigureAwait(false))
+        {
+            return false;
+        }
+
+        var length = BinaryPrimitives.ReadUInt32LittleEndian(lengthBuffer);
+        if (length != zoneColors.Length)
+        {
+            throw new InvalidDataException($"Extractor backend sent a {length}-byte zone record, expected {zoneColors.Length}");
+        }
+
+        return await ReadExactAsync(stream, zoneColors, cancellationToken).ConfigureAwait(false);
+    }
+
+    /// <summary>
+    /// Fills <paramref name="buffer"/> completely. Returns false on end of stream (a partial read is discarded).
+    /// </summary>
+    private static async Task<bool> ReadExactAsync(Stream stream, byte[] buffer, CancellationToken cancellationToken)
+    {
+        int readTotal = 0;
+        while (readTotal < buffer.Length)
+        {
+            int n = await stream.ReadAsync(buffer.AsMemory(readTotal), cancellationToken).ConfigureAwait(false);
+            if (n <= 0)
+            {
+                return false;
+            }
+            readTotal += n;
+        }
+
+        return true;
     }
 
     private string ResolveFfmpegPath()
@@ -98,64 +301,87 @@ public sealed class AmbilightInProcessExtractor
 
     /// <summary>
     /// Probe video file to get the actual FPS (frames per second).
-    /// Uses ffprobe to read the avg_frame_rate from the video stream.
+    /// Cross-checks avg_frame_rate against r_frame_rate and the spacing of the first packet PTS values;
+    /// when the header rates disagree or are implausible (remuxes reporting 1000 fps are common),
+    /// the measured rate is used instead. The returned value is what gets stored in the AMb2 header.
     /// </summary>
-    private async Task<float> ProbeVideoFps(string videoPath, CancellationToken cancellationToken)
+    private async Task<float> ProbeVideoFps(string videoPath, string stream, CancellationToken cancellationToken)
     {
         const float fallbackFps = 24.0f;
 
         try
         {
-            // Construct ffprobe path from ffmpeg path (usually in same directory)
-            string ffprobePath;
-            if (Path.IsPathRooted(_ffmpegPath))
+            // ffprobe -v error -select_streams <stream> -show_entries stream=avg_frame_rate,r_frame_rate -of default=noprint_wrappers=1 "video.mp4"
+            var output = await RunFfprobeAsync(
+                $"-v error -select_streams {stream} -show_entries stream=avg_frame_rate,r_frame_rate -of default=noprint_wrappers=1 \"{videoPath}\"",
+                cancellationToken).ConfigureAwait(false);
+
+            double avgFps = 0.0;
+            double rFps = 0.0;
+            foreach (var line in (output ?? string.Empty).Split('\n', StringSplitOptions.RemoveEmptyEntries))
+            {
+                var kv = line.Trim().Split('=', 2);
+                if (kv.Length != 2)
+                {
+                    continue;
+                }
+
+                if (kv[0] == "avg_frame_rate")
+                {
+                    avgFps = ParseFrameRate(kv[1]);
+                }
+                else if (kv[0] == "r_frame_rate")
+                {
+                    rFps = ParseFrameRate(kv[1]);
+                }
+            }
+
+            double measuredFps = await MeasurePtsFps(videoPath, stream, cancellationToken).ConfigureAwait(false);
+
+            bool avgPlausible = IsPlausibleFps(avgFps);
+            bool rPlausible = IsPlausibleFps(rFps);
+            bool headersAgree = avgPlausible && rPlausible && RelativeDifference(avgFps, rFps) < 0.01;
+
+            double chosen;
+            if (headersAgree)
             {
-                // Absolute path: replace filename only, keep directory
-                var dir = Path.GetDirectoryName(_ffmpegPath);
-                ffprobePath = Path.Combine(dir ?? "/", "ffprobe");
+                chosen = avgFps;
             }
-            else
+            else if (IsPlausibleFps(measuredFps))
             {
-                // Relative or PATH: just use "ffprobe"
-                ffprobePath = "ffprobe";
+                chosen = measuredFps;
+                _logger.LogWarning(
+                    "[Ambilight] Extractor: header frame rates disagree or look bogus for {Path} (avg_frame_rate={Avg:F3}, r_frame_rate={R:F3}); using measured {Measured:F3} fps",
+                    videoPath, avgFps, rFps, measuredFps);
             }
-            
-            // ffprobe -v error -select_streams v:0 -show_entries stream=avg_frame_rate -of default=noprint_wrappers=1:nokey=1 "video.mp4"
-            var ffprobe = new Process
+            else if (avgPlausible)
             {
-                StartInfo = new ProcessStartInfo
-                {
-                    FileName = ffprobePath,
-                    Arguments = $"-v error -select_streams v:0 -show_entries stream=avg_frame_rate -of default=noprint_wrappers=1:nokey=1 \"{videoPath}\"",
-                    UseShellExecute = false,
-                    RedirectStandardOutput = true,
-                    RedirectStandardError = true,
-                    CreateNoWindow = true
-                }
-            };
+                chosen = avgFps;
+            }
+            else if (rPlausible)
+            {
+                chosen = rFps;
+            }
+            else
+            {
+                chosen = 0.0;
+            }
 
-            ffprobe.Start();
-            var output = await ffprobe.StandardOutput.ReadToEndAsync(cancellationToken).ConfigureAwait(false);
-            await ffprobe.WaitForExitAsync(cancellationToken).ConfigureAwait(false);
+            // Even when both headers agree, a large gap to the measured spacing means the stream lies.
+            if (headersAgree && IsPlausibleFps(measuredFps) && RelativeDifference(chosen, measuredFps) > 0.05)
+            {
+                _logger.LogWarning(
+                    "[Ambilight] Extractor: header frame rate {Header:F3} does not match measured PTS spacing {Measured:F3} fps for {Path}; using measured value",
+                    chosen, measuredFps, videoPath);
+                chosen = measuredFps;
+            }
 
-            if (ffprobe.ExitCode == 0 && !string.IsNullOrWhiteSpace(output))
+            if (chosen > 0.0)
             {
-                // Parse fraction like "24000/1001" or "30/1"
-                var parts = output.Trim().Split('/');
-                if (parts.Length == 2 && 
-                    int.TryParse(parts[0], out var numerator) && 
-                    int.TryParse(parts[1], out var denominator) &&
-                    denominator > 0)
-                {
-                    var fps = (float)numerator / denominator;
-                    if (fps > 0.0f && fps < 200.0f) // Sanity check
-                    {
-                        return fps;
-                    }
-                }
+                return (float)chosen;
             }
         }
-        catch (Exception ex)
+        catch (Exception ex) when (ex is not OperationCanceledException)
         {
             _logger.LogWarning(ex, "[Ambilight] Extractor: failed to probe FPS with ffprobe, falling back to {Fps}", fallbackFps);
         }
@@ -164,370 +390,3682 @@ public sealed class AmbilightInProcessExtractor
         return fallbackFps;
     }
 
-    private string BuildFfmpegArguments(string videoPath)
-    {
-        var hwaccel = _config.HardwareAcceleration ?? "auto";
-        var baseArgs = "-hide_banner -loglevel error";
-        
-        // Hardware acceleration options
-        // Note: For most modes we keep it simple - just enable hwaccel for decoding
-        // and let ffmpeg handle the format conversion automatically
-        string hwaccelArgs = hwaccel.ToLower() switch
-        {
-            "vaapi" => "-hwaccel vaapi -hwaccel_device /dev/dri/renderD128",
-            "qsv" => "-hwaccel qsv",
-            "cuda" => "-hwaccel cuda",
-            "videotoolbox" => "-hwaccel videotoolbox",
-            "none" => "",
-            _ => "" // "auto" - let ffmpeg auto-detect, but don't force it
-        };
-        
-        // Use simple software filter chain - hardware acceleration is only for decoding
-        // ffmpeg will automatically transfer frames to system memory for filtering
-        string filterChain = $"scale={ExtractWidth}:{ExtractHeight}";
-        
-        return $"{baseArgs} {hwaccelArgs} -i \"{videoPath}\" -vf {filterChain} -pix_fmt rgb24 -f rawvideo pipe:1".Trim();
-    }
-
-    private async Task<float> ProbeVideoDuration(string videoPath, CancellationToken cancellationToken)
+    /// <summary>
+    /// Measures the frame rate from the median PTS delta of the first packets of the video stream.
+    /// Returns 0 when it cannot be measured.
+    /// </summary>
+    private async Task<double> MeasurePtsFps(string videoPath, string stream, CancellationToken cancellationToken)
     {
-        const float fallbackDuration = 60.0f; // 1 minute fallback
+        const int sampleFrames = 240;
 
         try
         {
-            // Construct ffprobe path from ffmpeg path
-            string ffprobePath;
-            if (Path.IsPathRooted(_ffmpegPath))
+            var output = await RunFfprobeAsync(
+                $"-v error -select_streams {stream} -read_intervals %+#{sampleFrames} -show_entries packet=pts_time -of csv=p=0 \"{videoPath}\"",
+                cancellationToken).ConfigureAwait(false);
+
+            var pts = new List<double>();
+            foreach (var line in (output ?? string.Empty).Split('\n', StringSplitOptions.RemoveEmptyEntries))
             {
-                var dir = Path.GetDirectoryName(_ffmpegPath);
-                ffprobePath = Path.Combine(dir ?? "/", "ffprobe");
+                if (double.TryParse(line.Trim().TrimEnd(','), NumberStyles.Float, CultureInfo.InvariantCulture, out var t))
+                {
+                    pts.Add(t);
+                }
             }
-            else
+
+            if (pts.Count < 3)
             {
-                ffprobePath = "ffprobe";
+                return 0.0;
             }
-            
-            // ffprobe -v error -show_entries format=duration -of default=noprint_wrappers=1:nokey=1 "video.mp4"
-            var ffprobe = new Process
+
+            // Packets arrive in decode order; B-frames reorder PTS, so sort before diffing.
+            pts.Sort();
+            var deltas = new List<double>(pts.Count - 1);
+            for (int i = 1; i < pts.Count; i++)
             {
-                StartInfo = new ProcessStartInfo
+                double d = pts[i] - pts[i - 1];
+                if (d > 0.0)
                 {
-                    FileName = ffprobePath,
-                    Arguments = $"-v error -show_entries format=duration -of default=noprint_wrappers=1:nokey=1 \"{videoPath}\"",
-                    UseShellExecute = false,
-                    RedirectStandardOutput = true,
-                    RedirectStandardError = true,
-                    CreateNoWindow = true
+                    deltas.Add(d);
                 }
-            };
-
-            ffprobe.Start();
-            var output = await ffprobe.StandardOutput.ReadToEndAsync(cancellationToken).ConfigureAwait(false);
-            await ffprobe.WaitForExitAsync(cancellationToken).ConfigureAwait(false);
+            }
 
-            if (ffprobe.ExitCode == 0 && !string.IsNullOrWhiteSpace(output))
+            if (deltas.Count == 0)
             {
-                if (float.TryParse(output.Trim(), out var duration) && duration > 0.0f)
-                {
-                    return duration;
-                }
+                return 0.0;
             }
+
+            deltas.Sort();
+            double median = deltas[deltas.Count / 2];
+            return median > 0.0 ? 1.0 / median : 0.0;
         }
-        catch (Exception ex)
+        catch (Exception ex) when (ex is not OperationCanceledException)
         {
-            _logger.LogWarning(ex, "[Ambilight] Extractor: Failed to probe video duration for {Path}", videoPath);
+            _logger.LogDebug(ex, "[Ambilight] Extractor: could not measure PTS frame rate for {Path}", videoPath);
+            return 0.0;
         }
-
-        return fallbackDuration;
     }
 
-    public async Task<bool> ExtractAsync(string videoPath, string outputPath, CancellationToken cancellationToken, IProgress<(ulong current, ulong total)>? progress = null)
+    /// <summary>
+    /// Presentation time of every video frame, in seconds from the first one, read from the container's packets
+    /// (demuxing only, no decoding), plus how far the first frame sits from the file's start time, which is what
+    /// ffmpeg's <c>-ss</c> counts from. Null when any packet lacks a PTS, the packet count does not match the stream's
+    /// frame count (field-coded or split packets) or ffprobe fails; frames are then timed by their index and the frame rate.
+    /// </summary>
+    private async Task<(double[] Pts, double Origin)?> ProbeFramePtsAsync(string videoPath, string stream, CancellationToken cancellationToken)
     {
-        if (string.IsNullOrEmpty(videoPath) || !File.Exists(videoPath))
-        {
-            _logger.LogWarning("[Ambilight] Extractor: video file not found: {Path}", videoPath);
-            return false;
-        }
-
         try
         {
-            // Probe video to get actual FPS and duration
-            float fps = await ProbeVideoFps(videoPath, cancellationToken).ConfigureAwait(false);
-            float duration = await ProbeVideoDuration(videoPath, cancellationToken).ConfigureAwait(false);
-            ulong estimatedFrames = (ulong)(duration * fps);
-            
-            if (_config.Debug)
-            {
-                _logger.LogInformation("[Ambilight] Extractor: video FPS: {Fps:F3}, duration: {Duration:F1}s, estimated frames: {Frames}", fps, duration, estimatedFrames);
-            }
-
-            // Prepare header values
-            ushort topCount = (ushort)Math.Max(0, _config.AmbilightTopLedCount);
-            ushort bottomCount = (ushort)Math.Max(0, _config.AmbilightBottomLedCount);
-            ushort leftCount = (ushort)Math.Max(0, _config.AmbilightLeftLedCount);
-            ushort rightCount = (ushort)Math.Max(0, _config.AmbilightRightLedCount);
-            bool rgbw = _config.AmbilightRgbw;
-            byte fmt = rgbw ? (byte)1 : (byte)0;
-
-            int bytesPerLed = rgbw ? 4 : 3;
-            var zones = ComputeLedZones(ExtractWidth, ExtractHeight, topCount, bottomCount, leftCount, rightCount);
-            int ledsPerFrame = zones.Count;
-            if (ledsPerFrame == 0)
+            var packets = await RunFfprobeAsync(
+                $"-v error -select_streams {stream} -show_entries packet=pts_time -of csv=p=0 \"{videoPath}\"",
+                cancellationToken).ConfigureAwait(false);
+            if (packets == null)
             {
-                _logger.LogWarning("[Ambilight] Extractor: no LED zones computed – check LED counts.");
-                return false;
+                return null;
             }
 
-            // Build ffmpeg arguments with hardware acceleration
-            string ffmpegArgs = BuildFfmpegArguments(videoPath);
-            
-            // Start ffmpeg to produce a scaled RGB24 raw video stream.
-            var ffmpeg = new Process
+            var pts = new List<double>();
+            foreach (var line in packets.Split('\n', StringSplitOptions.RemoveEmptyEntries))
             {
-                StartInfo = new ProcessStartInfo
+                var value = line.Trim().TrimEnd(',');
+                if (value.Length == 0)
                 {
-                    FileName = _ffmpegPath,
-                    Arguments = ffmpegArgs,
-                    UseShellExecute = false,
-                    RedirectStandardOutput = true,
-                    RedirectStandardError = true,
-                    CreateNoWindow = true,
-                    WorkingDirectory = Path.GetDirectoryName(videoPath) ?? "/"
+                    continue;
                 }
-            };
 
-            Task<string>? stderrTask = null;
-            
-            try
-            {
-                if (_config.Debug)
+                if (!double.TryParse(value, NumberStyles.Float, CultureInfo.InvariantCulture, out var t))
                 {
-                    _logger.LogInformation("[Ambilight] Extractor: starting ffmpeg for {Path}", videoPath);
-                    _logger.LogInformation("[Ambilight] Extractor: ffmpeg args: {Args}", ffmpegArgs);
+                    return null; // N/A: no usable timing in this container
                 }
-                ffmpeg.Start();
-                
-                // Capture stderr asynchronously for error reporting
-                stderrTask = ffmpeg.StandardError.ReadToEndAsync(cancellationToken);
+
+                pts.Add(t);
             }
-            catch (Exception ex)
+
+            if (pts.Count < 2)
             {
-                _logger.LogError(ex, "[Ambilight] Extractor: failed to start ffmpeg. Ensure ffmpeg is installed and in PATH.");
-                return false;
+                return null;
             }
 
-            var stdout = ffmpeg.StandardOutput.BaseStream;
-            int frameSize = ExtractWidth * ExtractHeight * 3; // rgb24
-            var frameBuffer = new byte[frameSize];
-
-            // Accumulate AMb2 data in memory as the Rust extractor does.
-            using var ms = new MemoryStream();
-            using var writer = new BinaryWriter(ms);
-
-            // Write AMb2 header (magic + fps + counts + fmt)
-            writer.Write(new[] { (byte)'A', (byte)'M', (byte)'b', (byte)'2' });
-            writer.Write(fps);
-            writer.Write(topCount);
-            writer.Write(bottomCount);
-            writer.Write(leftCount);
-            writer.Write(rightCount);
-            writer.Write(fmt);
-
-            ulong frameIndex = 0;
-            var zoning = zones.ToArray();
-            var zoneColors = new byte[ledsPerFrame * bytesPerLed];
+            // One PTS per decoded frame only holds when every packet carries exactly one frame.
+            double expected = await ProbeStreamFrameCountAsync(videoPath, stream, cancellationToken).ConfigureAwait(false);
+            if (expected > 0 && Math.Abs(pts.Count - expected) > Math.Max(2.0, expected * 0.01))
+            {
+                _logger.LogDebug("[Ambilight] Extractor: {Packets} video packets but {Frames:F0} frames in {Path}; timing frames by the frame rate",
+                    pts.Count, expected, videoPath);
+                return null;
+            }
 
-            while (!cancellationToken.IsCancellationRequested)
+            // Packets arrive in decode order; B-frames reorder PTS, so sort to get presentation order.
+            pts.Sort();
+            double first = pts[0];
+            var relative = new double[pts.Count];
+            for (int i = 0; i < relative.Length; i++)
             {
-                int readTotal = 0;
-                while (readTotal < frameSize)
-                {
-                    int n = await stdout.ReadAsync(frameBuffer, readTotal, frameSize - readTotal, cancellationToken).ConfigureAwait(false);
-                    if (n <= 0)
-                    {
-                        readTotal = 0;
-                        break; // EOF
-                    }
-                    readTotal += n;
-                }
+                relative[i] = pts[i] - first;
+            }
 
-                if (readTotal == 0)
-                {
-                    break; // no more frames
-                }
+            var start = await RunFfprobeAsync(
+                $"-v error -show_entries format=start_time -of default=noprint_wrappers=1:nokey=1 \"{videoPath}\"",
+                cancellationToken).ConfigureAwait(false);
+            double origin = double.TryParse(start?.Trim(), NumberStyles.Float, CultureInfo.InvariantCulture, out var startTime)
+                ? Math.Max(0.0, first - startTime)
+                : 0.0;
+
+            return (relative, origin);
+        }
+        catch (Exception ex) when (ex is not OperationCanceledException)
+        {
+            _logger.LogDebug(ex, "[Ambilight] Extractor: could not read frame timestamps for {Path}", videoPath);
+            return null;
+        }
+    }
 
-                // Calculate timestamp in microseconds using actual video FPS (matching Rust implementation)
-                ulong tsUs = (ulong)(frameIndex * 1_000_000.0 / fps);
-                writer.Write(tsUs);
+    /// <summary>
+    /// Frame count of a video stream: <c>nb_frames</c> when the container records it, else the average frame rate times
+    /// the stream's (or the file's) duration. 0 when neither is known.
+    /// </summary>
+    private async Task<double> ProbeStreamFrameCountAsync(string videoPath, string stream, CancellationToken cancellationToken)
+    {
+        var output = await RunFfprobeAsync(
+            $"-v error -select_streams {stream} -show_entries stream=nb_frames,avg_frame_rate,duration:format=duration -of default=noprint_wrappers=1 \"{videoPath}\"",
+            cancellationToken).ConfigureAwait(false);
 
-                // Compute colors for each zone
-                ComputeFrameColors(frameBuffer, ExtractWidth, ExtractHeight, zoning, rgbw, zoneColors);
-                writer.Write(zoneColors);
+        double rate = 0.0;
+        double duration = 0.0;
+        foreach (var line in (output ?? string.Empty).Split('\n', StringSplitOptions.RemoveEmptyEntries))
+        {
+            var kv = line.Trim().Split('=', 2);
+            if (kv.Length != 2)
+            {
+                continue;
+            }
 
-                frameIndex++;
-                
-                // Report progress every 200 frames to avoid overhead
-                if (progress != null && frameIndex % 200 == 0)
-                {
-                    progress.Report((frameIndex, estimatedFrames));
-                }
-                
-                if (_config.Debug && frameIndex % 200 == 0)
-                {
-                    _logger.LogInformation("[Ambilight] Extractor: processed {Frames} frames for {Path}", frameIndex, videoPath);
-                }
+            if (kv[0] == "nb_frames" && long.TryParse(kv[1], NumberStyles.Integer, CultureInfo.InvariantCulture, out var frames) && frames > 0)
+            {
+                return frames;
             }
 
-            try
+            if (kv[0] == "avg_frame_rate")
             {
-                await ffmpeg.WaitForExitAsync(cancellationToken).ConfigureAwait(false);
+                rate = ParseFrameRate(kv[1]);
             }
-            catch
+            else if (kv[0] == "duration" && duration <= 0.0 && double.TryParse(kv[1], NumberStyles.Float, CultureInfo.InvariantCulture, out var seconds))
             {
-                // ignore cancellation / wait errors
+                // The stream's duration comes first; the file's is the fallback.
+                duration = seconds;
+            }
+        }
+
+        return IsPlausibleFps(rate) && duration > 0.0 ? rate * duration : 0.0;
+    }
+
+    /// <summary>Picture format of a video stream; <c>PeakNits</c> only means something for PQ.</summary>
+    private readonly record struct VideoFormat(bool Pq, bool Bt2020, double PeakNits, bool HighBitDepth, bool Interlaced, int Rotation);
+
+    /// <summary>
+    /// Whether a video is HDR10 (PQ; HLG counts as SDR here), has BT.2020 primaries, more than 8 bits per sample and
+    /// interlaced fields, and how far it is rotated for display (clockwise, a multiple of 90 degrees). The peak luminance
+    /// of PQ is MaxCLL when the stream has it, else the mastering display's peak, else 1000 nits. Null when ffprobe
+    /// cannot tell.
+    /// </summary>
+    private async Task<VideoFormat?> ProbeVideoFormatAsync(string videoPath, string stream, CancellationToken cancellationToken)
+    {
+        try
+        {
+            var output = await RunFfprobeAsync(
+                $"-v error -select_streams {stream} -show_entries stream=pix_fmt,field_order,color_transfer,color_primaries:stream_side_data=max_content,max_luminance,rotation:stream_tags=rotate -of default=noprint_wrappers=1 \"{videoPath}\"",
+                cancellationToken).ConfigureAwait(false);
+            if (output == null)
+            {
+                return null;
             }
 
-            if (frameIndex == 0)
+            bool pq = false;
+            bool bt2020 = false;
+            bool highBitDepth = false;
+            bool interlaced = false;
+            double rotation = 0;
+            double maxContent = 0;
+            double maxLuminance = 0;
+            foreach (var line in output.Split('\n', StringSplitOptions.RemoveEmptyEntries))
             {
-                // Try to get stderr output for better error reporting
-                string stderrOutput = "";
-                if (stderrTask != null)
-                {
-                    try
-                    {
-                        stderrOutput = await stderrTask.ConfigureAwait(false);
-                    }
-                    catch
-                    {
-                        // Ignore errors reading stderr
-                    }
-                }
-                
-                if (!string.IsNullOrWhiteSpace(stderrOutput))
+                var parts = line.Trim().Split('=', 2);
+                if (parts.Length != 2)
                 {
-                    _logger.LogWarning("[Ambilight] Extractor: no frames decoded for {Path}. ffmpeg stderr: {Error}", videoPath, stderrOutput);
+                    continue;
                 }
-                else
+
+                switch (parts[0])
                 {
-                    _logger.LogWarning("[Ambilight] Extractor: no frames decoded for {Path}", videoPath);
+                    case "color_transfer":
+                        pq = parts[1] == "smpte2084";
+                        break;
+                    case "color_primaries":
+                        bt2020 = parts[1] == "bt2020";
+                        break;
+                    case "pix_fmt":
+                        highBitDepth = SampleBits(parts[1]) > 8;
+                        break;
+                    case "field_order":
+                        // tt, bb, tb or bt; "unknown" (common for progressive web rips) counts as progressive.
+                        interlaced = parts[1] is not ("progressive" or "unknown");
+                        break;
+                    case "rotation":
+                        // Display matrix side data, counterclockwise (phone portrait video: -90).
+                        rotation = -ParseRational(parts[1]);
+                        break;
+                    case "TAG:rotate":
+                        // Older ffprobe reports the same as a clockwise tag.
+                        rotation = ParseRational(parts[1]);
+                        break;
+                    case "max_content":
+                        maxContent = ParseRational(parts[1]);
+                        break;
+                    case "max_luminance":
+                        maxLuminance = ParseRational(parts[1]);
+                        break;
                 }
-                return false;
-            }
-
-            // Atomic write to target path
-            var outDir = Path.GetDirectoryName(outputPath);
-            if (!string.IsNullOrEmpty(outDir))
-            {
-                Directory.CreateDirectory(outDir);
             }
 
-            var tempPath = outputPath + ".tmp";
-            await File.WriteAllBytesAsync(tempPath, ms.ToArray(), cancellationToken).ConfigureAwait(false);
-            File.Move(tempPath, outputPath, overwrite: true);
+            return new VideoFormat(pq, bt2020, maxContent > 0 ? maxContent : maxLuminance > 0 ? maxLuminance : 1000.0, highBitDepth, interlaced, ((int)Math.Round(rotation / 90.0) % 4 + 4) % 4 * 90);
+        }
+        catch (Exception ex) when (ex is not OperationCanceledException)
+        {
+            _logger.LogDebug(ex, "[Ambilight] Extractor: could not read the picture format of {Path}", videoPath);
+            return null;
+        }
+
+        // Formats wider than 8 bits carry their depth and endianness at the end: yuv420p10le, p010le.
+        static int SampleBits(string pixFmt)
+        {
+            if (!pixFmt.EndsWith("le", StringComparison.Ordinal) && !pixFmt.EndsWith("be", StringComparison.Ordinal))
+            {
+                return 8;
+            }
+
+            var name = pixFmt[..^2];
+            int digits = name.Length;
+            while (digits > 0 && char.IsAsciiDigit(name[digits - 1]))
+            {
+                digits--;
+            }
+
+            return int.TryParse(name[digits..], NumberStyles.None, CultureInfo.InvariantCulture, out var bits) ? bits : 8;
+        }
+
+        // ffprobe prints mastering luminance as a fraction ("10000000/10000").
+        static double ParseRational(string value)
+        {
+            var parts = value.Trim().Split('/');
+            if (!double.TryParse(parts[0], NumberStyles.Float, CultureInfo.InvariantCulture, out var n))
+            {
+                return 0;
+            }
+
+            return parts.Length == 2 && double.TryParse(parts[1], NumberStyles.Float, CultureInfo.InvariantCulture, out var d) && d > 0 ? n / d : n;
+        }
+    }
+
+    /// <summary>
+    /// Converter to SDR BT.709 per the HDR and gamut settings, or null when the video already is SDR BT.709 or both
+    /// conversions are off.
+    /// </summary>
+    private AmbilightColorConverter? CreateColorConverter(string videoPath, VideoFormat? probed)
+    {
+        if (probed is not { } color)
+        {
+            return null;
+        }
+
+        var mode = (_config.HdrToneMapping ?? "bt2390").Trim().ToLowerInvariant();
+        bool toneMap = color.Pq && mode != "off";
+        bool gamut = color.Bt2020 && _config.ConvertWideGamut;
+        if (!toneMap && !gamut)
+        {
+            return null;
+        }
+
+        var converter = AmbilightColorConverter.Create(
+            toneMap ? (mode == "hable" ? ToneMapCurve.Hable : ToneMapCurve.Bt2390) : null,
+            _config.HdrPeakNits > 0 ? _config.HdrPeakNits : color.PeakNits,
+            _config.HdrToneMapStrength / 100.0,
+            gamut);
+        if (toneMap)
+        {
+            _logger.LogInformation("[Ambilight] Extractor: {Path} is HDR10; tone mapping from {Peak:0} nits with {Curve} at {Strength:P0} strength",
+                videoPath, converter.PeakNits, converter.Curve, converter.Strength);
+        }
+
+        if (gamut)
+        {
+            _logger.LogInformation("[Ambilight] Extractor: {Path} has BT.2020 primaries; converting to BT.709", videoPath);
+        }
+
+        return converter;
+    }
+
+    /// <summary>
+    /// The deinterlace filter for a video per the setting: the configured one for streams with interlaced fields, null
+    /// for progressive ones or when deinterlacing is off.
+    /// </summary>
+    private string? SelectDeinterlacer(string videoPath, VideoFormat? format)
+    {
+        var mode = (_config.ExtractionDeinterlace ?? "bwdif").Trim().ToLowerInvariant();
+        if (mode == "off" || format is not { Interlaced: true })
+        {
+            return null;
+        }
+
+        var filter = mode == "yadif" ? "yadif" : "bwdif";
+        _logger.LogInformation("[Ambilight] Extractor: {Path} is interlaced; deinterlacing with {Filter}", videoPath, filter);
+        return filter;
+    }
+
+    private string GetFfprobePath()
+    {
+        // Construct ffprobe path from ffmpeg path (usually in same directory)
+        if (Path.IsPathRooted(_ffmpegPath))
+        {
+            var dir = Path.GetDirectoryName(_ffmpegPath);
+            return Path.Combine(dir ?? "/", "ffprobe");
+        }
+
+        return "ffprobe";
+    }
+
+    private async Task<string?> RunFfprobeAsync(string arguments, CancellationToken cancellationToken)
+    {
+        using var ffprobe = new Process
+        {
+            StartInfo = new ProcessStartInfo
+            {
+                FileName = GetFfprobePath(),
+                Arguments = arguments,
+                UseShellExecute = false,
+                RedirectStandardOutput = true,
+                RedirectStandardError = true,
+                CreateNoWindow = true
+            }
+        };
+
+        ffprobe.Start();
+
+        // stderr is drained alongside stdout: a damaged file can make ffprobe write enough errors to fill the pipe and stall.
+        var stderrTask = ffprobe.StandardError.ReadToEndAsync(CancellationToken.None);
+        try
+        {
+            var output = await ffprobe.StandardOutput.ReadToEndAsync(cancellationToken).ConfigureAwait(false);
+            await ffprobe.WaitForExitAsync(cancellationToken).ConfigureAwait(false);
+            await stderrTask.ConfigureAwait(false);
+            return ffprobe.ExitCode == 0 ? output : null;
+        }
+        finally
+        {
+            // Cancelled mid-probe (whole-file packet probes take a while): do not leave ffprobe running.
+            if (!ffprobe.HasExited)
+            {
+                try
+                {
+                    ffprobe.Kill(entireProcessTree: true);
+                }
+                catch (InvalidOperationException)
+                {
+                    // already gone
+                }
+            }
+        }
+    }
+
+    private static double ParseFrameRate(string value)
+    {
+        // Fraction like "24000/1001" or "30/1"
+        var parts = value.Trim().Split('/');
+        if (parts.Length == 2 &&
+            double.TryParse(parts[0], NumberStyles.Float, CultureInfo.InvariantCulture, out var numerator) &&
+            double.TryParse(parts[1], NumberStyles.Float, CultureInfo.InvariantCulture, out var denominator) &&
+            denominator > 0)
+        {
+            return numerator / denominator;
+        }
+
+        return double.TryParse(value.Trim(), NumberStyles.Float, CultureInfo.InvariantCulture, out var plain) ? plain : 0.0;
+    }
+
+    private static bool IsPlausibleFps(double fps) => fps > 1.0 && fps < 200.0;
+
+    private static double RelativeDifference(double a, double b) => Math.Abs(a - b) / Math.Max(Math.Abs(a), Math.Abs(b));
+
+    /// <summary>
+    /// Turns the configured <see cref="PluginConfiguration.HardwareAcceleration"/> into the decoder to use:
+    /// "none", "vaapi", "nvdec", "v4l2m2m", "qsv" or "videotoolbox". "auto" picks the first one whose device is
+    /// present ("cuda" is the old name for NVDEC).
+    /// </summary>
+    private string ResolveHardwareDecoder()
+    {
+        var mode = (_config.HardwareAcceleration ?? "auto").Trim().ToLowerInvariant();
+        switch (mode)
+        {
+            case "vaapi" or "nvdec" or "v4l2m2m" or "qsv" or "videotoolbox" or "none":
+                return mode;
+            case "cuda":
+                return "nvdec";
+        }
+
+        if (OperatingSystem.IsMacOS())
+        {
+            return "videotoolbox";
+        }
+
+        if (!OperatingSystem.IsLinux())
+        {
+            return "none";
+        }
+
+        if (File.Exists(VaapiDevice))
+        {
+            return "vaapi";
+        }
+
+        if (File.Exists("/dev/nvidia0"))
+        {
+            return "nvdec";
+        }
+
+        // Raspberry Pi (bcm2835-codec) and similar SoCs expose their stateful decoder as /dev/video10.
+        return File.Exists("/dev/video10") ? "v4l2m2m" : "none";
+    }
+
+    /// <summary>Render node for VAAPI: the configured device, or the first one.</summary>
+    private string VaapiDevice => string.IsNullOrWhiteSpace(_config.HardwareAccelerationDevice)
+        ? "/dev/dri/renderD128"
+        : _config.HardwareAccelerationDevice.Trim();
+
+    /// <summary>
+    /// ffmpeg arguments that decode <paramref name="videoPath"/> with <paramref name="decoder"/> (see
+    /// <see cref="ResolveHardwareDecoder"/>) into scaled rgb48le frames on stdout. <paramref name="codec"/> is the
+    /// stream's codec name, needed to pick a v4l2m2m decoder. <paramref name="startSeconds"/> and
+    /// <paramref name="frameLimit"/> restrict the output to one segment of the video. With <paramref name="passthrough"/>,
+    /// every decoded frame is output exactly once (no duplicating or dropping to a constant rate), to be paired
+    /// with the container's timestamps. <paramref name="deinterlacer"/> (bwdif or yadif) deinterlaces frames flagged as
+    /// interlaced before scaling. <paramref name="rotation"/> (clockwise degrees from the display matrix) turns frames
+    /// upright, so each side of the analysed frame is the side the LEDs of that edge face. <paramref name="stream"/> is the
+    /// specifier of the video stream to decode (see <see cref="SelectVideoStreamAsync"/>). <paramref name="outputFps"/> resamples
+    /// the output to a constant lower rate, for previews.
+    /// </summary>
+    private string BuildFfmpegArguments(string videoPath, string decoder, string? codec, double startSeconds = 0, ulong? frameLimit = null, bool passthrough = false, bool highBitDepth = false, string? deinterlacer = null, int rotation = 0, string stream = "V:0", float? outputFps = null)
+    {
+        // ffmpeg's own autorotation only works for frames in system memory, so it is off and frames are turned here
+        // instead, after scaling and downloading, where it costs next to nothing. Sideways videos are scaled to the
+        // transposed size first.
+        var baseArgs = "-hide_banner -loglevel error -noautorotate";
+        bool sideways = rotation is 90 or 270;
+        int scaleWidth = sideways ? AnalysisHeight : AnalysisWidth;
+        int scaleHeight = sideways ? AnalysisWidth : AnalysisHeight;
+        string rotate = rotation switch
+        {
+            90 => ",transpose=clock",
+            180 => ",hflip,vflip",
+            270 => ",transpose=cclock",
+            _ => string.Empty
+        };
+
+        // Scaling and the RGB conversion happen in the same swscale pass, at the analysis size. Frames come out as
+        // 16-bit RGB so 10-bit sources keep their shadow detail through scaling and zone averaging; zone colors are only
+        // rounded to 8 bits when they are written.
+        string scale = $"scale={scaleWidth}:{scaleHeight},format=rgb48le{rotate}";
+
+        // Deinterlacing outputs one frame per frame (send_frame), so frames still pair up with the container
+        // timestamps, and only touches frames flagged as interlaced, so progressive parts of mixed rips pass unchanged.
+        // On the GPU, VAAPI has its own deinterlacer and CUDA yadif_cuda (bwdif_cuda needs ffmpeg 7).
+        string deinterlace = deinterlacer != null ? $"{deinterlacer}=mode=send_frame:deint=interlaced," : string.Empty;
+        string vaapiDeinterlace = deinterlacer != null ? "deinterlace_vaapi=rate=frame:auto=1," : string.Empty;
+        string cudaDeinterlace = deinterlacer != null ? "yadif_cuda=mode=send_frame:deint=interlaced," : string.Empty;
+        scale = deinterlace + scale;
+
+        // VAAPI and NVDEC set up an explicit device context and keep decoded frames on the GPU, scale them there and
+        // download only the small analysis-size result; a 4K frame never crosses the bus. The scaler also picks the
+        // download format: p010 for sources above 8 bits, so they stay 10-bit, nv12 for the rest.
+        // v4l2m2m decoders hand back frames in system memory, so only the software scale remains.
+        string hwFormat = highBitDepth ? "p010" : "nv12";
+        string hwaccelArgs;
+        string filterChain;
+        switch (decoder)
+        {
+            case "vaapi":
+                hwaccelArgs = $"-init_hw_device vaapi=hw:{VaapiDevice} -hwaccel vaapi -hwaccel_device hw -hwaccel_output_format vaapi";
+                filterChain = $"{vaapiDeinterlace}scale_vaapi=w={scaleWidth}:h={scaleHeight}:format={hwFormat},hwdownload,format={hwFormat}{rotate}";
+                break;
+
+            case "nvdec":
+                var gpu = string.IsNullOrWhiteSpace(_config.HardwareAccelerationDevice) ? "0" : _config.HardwareAccelerationDevice.Trim();
+                hwaccelArgs = $"-init_hw_device cuda=hw:{gpu} -hwaccel cuda -hwaccel_device hw -hwaccel_output_format cuda";
+                filterChain = $"{cudaDeinterlace}scale_cuda=w={scaleWidth}:h={scaleHeight}:format={hwFormat},hwdownload,format={hwFormat}{rotate}";
+                break;
+
+            case "v4l2m2m":
+                hwaccelArgs = V4l2m2mCodecs.Contains(codec ?? string.Empty) ? $"-c:v {codec}_v4l2m2m" : string.Empty;
+                filterChain = scale;
+                break;
+
+            case "qsv":
+                // Decode only; ffmpeg transfers the frames to system memory for the software scale.
+                hwaccelArgs = "-hwaccel qsv";
+                filterChain = scale;
+                break;
+
+            case "videotoolbox":
+                hwaccelArgs = "-hwaccel videotoolbox";
+                filterChain = scale;
+                break;
+
+            default:
+                hwaccelArgs = string.Empty;
+                filterChain = scale;
+                break;
+        }
+
+        if (hwaccelArgs.Length > 0)
+        {
+            hwaccelArgs += " ";
+        }
+
+        // Input seeking (-ss before -i) is frame-accurate when transcoding: ffmpeg decodes from the previous keyframe
+        // and drops everything before the start.
+        string seekArgs = startSeconds > 0 ? string.Create(CultureInfo.InvariantCulture, $"-ss {startSeconds:0.######} ") : string.Empty;
+        string limitArgs = frameLimit.HasValue ? $"-frames:v {frameLimit.Value} " : string.Empty;
+        string threadArgs = _config.ExtractionDecoderThreads > 0 ? $"-threads {_config.ExtractionDecoderThreads} " : string.Empty;
+        string fpsModeArgs = passthrough ? "-fps_mode passthrough "
+            : outputFps.HasValue ? string.Create(CultureInfo.InvariantCulture, $"-r {outputFps.Value:0.###} ")
+            : string.Empty;
+
+        return $"{baseArgs} {hwaccelArgs}{threadArgs}{seekArgs}-i \"{videoPath}\" -map 0:{stream} {limitArgs}{fpsModeArgs}-vf {filterChain} -pix_fmt rgb48le -f rawvideo pipe:1";
+    }
+
+    // Codecs with a v4l2m2m decoder in ffmpeg (<codec>_v4l2m2m).
+    private static readonly HashSet<string> V4l2m2mCodecs = new(StringComparer.Ordinal) { "h264", "hevc", "mpeg2video", "mpeg4", "vc1", "vp8", "vp9" };
+
+    private async Task<string?> ProbeVideoCodecAsync(string videoPath, string stream, CancellationToken cancellationToken)
+    {
+        var output = await RunFfprobeAsync(
+            $"-v error -select_streams {stream} -show_entries stream=codec_name -of csv=p=0 \"{videoPath}\"",
+            cancellationToken).ConfigureAwait(false);
+        return string.IsNullOrWhiteSpace(output) ? null : output.Trim().Split('\n')[0].Trim();
+    }
+
+    /// <summary>Coded width and height of <paramref name="stream"/>, or zeros when ffprobe cannot tell.</summary>
+    private async Task<(int Width, int Height)> ProbeVideoSizeAsync(string videoPath, string stream, CancellationToken cancellationToken)
+    {
+        var output = await RunFfprobeAsync(
+            $"-v error -select_streams {stream} -show_entries stream=width,height -of csv=p=0 \"{videoPath}\"",
+            cancellationToken).ConfigureAwait(false);
+        var parts = (output ?? string.Empty).Trim().Split('\n')[0].Split(',');
+        return parts.Length >= 2
+            && int.TryParse(parts[0], NumberStyles.Integer, CultureInfo.InvariantCulture, out var width)
+            && int.TryParse(parts[1], NumberStyles.Integer, CultureInfo.InvariantCulture, out var height)
+            ? (width, height)
+            : (0, 0);
+    }
+
+    /// <summary>
+    /// True if the file has a video stream that is not an attached picture (cover art in audio files,
+    /// MKV attachments). Errs on the side of true when ffprobe cannot be run.
+    /// </summary>
+    private async Task<bool> HasMotionVideoStreamAsync(string videoPath, CancellationToken cancellationToken)
+    {
+        try
+        {
+            // Capital V excludes attached_pic streams.
+            var output = await RunFfprobeAsync(
+                $"-v error -select_streams V -show_entries stream=index -of csv=p=0 \"{videoPath}\"",
+                cancellationToken).ConfigureAwait(false);
+            return output == null || !string.IsNullOrWhiteSpace(output);
+        }
+        catch (Exception ex) when (ex is not OperationCanceledException)
+        {
+            _logger.LogDebug(ex, "[Ambilight] Extractor: could not list video streams for {Path}", videoPath);
+            return true;
+        }
+    }
+
+    // Codecs of single pictures; a video stream in one of these is cover art even when it is not flagged attached_pic.
+    private static readonly HashSet<string> StillImageCodecs = new(StringComparer.Ordinal) { "mjpeg", "png", "bmp", "gif", "webp", "tiff" };
+
+    /// <summary>
+    /// Index of the video stream to extract: <see cref="PluginConfiguration.VideoStreamIndex"/> when set, else the best
+    /// ranked one. Attached pictures and still-image codecs rank last (a 600x600 JPEG can come before the movie), then
+    /// the highest resolution, the longest duration and the default disposition win. Null when ffprobe cannot list
+    /// the streams; ffmpeg's own pick (the first stream that is not an attached picture) is used then.
+    /// </summary>
+    private async Task<int?> SelectVideoStreamAsync(string videoPath, CancellationToken cancellationToken)
+    {
+        if (_config.VideoStreamIndex >= 0)
+        {
+            return _config.VideoStreamIndex;
+        }
+
+        try
+        {
+            var output = await RunFfprobeAsync(
+                $"-v error -select_streams v -show_entries stream=index,codec_name,width,height,duration:stream_disposition=default,attached_pic -of json \"{videoPath}\"",
+                cancellationToken).ConfigureAwait(false);
+            if (string.IsNullOrWhiteSpace(output))
+            {
+                return null;
+            }
+
+            using var doc = JsonDocument.Parse(output);
+            if (!doc.RootElement.TryGetProperty("streams", out var streams))
+            {
+                return null;
+            }
+
+            var candidates = new List<(int Index, bool Picture, long Pixels, double Duration, bool Default)>();
+            foreach (var stream in streams.EnumerateArray())
+            {
+                if (!stream.TryGetProperty("index", out var index) || !index.TryGetInt32(out var streamIndex))
+                {
+                    continue;
+                }
+
+                var codec = stream.TryGetProperty("codec_name", out var name) ? name.GetString() ?? string.Empty : string.Empty;
+                long pixels = (long)Number(stream, "width") * Number(stream, "height");
+                double duration = stream.TryGetProperty("duration", out var d) && d.ValueKind == JsonValueKind.String
+                    && double.TryParse(d.GetString(), NumberStyles.Float, CultureInfo.InvariantCulture, out var seconds) ? seconds : 0;
+                bool attached = false;
+                bool isDefault = false;
+                if (stream.TryGetProperty("disposition", out var disposition))
+                {
+                    attached = Number(disposition, "attached_pic") != 0;
+                    isDefault = Number(disposition, "default") != 0;
+                }
+
+                candidates.Add((streamIndex, attached || StillImageCodecs.Contains(codec), pixels, duration, isDefault));
+            }
+
+            if (candidates.Count == 0)
+            {
+                return null;
+            }
+
+            var best = candidates
+                .OrderBy(c => c.Picture)
+                .ThenByDescending(c => c.Pixels)
+                .ThenByDescending(c => c.Duration)
+                .ThenByDescending(c => c.Default)
+                .First();
+            if (candidates.Count > 1)
+            {
+                _logger.LogInformation("[Ambilight] Extractor: {Path} has {Count} video streams; extracting stream {Index}", videoPath, candidates.Count, best.Index);
+            }
+
+            return best.Index;
+        }
+        catch (Exception ex) when (ex is not OperationCanceledException)
+        {
+            _logger.LogDebug(ex, "[Ambilight] Extractor: could not rank the video streams of {Path}", videoPath);
+            return null;
+        }
+
+        static int Number(JsonElement element, string property) =>
+            element.TryGetProperty(property, out var value) && value.ValueKind == JsonValueKind.Number && value.TryGetInt32(out var n) ? n : 0;
+    }
+
+    /// <summary>ffmpeg stream specifier for <paramref name="index"/>; without one, the first stream that is not an attached picture.</summary>
+    private static string StreamSpecifier(int? index) => index?.ToString(CultureInfo.InvariantCulture) ?? "V:0";
+
+    private async Task<float> ProbeVideoDuration(string videoPath, CancellationToken cancellationToken)
+    {
+        const float fallbackDuration = 60.0f; // 1 minute fallback
+
+        try
+        {
+            // ffprobe -v error -show_entries format=duration -of default=noprint_wrappers=1:nokey=1 "video.mp4"
+            var output = await RunFfprobeAsync(
+                $"-v error -show_entries format=duration -of default=noprint_wrappers=1:nokey=1 \"{videoPath}\"",
+                cancellationToken).ConfigureAwait(false);
+
+            if (!string.IsNullOrWhiteSpace(output) &&
+                float.TryParse(output.Trim(), NumberStyles.Float, CultureInfo.InvariantCulture, out var duration) &&
+                duration > 0.0f)
+            {
+                return duration;
+            }
+        }
+        catch (Exception ex) when (ex is not OperationCanceledException)
+        {
+            _logger.LogWarning(ex, "[Ambilight] Extractor: Failed to probe video duration for {Path}", videoPath);
+        }
+
+        return fallbackDuration;
+    }
+
+    /// <summary>
+    /// Extracts <paramref name="videoPath"/> into an AMb3 file at <paramref name="outputPath"/>.
+    /// Returns <see cref="AmbilightExtractionResult.NotExtractable"/> for files that will never give useful
+    /// output (only cover art, or shorter than the configured minimum), so they are not retried.
+    /// <paramref name="whiteBalance"/> optionally scales each side's colors to correct a tinted source, and
+    /// <paramref name="colorMode"/> overrides <see cref="PluginConfiguration.ExtractionColorMode"/>. On success, <paramref name="pictureAreas"/> receives the picture area timeline when black bars are tracked.
+    /// With <see cref="PluginConfiguration.SkipUpToDateExtractions"/>, an output that <see cref="IsUpToDate"/> is left
+    /// alone and <see cref="AmbilightExtractionResult.UpToDate"/> returned, unless <paramref name="force"/> is set.
+    /// </summary>
+    public Task<AmbilightExtractionResult> ExtractAsync(string videoPath, string outputPath, CancellationToken cancellationToken, IProgress<(ulong current, ulong total)>? progress = null, ExtractionWhiteBalance? whiteBalance = null, List<AmbilightPictureArea>? pictureAreas = null, bool force = false, string? colorMode = null)
+    {
+        if (!force && _config.SkipUpToDateExtractions && IsUpToDate(videoPath, outputPath, whiteBalance, colorMode))
+        {
+            _logger.LogInformation("[Ambilight] Extractor: {Output} is up to date with {Path} and the current settings; not extracting again", outputPath, videoPath);
+            return Task.FromResult(AmbilightExtractionResult.UpToDate);
+        }
+
+        return ExtractAsync(videoPath, outputPath, null, cancellationToken, progress, whiteBalance, colorMode, pictureAreas, null);
+    }
+
+    /// <summary>
+    /// True when <paramref name="outputPath"/> still matches its checksum and its parameters sidecar says it was
+    /// extracted from <paramref name="videoPath"/> as the file is now (same size and modification time), with the
+    /// current extraction settings, <paramref name="whiteBalance"/> and <paramref name="colorMode"/>: extracting again
+    /// would give the same binary.
+    /// </summary>
+    public bool IsUpToDate(string videoPath, string outputPath, ExtractionWhiteBalance? whiteBalance = null, string? colorMode = null)
+        => ParametersMatch(videoPath, outputPath, whiteBalance, colorMode) == true && AmbilightStorageService.ChecksumMatches(outputPath);
+
+    /// <summary>
+    /// Whether the parameters sidecar of <paramref name="outputPath"/> matches the video and settings now; null when
+    /// there is no sidecar (binaries extracted before they were written) or it cannot be read.
+    /// </summary>
+    private bool? ParametersMatch(string videoPath, string outputPath, ExtractionWhiteBalance? whiteBalance, string? colorMode)
+    {
+        var parametersPath = outputPath + AmbilightStorageService.ParametersExtension;
+        try
+        {
+            if (!File.Exists(parametersPath))
+            {
+                return null;
+            }
+
+            if (!File.Exists(videoPath))
+            {
+                return false;
+            }
+
+            var recorded = JsonSerializer.Deserialize<SortedDictionary<string, string>>(File.ReadAllText(parametersPath));
+            var current = ExtractionParameters(videoPath, whiteBalance, colorMode);
+            if (recorded == null || !recorded.SequenceEqual(current))
+            {
+                if (_config.Debug)
+                {
+                    var changed = current.Where(kv => recorded == null || !recorded.TryGetValue(kv.Key, out var r) || r != kv.Value).Select(kv => kv.Key);
+                    _logger.LogInformation("[Ambilight] Extractor: {Output} is out of date ({Changed})", outputPath, string.Join(", ", changed));
+                }
+
+                return false;
+            }
+
+            return true;
+        }
+        catch (Exception ex) when (ex is IOException or JsonException or UnauthorizedAccessException)
+        {
+            _logger.LogDebug("[Ambilight] Extractor: could not check {Output} against its parameters: {Message}", outputPath, ex.Message);
+            return null;
+        }
+    }
+
+    /// <summary>
+    /// The source video's identity and every setting that changes what an extraction writes, as stored in the
+    /// parameters sidecar. Hardware decoding, threads and other settings that only change how fast it runs are left out.
+    /// </summary>
+    private SortedDictionary<string, string> ExtractionParameters(string videoPath, ExtractionWhiteBalance? whiteBalance, string? colorMode)
+    {
+        var video = new FileInfo(videoPath);
+        var inv = CultureInfo.InvariantCulture;
+        return new SortedDictionary<string, string>(StringComparer.Ordinal)
+        {
+            ["format"] = _config.CompressBinaries ? "AMb3+zstd" : "AMb3",
+            ["source.path"] = videoPath,
+            ["source.size"] = video.Length.ToString(inv),
+            ["source.modified"] = video.LastWriteTimeUtc.ToString("O", inv),
+            ["backend"] = UseExternalBackend ? "external" : "inprocess",
+            ["leds"] = string.Create(inv, $"{_config.AmbilightTopLedCount},{_config.AmbilightBottomLedCount},{_config.AmbilightLeftLedCount},{_config.AmbilightRightLedCount}"),
+            ["rgbw"] = _config.AmbilightRgbw.ToString(inv),
+            ["depthFactor"] = _config.AmbilightZoneDepthFactor.ToString("R", inv),
+            ["bandDepthPercent"] = BandDepthPercent.ToString(),
+            ["sceneCuts"] = _config.SceneCutFlags ? SceneCutThreshold.ToString("R", inv) : "off",
+            ["frameLuminance"] = _config.FrameLuminance.ToString(inv),
+            ["audioEnergy"] = _config.AudioEnergy.ToString(inv),
+            ["credits"] = _config.CreditsDetection.ToString(inv),
+            ["sceneWhiteBalance"] = SceneBalanceStrength > 0 ? string.Create(inv, $"{SceneBalanceStrength:R},{SceneCutThreshold:R}") : "off",
+            ["corners"] = Corners == AmbilightBinary.CornersDedicated
+                ? string.Create(inv, $"dedicated:{CornerZones}")
+                : Corners == AmbilightBinary.CornersBlended ? "blend" : "share",
+            ["edgeWeight"] = Math.Clamp(_config.AmbilightEdgeWeight, 0.0, 1.0).ToString("R", inv),
+            ["quality"] = Quality.ToString(),
+            ["colorMode"] = ColorModeFor(colorMode).ToString(),
+            ["saliency"] = Saliency.ToString(),
+            ["analysisHeight"] = AnalysisHeight.ToString(inv),
+            ["videoStream"] = _config.VideoStreamIndex.ToString(inv),
+            ["blackBars"] = _config.DetectBlackBars.ToString(inv),
+            ["subtitleGuard"] = string.Create(inv, $"{_config.SubtitleGuard},{_config.SubtitleGuardWidthPercent}"),
+            ["deinterlace"] = _config.ExtractionDeinterlace ?? string.Empty,
+            ["hdr"] = string.Create(inv, $"{_config.HdrToneMapping},{_config.HdrPeakNits},{_config.HdrToneMapStrength}"),
+            ["wideGamut"] = _config.ConvertWideGamut.ToString(inv),
+            ["brightness"] = _config.BrightnessNormalization ? _config.BrightnessNormalizationMaxGain.ToString("R", inv) : "off",
+            ["whiteBalance"] = whiteBalance == null
+                ? string.Empty
+                : $"{whiteBalance.TopGains};{whiteBalance.RightGains};{whiteBalance.BottomGains};{whiteBalance.LeftGains}"
+        };
+    }
+
+    /// <summary>
+    /// Writes the parameters sidecar of a finished binary, replacing any previous one in one rename. A
+    /// <paramref name="partial"/> binary gets a "partial" entry with the seconds it covers of the video's, so it never
+    /// matches the current settings and is extracted again.
+    /// </summary>
+    private void WriteParameters(string outputPath, string videoPath, ExtractionWhiteBalance? whiteBalance, string? colorMode, string? partial = null)
+    {
+        var parametersPath = outputPath + AmbilightStorageService.ParametersExtension;
+        try
+        {
+            var parameters = ExtractionParameters(videoPath, whiteBalance, colorMode);
+            if (partial != null)
+            {
+                parameters["partial"] = partial;
+            }
+
+            File.WriteAllText(parametersPath + ".tmp", JsonSerializer.Serialize(parameters, new JsonSerializerOptions { WriteIndented = true }));
+            File.Move(parametersPath + ".tmp", parametersPath, overwrite: true);
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException)
+        {
+            // Without it the binary is only re-extracted when something asks for it; it still plays.
+            _logger.LogWarning("[Ambilight] Extractor: could not write {Path}: {Message}", parametersPath, ex.Message);
+        }
+    }
+
+    /// <summary>
+    /// Copies the chapters of <paramref name="videoPath"/> (as ffprobe reads them from the container) into the chapters
+    /// sidecar of a finished binary, so players and tools can seek by chapter. Nothing is written for videos without
+    /// chapters.
+    /// </summary>
+    private async Task WriteChaptersAsync(string outputPath, string videoPath, CancellationToken cancellationToken)
+    {
+        var chaptersPath = outputPath + AmbilightStorageService.ChaptersExtension;
+        try
+        {
+            var output = await RunFfprobeAsync($"-v error -show_chapters -of json \"{videoPath}\"", cancellationToken).ConfigureAwait(false);
+            if (string.IsNullOrWhiteSpace(output))
+            {
+                return;
+            }
+
+            var chapters = new List<AmbilightChapter>();
+            using (var doc = JsonDocument.Parse(output))
+            {
+                if (!doc.RootElement.TryGetProperty("chapters", out var list) || list.ValueKind != JsonValueKind.Array)
+                {
+                    return;
+                }
+
+                foreach (var chapter in list.EnumerateArray())
+                {
+                    if (!chapter.TryGetProperty("start_time", out var start) || start.ValueKind != JsonValueKind.String
+                        || !double.TryParse(start.GetString(), NumberStyles.Float, CultureInfo.InvariantCulture, out var startSeconds))
+                    {
+                        continue;
+                    }
+
+                    double endSeconds = chapter.TryGetProperty("end_time", out var end) && end.ValueKind == JsonValueKind.String
+                        && double.TryParse(end.GetString(), NumberStyles.Float, CultureInfo.InvariantCulture, out var e) ? e : startSeconds;
+                    string title = chapter.TryGetProperty("tags", out var tags) && tags.TryGetProperty("title", out var t) ? t.GetString() ?? string.Empty : string.Empty;
+                    chapters.Add(new AmbilightChapter { StartSeconds = startSeconds, EndSeconds = endSeconds, Title = title });
+                }
+            }
+
+            if (chapters.Count == 0)
+            {
+                return;
+            }
+
+            File.WriteAllText(chaptersPath + ".tmp", JsonSerializer.Serialize(chapters.OrderBy(c => c.StartSeconds), new JsonSerializerOptions { WriteIndented = true }));
+            File.Move(chaptersPath + ".tmp", chaptersPath, overwrite: true);
+            if (_config.Debug)
+            {
+                _logger.LogInformation("[Ambilight] Extractor: copied {Count} chapters of {Path}", chapters.Count, videoPath);
+            }
+        }
+        catch (Exception ex) when (ex is not OperationCanceledException)
+        {
+            // The binary plays without it; only seeking by chapter is unavailable.
+            _logger.LogWarning("[Ambilight] Extractor: could not write {Path}: {Message}", chaptersPath, ex.Message);
+        }
+    }
+
+    /// <summary>True when the parameters sidecar of <paramref name="outputPath"/> marks it as the output of an interrupted run.</summary>
+    private static bool IsPartial(string outputPath)
+    {
+        try
+        {
+            var parametersPath = outputPath + AmbilightStorageService.ParametersExtension;
+            return File.Exists(parametersPath)
+                && JsonSerializer.Deserialize<Dictionary<string, string>>(File.ReadAllText(parametersPath))?.ContainsKey("partial") == true;
+        }
+        catch (Exception ex) when (ex is IOException or JsonException or UnauthorizedAccessException)
+        {
+            return false;
+        }
+    }
+
+    /// <summary>
+    /// Extracts a quick, approximate binary of the first <paramref name="minutes"/> of <paramref name="videoPath"/> at
+    /// <paramref name="fps"/> frames per second (or the video's own rate when lower), to check LED counts, orientation
+    /// and colors on new hardware before a full extraction. Always decoded in-process in one pass, and written without
+    /// a parameters sidecar, so it is never taken for an up-to-date full extraction.
+    /// </summary>
+    public Task<AmbilightExtractionResult> ExtractPreviewAsync(string videoPath, string outputPath, double minutes, double fps, CancellationToken cancellationToken, IProgress<(ulong current, ulong total)>? progress = null, ExtractionWhiteBalance? whiteBalance = null, string? colorMode = null)
+        => ExtractAsync(videoPath, outputPath, null, cancellationToken, progress, whiteBalance, colorMode, null, (minutes * 60.0, (float)fps));
+
+    /// <param name="decoder">Decoder to use, or null for the configured one (see <see cref="ResolveHardwareDecoder"/>).</param>
+    /// <param name="preview">Seconds from the start and frame rate of a preview extraction, or null for a full one.</param>
+    private async Task<AmbilightExtractionResult> ExtractAsync(string videoPath, string outputPath, string? decoder, CancellationToken cancellationToken, IProgress<(ulong current, ulong total)>? progress, ExtractionWhiteBalance? whiteBalance, string? colorMode, List<AmbilightPictureArea>? pictureAreas, (double Seconds, float Fps)? preview = null)
+    {
+        if (string.IsNullOrEmpty(videoPath) || !File.Exists(videoPath))
+        {
+            _logger.LogWarning("[Ambilight] Extractor: video file not found: {Path}", videoPath);
+            return AmbilightExtractionResult.Failed;
+        }
+
+        var tempPath = outputPath + ".tmp";
+        var checkpointPath = outputPath + ".checkpoint";
+        bool checkpointed = false;
+        FileStream? output = null;
+        try
+        {
+            if (!await HasMotionVideoStreamAsync(videoPath, cancellationToken).ConfigureAwait(false))
+            {
+                _logger.LogInformation("[Ambilight] Extractor: {Path} has no video stream besides attached pictures; not extractable", videoPath);
+                return AmbilightExtractionResult.NotExtractable;
+            }
+
+            int? streamIndex = await SelectVideoStreamAsync(videoPath, cancellationToken).ConfigureAwait(false);
+            string stream = StreamSpecifier(streamIndex);
+
+            // Probe video to get actual FPS and duration
+            float fps = await ProbeVideoFps(videoPath, stream, cancellationToken).ConfigureAwait(false);
+            float duration = await ProbeVideoDuration(videoPath, cancellationToken).ConfigureAwait(false);
+            if (preview is { } span)
+            {
+                // A preview covers the start of the video at a constant, reduced rate, its frames timed by index.
+                fps = Math.Min(fps, span.Fps);
+                duration = (float)Math.Min(duration, span.Seconds);
+                _logger.LogInformation("[Ambilight] Extractor: previewing the first {Seconds:F0}s of {Path} at {Fps:F2} fps", duration, videoPath, fps);
+            }
+
+            ulong estimatedFrames = (ulong)(duration * fps);
+            
+            if (_config.Debug)
+            {
+                _logger.LogInformation("[Ambilight] Extractor: video FPS: {Fps:F3}, duration: {Duration:F1}s, estimated frames: {Frames}", fps, duration, estimatedFrames);
+            }
+
+            // Prepare header values
+            ushort topCount = (ushort)Math.Max(0, _config.AmbilightTopLedCount);
+            ushort bottomCount = (ushort)Math.Max(0, _config.AmbilightBottomLedCount);
+            ushort leftCount = (ushort)Math.Max(0, _config.AmbilightLeftLedCount);
+            ushort rightCount = (ushort)Math.Max(0, _config.AmbilightRightLedCount);
+            bool rgbw = _config.AmbilightRgbw;
+            byte fmt = AmbilightBinary.PackFormat(rgbw ? AmbilightBinary.FormatRgbw : AmbilightBinary.FormatRgb, Corners, CornerZones, frameInfo: _config.SceneCutFlags || _config.FrameLuminance || _config.AudioEnergy || _config.CreditsDetection);
+
+            int bytesPerLed = rgbw ? 4 : 3;
+            var area = !UseExternalBackend && _config.DetectBlackBars
+                ? await DetectActiveAreaAsync(videoPath, stream, duration, cancellationToken).ConfigureAwait(false)
+                : ActiveArea.Full;
+            var zones = ComputeZonesInArea(area, topCount, bottomCount, leftCount, rightCount);
+            double edgeWeight = Math.Clamp(_config.AmbilightEdgeWeight, 0.0, 1.0);
+            int ledsPerFrame = zones.Count;
+            if (ledsPerFrame == 0)
+            {
+                _logger.LogWarning("[Ambilight] Extractor: no LED zones computed – check LED counts.");
+                return AmbilightExtractionResult.Failed;
+            }
+
+            var gains = BuildWhiteBalanceGains(whiteBalance, topCount, rightCount, bottomCount, leftCount);
+            if (gains != null && _config.Debug)
+            {
+                _logger.LogInformation("[Ambilight] Extractor: applying white balance (library {Library}) to {Path}",
+                    string.IsNullOrEmpty(whiteBalance!.LibraryId) ? "default" : whiteBalance.LibraryId, videoPath);
+            }
+
+            // Build ffmpeg arguments with hardware acceleration, or the backend's when it does the decoding.
+            bool external = UseExternalBackend && preview == null;
+            decoder ??= ResolveHardwareDecoder();
+            string? codec = !external && decoder == "v4l2m2m" ? await ProbeVideoCodecAsync(videoPath, stream, cancellationToken).ConfigureAwait(false) : null;
+            string decoderPath = external ? (_binaries?.GetExtractorPath() ?? "ambilight-extractor") : _ffmpegPath;
+            var timing = external || preview != null ? null : await ProbeFramePtsAsync(videoPath, stream, cancellationToken).ConfigureAwait(false);
+            var format = external ? null : await ProbeVideoFormatAsync(videoPath, stream, cancellationToken).ConfigureAwait(false);
+            var job = new FrameJob(zones.ToArray(), rgbw, bytesPerLed, edgeWeight, Quality, gains, fps, external, timing?.Pts, timing?.Origin ?? 0, area,
+                !external && _config.DetectBlackBars ? a => ComputeZonesInArea(a, topCount, bottomCount, leftCount, rightCount).ToArray() : null,
+                CreateColorConverter(videoPath, format),
+                format?.HighBitDepth ?? false,
+                SelectDeinterlacer(videoPath, format),
+                format?.Rotation ?? 0,
+                external ? null : SubtitleGuardFills(topCount, rightCount, bottomCount, leftCount),
+                stream,
+                ColorMode: ColorModeFor(colorMode),
+                Saliency: Saliency,
+                CornerPairs: !external && Corners == AmbilightBinary.CornersBlended ? CornerPairs(topCount, rightCount, bottomCount, leftCount) : null,
+                SceneCutFlags: _config.SceneCutFlags,
+                FrameLuminance: _config.FrameLuminance,
+                SceneCutThreshold: SceneCutThreshold,
+                SceneBalance: SceneBalanceStrength);
+            if (job.Rotation != 0)
+            {
+                _logger.LogInformation("[Ambilight] Extractor: {Path} is displayed rotated by {Degrees} degrees; frames are turned upright before analysis", videoPath, job.Rotation);
+            }
+
+            if (_config.BrightnessNormalization && preview == null)
+            {
+                job = job with { Brightness = await MeasureBrightnessCurveAsync(videoPath, stream, duration, job.ColorConverter, job.Area, cancellationToken).ConfigureAwait(false) };
+            }
+
+            if (_config.AudioEnergy && preview == null)
+            {
+                job = job with { AudioEnergy = await MeasureAudioEnergyAsync(videoPath, cancellationToken).ConfigureAwait(false) };
+            }
+
+            if (_config.CreditsDetection && preview == null)
+            {
+                job = job with { Credits = await DetectCreditsAsync(videoPath, stream, duration, cancellationToken).ConfigureAwait(false) };
+            }
+
+            if (timing != null)
+            {
+                // The container knows how many frames there are; the duration x fps estimate is off for VFR files.
+                estimatedFrames = (ulong)timing.Value.Pts.Length;
+                if (_config.Debug)
+                {
+                    _logger.LogInformation("[Ambilight] Extractor: timing {Frames} frames of {Path} by their container timestamps", estimatedFrames, videoPath);
+                }
+            }
+
+            // Frames go straight to the temp file through a 1 MB buffer, so memory use does not grow with the length
+            // of the video; the file is renamed into place once complete. Every header field is known before the first
+            // frame, so nothing has to be patched at the end. The checksum is computed on the way out.
+            var outDir = Path.GetDirectoryName(outputPath);
+            if (!string.IsNullOrEmpty(outDir))
+            {
+                Directory.CreateDirectory(outDir);
+            }
+
+            // A run killed part way (container restart, OOM) leaves its temp file and last checkpoint behind; when both
+            // belong to the same video and settings, decoding picks up at the checkpointed frame instead of frame 0.
+            bool resumable = !external && preview == null && _config.ExtractionResume;
+            string fingerprint = CheckpointFingerprint(videoPath, job, fps, topCount, bottomCount, leftCount, rightCount, fmt);
+            using var sha256 = SHA256.Create();
+            var checkpoint = resumable ? ReadCheckpoint(checkpointPath, fingerprint) : null;
+            if (checkpoint != null)
+            {
+                output = await ResumeTempFileAsync(tempPath, checkpoint, sha256, cancellationToken).ConfigureAwait(false);
+                if (output == null)
+                {
+                    checkpoint = null;
+                }
+            }
+
+            // Read as well as written: the seek index is built from the finished records.
+            output ??= new FileStream(tempPath, FileMode.Create, FileAccess.ReadWrite, FileShare.None, OutputBufferSize);
+            using var hashing = new CryptoStream(output, sha256, CryptoStreamMode.Write, leaveOpen: true);
+            using var writer = new BinaryWriter(hashing, Encoding.UTF8, leaveOpen: true);
+            long peakWorkingSet = Environment.WorkingSet;
+
+            if (checkpoint == null)
+            {
+                // Write AMb3 header (magic + fps + counts + fmt); the records that follow are AMb2's
+                writer.Write(new[] { (byte)'A', (byte)'M', (byte)'b', (byte)'3' });
+                writer.Write(fps);
+                writer.Write(topCount);
+                writer.Write(bottomCount);
+                writer.Write(leftCount);
+                writer.Write(rightCount);
+                writer.Write(fmt);
+                writer.Flush();
+            }
+
+            ulong resumeFrom = checkpoint?.Frames ?? 0;
+            ulong frameIndex = 0;
+            bool interrupted = false;
+            string stderrOutput = string.Empty;
+            var areaChanges = new List<(ulong Frame, ActiveArea Area)>();
+            if (checkpoint != null)
+            {
+                // Tracking starts from the file's area again at the resume point, as it does for every segment.
+                areaChanges.AddRange(checkpoint.AreaChanges.Where(c => c.Frame < resumeFrom).Select(c => (c.Frame, c.Area)));
+                areaChanges.Add((resumeFrom, job.Area));
+                checkpointed = true;
+                _logger.LogInformation("[Ambilight] Extractor: resuming {Path} at frame {Frame} of about {Frames}", videoPath, resumeFrom, estimatedFrames);
+            }
+
+            // Segments decode into their own part files, so only single-pass runs are checkpointed and resumed.
+            int segments = external || checkpoint != null || preview != null ? 1 : SegmentCount(duration);
+            if (segments > 1)
+            {
+                var segmented = await ExtractSegmentsAsync(videoPath, tempPath, decoder, codec, segments, estimatedFrames, job, hashing, progress, cancellationToken).ConfigureAwait(false);
+                frameIndex = segmented.Frames;
+                areaChanges = segmented.AreaChanges;
+                peakWorkingSet = Math.Max(peakWorkingSet, Environment.WorkingSet);
+            }
+
+            if (frameIndex == 0)
+            {
+                string args = external
+                    ? BuildZonesOnlyArguments(videoPath, topCount, bottomCount, leftCount, rightCount, rgbw, decoder, streamIndex, job.ColorMode)
+                    : BuildFfmpegArguments(videoPath, decoder, codec, StartSeconds(job, resumeFrom), preview != null ? estimatedFrames : null, passthrough: job.Pts != null, highBitDepth: job.HighBitDepth, deinterlacer: job.Deinterlacer, rotation: job.Rotation, stream: job.Stream, outputFps: preview != null ? fps : null);
+                ulong written = resumeFrom;
+                long lastCheckpoint = Stopwatch.GetTimestamp();
+                var run = await DecodeAsync(decoderPath, args, videoPath, writer, resumeFrom, job, external ? 1 : ExtractionWorkers, CpuBudgetCores, OnFrame, cancellationToken, areaChanges).ConfigureAwait(false);
+                interrupted = cancellationToken.IsCancellationRequested;
+                if (!run.Started)
+                {
+                    return AmbilightExtractionResult.Failed;
+                }
+
+                if (resumeFrom > 0 && run.Frames == 0 && !cancellationToken.IsCancellationRequested)
+                {
+                    // Nothing decodes past the checkpoint (the video changed in a way the fingerprint missed): start over.
+                    _logger.LogWarning("[Ambilight] Extractor: could not resume {Path} at frame {Frame}; extracting from the start", videoPath, resumeFrom);
+                    writer.Dispose();
+                    hashing.Dispose();
+                    output.Dispose();
+                    output = null;
+                    DeleteQuietly(tempPath);
+                    DeleteQuietly(checkpointPath);
+                    return await ExtractAsync(videoPath, outputPath, decoder, cancellationToken, progress, whiteBalance, colorMode, pictureAreas, preview).ConfigureAwait(false);
+                }
+
+                frameIndex = resumeFrom + run.Frames;
+                stderrOutput = run.Stderr;
+
+                void OnFrame()
+                {
+                    written++;
+                    if (written % 200 != 0)
+                    {
+                        return;
+                    }
+
+                    peakWorkingSet = Math.Max(peakWorkingSet, Environment.WorkingSet);
+
+                    if (resumable && Stopwatch.GetElapsedTime(lastCheckpoint) >= CheckpointInterval)
+                    {
+                        // Everything counted so far must be on disk before the checkpoint claims it.
+                        writer.Flush();
+                        hashing.Flush();
+                        output!.Flush(true);
+                        WriteCheckpoint(checkpointPath, new ExtractionCheckpoint(fingerprint, written, output.Position,
+                            areaChanges.Select(c => new CheckpointAreaChange(c.Frame, c.Area)).ToList()));
+                        checkpointed = true;
+                        lastCheckpoint = Stopwatch.GetTimestamp();
+                    }
+
+                    // Report progress every 200 frames to avoid overhead
+                    progress?.Report((written, estimatedFrames));
+
+                    if (_config.Debug)
+                    {
+                        _logger.LogInformation("[Ambilight] Extractor: processed {Frames} frames for {Path}", written, videoPath);
+                    }
+                }
+            }
+
+            if (frameIndex == 0)
+            {
+                cancellationToken.ThrowIfCancellationRequested();
+                if (!string.IsNullOrWhiteSpace(stderrOutput))
+                {
+                    _logger.LogWarning("[Ambilight] Extractor: no frames decoded for {Path}. ffmpeg stderr: {Error}", videoPath, stderrOutput);
+                }
+                else
+                {
+                    _logger.LogWarning("[Ambilight] Extractor: no frames decoded for {Path}", videoPath);
+                }
+
+                if (!external && decoder != "none" && !cancellationToken.IsCancellationRequested)
+                {
+                    // The GPU could not decode this stream (unsupported codec or profile, missing driver): redo it on the CPU.
+                    _logger.LogWarning("[Ambilight] Extractor: {Decoder} decoding failed for {Path}; retrying with software decoding", decoder, videoPath);
+                    writer.Dispose();
+                    hashing.Dispose();
+                    output.Dispose();
+                    output = null;
+                    DeleteQuietly(tempPath);
+                    DeleteQuietly(checkpointPath);
+                    return await ExtractAsync(videoPath, outputPath, "none", cancellationToken, progress, whiteBalance, colorMode, pictureAreas, preview).ConfigureAwait(false);
+                }
+
+                return AmbilightExtractionResult.Failed;
+            }
+
+            double decodedSeconds = frameIndex / (double)fps;
+            if (interrupted)
+            {
+                // Stopped part way (server shutdown): nothing to keep from a preview, too little to play, or a previous
+                // full binary, which is better than a shorter one.
+                if (preview != null || decodedSeconds < _config.MinExtractDurationSeconds || (File.Exists(outputPath) && !IsPartial(outputPath)))
+                {
+                    cancellationToken.ThrowIfCancellationRequested();
+                }
+
+                writer.Flush();
+                hashing.Flush();
+                output.Flush(true);
+                if (resumable)
+                {
+                    // Up to the last frame written, so the next run loses nothing.
+                    WriteCheckpoint(checkpointPath, new ExtractionCheckpoint(fingerprint, frameIndex, output.Position,
+                        areaChanges.Select(c => new CheckpointAreaChange(c.Frame, c.Area)).ToList()));
+                    checkpointed = true;
+                }
+
+                bool compress = _config.CompressBinaries;
+                if (!compress)
+                {
+                    // A resumed run cuts the temp file back to the checkpoint, index and all.
+                    writer.Write(AmbilightBinary.BuildSeekIndex(output));
+                    writer.Flush();
+                }
+
+                hashing.FlushFinalBlock();
+                if (compress)
+                {
+                    // The temp file keeps its uncompressed records for the checkpoint to resume from.
+                    CompressBinary(output, outputPath + ".partial");
+                }
+
+                output.Dispose();
+                output = null;
+
+                // The temp file stays behind for the checkpoint to resume from; the binary is a copy of it.
+                File.Delete(outputPath + ".sha256");
+                File.Delete(outputPath + AmbilightStorageService.ParametersExtension);
+                File.Delete(outputPath + AmbilightStorageService.ChaptersExtension);
+                if (compress)
+                {
+                    File.Move(outputPath + ".partial", outputPath, overwrite: true);
+                    if (!checkpointed)
+                    {
+                        DeleteQuietly(tempPath);
+                    }
+                }
+                else if (checkpointed)
+                {
+                    File.Copy(tempPath, outputPath + ".partial", overwrite: true);
+                    File.Move(outputPath + ".partial", outputPath, overwrite: true);
+                }
+                else
+                {
+                    File.Move(tempPath, outputPath, overwrite: true);
+                }
+
+                if (compress)
+                {
+                    AmbilightStorageService.WriteChecksum(outputPath);
+                }
+                else
+                {
+                    AmbilightStorageService.WriteChecksumForHash(outputPath, sha256.Hash!);
+                }
+
+                WriteParameters(outputPath, videoPath, whiteBalance, colorMode, string.Create(CultureInfo.InvariantCulture, $"{decodedSeconds:F1}/{duration:F1}"));
+                await WriteChaptersAsync(outputPath, videoPath, CancellationToken.None).ConfigureAwait(false);
+                FillPictureAreas(pictureAreas, job, areaChanges);
+                _logger.LogInformation("[Ambilight] Extractor: stopped part way through {Path}; wrote the first {Seconds:F0}s of {Duration:F0}s ({Frames} frames) to {Output}",
+                    videoPath, decodedSeconds, duration, frameIndex, outputPath);
+                return AmbilightExtractionResult.Partial;
+            }
+
+            if (decodedSeconds < _config.MinExtractDurationSeconds)
+            {
+                _logger.LogInformation("[Ambilight] Extractor: {Path} decoded to only {Seconds:F1}s ({Frames} frames), below the {Min:F0}s minimum; not extractable",
+                    videoPath, decodedSeconds, frameIndex, _config.MinExtractDurationSeconds);
+                return AmbilightExtractionResult.NotExtractable;
+            }
+
+            // Atomic write to target path
+            bool compressed = _config.CompressBinaries;
+            writer.Flush();
+            hashing.Flush();
+            if (!compressed)
+            {
+                writer.Write(AmbilightBinary.BuildSeekIndex(output));
+                writer.Flush();
+            }
+
+            hashing.FlushFinalBlock();
+            output.Flush(true);
+            if (compressed)
+            {
+                CompressBinary(output, tempPath + ".zst");
+            }
+
+            output.Dispose();
+            output = null;
+
+            // Drop the previous sidecars first so they are never paired with the new binary.
+            File.Delete(outputPath + ".sha256");
+            File.Delete(outputPath + AmbilightStorageService.ParametersExtension);
+            File.Delete(outputPath + AmbilightStorageService.ChaptersExtension);
+            if (compressed)
+            {
+                File.Move(tempPath + ".zst", outputPath, overwrite: true);
+                DeleteQuietly(tempPath);
+                AmbilightStorageService.WriteChecksum(outputPath);
+            }
+            else
+            {
+                File.Move(tempPath, outputPath, overwrite: true);
+                AmbilightStorageService.WriteChecksumForHash(outputPath, sha256.Hash!);
+            }
+
+            if (preview == null)
+            {
+                WriteParameters(outputPath, videoPath, whiteBalance, colorMode);
+                await WriteChaptersAsync(outputPath, videoPath, cancellationToken).ConfigureAwait(false);
+            }
+            DeleteQuietly(checkpointPath);
             
             // Report 100% completion
             progress?.Report((frameIndex, estimatedFrames));
 
-            long fileSize = 0;
-            try
+            FillPictureAreas(pictureAreas, job, areaChanges);
+            if (pictureAreas?.Count > 1)
+            {
+                _logger.LogInformation("[Ambilight] Extractor: picture area of {Path} changed {Changes} times; zones followed it", videoPath, pictureAreas.Count - 1);
+            }
+
+            long fileSize = 0;
+            try
+            {
+                var fi = new FileInfo(outputPath);
+                if (fi.Exists)
+                {
+                    fileSize = fi.Length;
+                }
+            }
+            catch
+            {
+                // ignore size errors
+            }
+
+            if (_config.Debug)
+            {
+                _logger.LogInformation("[Ambilight] Extractor: wrote AMb3 file {Output} with {Frames} frames", outputPath, frameIndex);
+            }
+            if (_config.Debug)
+            {
+                _logger.LogInformation("[Ambilight] Extractor: final file {Output} size {SizeBytes} bytes (~{SizeMb:F2} MB)",
+                    outputPath,
+                    fileSize,
+                    fileSize / 1024.0 / 1024.0);
+            }
+
+            if (_config.Debug)
+            {
+                _logger.LogInformation("[Ambilight] Extractor: memory for {Path}: peak working set {PeakMb} MB",
+                    videoPath,
+                    peakWorkingSet / (1024 * 1024));
+            }
+            return AmbilightExtractionResult.Success;
+        }
+        catch (Exception ex) when (!cancellationToken.IsCancellationRequested)
+        {
+            _logger.LogError(ex, "[Ambilight] Extractor: exception extracting {Path}", videoPath);
+            return AmbilightExtractionResult.Failed;
+        }
+        finally
+        {
+            if (output != null)
+            {
+                // Failed or not extractable: don't leave a partial temp file behind. A checkpointed run that was
+                // cancelled (server shutdown) keeps it, so the next run resumes.
+                output.Dispose();
+                if (!(checkpointed && cancellationToken.IsCancellationRequested))
+                {
+                    DeleteQuietly(tempPath);
+                    DeleteQuietly(checkpointPath);
+                }
+            }
+        }
+    }
+
+    /// <summary>Lists the picture areas a binary's zones were laid out on over time, when black bars were tracked.</summary>
+    private static void FillPictureAreas(List<AmbilightPictureArea>? pictureAreas, FrameJob job, List<(ulong Frame, ActiveArea Area)> areaChanges)
+    {
+        if (pictureAreas == null || job.ZonesFor == null)
+        {
+            return;
+        }
+
+        pictureAreas.Clear();
+        pictureAreas.Add(ToPictureArea(0, job.Area));
+        var last = job.Area;
+        foreach (var (frame, changed) in areaChanges)
+        {
+            if (changed != last)
+            {
+                pictureAreas.Add(ToPictureArea(FrameTimestampUs(job, frame) / 1_000_000.0, changed));
+                last = changed;
+            }
+        }
+    }
+
+    /// <summary>
+    /// Everything needed to turn decoded frames into AMb2 frame records. <c>Pts</c> holds each frame's presentation
+    /// time from the container (see <see cref="ProbeFramePtsAsync"/>), or is null to time frames by index and fps.
+    /// <c>Zones</c> are laid out on <c>Area</c>; with <c>ZonesFor</c>, black bars are tracked while decoding and the
+    /// zones follow the picture through aspect ratio changes. <c>ColorConverter</c> brings HDR10 and BT.2020 frames to SDR BT.709 first.
+    /// <c>HighBitDepth</c> sources are kept at 10 bits or more on hardware decoders too, and <c>Deinterlacer</c> names
+    /// the filter interlaced frames go through (null = none). <c>Rotation</c> is the display rotation frames are turned by.
+    /// <c>Fills</c> colors the zones the subtitle guard excludes from their neighbors before the record is written.
+    /// <c>Stream</c> is the ffmpeg specifier of the video stream to decode. <c>Brightness</c> is the brightness
+    /// normalization scale by brightest channel (see <see cref="BuildBrightnessCurve"/>), or null to write colors as computed.
+    /// <c>ColorMode</c> is how each zone's pixels are reduced to its color, and <c>Saliency</c> what makes a pixel count more.
+    /// </summary>
+    private sealed record FrameJob(
+        (int x1, int y1, int x2, int y2)[] Zones,
+        bool Rgbw,
+        int BytesPerLed,
+        double EdgeWeight,
+        ZoneQuality Quality,
+        float[]? Gains,
+        float Fps,
+        bool External,
+        double[]? Pts = null,
+        double PtsOrigin = 0,
+        ActiveArea Area = default,
+        Func<ActiveArea, (int x1, int y1, int x2, int y2)[]>? ZonesFor = null,
+        AmbilightColorConverter? ColorConverter = null,
+        bool HighBitDepth = false,
+        string? Deinterlacer = null,
+        int Rotation = 0,
+        (int Zone, int From, int To, float T)[]? Fills = null,
+        string Stream = "V:0",
+        float[]? Brightness = null,
+        ZoneColorMode ColorMode = ZoneColorMode.Edge,
+        ZoneSaliency Saliency = ZoneSaliency.Off,
+        (int A, int B)[]? CornerPairs = null,
+        bool SceneCutFlags = false,
+        bool FrameLuminance = false,
+        byte[]? AudioEnergy = null,
+        (ulong StartUs, ulong EndUs)? Credits = null,
+        double SceneCutThreshold = 0,
+        float SceneBalance = 0);
+
+    /// <summary>Result of one decoder run; <c>AreaChanges</c> lists the frames where the tracked picture area changed.</summary>
+    private readonly record struct DecodeRun(bool Started, ulong Frames, string Stderr, List<(ulong Frame, ActiveArea Area)>? AreaChanges = null);
+
+    // Below this, a segment spends too much of its time seeking and warming up the decoder to be worth its own process.
+    private const double MinSegmentSeconds = 120.0;
+
+    /// <summary>
+    /// Segments for a video of <paramref name="duration"/> seconds: <see cref="PluginConfiguration.ExtractionSegments"/>,
+    /// capped so that each one is at least <see cref="MinSegmentSeconds"/> long.
+    /// </summary>
+    private int SegmentCount(float duration) =>
+        Math.Clamp(Math.Min(_config.ExtractionSegments, (int)(duration / MinSegmentSeconds)), 1, Environment.ProcessorCount);
+
+    /// <summary>
+    /// Splits the video into <paramref name="segments"/> runs of frames and decodes them at the same time, one ffmpeg per
+    /// segment seeking to its start, each into its own part file next to <paramref name="tempPath"/>. The parts are then
+    /// appended to <paramref name="destination"/> in order. Returns the number of frames written, or 0 with nothing
+    /// written when a segment failed or came up short, in which case the caller extracts in one pass instead, and
+    /// where the tracked picture area changed.
+    /// </summary>
+    private async Task<(ulong Frames, List<(ulong Frame, ActiveArea Area)> AreaChanges)> ExtractSegmentsAsync(
+        string videoPath,
+        string tempPath,
+        string decoder,
+        string? codec,
+        int segments,
+        ulong estimatedFrames,
+        FrameJob job,
+        Stream destination,
+        IProgress<(ulong current, ulong total)>? progress,
+        CancellationToken cancellationToken)
+    {
+        var starts = new ulong[segments + 1];
+        for (int k = 0; k <= segments; k++)
+        {
+            starts[k] = estimatedFrames * (ulong)k / (ulong)segments;
+        }
+
+        var partPaths = Enumerable.Range(0, segments).Select(k => string.Create(CultureInfo.InvariantCulture, $"{tempPath}.{k}")).ToArray();
+        int workers = Math.Max(1, ExtractionWorkers / segments);
+        long done = 0;
+
+        if (_config.Debug)
+        {
+            _logger.LogInformation("[Ambilight] Extractor: extracting {Path} in {Segments} segments, {Workers} zone threads each", videoPath, segments, workers);
+        }
+
+        try
+        {
+            var runs = await Task.WhenAll(Enumerable.Range(0, segments).Select(k => DecodeSegmentAsync(k))).ConfigureAwait(false);
+
+            // Segments stopped part way leave holes, so a segmented run is never written out partially.
+            cancellationToken.ThrowIfCancellationRequested();
+            for (int k = 0; k < segments; k++)
+            {
+                // Every segment but the last is cut at a frame count; the last runs to the end of the file, which the
+                // probed duration only estimates.
+                ulong expected = starts[k + 1] - starts[k];
+                bool isShort = k < segments - 1 ? runs[k].Frames + 1 < expected : runs[k].Frames < expected / 2;
+                if (!runs[k].Started || runs[k].Frames == 0 || isShort)
+                {
+                    _logger.LogWarning("[Ambilight] Extractor: segment {Segment}/{Segments} of {Path} decoded {Frames} of {Expected} frames; extracting in one pass instead. ffmpeg stderr: {Error}",
+                        k + 1, segments, videoPath, runs[k].Frames, expected, runs[k].Stderr);
+                    return (0, new List<(ulong Frame, ActiveArea Area)>());
+                }
+            }
+
+            // Every segment starts tracking from the file's area again.
+            var areaChanges = new List<(ulong Frame, ActiveArea Area)>();
+            for (int k = 0; k < segments; k++)
+            {
+                if (k > 0)
+                {
+                    areaChanges.Add((starts[k], job.Area));
+                }
+
+                areaChanges.AddRange(runs[k].AreaChanges ?? new List<(ulong Frame, ActiveArea Area)>());
+            }
+
+            ulong total = 0;
+            foreach (var (path, run) in partPaths.Zip(runs))
+            {
+                await using var part = new FileStream(path, FileMode.Open, FileAccess.Read, FileShare.Read, OutputBufferSize);
+                await part.CopyToAsync(destination, cancellationToken).ConfigureAwait(false);
+                total += run.Frames;
+            }
+
+            return (total, areaChanges);
+        }
+        finally
+        {
+            foreach (var path in partPaths)
+            {
+                DeleteQuietly(path);
+            }
+        }
+
+        async Task<DecodeRun> DecodeSegmentAsync(int k)
+        {
+            await using var part = new FileStream(partPaths[k], FileMode.Create, FileAccess.Write, FileShare.None, OutputBufferSize);
+            using var writer = new BinaryWriter(part, Encoding.UTF8, leaveOpen: true);
+
+            ulong? limit = k < segments - 1 ? starts[k + 1] - starts[k] : null;
+            var args = BuildFfmpegArguments(videoPath, decoder, codec, StartSeconds(job, starts[k]), limit, passthrough: job.Pts != null, highBitDepth: job.HighBitDepth, deinterlacer: job.Deinterlacer, rotation: job.Rotation, stream: job.Stream);
+            return await Task.Run(() => DecodeAsync(_ffmpegPath, args, videoPath, writer, starts[k], job, workers, CpuBudgetCores / segments, OnFrame, cancellationToken), cancellationToken).ConfigureAwait(false);
+        }
+
+        void OnFrame()
+        {
+            long n = Interlocked.Increment(ref done);
+            if (n % 200 == 0)
+            {
+                progress?.Report(((ulong)n, estimatedFrames));
+            }
+        }
+    }
+
+    /// <summary>
+    /// Where ffmpeg seeks to for decoding to begin at <paramref name="frame"/>: half a frame early, so rounding never
+    /// loses that frame to the one before it.
+    /// </summary>
+    private static double StartSeconds(FrameJob job, ulong frame) =>
+        frame == 0 ? 0
+        : job.Pts != null && frame < (ulong)job.Pts.Length ? job.PtsOrigin + (job.Pts[frame - 1] + job.Pts[frame]) / 2
+        : (frame - 0.5) / job.Fps;
+
+    // How often a single-pass extraction records how far it got.
+    private static readonly TimeSpan CheckpointInterval = TimeSpan.FromSeconds(30);
+
+    /// <summary>
+    /// Progress of an interrupted extraction: the first <c>Frames</c> records fill the first <c>Bytes</c> of the temp
+    /// file. <c>Fingerprint</c> ties it to the video and the settings that shape the output.
+    /// </summary>
+    private sealed record ExtractionCheckpoint(string Fingerprint, ulong Frames, long Bytes, List<CheckpointAreaChange> AreaChanges);
+
+    private sealed record CheckpointAreaChange(ulong Frame, ActiveArea Area);
+
+    /// <summary>
+    /// Everything a resumed run must share with the interrupted one for the records to line up: the video file
+    /// (path, size and modification time), the header and the settings zone colors are computed with.
+    /// </summary>
+    private string CheckpointFingerprint(string videoPath, FrameJob job, float fps, ushort top, ushort bottom, ushort left, ushort right, byte fmt)
+    {
+        var video = new FileInfo(videoPath);
+        return string.Create(
+            CultureInfo.InvariantCulture,
+            $"AMb3|{videoPath}|{video.Length}|{video.LastWriteTimeUtc.Ticks}|{fps:R}|{top},{bottom},{left},{right}|{fmt}|{job.Stream}|{AnalysisHeight}|{job.Quality}|{job.ColorMode},{job.Saliency}|{_config.AmbilightZoneDepthFactor}|{BandDepthPercent}|{job.EdgeWeight}|{job.Area}|{job.ZonesFor != null}|{string.Join(",", job.Gains ?? Array.Empty<float>())}|{job.ColorConverter?.Curve},{job.ColorConverter?.PeakNits},{job.ColorConverter?.Strength},{job.ColorConverter?.Bt2020}|{job.Deinterlacer}|{_config.SubtitleGuard},{_config.SubtitleGuardWidthPercent}|{job.Brightness?[0]}|{job.SceneCutThreshold},{job.SceneBalance},{job.FrameLuminance},{job.AudioEnergy?.Length},{job.Credits}|{job.Pts?.Length}");
+    }
+
+    /// <summary>The checkpoint at <paramref name="path"/> when it matches <paramref name="fingerprint"/>, else null.</summary>
+    private ExtractionCheckpoint? ReadCheckpoint(string path, string fingerprint)
+    {
+        try
+        {
+            if (!File.Exists(path))
+            {
+                return null;
+            }
+
+            var checkpoint = JsonSerializer.Deserialize<ExtractionCheckpoint>(File.ReadAllText(path));
+            if (checkpoint != null && checkpoint.Fingerprint == fingerprint && checkpoint.Frames > 0)
+            {
+                return checkpoint;
+            }
+
+            _logger.LogInformation("[Ambilight] Extractor: discarding checkpoint {Path} from different settings or another version of the video", path);
+        }
+        catch (Exception ex) when (ex is IOException or JsonException or UnauthorizedAccessException)
+        {
+            _logger.LogWarning("[Ambilight] Extractor: could not read checkpoint {Path}: {Message}", path, ex.Message);
+        }
+
+        DeleteQuietly(path);
+        return null;
+    }
+
+    /// <summary>Replaces the checkpoint at <paramref name="path"/> in one rename, so a kill never leaves half of one.</summary>
+    private void WriteCheckpoint(string path, ExtractionCheckpoint checkpoint)
+    {
+        try
+        {
+            File.WriteAllText(path + ".tmp", JsonSerializer.Serialize(checkpoint));
+            File.Move(path + ".tmp", path, overwrite: true);
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException)
+        {
+            _logger.LogWarning("[Ambilight] Extractor: could not write checkpoint {Path}: {Message}", path, ex.Message);
+        }
+    }
+
+    /// <summary>
+    /// Opens the temp file of an interrupted run for appending: cuts it back to the checkpointed length (records written
+    /// after the last checkpoint are decoded again) and feeds what stays into <paramref name="hash"/>, so the checksum
+    /// still covers the whole file. Null when the file is missing or shorter than the checkpoint says.
+    /// </summary>
+    private static async Task<FileStream?> ResumeTempFileAsync(string tempPath, ExtractionCheckpoint checkpoint, HashAlgorithm hash, CancellationToken cancellationToken)
+    {
+        if (!File.Exists(tempPath) || new FileInfo(tempPath).Length < checkpoint.Bytes)
+        {
+            return null;
+        }
+
+        var file = new FileStream(tempPath, FileMode.Open, FileAccess.ReadWrite, FileShare.None, OutputBufferSize);
+        file.SetLength(checkpoint.Bytes);
+        var buffer = new byte[OutputBufferSize];
+        int n;
+        while ((n = await file.ReadAsync(buffer, cancellationToken).ConfigureAwait(false)) > 0)
+        {
+            hash.TransformBlock(buffer, 0, n, null, 0);
+        }
+
+        return file;
+    }
+
+    // zstd level for compressed binaries: zone colors are small and repetitive, so higher levels gain little.
+    private const int CompressionLevel = 9;
+
+    /// <summary>
+    /// Writes the compressed AMb3 form of the finished, uncompressed records in <paramref name="raw"/> (a header and
+    /// records, no index) to <paramref name="path"/>.
+    /// </summary>
+    private void CompressBinary(Stream raw, string path)
+    {
+        using (var packed = new FileStream(path, FileMode.Create, FileAccess.Write, FileShare.None, OutputBufferSize))
+        {
+            AmbilightBinary.WriteCompressed(raw, packed, CompressionLevel);
+            packed.Flush(true);
+        }
+
+        if (_config.Debug)
+        {
+            long size = new FileInfo(path).Length;
+            _logger.LogInformation("[Ambilight] Extractor: compressed {Raw} bytes of frames to {Packed} ({Ratio:F1}x)", raw.Length, size, raw.Length / (double)Math.Max(1, size));
+        }
+    }
+
+    /// <summary>
+    /// Runs the decoder (ffmpeg, or the external backend) and writes an AMb2 frame record to <paramref name="writer"/>
+    /// for every frame it produces, numbering them from <paramref name="firstFrame"/>. <paramref name="onFrame"/> is
+    /// called after each record. Picture area changes are added to <paramref name="areaChanges"/> when given. With a <paramref name="cpuBudget"/> (in cores), reading pauses whenever the decoder and
+    /// zone computation have used more CPU time than the budget allows; the decoder then blocks on the full pipe.
+    /// </summary>
+    private async Task<DecodeRun> DecodeAsync(
+        string decoderPath,
+        string arguments,
+        string videoPath,
+        BinaryWriter writer,
+        ulong firstFrame,
+        FrameJob job,
+        int workers,
+        double cpuBudget,
+        Action onFrame,
+        CancellationToken cancellationToken,
+        List<(ulong Frame, ActiveArea Area)>? areaChanges = null)
+    {
+        // Start ffmpeg to produce a scaled rgb48le raw video stream (or the backend to produce zone colors).
+        using var ffmpeg = new Process
+        {
+            StartInfo = new ProcessStartInfo
+            {
+                FileName = decoderPath,
+                Arguments = arguments,
+                UseShellExecute = false,
+                RedirectStandardOutput = true,
+                RedirectStandardError = true,
+                CreateNoWindow = true,
+                WorkingDirectory = Path.GetDirectoryName(videoPath) ?? "/"
+            }
+        };
+
+        Task<string> stderrTask;
+        try
+        {
+            if (_config.Debug)
+            {
+                _logger.LogInformation("[Ambilight] Extractor: starting {Decoder} for {Path}", decoderPath, videoPath);
+                _logger.LogInformation("[Ambilight] Extractor: args: {Args}", arguments);
+            }
+            ffmpeg.Start();
+
+            // Capture stderr asynchronously for error reporting
+            stderrTask = ffmpeg.StandardError.ReadToEndAsync(cancellationToken);
+        }
+        catch (Exception ex)
+        {
+            _logger.LogError(ex, job.External
+                ? "[Ambilight] Extractor: failed to start the extractor backend. Check the Rust extractor path or switch the backend to in-process."
+                : "[Ambilight] Extractor: failed to start ffmpeg. Ensure ffmpeg is installed and in PATH.");
+            return new DecodeRun(false, 0, string.Empty);
+        }
+
+        var stdout = ffmpeg.StandardOutput.BaseStream;
+        int frameSize = AnalysisWidth * AnalysisHeight * 6; // rgb48le
+        int colorSize = job.Zones.Length * job.BytesPerLed;
+        var lengthBuffer = new byte[4];
+        ulong frames = 0;
+
+        // Zone colors are computed a batch of frames at a time on up to `workers` threads, while the next batch is
+        // read from ffmpeg into the other set of buffers. Batches are written in order, so the output is the same as
+        // a single-threaded run. The external backend sends finished zone colors, so there is nothing to spread out.
+        // With OpenCL the GPU takes whole batches instead, and larger ones, since each costs a round trip.
+        var gpu = job.External ? null : CreateGpuReducer(job);
+        int batchSize = gpu != null ? GpuBatchSize : workers > 1 ? workers * 4 : 1;
+        var frameBatches = job.External ? null : new[] { NewBuffers(batchSize, frameSize), NewBuffers(batchSize, frameSize) };
+        var colorBatches = new[] { NewBuffers(batchSize, colorSize), NewBuffers(batchSize, colorSize) };
+        var gpuColors = gpu != null ? new byte[batchSize * job.Zones.Length * 3] : null;
+        var gpuZones = job.Zones;
+        var throttle = cpuBudget > 0 ? new CpuThrottle(cpuBudget) : null;
+
+        // Motion saliency compares each frame with the one before it. The last frame of a batch is copied here before
+        // the next batch is computed, since its own buffer is refilled meanwhile.
+        var carry = !job.External && job.Saliency == ZoneSaliency.Motion ? new byte[frameSize] : null;
+        var cuts = job.SceneCutFlags || job.SceneBalance > 0 ? new SceneCutDetector(job.SceneCutThreshold, job.Fps) : null;
+        var sceneBalance = job.SceneBalance > 0 ? new SceneWhiteBalance(job.SceneBalance, job.BytesPerLed) : null;
+
+        // The zones each frame of a batch is computed with: the current picture area's while black bars are tracked.
+        var tracker = job.ZonesFor != null ? new BlackBarTracker(job.Area, AnalysisWidth, AnalysisHeight) : null;
+        int trackEvery = Math.Max(1, (int)Math.Round(job.Fps / BlackBarTracker.SamplesPerSecond));
+        var zoneCache = new Dictionary<ActiveArea, (int x1, int y1, int x2, int y2)[]> { [job.Area] = job.Zones };
+        var currentZones = job.Zones;
+        var frameZones = new[] { new (int x1, int y1, int x2, int y2)[batchSize][], new (int x1, int y1, int x2, int y2)[batchSize][] };
+        areaChanges ??= new List<(ulong Frame, ActiveArea Area)>();
+        ulong read = 0;
+        TimeSpan decoderCpu = TimeSpan.Zero;
+        if (gpu == null && workers > 1 && _config.Debug)
+        {
+            _logger.LogInformation("[Ambilight] Extractor: computing zones on {Workers} threads, {Batch} frames per batch", workers, batchSize);
+        }
+
+        try
+        {
+            Task? processing = null;
+            int processingSet = 0;
+            int processingCount = 0;
+            int set = 0;
+            while (true)
+            {
+                int count = 0;
+                while (count < batchSize && !cancellationToken.IsCancellationRequested)
+                {
+                    bool gotFrame;
+                    try
+                    {
+                        gotFrame = job.External
+                            ? await ReadZoneRecordAsync(stdout, lengthBuffer, colorBatches[set][count], cancellationToken).ConfigureAwait(false)
+                            : await ReadExactAsync(stdout, frameBatches![set][count], cancellationToken).ConfigureAwait(false);
+                    }
+                    catch (OperationCanceledException) when (cancellationToken.IsCancellationRequested)
+                    {
+                        // Stopping: the frame being read is dropped, the ones already read are still written.
+                        gotFrame = false;
+                    }
+
+                    if (!gotFrame)
+                    {
+                        break; // no more frames
+                    }
+
+                    job.ColorConverter?.Apply(Samples(frameBatches![set][count]));
+                    if (tracker != null && read % (ulong)trackEvery == 0 && tracker.Observe(Samples(frameBatches![set][count])))
+                    {
+                        if (!zoneCache.TryGetValue(tracker.Current, out var zones))
+                        {
+                            zones = job.ZonesFor!(tracker.Current);
+                            zoneCache[tracker.Current] = zones;
+                        }
+
+                        currentZones = zones;
+                        areaChanges.Add((firstFrame + read, tracker.Current));
+                        if (_config.Debug)
+                        {
+                            _logger.LogInformation("[Ambilight] Extractor: picture area of {Path} changed at frame {Frame} to {Area}", videoPath, firstFrame + read, tracker.Current);
+                        }
+                    }
+
+                    frameZones[set][count] = currentZones;
+                    read++;
+                    count++;
+                }
+
+                if (processing != null)
+                {
+                    await processing.ConfigureAwait(false);
+                    for (int i = 0; i < processingCount; i++)
+                    {
+                        WriteFrameRecord(writer, job, firstFrame + frames, colorBatches[processingSet][i], cuts, sceneBalance);
+                        frames++;
+                        onFrame();
+                    }
+
+                    processing = null;
+
+                    if (throttle != null)
+                    {
+                        try
+                        {
+                            decoderCpu = ffmpeg.TotalProcessorTime;
+                        }
+                        catch (InvalidOperationException)
+                        {
+                            // exited; keep the last reading
+                        }
+
+                        var pause = throttle.Pause(decoderCpu);
+                        if (pause > TimeSpan.Zero && !cancellationToken.IsCancellationRequested)
+                        {
+                            await Task.Delay(pause, CancellationToken.None).ConfigureAwait(false);
+                        }
+                    }
+                }
+
+                if (count == 0)
+                {
+                    break;
+                }
+
+                byte[]? previous = null;
+                if (carry != null && processingCount > 0)
+                {
+                    Buffer.BlockCopy(frameBatches![processingSet][processingCount - 1], 0, carry, 0, frameSize);
+                    previous = carry;
+                }
+
+                processing = job.External ? Task.CompletedTask : MeasuredComputeAsync(set, count, previous);
+                processingSet = set;
+                processingCount = count;
+                set ^= 1;
+            }
+
+            try
+            {
+                await ffmpeg.WaitForExitAsync(cancellationToken).ConfigureAwait(false);
+            }
+            catch
+            {
+                // ignore cancellation / wait errors
+            }
+        }
+        finally
+        {
+            gpu?.Dispose();
+            if (!ffmpeg.HasExited)
+            {
+                try
+                {
+                    ffmpeg.Kill(entireProcessTree: true);
+                }
+                catch (InvalidOperationException)
+                {
+                    // already gone
+                }
+            }
+        }
+
+        string stderr = string.Empty;
+        try
+        {
+            stderr = await stderrTask.ConfigureAwait(false);
+        }
+        catch
+        {
+            // Ignore errors reading stderr
+        }
+
+        return new DecodeRun(true, frames, stderr, areaChanges);
+
+        async Task MeasuredComputeAsync(int batch, int count, byte[]? previous)
+        {
+            long started = Stopwatch.GetTimestamp();
+            bool onGpu = gpu != null;
+            await ComputeAsync(batch, count, previous).ConfigureAwait(false);
+            throttle?.AddZoneTime(Stopwatch.GetElapsedTime(started), onGpu ? 1 : Math.Min(workers, count));
+        }
+
+        // A batch that was read is computed to the end even when stopping, so that its frames can still be written.
+        Task ComputeAsync(int batch, int count, byte[]? previous)
+        {
+            if (gpu == null)
+            {
+                return ComputeBatchAsync(frameBatches![batch], colorBatches[batch], count, AnalysisWidth, AnalysisHeight, frameZones[batch], job.Rgbw, job.EdgeWeight, job.Quality, workers, CancellationToken.None, job.ColorMode, job.Saliency, previous);
+            }
+
+            return Task.Run(
+                async () =>
+                {
+                    try
+                    {
+                        var (step, meanOnly) = Sampling(job.Quality);
+                        meanOnly |= job.ColorMode == ZoneColorMode.Mean;
+
+                        // One kernel run per stretch of frames sharing a zone layout; the layout only changes with the picture area.
+                        for (int start = 0, end; start < count; start = end)
+                        {
+                            var runZones = frameZones[batch][start];
+                            for (end = start + 1; end < count && ReferenceEquals(frameZones[batch][end], runZones); end++)
+                            {
+                            }
+
+                            if (!ReferenceEquals(runZones, gpuZones))
+                            {
+                                gpu.SetZones(ClampZones(runZones));
+                                gpuZones = runZones;
+                            }
+
+                            gpu.Compute(frameBatches![batch], start, end - start, job.EdgeWeight, step, meanOnly, gpuColors!);
+                            for (int i = start; i < end; i++)
+                            {
+                                var colors = colorBatches[batch][i];
+                                for (int z = 0; z < runZones.Length; z++)
+                                {
+                                    int src = ((i - start) * runZones.Length + z) * 3;
+                                    StoreZoneColor(colors, z, gpuColors![src], gpuColors[src + 1], gpuColors[src + 2], job.Rgbw);
+                                }
+                            }
+                        }
+                    }
+                    catch (AmbilightOpenClZoneReducer.OpenClException ex)
+                    {
+                        // A lost device or driver reset mid-run: finish this file on the CPU rather than failing it.
+                        _logger.LogWarning("[Ambilight] Extractor: OpenCL zone computation failed on {Device} ({Error}); continuing on the CPU", gpu.Device, ex.Message);
+                        gpu.Dispose();
+                        gpu = null;
+                        await ComputeBatchAsync(frameBatches![batch], colorBatches[batch], count, AnalysisWidth, AnalysisHeight, frameZones[batch], job.Rgbw, job.EdgeWeight, job.Quality, workers, CancellationToken.None, job.ColorMode, job.Saliency, previous).ConfigureAwait(false);
+                    }
+                },
+                CancellationToken.None);
+        }
+    }
+
+    private const int GpuBatchSize = 32;
+
+    /// <summary>Zones clipped to the analysis frame, as the OpenCL kernel expects them.</summary>
+    private (int x1, int y1, int x2, int y2)[] ClampZones((int x1, int y1, int x2, int y2)[] zones) => zones
+        .Select(z => (Math.Clamp(z.x1, 0, AnalysisWidth), Math.Clamp(z.y1, 0, AnalysisHeight), Math.Clamp(z.x2, 0, AnalysisWidth), Math.Clamp(z.y2, 0, AnalysisHeight)))
+        .ToArray();
+
+    /// <summary>Fraction of full range from <see cref="PluginConfiguration.SceneCutThreshold"/>.</summary>
+    private double SceneCutThreshold => Math.Clamp(_config.SceneCutThreshold, 1, 100) / 100.0;
+
+    /// <summary>Strength (0-1) of <see cref="PluginConfiguration.SceneWhiteBalance"/>, or 0 when it is off.</summary>
+    private float SceneBalanceStrength => _config.SceneWhiteBalance ? Math.Clamp(_config.SceneWhiteBalanceStrength, 0, 100) / 100f : 0f;
+
+    /// <summary>
+    /// Finds scene cuts in the zone colors of consecutive frames: a cut is a frame whose LED channels differ from the
+    /// previous frame's by more than the threshold on average, and at least <see cref="MinShotSeconds"/> after the last
+    /// cut. A segment's first frame has nothing to compare with and is never a cut.
+    /// </summary>
+    private sealed class SceneCutDetector
+    {
+        // Strobes and muzzle flashes would otherwise flag every other frame.
+        private const double MinShotSeconds = 0.5;
+
+        private readonly double _threshold;
+        private readonly int _minFrames;
+        private byte[]? _previous;
+        private int _sinceCut = int.MaxValue;
+
+        public SceneCutDetector(double threshold, float fps)
+        {
+            _threshold = threshold;
+            _minFrames = Math.Max(1, (int)Math.Round(fps * MinShotSeconds));
+        }
+
+        /// <summary>Whether <paramref name="zoneColors"/> start a new shot; call once per frame, in order.</summary>
+        public bool Observe(byte[] zoneColors)
+        {
+            bool cut = false;
+            if (_previous == null)
+            {
+                _previous = new byte[zoneColors.Length];
+            }
+            else
+            {
+                long difference = 0;
+                for (int i = 0; i < zoneColors.Length; i++)
+                {
+                    difference += Math.Abs(zoneColors[i] - _previous[i]);
+                }
+
+                cut = _sinceCut >= _minFrames && difference > _threshold * 255.0 * zoneColors.Length;
+            }
+
+            _sinceCut = cut ? 0 : _sinceCut == int.MaxValue ? _sinceCut : _sinceCut + 1;
+            Buffer.BlockCopy(zoneColors, 0, _previous, 0, zoneColors.Length);
+            return cut;
+        }
+    }
+
+    /// <summary>
+    /// Pulls each scene's colors part of the way towards neutral (gray world): the mean of every RGB channel over the
+    /// scene so far, reset at each cut, gives a gain per channel that would make it gray, and <c>strength</c> of that
+    /// correction is applied. Gains are limited to <see cref="MaxGain"/> either way and near-black scenes are left alone,
+    /// so a grade is toned down rather than removed. White channels are not touched.
+    /// </summary>
+    private sealed class SceneWhiteBalance
+    {
+        private const float MaxGain = 1.5f;
+
+        // Scene mean below which there is too little color to judge the cast (on a 0-255 scale).
+        private const double MinLevel = 16.0;
+
+        private readonly float _strength;
+        private readonly int _bytesPerLed;
+        private readonly double[] _sums = new double[3];
+        private readonly float[] _gains = new float[3];
+        private long _count;
+
+        public SceneWhiteBalance(float strength, int bytesPerLed)
+        {
+            _strength = strength;
+            _bytesPerLed = bytesPerLed;
+        }
+
+        /// <summary>Adds the frame to its scene (a new one when <paramref name="cut"/>) and applies the scene's gains.</summary>
+        public void Apply(byte[] zoneColors, bool cut)
+        {
+            if (cut)
+            {
+                Array.Clear(_sums);
+                _count = 0;
+            }
+
+            for (int o = 0; o + 2 < zoneColors.Length; o += _bytesPerLed)
+            {
+                _sums[0] += zoneColors[o];
+                _sums[1] += zoneColors[o + 1];
+                _sums[2] += zoneColors[o + 2];
+                _count++;
+            }
+
+            double gray = (_sums[0] + _sums[1] + _sums[2]) / (3.0 * Math.Max(1, _count));
+            for (int c = 0; c < 3; c++)
+            {
+                double mean = _sums[c] / Math.Max(1, _count);
+                _gains[c] = gray < MinLevel
+                    ? 1f
+                    : Math.Clamp((float)Math.Pow(gray / Math.Max(mean, 1.0), _strength), 1f / MaxGain, MaxGain);
+            }
+
+            for (int o = 0; o + 2 < zoneColors.Length; o += _bytesPerLed)
+            {
+                for (int c = 0; c < 3; c++)
+                {
+                    zoneColors[o + c] = (byte)Math.Clamp((int)Math.Round(zoneColors[o + c] * _gains[c]), 0, 255);
+                }
+            }
+        }
+    }
+
+    /// <summary>
+    /// Keeps extraction to an average of a given number of cores. Decoder CPU time is read from its process; zone
+    /// computation runs inside Jellyfin, so its time is estimated as the batch's wall time on every thread it used,
+    /// which errs on the side of pausing too much.
+    /// </summary>
+    private sealed class CpuThrottle
+    {
+        // Pauses are capped so cancellation and progress stay responsive; the rest carries over to the next batch.
+        private static readonly TimeSpan MaxPause = TimeSpan.FromSeconds(2);
+        private static readonly TimeSpan MinPause = TimeSpan.FromMilliseconds(10);
+
+        private readonly double _cores;
+        private readonly Stopwatch _wall = Stopwatch.StartNew();
+        private TimeSpan _zoneCpu;
+
+        public CpuThrottle(double cores) => _cores = cores;
+
+        public void AddZoneTime(TimeSpan elapsed, int threads) => _zoneCpu += elapsed * threads;
+
+        /// <summary>How long to pause so that CPU time used so far fits the budget.</summary>
+        public TimeSpan Pause(TimeSpan decoderCpu)
+        {
+            var over = decoderCpu + _zoneCpu - _wall.Elapsed * _cores;
+            if (over <= TimeSpan.Zero)
+            {
+                return TimeSpan.Zero;
+            }
+
+            var pause = over / _cores;
+            return pause < MinPause ? TimeSpan.Zero : pause > MaxPause ? MaxPause : pause;
+        }
+    }
+
+    // Set once the missing or failing OpenCL setup has been logged, so a library scan logs it once rather than per file.
+    private static int _openClWarningLogged;
+
+    /// <summary>
+    /// OpenCL zone reducer for <paramref name="job"/> when <see cref="PluginConfiguration.ExtractionCompute"/> asks for
+    /// it, or null to compute on the CPU (not configured, or no usable GPU).
+    /// </summary>
+    private AmbilightOpenClZoneReducer? CreateGpuReducer(FrameJob job)
+    {
+        if (!string.Equals(_config.ExtractionCompute, "opencl", StringComparison.OrdinalIgnoreCase))
+        {
+            return null;
+        }
+
+        if (job.ColorMode is ZoneColorMode.Dominant or ZoneColorMode.Median || job.Saliency != ZoneSaliency.Off)
+        {
+            // The kernel only computes plain and edge weighted means; histograms and saliency stay on the CPU.
+            if (_config.Debug)
+            {
+                _logger.LogInformation("[Ambilight] Extractor: {Mode} zone colors with {Saliency} saliency are computed on the CPU", job.ColorMode, job.Saliency);
+            }
+
+            return null;
+        }
+
+        var gpu = AmbilightOpenClZoneReducer.TryCreate(AnalysisWidth, AnalysisHeight, ClampZones(job.Zones), GpuBatchSize, out var error);
+        if (gpu == null)
+        {
+            if (Interlocked.Exchange(ref _openClWarningLogged, 1) == 0)
+            {
+                _logger.LogWarning("[Ambilight] Extractor: OpenCL zone computation unavailable ({Error}); computing zones on the CPU", error);
+            }
+
+            return null;
+        }
+
+        if (_config.Debug)
+        {
+            _logger.LogInformation("[Ambilight] Extractor: computing zones with OpenCL on {Device}", gpu.Device);
+        }
+
+        return gpu;
+    }
+
+    /// <summary>
+    /// Timestamp of frame <paramref name="frameIndex"/> in microseconds: its container PTS when known, continuing
+    /// at the frame rate past the last one; otherwise index / fps (matching the Rust implementation).
+    /// </summary>
+    private static ulong FrameTimestampUs(FrameJob job, ulong frameIndex)
+    {
+        var pts = job.Pts;
+        if (pts == null)
+        {
+            return (ulong)(frameIndex * 1_000_000.0 / job.Fps);
+        }
+
+        if (frameIndex < (ulong)pts.Length)
+        {
+            return (ulong)Math.Round(pts[frameIndex] * 1_000_000.0);
+        }
+
+        return (ulong)Math.Round((pts[^1] + (frameIndex - (ulong)pts.Length + 1) / (double)job.Fps) * 1_000_000.0);
+    }
+
+    private static void WriteFrameRecord(BinaryWriter writer, FrameJob job, ulong frameIndex, byte[] zoneColors, SceneCutDetector? cuts = null, SceneWhiteBalance? sceneBalance = null)
+    {
+        bool cut = cuts?.Observe(zoneColors) ?? false;
+
+        if (job.Fills != null)
+        {
+            foreach (var (zone, from, to, t) in job.Fills)
+            {
+                for (int c = 0; c < job.BytesPerLed; c++)
+                {
+                    float a = zoneColors[from * job.BytesPerLed + c];
+                    float b = zoneColors[to * job.BytesPerLed + c];
+                    zoneColors[zone * job.BytesPerLed + c] = (byte)Math.Round(a + (b - a) * t);
+                }
+            }
+        }
+
+        if (job.CornerPairs != null)
+        {
+            BlendCorners(zoneColors, job.BytesPerLed, job.CornerPairs);
+        }
+
+        sceneBalance?.Apply(zoneColors, cut);
+
+        if (job.Brightness != null)
+        {
+            ApplyBrightness(zoneColors, job.BytesPerLed, job.Brightness);
+        }
+
+        if (job.Gains != null)
+        {
+            ApplyWhiteBalance(zoneColors, job.BytesPerLed, job.Gains);
+        }
+
+        ulong timestamp = FrameTimestampUs(job, frameIndex);
+        if (job.SceneCutFlags || job.FrameLuminance || job.AudioEnergy != null || job.Credits != null)
+        {
+            uint info = cut && job.SceneCutFlags ? AmbilightBinary.FrameSceneCut : 0;
+            if (job.Credits is { } credits && timestamp >= credits.StartUs && timestamp < credits.EndUs)
+            {
+                info |= AmbilightBinary.FrameCredits;
+            }
+            if (job.FrameLuminance)
+            {
+                info |= AmbilightBinary.FrameLuminance | AverageLuma(zoneColors, job.BytesPerLed);
+            }
+
+            if (job.AudioEnergy is { Length: > 0 } energy)
+            {
+                int window = (int)Math.Min(timestamp / (1_000_000UL / AudioEnergyRate), (ulong)energy.Length - 1);
+                info |= AmbilightBinary.FrameAudioEnergy | ((uint)energy[window] << 8);
+            }
+
+            timestamp = AmbilightBinary.PackTimestamp(timestamp, info);
+        }
+
+        writer.Write(timestamp);
+        writer.Write(zoneColors);
+    }
+
+    /// <summary>
+    /// Mean BT.709 luma of the LEDs, rounded to 0-255: what the player would otherwise work out every frame for its
+    /// adaptive gamma and brightness.
+    /// </summary>
+    private static byte AverageLuma(byte[] zoneColors, int bytesPerLed)
+    {
+        double sum = 0;
+        int leds = 0;
+        for (int o = 0; o + 2 < zoneColors.Length; o += bytesPerLed, leds++)
+        {
+            sum += 0.2126 * zoneColors[o] + 0.7152 * zoneColors[o + 1] + 0.0722 * zoneColors[o + 2];
+        }
+
+        return leds > 0 ? (byte)Math.Clamp((int)Math.Round(sum / leds), 0, 255) : (byte)0;
+    }
+
+    private static void DeleteQuietly(string path)
+    {
+        try
+        {
+            File.Delete(path);
+        }
+        catch (IOException)
+        {
+            // best effort
+        }
+    }
+
+    /// <summary>
+    /// Grabs the frame at <paramref name="timestampSeconds"/>, computes zone colors for a grid of band depths
+    /// and edge weights, writes a PPM preview per combination (frame framed by its LED colors) into
+    /// <paramref name="previewFolder"/> and suggests the combination whose colors best match the picture edge
+    /// while staying vivid. Returns null if the frame cannot be decoded.
+    /// </summary>
+    public async Task<AmbilightTuneReport?> TuneFromFrameAsync(string videoPath, double timestampSeconds, string previewFolder, CancellationToken cancellationToken)
+    {
+        var stream = StreamSpecifier(await SelectVideoStreamAsync(videoPath, cancellationToken).ConfigureAwait(false));
+        var frame = await GrabFrameAsync(videoPath, stream, timestampSeconds, cancellationToken).ConfigureAwait(false);
+        if (frame == null)
+        {
+            _logger.LogWarning("[Ambilight] Tune: could not decode a frame at {Seconds:F1}s from {Path}", timestampSeconds, videoPath);
+            return null;
+        }
+
+        ushort top = (ushort)Math.Max(0, _config.AmbilightTopLedCount);
+        ushort bottom = (ushort)Math.Max(0, _config.AmbilightBottomLedCount);
+        ushort left = (ushort)Math.Max(0, _config.AmbilightLeftLedCount);
+        ushort right = (ushort)Math.Max(0, _config.AmbilightRightLedCount);
+
+        Directory.CreateDirectory(previewFolder);
+
+        var report = new AmbilightTuneReport
+        {
+            VideoPath = videoPath,
+            TimestampSeconds = timestampSeconds,
+            PreviewFolder = previewFolder
+        };
+
+        foreach (var depthFactor in TuneDepthFactors)
+        {
+            var zones = ComputeLedZones(PreviewWidth, PreviewHeight, top, bottom, left, right, depthFactor, BandDepthPercent).ToArray();
+            if (zones.Length == 0)
+            {
+                return null;
+            }
+
+            foreach (var edgeWeight in TuneEdgeWeights)
+            {
+                var colors = new byte[zones.Length * 3];
+                ComputeFrameColors(Widen(frame), PreviewWidth, PreviewHeight, zones, false, edgeWeight, ZoneQuality.Best, colors);
+
+                var (error, saturation) = ScoreZoneColors(frame, zones, colors, top, right, bottom);
+                var previewPath = Path.Combine(previewFolder, string.Create(CultureInfo.InvariantCulture, $"tune_depth{depthFactor:0.#}_edge{edgeWeight:0.0#}.ppm"));
+                await File.WriteAllBytesAsync(previewPath, RenderPreviewPpm(frame, zones, colors, top, right, bottom), cancellationToken).ConfigureAwait(false);
+
+                report.Candidates.Add(new AmbilightTuneCandidate
+                {
+                    DepthFactor = depthFactor,
+                    EdgeWeight = edgeWeight,
+                    EdgeError = Math.Round(error, 2),
+                    Saturation = Math.Round(saturation, 3),
+                    // Lower is better: edge fidelity first, with a bonus for vivid (non-muddy) colors.
+                    Score = Math.Round(error - (saturation * 20.0), 2),
+                    PreviewPath = previewPath
+                });
+            }
+        }
+
+        report.Suggested = report.Candidates.OrderBy(c => c.Score).FirstOrDefault();
+        if (report.Suggested != null)
+        {
+            _logger.LogInformation("[Ambilight] Tune: suggested zone depth factor {Depth} and edge weight {Edge} for {Path} at {Seconds:F1}s (previews in {Folder})",
+                report.Suggested.DepthFactor, report.Suggested.EdgeWeight, videoPath, timestampSeconds, previewFolder);
+        }
+
+        return report;
+    }
+
+    // Frames compared against the video by a spot check, and the mean difference per channel (0-255) a frame may show
+    // and still pass. Spot checks recompute colors from a preview frame without HDR conversion, white balance or black
+    // bar tracking, so this only catches a binary made from different content, not small color differences.
+    private const int SpotCheckFrames = 5;
+    private const double SpotCheckTolerance = 32.0;
+
+    /// <summary>
+    /// Checks an existing binary against <paramref name="videoPath"/>: header and layout, checksum, timestamps strictly
+    /// increasing, the frames covering the video's duration and the parameters sidecar matching the current settings.
+    /// With <paramref name="spotCheck"/>, a few frames are also decoded from the video and their zone colors compared
+    /// with the stored ones. The verdict is "corrupt" for a file that cannot be played as it is, "stale" for one that
+    /// plays but no longer fits the video or settings, and "ok" otherwise.
+    /// </summary>
+    public async Task<AmbilightVerifyReport> VerifyAsync(string videoPath, string binPath, bool spotCheck, ExtractionWhiteBalance? whiteBalance, CancellationToken cancellationToken, string? colorMode = null)
+    {
+        var report = new AmbilightVerifyReport { VideoPath = videoPath, BinaryPath = binPath };
+        report.Error = AmbilightBinary.Validate(binPath, out var message);
+        if (report.Error != AmbilightBinaryError.None)
+        {
+            report.Corrupt(message);
+            return report;
+        }
+
+        if (File.Exists(binPath + AmbilightStorageService.ChecksumExtension) && !AmbilightStorageService.ChecksumMatches(binPath))
+        {
+            report.Corrupt("contents do not match the checksum sidecar");
+        }
+
+        using var binary = AmbilightBinary.OpenStreaming(binPath, cancellationToken);
+        report.FrameCount = binary.FrameCount;
+        report.Fps = binary.Fps;
+        for (int i = 1; i < binary.FrameCount; i++)
+        {
+            ulong previous = binary.TimestampUs(i - 1);
+            ulong current = binary.TimestampUs(i);
+            if (current <= previous)
+            {
+                report.Corrupt($"frame {i} is at {current} us, not after frame {i - 1} at {previous} us");
+                break;
+            }
+        }
+
+        report.CoveredSeconds = binary.TimestampUs(binary.FrameCount - 1) / 1_000_000.0 + (binary.Fps > 0 ? 1.0 / binary.Fps : 0.0);
+        var probed = await RunFfprobeAsync(
+            $"-v error -show_entries format=duration -of default=noprint_wrappers=1:nokey=1 \"{videoPath}\"",
+            cancellationToken).ConfigureAwait(false);
+        if (double.TryParse(probed?.Trim(), NumberStyles.Float, CultureInfo.InvariantCulture, out var duration) && duration > 0)
+        {
+            report.VideoSeconds = duration;
+            if (Math.Abs(report.CoveredSeconds - duration) > Math.Max(2.0, duration * 0.02))
+            {
+                report.Stale(string.Create(CultureInfo.InvariantCulture, $"frames cover {report.CoveredSeconds:F1}s of a {duration:F1}s video"));
+            }
+        }
+        else
+        {
+            report.Problems.Add("could not read the video's duration; frame coverage not checked");
+        }
+
+        report.ParametersMatch = ParametersMatch(videoPath, binPath, whiteBalance, colorMode);
+        if (report.ParametersMatch == false)
+        {
+            report.Stale("extracted from a different version of the video or with different settings");
+        }
+
+        if (spotCheck && report.Verdict != "corrupt")
+        {
+            await SpotCheckAsync(videoPath, binary, report, ColorModeFor(colorMode), cancellationToken).ConfigureAwait(false);
+        }
+
+        _logger.LogInformation("[Ambilight] Extractor: verified {Binary} against {Path}: {Verdict}{Problems}",
+            binPath, videoPath, report.Verdict, report.Problems.Count > 0 ? " (" + string.Join("; ", report.Problems) + ")" : string.Empty);
+        return report;
+    }
+
+    /// <summary>
+    /// Recomputes the zone colors of <see cref="SpotCheckFrames"/> frames spread over the video with the binary's own
+    /// layout and compares them with the stored frames; more than half failing makes the binary stale.
+    /// </summary>
+    private async Task SpotCheckAsync(string videoPath, AmbilightBinary binary, AmbilightVerifyReport report, ZoneColorMode colorMode, CancellationToken cancellationToken)
+    {
+        var stream = StreamSpecifier(await SelectVideoStreamAsync(videoPath, cancellationToken).ConfigureAwait(false));
+        var zones = ComputeLedZones(PreviewWidth, PreviewHeight, (ushort)binary.TopCount, (ushort)binary.BottomCount, (ushort)binary.LeftCount, (ushort)binary.RightCount, _config.AmbilightZoneDepthFactor, BandDepthPercent, binary.CornerZones).ToArray();
+        var computed = new byte[zones.Length * binary.Channels];
+        var stored = new float[binary.LedCount * binary.Channels];
+        double span = report.CoveredSeconds;
+
+        // Normalized binaries are compared with colors lifted by a gain measured the same way, over the full frame.
+        var brightness = _config.BrightnessNormalization
+            ? await MeasureBrightnessCurveAsync(videoPath, stream, (float)span, CreateColorConverter(videoPath, await ProbeVideoFormatAsync(videoPath, stream, cancellationToken).ConfigureAwait(false)), ActiveArea.Full, cancellationToken).ConfigureAwait(false)
+            : null;
+        int failed = 0;
+        for (int k = 1; k <= SpotCheckFrames; k++)
+        {
+            double seconds = span * k / (SpotCheckFrames + 1.0);
+            var frame = await GrabFrameAsync(videoPath, stream, seconds, cancellationToken).ConfigureAwait(false);
+            if (frame == null)
+            {
+                report.Problems.Add(string.Create(CultureInfo.InvariantCulture, $"could not decode the video at {seconds:F1}s for a spot check"));
+                continue;
+            }
+
+            ComputeFrameColors(Widen(frame), PreviewWidth, PreviewHeight, zones, binary.Rgbw, Math.Clamp(_config.AmbilightEdgeWeight, 0.0, 1.0), ZoneQuality.Best, computed, colorMode, Saliency);
+            if (binary.Corners == AmbilightBinary.CornersBlended)
+            {
+                BlendCorners(computed, binary.Channels, CornerPairs(binary.TopCount, binary.RightCount, binary.BottomCount, binary.LeftCount));
+            }
+
+            if (brightness != null)
+            {
+                ApplyBrightness(computed, binary.Channels, brightness);
+            }
+
+            int index = binary.FindFrameIndex(seconds);
+            binary.DecodeFrame(index, stored);
+            double error = 0;
+            int values = Math.Min(computed.Length, stored.Length);
+            for (int i = 0; i < values; i++)
+            {
+                error += Math.Abs(computed[i] - stored[i]);
+            }
+
+            error = values > 0 ? error / values : 0;
+            bool passed = error <= SpotCheckTolerance;
+            failed += passed ? 0 : 1;
+            report.SpotChecks.Add(new AmbilightSpotCheck { Seconds = Math.Round(seconds, 3), Frame = index, MeanError = Math.Round(error, 2), Passed = passed });
+        }
+
+        if (report.SpotChecks.Count > 0 && failed * 2 > report.SpotChecks.Count)
+        {
+            report.Stale($"{failed} of {report.SpotChecks.Count} spot-checked frames do not match the video");
+        }
+    }
+
+    // Frames decoded from the middle of the video to time the pipeline on; a few seconds on most hardware.
+    private const int BenchmarkFrames = 300;
+
+    /// <summary>
+    /// Predicts what extracting <paramref name="videoPath"/> with the current settings would give, without extracting it:
+    /// frame count and binary size from the probed duration, frame rate and LED counts, and with
+    /// <paramref name="benchmark"/> the time a single-pass extraction takes, from decoding <see cref="BenchmarkFrames"/>
+    /// frames from the middle of the video through the same pipeline and discarding them. The analysis done before
+    /// decoding (black bars, brightness normalization) is not included.
+    /// </summary>
+    public async Task<AmbilightEstimate> EstimateAsync(string videoPath, bool benchmark, CancellationToken cancellationToken)
+    {
+        var estimate = new AmbilightEstimate { VideoPath = videoPath };
+        if (string.IsNullOrEmpty(videoPath) || !File.Exists(videoPath))
+        {
+            estimate.Problems.Add("video file not found");
+            return estimate;
+        }
+
+        if (!await HasMotionVideoStreamAsync(videoPath, cancellationToken).ConfigureAwait(false))
+        {
+            estimate.Problems.Add("no video stream besides attached pictures; not extractable");
+            return estimate;
+        }
+
+        string stream = StreamSpecifier(await SelectVideoStreamAsync(videoPath, cancellationToken).ConfigureAwait(false));
+        float fps = await ProbeVideoFps(videoPath, stream, cancellationToken).ConfigureAwait(false);
+        float duration = await ProbeVideoDuration(videoPath, cancellationToken).ConfigureAwait(false);
+        (estimate.Width, estimate.Height) = await ProbeVideoSizeAsync(videoPath, stream, cancellationToken).ConfigureAwait(false);
+
+        ushort topCount = (ushort)Math.Max(0, _config.AmbilightTopLedCount);
+        ushort bottomCount = (ushort)Math.Max(0, _config.AmbilightBottomLedCount);
+        ushort leftCount = (ushort)Math.Max(0, _config.AmbilightLeftLedCount);
+        ushort rightCount = (ushort)Math.Max(0, _config.AmbilightRightLedCount);
+        int bytesPerLed = _config.AmbilightRgbw ? 4 : 3;
+        estimate.Fps = fps;
+        estimate.VideoSeconds = duration;
+        estimate.LedCount = topCount + bottomCount + leftCount + rightCount + (4 * CornerZones);
+        estimate.Frames = (ulong)(duration * fps);
+        estimate.BytesPerFrame = 8 + estimate.LedCount * bytesPerLed;
+        estimate.Bytes = AmbilightBinary.HeaderSize + (long)estimate.Frames * estimate.BytesPerFrame;
+        estimate.Segments = UseExternalBackend ? 1 : SegmentCount(duration);
+        if (duration <= 0)
+        {
+            estimate.Problems.Add("ffprobe could not tell the duration; frame count and size are unknown");
+            return estimate;
+        }
+
+        if (!benchmark)
+        {
+            return estimate;
+        }
+
+        if (UseExternalBackend)
+        {
+            estimate.Problems.Add("the extraction time is only benchmarked with the in-process backend");
+            return estimate;
+        }
+
+        string decoder = ResolveHardwareDecoder();
+        string? codec = decoder == "v4l2m2m" ? await ProbeVideoCodecAsync(videoPath, stream, cancellationToken).ConfigureAwait(false) : null;
+        var format = await ProbeVideoFormatAsync(videoPath, stream, cancellationToken).ConfigureAwait(false);
+        var job = new FrameJob(ComputeZonesInArea(ActiveArea.Full, topCount, bottomCount, leftCount, rightCount).ToArray(), _config.AmbilightRgbw, bytesPerLed,
+            Math.Clamp(_config.AmbilightEdgeWeight, 0.0, 1.0), Quality, null, fps, false,
+            ColorConverter: CreateColorConverter(videoPath, format),
+            HighBitDepth: format?.HighBitDepth ?? false,
+            Deinterlacer: SelectDeinterlacer(videoPath, format),
+            Rotation: format?.Rotation ?? 0,
+            Stream: stream,
+            ColorMode: ColorModeFor(null),
+            Saliency: Saliency);
+        double start = Math.Max(0.0, duration / 2 - BenchmarkFrames / 2.0 / fps);
+        var args = BuildFfmpegArguments(videoPath, decoder, codec, start, BenchmarkFrames, highBitDepth: job.HighBitDepth, deinterlacer: job.Deinterlacer, rotation: job.Rotation, stream: stream);
+
+        using var writer = new BinaryWriter(Stream.Null);
+        long started = Stopwatch.GetTimestamp();
+        var run = await DecodeAsync(_ffmpegPath, args, videoPath, writer, 0, job, ExtractionWorkers, CpuBudgetCores, () => { }, cancellationToken).ConfigureAwait(false);
+        double elapsed = Stopwatch.GetElapsedTime(started).TotalSeconds;
+        cancellationToken.ThrowIfCancellationRequested();
+
+        estimate.Decoder = decoder;
+        estimate.BenchmarkFrames = run.Frames;
+        if (!run.Started || run.Frames == 0 || elapsed <= 0)
+        {
+            estimate.Problems.Add($"the benchmark decoded no frames with {decoder} decoding");
+            return estimate;
+        }
+
+        estimate.BenchmarkFps = Math.Round(run.Frames / elapsed, 1);
+        estimate.Seconds = Math.Round(estimate.Frames / (run.Frames / elapsed));
+        return estimate;
+    }
+
+    private static readonly double[] TuneDepthFactors = { 2.0, 4.0, 6.0 };
+    private static readonly double[] TuneEdgeWeights = { 0.0, 0.4, 0.7, 0.9 };
+
+    private async Task<byte[]?> GrabFrameAsync(string videoPath, string stream, double timestampSeconds, CancellationToken cancellationToken)
+    {
+        var seek = Math.Max(0.0, timestampSeconds).ToString("0.###", CultureInfo.InvariantCulture);
+        using var ffmpeg = new Process
+        {
+            StartInfo = new ProcessStartInfo
+            {
+                FileName = _ffmpegPath,
+                Arguments = $"-hide_banner -loglevel error -ss {seek} -i \"{videoPath}\" -map 0:{stream} -frames:v 1 -vf scale={PreviewWidth}:{PreviewHeight} -pix_fmt rgb24 -f rawvideo pipe:1",
+                UseShellExecute = false,
+                RedirectStandardOutput = true,
+                RedirectStandardError = true,
+                CreateNoWindow = true
+            }
+        };
+
+        ffmpeg.Start();
+        var stderrTask = ffmpeg.StandardError.ReadToEndAsync(cancellationToken);
+
+        var frame = new byte[PreviewWidth * PreviewHeight * 3];
+        int readTotal = 0;
+        var stdout = ffmpeg.StandardOutput.BaseStream;
+        while (readTotal < frame.Length)
+        {
+            int n = await stdout.ReadAsync(frame.AsMemory(readTotal), cancellationToken).ConfigureAwait(false);
+            if (n <= 0)
+            {
+                break;
+            }
+            readTotal += n;
+        }
+
+        await ffmpeg.WaitForExitAsync(cancellationToken).ConfigureAwait(false);
+        await stderrTask.ConfigureAwait(false);
+        return readTotal == frame.Length ? frame : null;
+    }
+
+    /// <summary>
+    /// Mean RGB distance between each zone color and the outermost pixels of its zone (what the wall
+    /// should continue), and mean HSV saturation of the zone colors.
+    /// </summary>
+    private static (double error, double saturation) ScoreZoneColors(byte[] frame, (int x1, int y1, int x2, int y2)[] zones, byte[] colors, int top, int right, int bottom)
+    {
+        const int edgeDepth = 3;
+        double errorSum = 0.0;
+        double saturationSum = 0.0;
+
+        for (int i = 0; i < zones.Length; i++)
+        {
+            var (x1, y1, x2, y2) = zones[i];
+            x1 = Math.Clamp(x1, 0, PreviewWidth);
+            x2 = Math.Clamp(x2, 0, PreviewWidth);
+            y1 = Math.Clamp(y1, 0, PreviewHeight);
+            y2 = Math.Clamp(y2, 0, PreviewHeight);
+
+            // Shrink the zone to the strip that touches the screen border (zone order: top, right, bottom, left).
+            if (i < top) y2 = Math.Min(y2, edgeDepth);
+            else if (i < top + right) x1 = Math.Max(x1, PreviewWidth - edgeDepth);
+            else if (i < top + right + bottom) y1 = Math.Max(y1, PreviewHeight - edgeDepth);
+            else x2 = Math.Min(x2, edgeDepth);
+
+            double r = 0.0, g = 0.0, b = 0.0;
+            int count = 0;
+            for (int y = y1; y < y2; y++)
+            {
+                for (int x = x1; x < x2; x++)
+                {
+                    int idx = (y * PreviewWidth + x) * 3;
+                    r += frame[idx];
+                    g += frame[idx + 1];
+                    b += frame[idx + 2];
+                    count++;
+                }
+            }
+
+            if (count > 0)
+            {
+                double dr = colors[i * 3] - (r / count);
+                double dg = colors[i * 3 + 1] - (g / count);
+                double db = colors[i * 3 + 2] - (b / count);
+                errorSum += Math.Sqrt((dr * dr) + (dg * dg) + (db * db));
+            }
+
+            int max = Math.Max(colors[i * 3], Math.Max(colors[i * 3 + 1], colors[i * 3 + 2]));
+            int min = Math.Min(colors[i * 3], Math.Min(colors[i * 3 + 1], colors[i * 3 + 2]));
+            saturationSum += max > 0 ? (max - min) / (double)max : 0.0;
+        }
+
+        return (errorSum / zones.Length, saturationSum / zones.Length);
+    }
+
+    /// <summary>
+    /// Binary PPM of the frame surrounded by a border showing each LED's color at its zone position.
+    /// </summary>
+    private static byte[] RenderPreviewPpm(byte[] frame, (int x1, int y1, int x2, int y2)[] zones, byte[] colors, int top, int right, int bottom)
+    {
+        const int border = 16;
+        int width = PreviewWidth + (2 * border);
+        int height = PreviewHeight + (2 * border);
+        var header = Encoding.ASCII.GetBytes($"P6\n{width} {height}\n255\n");
+        var ppm = new byte[header.Length + (width * height * 3)];
+        Buffer.BlockCopy(header, 0, ppm, 0, header.Length);
+
+        void Fill(int px1, int py1, int px2, int py2, byte r, byte g, byte b)
+        {
+            for (int y = Math.Max(0, py1); y < Math.Min(height, py2); y++)
+            {
+                for (int x = Math.Max(0, px1); x < Math.Min(width, px2); x++)
+                {
+                    int o = header.Length + ((y * width + x) * 3);
+                    ppm[o] = r;
+                    ppm[o + 1] = g;
+                    ppm[o + 2] = b;
+                }
+            }
+        }
+
+        for (int y = 0; y < PreviewHeight; y++)
+        {
+            Buffer.BlockCopy(frame, y * PreviewWidth * 3, ppm, header.Length + (((y + border) * width + border) * 3), PreviewWidth * 3);
+        }
+
+        // Zone order matches ComputeLedZones: top, right, bottom, left.
+        for (int i = 0; i < zones.Length; i++)
+        {
+            var (x1, y1, x2, y2) = zones[i];
+            byte r = colors[i * 3], g = colors[i * 3 + 1], b = colors[i * 3 + 2];
+            if (i < top)
+            {
+                Fill(x1 + border, 0, x2 + border, border, r, g, b);
+            }
+            else if (i < top + right)
+            {
+                Fill(width - border, y1 + border, width, y2 + border, r, g, b);
+            }
+            else if (i < top + right + bottom)
+            {
+                Fill(x1 + border, height - border, x2 + border, height, r, g, b);
+            }
+            else
+            {
+                Fill(0, y1 + border, border, y2 + border, r, g, b);
+            }
+        }
+
+        return ppm;
+    }
+
+    /// <summary>
+    /// Expands the per-side gains of <paramref name="whiteBalance"/> into one RGB triple per LED, in zone order
+    /// (top, right, bottom, left). Returns null when there is nothing to apply.
+    /// </summary>
+    private float[]? BuildWhiteBalanceGains(ExtractionWhiteBalance? whiteBalance, int top, int right, int bottom, int left)
+    {
+        if (whiteBalance == null)
+        {
+            return null;
+        }
+
+        var sides = new[]
+        {
+            (Count: top, Gains: ParseGains(whiteBalance.TopGains)),
+            (Count: right, Gains: ParseGains(whiteBalance.RightGains)),
+            (Count: bottom, Gains: ParseGains(whiteBalance.BottomGains)),
+            (Count: left, Gains: ParseGains(whiteBalance.LeftGains))
+        };
+        if (sides.All(side => side.Gains.All(g => g == 1.0f)))
+        {
+            return null;
+        }
+
+        var gains = new float[(top + right + bottom + left) * 3];
+        int led = 0;
+        foreach (var side in sides)
+        {
+            for (int i = 0; i < side.Count; i++, led++)
+            {
+                Array.Copy(side.Gains, 0, gains, led * 3, 3);
+            }
+        }
+
+        return gains;
+    }
+
+    /// <summary>
+    /// Parses an "r,g,b" gain triple. Missing or invalid values count as 1 (unchanged); gains are clamped to 0–4.
+    /// </summary>
+    private float[] ParseGains(string? text)
+    {
+        var gains = new[] { 1.0f, 1.0f, 1.0f };
+        var parts = (text ?? string.Empty).Split(',', StringSplitOptions.TrimEntries);
+        for (int c = 0; c < 3 && c < parts.Length; c++)
+        {
+            if (parts[c].Length == 0)
+            {
+                continue;
+            }
+
+            if (float.TryParse(parts[c], NumberStyles.Float, CultureInfo.InvariantCulture, out var g) && float.IsFinite(g))
+            {
+                gains[c] = Math.Clamp(g, 0.0f, 4.0f);
+            }
+            else
+            {
+                _logger.LogWarning("[Ambilight] Extractor: ignoring invalid white balance gain '{Value}' in '{Gains}'", parts[c], text);
+            }
+        }
+
+        return gains;
+    }
+
+    private static void ApplyWhiteBalance(byte[] zoneColors, int bytesPerLed, float[] gains)
+    {
+        // RGB only; the white channel of RGBW output is left as extracted.
+        for (int led = 0, o = 0; led * 3 < gains.Length && o + 2 < zoneColors.Length; led++, o += bytesPerLed)
+        {
+            for (int c = 0; c < 3; c++)
+            {
+                zoneColors[o + c] = (byte)Math.Clamp((int)Math.Round(zoneColors[o + c] * gains[led * 3 + c]), 0, 255);
+            }
+        }
+    }
+
+    private const int BrightnessSamples = 48;
+
+    // Where the brightest tenth of a typical film's picture sits; darker films are lifted towards it.
+    private const double BrightnessPercentile = 0.9;
+    private const double BrightnessReferenceLevel = 0.6;
+
+    /// <summary>
+    /// The analysis pass of brightness normalization: measures the luma distribution inside <paramref name="area"/> over
+    /// <see cref="BrightnessSamples"/> frames spread over the video, converted like the extraction converts them, and
+    /// returns the curve that lifts its <see cref="BrightnessPercentile"/> towards <see cref="BrightnessReferenceLevel"/>,
+    /// or null when the film is bright enough or too few frames could be decoded.
+    /// </summary>
+    private async Task<float[]?> MeasureBrightnessCurveAsync(string videoPath, string stream, float duration, AmbilightColorConverter? converter, ActiveArea area, CancellationToken cancellationToken)
+    {
+        int x1 = (int)(area.Left * PreviewWidth), x2 = (int)Math.Ceiling(area.Right * PreviewWidth);
+        int y1 = (int)(area.Top * PreviewHeight), y2 = (int)Math.Ceiling(area.Bottom * PreviewHeight);
+        var histogram = new long[256];
+        long pixels = 0;
+        int sampled = 0;
+        for (int i = 1; i <= BrightnessSamples; i++)
+        {
+            var frame = await GrabFrameAsync(videoPath, stream, duration * i / (BrightnessSamples + 1.0), cancellationToken).ConfigureAwait(false);
+            if (frame == null)
+            {
+                continue;
+            }
+
+            var samples = Widen(frame);
+            converter?.Apply(samples);
+            for (int y = y1; y < y2; y++)
+            {
+                for (int x = x1; x < x2; x++)
+                {
+                    int idx = (y * PreviewWidth + x) * 3;
+                    double luma = 0.2126 * samples[idx] + 0.7152 * samples[idx + 1] + 0.0722 * samples[idx + 2];
+                    histogram[Math.Clamp((int)(luma / 257.0 + 0.5), 0, 255)]++;
+                }
+            }
+
+            pixels += (long)(x2 - x1) * (y2 - y1);
+            sampled++;
+        }
+
+        if (sampled < BrightnessSamples / 2 || pixels == 0)
+        {
+            _logger.LogWarning("[Ambilight] Extractor: decoded {Sampled} of {Samples} frames of {Path} for brightness normalization; writing colors as computed", sampled, BrightnessSamples, videoPath);
+            return null;
+        }
+
+        int level = 0;
+        for (long below = 0; level < 255; level++)
+        {
+            below += histogram[level];
+            if (below >= pixels * BrightnessPercentile)
+            {
+                break;
+            }
+        }
+
+        // A black film (or one that is nearly so) would ask for any gain; the cap keeps noise from being amplified.
+        double maxGain = Math.Clamp(_config.BrightnessNormalizationMaxGain, 1.0, 8.0);
+        double gain = Math.Clamp(BrightnessReferenceLevel * 255.0 / Math.Max(level, 1), 1.0, maxGain);
+        _logger.LogInformation("[Ambilight] Extractor: {Path} has {Percentile:P0} of its picture below {Level:P0}; brightness gain {Gain:F2}",
+            videoPath, BrightnessPercentile, level / 255.0, gain);
+        return gain > 1.01 ? BuildBrightnessCurve(gain) : null;
+    }
+
+    // Credits detection: how much of the end is searched (a share of the video, capped), the spacing of the sampled
+    // frames, the shortest block that counts as credits, and how many samples in a row may look like something else
+    // (a logo, a still between cards) before a block ends.
+    private const double CreditsSearchShare = 0.2;
+    private const double CreditsSearchMaxSeconds = 900.0;
+    private const double CreditsSampleSeconds = 8.0;
+    private const double CreditsMinSeconds = 60.0;
+    private const int CreditsMaxMisses = 2;
+
+    /// <summary>
+    /// The analysis pass of credits detection: samples a frame every <see cref="CreditsSampleSeconds"/> near the end of
+    /// the video and looks for the longest block of frames that look like credits, mostly black with a little white or
+    /// grey text (black frames between cards don't break a block). Returns its start and end in microseconds, or null
+    /// when there is no block of at least <see cref="CreditsMinSeconds"/>.
+    /// </summary>
+    private async Task<(ulong StartUs, ulong EndUs)?> DetectCreditsAsync(string videoPath, string stream, float duration, CancellationToken cancellationToken)
+    {
+        if (duration <= 0)
+        {
+            return null;
+        }
+
+        double search = Math.Min(duration * CreditsSearchShare, CreditsSearchMaxSeconds);
+        double from = duration - search;
+        int samples = (int)(search / CreditsSampleSeconds);
+        (double Start, double End, int Hits)? best = null;
+        double blockStart = 0, blockEnd = 0;
+        int hits = 0, misses = 0;
+        for (int i = 0; i <= samples; i++)
+        {
+            double seconds = from + i * CreditsSampleSeconds;
+            var frame = await GrabFrameAsync(videoPath, stream, seconds, cancellationToken).ConfigureAwait(false);
+            var look = frame == null ? CreditsLook.Black : ClassifyCreditsFrame(frame);
+            if (look == CreditsLook.Credits)
+            {
+                blockStart = hits == 0 ? seconds : blockStart;
+                blockEnd = seconds + CreditsSampleSeconds;
+                hits++;
+                misses = 0;
+            }
+            else if (look == CreditsLook.Picture && hits > 0 && ++misses > CreditsMaxMisses)
+            {
+                best = hits > (best?.Hits ?? 0) ? (blockStart, blockEnd, hits) : best;
+                hits = 0;
+                misses = 0;
+            }
+        }
+
+        best = hits > (best?.Hits ?? 0) ? (blockStart, blockEnd, hits) : best;
+        if (best is not { } block || block.End - block.Start < CreditsMinSeconds)
+        {
+            if (_config.Debug)
+            {
+                _logger.LogInformation("[Ambilight] Extractor: no end credits found in the last {Seconds:F0}s of {Path}", search, videoPath);
+            }
+
+            return null;
+        }
+
+        _logger.LogInformation("[Ambilight] Extractor: end credits of {Path} from {Start:F0}s to {End:F0}s", videoPath, block.Start, Math.Min(block.End, duration));
+        return ((ulong)(block.Start * 1_000_000.0), (ulong)(Math.Min(block.End, duration) * 1_000_000.0));
+    }
+
+    private enum CreditsLook
+    {
+        Picture,
+        Black,
+        Credits
+    }
+
+    /// <summary>
+    /// Credits: at least 85% of the frame near black and 0.2-15% bright, nearly colorless pixels (text). A frame with no
+    /// bright pixels is black, anything else a picture.
+    /// </summary>
+    private static CreditsLook ClassifyCreditsFrame(byte[] frame)
+    {
+        int pixels = frame.Length / 3;
+        int dark = 0, text = 0;
+        for (int o = 0; o + 2 < frame.Length; o += 3)
+        {
+            int r = frame[o], g = frame[o + 1], b = frame[o + 2];
+            double luma = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+            if (luma < 40)
             {
-                var fi = new FileInfo(outputPath);
-                if (fi.Exists)
+                dark++;
+            }
+            else if (luma > 128 && Math.Max(r, Math.Max(g, b)) - Math.Min(r, Math.Min(g, b)) < 48)
+            {
+                text++;
+            }
+        }
+
+        if (dark < pixels * 0.85)
+        {
+            return CreditsLook.Picture;
+        }
+
+        return text < pixels * 0.002 ? CreditsLook.Black : text <= pixels * 0.15 ? CreditsLook.Credits : CreditsLook.Picture;
+    }
+
+    // Audio energy track: values per second (frames take their window's), the decode rate of the audio pass, and the
+    // range below the film's loud moments that the scale spans (quieter windows are 0).
+    private const int AudioEnergyRate = 10;
+    private const int AudioSampleRate = 8000;
+    private const double AudioEnergyRangeDb = 30.0;
+
+    // Below this the loudest moments are too quiet to be a soundtrack (a silent track or a broken decode).
+    private const double AudioSilenceDb = -60.0;
+
+    /// <summary>
+    /// The audio pass of the energy track: decodes the first audio stream as 8 kHz mono and returns the RMS level of each
+    /// 1/<see cref="AudioEnergyRate"/> s window on a 0-255 scale, 255 at the film's loud moments (its 99th percentile)
+    /// and 0 at <see cref="AudioEnergyRangeDb"/> below them. Null when the video has no audio or it cannot be decoded.
+    /// </summary>
+    private async Task<byte[]?> MeasureAudioEnergyAsync(string videoPath, CancellationToken cancellationToken)
+    {
+        using var ffmpeg = new Process
+        {
+            StartInfo = new ProcessStartInfo
+            {
+                FileName = _ffmpegPath,
+                Arguments = $"-hide_banner -loglevel error -i \"{videoPath}\" -map 0:a:0? -vn -ac 1 -ar {AudioSampleRate} -f s16le pipe:1",
+                UseShellExecute = false,
+                RedirectStandardOutput = true,
+                RedirectStandardError = true,
+                CreateNoWindow = true
+            }
+        };
+
+        ffmpeg.Start();
+        var stderrTask = ffmpeg.StandardError.ReadToEndAsync(cancellationToken);
+
+        var levels = new List<double>();
+        var window = new byte[AudioSampleRate / AudioEnergyRate * 2];
+        try
+        {
+            var stdout = ffmpeg.StandardOutput.BaseStream;
+            while (await ReadExactAsync(stdout, window, cancellationToken).ConfigureAwait(false))
+            {
+                double sum = 0;
+                for (int i = 0; i < window.Length; i += 2)
                 {
-                    fileSize = fi.Length;
+                    double v = BinaryPrimitives.ReadInt16LittleEndian(window.AsSpan(i)) / 32768.0;
+                    sum += v * v;
                 }
+
+                levels.Add(10.0 * Math.Log10(Math.Max(sum / (window.Length / 2), 1e-10)));
             }
-            catch
+
+            await ffmpeg.WaitForExitAsync(cancellationToken).ConfigureAwait(false);
+        }
+        finally
+        {
+            // Cancelled mid-decode: ffmpeg would otherwise keep running with nobody reading its output.
+            if (!ffmpeg.HasExited)
             {
-                // ignore size errors
+                try
+                {
+                    ffmpeg.Kill(entireProcessTree: true);
+                }
+                catch (InvalidOperationException)
+                {
+                    // already gone
+                }
             }
+        }
 
-            if (_config.Debug)
+        var stderr = await stderrTask.ConfigureAwait(false);
+
+        var sorted = levels.ToArray();
+        Array.Sort(sorted);
+        double loud = sorted.Length > 0 ? sorted[(int)((sorted.Length - 1) * 0.99)] : double.NegativeInfinity;
+        if (loud < AudioSilenceDb)
+        {
+            _logger.LogWarning("[Ambilight] Extractor: no usable audio in {Path} for the energy track; writing frames without it. {Error}", videoPath, stderr.Trim());
+            return null;
+        }
+
+        var energy = new byte[levels.Count];
+        for (int i = 0; i < energy.Length; i++)
+        {
+            energy[i] = (byte)Math.Clamp((int)Math.Round((levels[i] - loud + AudioEnergyRangeDb) / AudioEnergyRangeDb * 255.0), 0, 255);
+        }
+
+        if (_config.Debug)
+        {
+            _logger.LogInformation("[Ambilight] Extractor: audio energy of {Path}: {Windows} windows, loud moments at {Loud:F1} dBFS", videoPath, energy.Length, loud);
+        }
+
+        return energy;
+    }
+
+    /// <summary>
+    /// Scale for each value of a zone's brightest channel: the curve 1 - (1 - x)^gain, which brightens the darkest zones
+    /// by <paramref name="gain"/>, lifts brighter ones less and leaves full white at full white, divided by x so that
+    /// the other channels keep their ratio to the brightest one (and the zone its hue). Entry 0 is the gain itself.
+    /// </summary>
+    private static float[] BuildBrightnessCurve(double gain)
+    {
+        var scale = new float[256];
+        scale[0] = (float)gain;
+        for (int v = 1; v < 256; v++)
+        {
+            double x = v / 255.0;
+            scale[v] = (float)((1.0 - Math.Pow(1.0 - x, gain)) / x);
+        }
+
+        return scale;
+    }
+
+    private static void ApplyBrightness(byte[] zoneColors, int bytesPerLed, float[] scale)
+    {
+        for (int o = 0; o + bytesPerLed <= zoneColors.Length; o += bytesPerLed)
+        {
+            float s = scale[Math.Max(zoneColors[o], Math.Max(zoneColors[o + 1], zoneColors[o + 2]))];
+            for (int c = 0; c < bytesPerLed; c++)
+            {
+                zoneColors[o + c] = (byte)Math.Min(255, (int)(zoneColors[o + c] * s + 0.5f));
+            }
+        }
+    }
+
+    private static List<(int x1, int y1, int x2, int y2)> ComputeLedZones(int width, int height, ushort top, ushort bottom, ushort left, ushort right, double depthFactor = 2.0, BandDepths depthPercent = default, int cornerZones = 0)
+    {
+        // Band depth is a multiple of the LED spacing (2x by default), clamped to 12% of the frame, unless a side
+        // has an explicit depth in percent of the frame height (top, bottom) or width (left, right).
+        // With corner zones the sides stop at the neighbouring bands and the corners are appended after the left side.
+        if (double.IsNaN(depthFactor) || depthFactor <= 0.0)
+        {
+            depthFactor = 2.0;
+        }
+
+        int w = width;
+        int h = height;
+
+        double topSpacing = top > 0 ? (double)w / top : w;
+        double bottomSpacing = bottom > 0 ? (double)w / bottom : w;
+        double leftSpacing = left > 0 ? (double)h / left : h;
+        double rightSpacing = right > 0 ? (double)h / right : h;
+
+        int Clamp(int v, int lo, int hi)
+        {
+            if (v < lo) return lo;
+            if (v > hi) return hi;
+            return v;
+        }
+
+        int Depth(double percent, double spacing, int size) => percent > 0
+            ? Clamp((int)Math.Round(size * Math.Min(percent, MaxBandDepthPercent) / 100.0), 1, size)
+            : Clamp((int)Math.Round(spacing * depthFactor), 12, (int)(size * 0.12));
+
+        int topH = Depth(depthPercent.Top, topSpacing, h);
+        int bottomH = Depth(depthPercent.Bottom, bottomSpacing, h);
+        int leftW = Depth(depthPercent.Left, leftSpacing, w);
+        int rightW = Depth(depthPercent.Right, rightSpacing, w);
+
+        // Span the sides are divided over: the whole edge, or the part between the corners.
+        int xStart = 0, xEnd = w, yStart = 0, yEnd = h;
+        if (cornerZones > 0 && w - leftW - rightW >= 1)
+        {
+            (xStart, xEnd) = (leftW, w - rightW);
+        }
+
+        if (cornerZones > 0 && h - topH - bottomH >= 1)
+        {
+            (yStart, yEnd) = (topH, h - bottomH);
+        }
+
+        double spanW = xEnd - xStart;
+        double spanH = yEnd - yStart;
+        var zones = new List<(int, int, int, int)>();
+
+        // Top: left → right
+        for (int i = 0; i < top; i++)
+        {
+            int x1 = xStart + (int)(i * spanW / top);
+            int x2 = xStart + (int)((i + 1) * spanW / top);
+            zones.Add((x1, 0, x2, topH));
+        }
+
+        // Right: top → bottom
+        for (int i = 0; i < right; i++)
+        {
+            int y1 = yStart + (int)(i * spanH / right);
+            int y2 = yStart + (int)((i + 1) * spanH / right);
+            zones.Add((w - rightW, y1, w, y2));
+        }
+
+        // Bottom: right → left
+        for (int i = 0; i < bottom; i++)
+        {
+            int x2 = (int)(xEnd - i * spanW / bottom);
+            int x1 = (int)(xEnd - (i + 1) * spanW / bottom);
+            zones.Add((x1, h - bottomH, x2, h));
+        }
+
+        // Left: bottom → top
+        for (int i = 0; i < left; i++)
+        {
+            int y2 = (int)(yEnd - i * spanH / left);
+            int y1 = (int)(yEnd - (i + 1) * spanH / left);
+            zones.Add((0, y1, leftW, y2));
+        }
+
+        // Corners: top-right, bottom-right, bottom-left, top-left, each cut into slices that continue the direction of
+        // the side before it (rightwards, downwards, leftwards, upwards).
+        void Corner(int x1, int y1, int x2, int y2, bool alongX, bool reverse)
+        {
+            int size = alongX ? x2 - x1 : y2 - y1;
+            for (int k = 0; k < cornerZones; k++)
+            {
+                int j = reverse ? cornerZones - 1 - k : k;
+                int a = (alongX ? x1 : y1) + (int)(j * size / (double)cornerZones);
+                int b = Math.Max(a + 1, (alongX ? x1 : y1) + (int)((j + 1) * size / (double)cornerZones));
+                zones.Add(alongX ? (a, y1, b, y2) : (x1, a, x2, b));
+            }
+        }
+
+        if (cornerZones > 0)
+        {
+            Corner(w - rightW, 0, w, topH, alongX: true, reverse: false);
+            Corner(w - rightW, h - bottomH, w, h, alongX: false, reverse: false);
+            Corner(0, h - bottomH, leftW, h, alongX: true, reverse: true);
+            Corner(0, 0, leftW, topH, alongX: false, reverse: true);
+        }
+
+        return zones;
+    }
+
+    /// <summary>Corner handling from <see cref="PluginConfiguration.AmbilightCorners"/>, as stored in the header.</summary>
+    private byte Corners => (_config.AmbilightCorners ?? "share").Trim().ToLowerInvariant() switch
+    {
+        "dedicated" => AmbilightBinary.CornersDedicated,
+        "blend" => AmbilightBinary.CornersBlended,
+        _ => AmbilightBinary.CornersShared
+    };
+
+    /// <summary>Zones per corner: <see cref="PluginConfiguration.AmbilightCornerZones"/> with dedicated corners, else 0.</summary>
+    private int CornerZones => Corners == AmbilightBinary.CornersDedicated
+        ? Math.Clamp(_config.AmbilightCornerZones, 1, AmbilightBinary.MaxCornerZones)
+        : 0;
+
+    /// <summary>
+    /// The last LED of each side and the first of the next one (top-right, bottom-right, bottom-left, top-left), whose
+    /// colors are averaged with blended corners. Corners next to a side without LEDs are left out.
+    /// </summary>
+    private static (int A, int B)[] CornerPairs(int top, int right, int bottom, int left)
+    {
+        var sides = new[] { top, right, bottom, left };
+        int total = sides.Sum();
+        var pairs = new List<(int, int)>();
+        int start = 0;
+        for (int side = 0; side < 4; side++)
+        {
+            int next = (side + 1) % 4;
+            if (sides[side] > 0 && sides[next] > 0)
+            {
+                int end = start + sides[side] - 1;
+                pairs.Add((end, (end + 1) % total));
+            }
+
+            start += sides[side];
+        }
+
+        return pairs.ToArray();
+    }
+
+    private static void BlendCorners(byte[] zoneColors, int bytesPerLed, (int A, int B)[] pairs)
+    {
+        foreach (var (a, b) in pairs)
+        {
+            for (int c = 0; c < bytesPerLed; c++)
+            {
+                byte mean = (byte)((zoneColors[a * bytesPerLed + c] + zoneColors[b * bytesPerLed + c] + 1) / 2);
+                zoneColors[a * bytesPerLed + c] = mean;
+                zoneColors[b * bytesPerLed + c] = mean;
+            }
+        }
+    }
+
+    // Deepest band a side can be set to; at half the frame, opposite bands meet in the middle.
+    private const double MaxBandDepthPercent = 50.0;
+
+    /// <summary>Band depth of each side in percent of the frame (0 = from the zone depth factor).</summary>
+    private readonly record struct BandDepths(double Top, double Right, double Bottom, double Left)
+    {
+        public bool IsSet => Top > 0 || Right > 0 || Bottom > 0 || Left > 0;
+
+        public override string ToString() => string.Create(CultureInfo.InvariantCulture, $"{Top},{Right},{Bottom},{Left}");
+    }
+
+    /// <summary>The per-side band depths from <see cref="PluginConfiguration.AmbilightBandDepthPercentTop"/> and its siblings.</summary>
+    private BandDepths BandDepthPercent => new(
+        Math.Max(0, _config.AmbilightBandDepthPercentTop),
+        Math.Max(0, _config.AmbilightBandDepthPercentRight),
+        Math.Max(0, _config.AmbilightBandDepthPercentBottom),
+        Math.Max(0, _config.AmbilightBandDepthPercentLeft));
+
+    /// <summary>Picture area inside black bars, as fractions of the frame (0..1 from the left and top edges).</summary>
+    private readonly record struct ActiveArea(double Left, double Top, double Right, double Bottom)
+    {
+        public static ActiveArea Full { get; } = new(0, 0, 1, 1);
+
+        public bool IsFull => Left <= 0 && Top <= 0 && Right >= 1 && Bottom >= 1;
+    }
+
+    // Rows and columns darker than this (mean luma, 0-255) count as black bar; compression noise in bars stays below it.
+    private const int BlackBarLuma = 20;
+
+    // Bars thinner than this share of the frame are ignored, and none is taken to be thicker than the max.
+    private const double MinBlackBar = 0.015;
+    private const double MaxBlackBar = 0.35;
+
+    private const int BlackBarSamples = 9;
+
+    /// <summary>
+    /// Finds letterbox or pillarbox bars from <see cref="BlackBarSamples"/> frames spread over the video, so zones
+    /// can be laid out on the picture rather than on permanently black bars. Each side takes the median over the
+    /// samples that are bright enough to tell (subtitles in the bottom bar only show on some), and opposite sides
+    /// share the thinner of the two so that a bright logo or subtitle never crops into the picture.
+    /// </summary>
+    private async Task<ActiveArea> DetectActiveAreaAsync(string videoPath, string stream, float duration, CancellationToken cancellationToken)
+    {
+        var bars = new List<(int top, int bottom, int left, int right)>();
+        for (int i = 1; i <= BlackBarSamples; i++)
+        {
+            var frame = await GrabFrameAsync(videoPath, stream, duration * i / (BlackBarSamples + 1.0), cancellationToken).ConfigureAwait(false);
+            if (frame != null && MeasureBlackBars(Widen(frame), PreviewWidth, PreviewHeight) is { } measured)
             {
-                _logger.LogInformation("[Ambilight] Extractor: wrote AMb2 file {Output} with {Frames} frames", outputPath, frameIndex);
+                bars.Add(measured);
             }
+        }
+
+        if (bars.Count < 3)
+        {
             if (_config.Debug)
             {
-                _logger.LogInformation("[Ambilight] Extractor: final file {Output} size {SizeBytes} bytes (~{SizeMb:F2} MB)",
-                    outputPath,
-                    fileSize,
-                    fileSize / 1024.0 / 1024.0);
+                _logger.LogInformation("[Ambilight] Extractor: too few bright frames to look for black bars in {Path}; using the full frame", videoPath);
+            }
+
+            return ActiveArea.Full;
+        }
+
+        static int Median(IEnumerable<int> values)
+        {
+            var sorted = values.OrderBy(v => v).ToList();
+            return sorted[sorted.Count / 2];
+        }
+
+        var area = AreaFromBars(
+            (Median(bars.Select(b => b.top)), Median(bars.Select(b => b.bottom)), Median(bars.Select(b => b.left)), Median(bars.Select(b => b.right))),
+            PreviewWidth,
+            PreviewHeight);
+        if (!area.IsFull)
+        {
+            _logger.LogInformation("[Ambilight] Extractor: black bars in {Path}: {Vertical:P1} top and bottom, {Horizontal:P1} left and right; zones follow the picture",
+                videoPath, area.Top, area.Left);
+        }
+
+        return area;
+    }
+
+    /// <summary>
+    /// The picture area left by measured bars: opposite sides share the thinner bar, and bars outside
+    /// <see cref="MinBlackBar"/>..<see cref="MaxBlackBar"/> are dropped or capped.
+    /// </summary>
+    private static ActiveArea AreaFromBars((int top, int bottom, int left, int right) bars, int width, int height)
+    {
+        double vertical = Math.Min(bars.top, bars.bottom) / (double)height;
+        double horizontal = Math.Min(bars.left, bars.right) / (double)width;
+        vertical = vertical < MinBlackBar ? 0 : Math.Min(vertical, MaxBlackBar);
+        horizontal = horizontal < MinBlackBar ? 0 : Math.Min(horizontal, MaxBlackBar);
+        return new ActiveArea(horizontal, vertical, 1 - horizontal, 1 - vertical);
+    }
+
+    private static AmbilightPictureArea ToPictureArea(double startSeconds, ActiveArea area) => new()
+    {
+        StartSeconds = Math.Round(startSeconds, 3),
+        Left = area.Left,
+        Top = area.Top,
+        Right = area.Right,
+        Bottom = area.Bottom
+    };
+
+    /// <summary>
+    /// Follows the black bars of decoded frames so zones can move with the picture when a film changes aspect ratio
+    /// (IMAX sequences, a 4:3 flashback). A new area only replaces the current one once it has been measured on
+    /// <see cref="HoldSamples"/> samples in a row, so a dark scene, a title card or a subtitle in a bar does not make
+    /// the zones jump; frames too dark to measure are skipped without breaking the run.
+    /// </summary>
+    private sealed class BlackBarTracker
+    {
+        public const double SamplesPerSecond = 2.0;
+
+        // Three seconds of agreeing samples.
+        private const int HoldSamples = 6;
+
+        // Areas closer than this on every side are the same picture; measurements wobble by a row or two.
+        private const double Tolerance = 0.02;
+
+        private readonly int _width;
+        private readonly int _height;
+        private ActiveArea _pending;
+        private int _pendingCount;
+
+        public BlackBarTracker(ActiveArea initial, int width, int height)
+        {
+            Current = initial;
+            _width = width;
+            _height = height;
+        }
+
+        public ActiveArea Current { get; private set; }
+
+        /// <summary>Measures one rgb48le frame; returns true when it confirmed a change of <see cref="Current"/>.</summary>
+        public bool Observe(ReadOnlySpan<ushort> frame)
+        {
+            if (MeasureBlackBars(frame, _width, _height) is not { } bars)
+            {
+                return false;
             }
+
+            var area = AreaFromBars(bars, _width, _height);
+            if (Close(area, Current))
+            {
+                _pendingCount = 0;
+                return false;
+            }
+
+            if (_pendingCount > 0 && Close(area, _pending))
+            {
+                _pendingCount++;
+            }
+            else
+            {
+                _pending = area;
+                _pendingCount = 1;
+            }
+
+            if (_pendingCount < HoldSamples)
+            {
+                return false;
+            }
+
+            Current = _pending;
+            _pendingCount = 0;
             return true;
         }
-        catch (Exception ex) when (!cancellationToken.IsCancellationRequested)
+
+        private static bool Close(ActiveArea a, ActiveArea b)
+            => Math.Abs(a.Left - b.Left) < Tolerance && Math.Abs(a.Top - b.Top) < Tolerance
+                && Math.Abs(a.Right - b.Right) < Tolerance && Math.Abs(a.Bottom - b.Bottom) < Tolerance;
+    }
+
+    /// <summary>
+    /// Rows or columns of black bar on each side of an rgb48le frame, or null when the frame is too dark overall to
+    /// tell bars from picture.
+    /// </summary>
+    private static (int top, int bottom, int left, int right)? MeasureBlackBars(ReadOnlySpan<ushort> frame, int width, int height)
+    {
+        long total = 0;
+        var rows = new int[height];
+        var cols = new long[width];
+        for (int y = 0; y < height; y++)
+        {
+            long rowSum = 0;
+            var row = frame.Slice(y * width * 3, width * 3);
+            for (int x = 0; x < width; x++)
+            {
+                int luma = (row[x * 3] * 77 + row[x * 3 + 1] * 150 + row[x * 3 + 2] * 29) >> 16;
+                rowSum += luma;
+                cols[x] += luma;
+            }
+
+            rows[y] = (int)(rowSum / width);
+            total += rowSum;
+        }
+
+        if (total / ((long)width * height) < BlackBarLuma * 2)
+        {
+            return null;
+        }
+
+        int top = 0;
+        while (top < height / 2 && rows[top] < BlackBarLuma)
+        {
+            top++;
+        }
+
+        int bottom = 0;
+        while (bottom < height / 2 && rows[height - 1 - bottom] < BlackBarLuma)
         {
-            _logger.LogError(ex, "[Ambilight] Extractor: exception extracting {Path}", videoPath);
-            return false;
+            bottom++;
+        }
+
+        int left = 0;
+        while (left < width / 2 && cols[left] / height < BlackBarLuma)
+        {
+            left++;
+        }
+
+        int right = 0;
+        while (right < width / 2 && cols[width - 1 - right] / height < BlackBarLuma)
+        {
+            right++;
         }
+
+        return (top, bottom, left, right);
     }
 
-    private static List<(int x1, int y1, int x2, int y2)> ComputeLedZones(int width, int height, ushort top, ushort bottom, ushort left, ushort right)
+    /// <summary>LED zones at the analysis size, laid out on <paramref name="area"/> instead of the whole frame.</summary>
+    private List<(int x1, int y1, int x2, int y2)> ComputeZonesInArea(ActiveArea area, ushort top, ushort bottom, ushort left, ushort right)
     {
-        int w = width;
-        int h = height;
+        int x0 = (int)Math.Round(area.Left * AnalysisWidth);
+        int y0 = (int)Math.Round(area.Top * AnalysisHeight);
+        int x1 = (int)Math.Round(area.Right * AnalysisWidth);
+        int y1 = (int)Math.Round(area.Bottom * AnalysisHeight);
+        var zones = ComputeLedZones(x1 - x0, y1 - y0, top, bottom, left, right, _config.AmbilightZoneDepthFactor, BandDepthPercent, CornerZones);
+        ApplySubtitleGuard(zones, top, right, bottom, y1 - y0);
+        return zones
+            .Select(z => (z.x1 + x0, z.y1 + y0, z.x2 + x0, z.y2 + y0))
+            .ToList();
+    }
 
-        double topSpacing = top > 0 ? (double)w / top : w;
-        double bottomSpacing = bottom > 0 ? (double)w / bottom : w;
-        double leftSpacing = left > 0 ? (double)h / left : h;
-        double rightSpacing = right > 0 ? (double)h / right : h;
+    // Depth "shrink" cuts guarded bottom zones to, as a share of the picture height; subtitles rarely reach the last rows.
+    private const double SubtitleGuardDepth = 0.02;
 
-        int Clamp(int v, int lo, int hi)
+    private string SubtitleGuardMode => (_config.SubtitleGuard ?? "off").Trim().ToLowerInvariant();
+
+    /// <summary>
+    /// Whether bottom zone <paramref name="i"/> of <paramref name="bottom"/> lies in the bottom-center region where
+    /// hardcoded subtitles and lower thirds appear (<see cref="PluginConfiguration.SubtitleGuardWidthPercent"/> of the width).
+    /// </summary>
+    private bool IsSubtitleGuarded(int i, int bottom)
+    {
+        double half = Math.Clamp(_config.SubtitleGuardWidthPercent, 0, 100) / 200.0;
+        return Math.Abs((i + 0.5) / bottom - 0.5) < half;
+    }
+
+    /// <summary>
+    /// Keeps the bottom zones under the subtitle region from sampling the text: "shrink" cuts them to a thin strip
+    /// along the bottom edge, "exclude" empties them so they are filled in from their neighbors (see
+    /// <see cref="SubtitleGuardFills"/>).
+    /// </summary>
+    private void ApplySubtitleGuard(List<(int x1, int y1, int x2, int y2)> zones, int top, int right, int bottom, int height)
+    {
+        var mode = SubtitleGuardMode;
+        if (mode is not ("shrink" or "exclude"))
         {
-            if (v < lo) return lo;
-            if (v > hi) return hi;
-            return v;
+            return;
         }
 
-        int topH = Clamp((int)Math.Round(topSpacing * 2.0), 12, (int)(h * 0.12));
-        int bottomH = Clamp((int)Math.Round(bottomSpacing * 2.0), 12, (int)(h * 0.12));
-        int leftW = Clamp((int)Math.Round(leftSpacing * 2.0), 12, (int)(w * 0.12));
-        int rightW = Clamp((int)Math.Round(rightSpacing * 2.0), 12, (int)(w * 0.12));
+        int depth = Math.Max(2, (int)Math.Round(height * SubtitleGuardDepth));
+        for (int i = 0; i < bottom; i++)
+        {
+            if (!IsSubtitleGuarded(i, bottom))
+            {
+                continue;
+            }
 
-        var zones = new List<(int, int, int, int)>();
+            int z = top + right + i;
+            var (x1, y1, x2, y2) = zones[z];
+            zones[z] = mode == "exclude" ? (x1, y2, x1, y2) : (x1, Math.Max(y1, y2 - depth), x2, y2);
+        }
+    }
 
-        // Top: left → right
-        for (int i = 0; i < top; i++)
+    /// <summary>
+    /// For the "exclude" subtitle guard, each excluded bottom zone with the nearest sampled zones on either side (around
+    /// the corners if the whole bottom edge is excluded) and how far it is between them, or null when nothing is excluded.
+    /// </summary>
+    private (int Zone, int From, int To, float T)[]? SubtitleGuardFills(int top, int right, int bottom, int left)
+    {
+        int total = top + right + bottom + left;
+        if (SubtitleGuardMode != "exclude" || bottom == 0)
         {
-            int x1 = (int)(i * w / (double)top);
-            int x2 = (int)((i + 1) * w / (double)top);
-            zones.Add((x1, 0, x2, topH));
+            return null;
         }
 
-        // Right: top → bottom
-        for (int i = 0; i < right; i++)
+        var excluded = new HashSet<int>(Enumerable.Range(0, bottom).Where(i => IsSubtitleGuarded(i, bottom)).Select(i => top + right + i));
+        if (excluded.Count == 0 || excluded.Count == total)
         {
-            int y1 = (int)(i * h / (double)right);
-            int y2 = (int)((i + 1) * h / (double)right);
-            zones.Add((w - rightW, y1, w, y2));
+            return null;
         }
 
-        // Bottom: right → left
-        for (int i = 0; i < bottom; i++)
+        var fills = new List<(int Zone, int From, int To, float T)>();
+        foreach (var zone in excluded.OrderBy(z => z))
         {
-            int x2 = (int)(w - i * w / (double)bottom);
-            int x1 = (int)(w - (i + 1) * w / (double)bottom);
-            zones.Add((x1, h - bottomH, x2, h));
+            int before = 1;
+            while (excluded.Contains((zone - before + total) % total))
+            {
+                before++;
+            }
+
+            int after = 1;
+            while (excluded.Contains((zone + after) % total))
+            {
+                after++;
+            }
+
+            fills.Add((zone, (zone - before + total) % total, (zone + after) % total, before / (float)(before + after)));
         }
 
-        // Left: bottom → top
-        for (int i = 0; i < left; i++)
+        return fills.ToArray();
+    }
+
+    /// <summary>
+    /// The 16-bit samples of an rgb48le frame as read from ffmpeg. Little-endian, like every platform Jellyfin runs on.
+    /// </summary>
+    private static Span<ushort> Samples(byte[] frame) => MemoryMarshal.Cast<byte, ushort>(frame.AsSpan());
+
+    /// <summary>An RGB24 preview frame as 16-bit samples, for code that works on decoded rgb48le frames.</summary>
+    private static ushort[] Widen(byte[] rgb24)
+    {
+        var samples = new ushort[rgb24.Length];
+        for (int i = 0; i < rgb24.Length; i++)
         {
-            int y2 = (int)(h - i * h / (double)left);
-            int y1 = (int)(h - (i + 1) * h / (double)left);
-            zones.Add((0, y1, leftW, y2));
+            samples[i] = (ushort)(rgb24[i] * 257);
         }
 
-        return zones;
+        return samples;
+    }
+
+    private static byte[][] NewBuffers(int count, int size)
+    {
+        var buffers = new byte[count][];
+        for (int i = 0; i < count; i++)
+        {
+            buffers[i] = new byte[size];
+        }
+
+        return buffers;
+    }
+
+    /// <summary>
+    /// Computes the zone colors of <paramref name="count"/> frames, each with its own <paramref name="zones"/>, into the matching <paramref name="colors"/> buffers,
+    /// in the background on up to <paramref name="workers"/> threads (inline when there is only one).
+    /// </summary>
+    private static Task ComputeBatchAsync(
+        byte[][] frames,
+        byte[][] colors,
+        int count,
+        int width,
+        int height,
+        (int x1, int y1, int x2, int y2)[][] zones,
+        bool rgbw,
+        double edgeWeight,
+        ZoneQuality quality,
+        int workers,
+        CancellationToken cancellationToken,
+        ZoneColorMode colorMode = ZoneColorMode.Edge,
+        ZoneSaliency saliency = ZoneSaliency.Off,
+        byte[]? previous = null)
+    {
+        // Each frame's predecessor for motion saliency: the frame before it in the batch, or the one carried over.
+        ReadOnlySpan<ushort> Previous(int i) =>
+            saliency != ZoneSaliency.Motion ? default : i > 0 ? Samples(frames[i - 1]) : previous != null ? Samples(previous) : default;
+
+        if (workers <= 1)
+        {
+            for (int i = 0; i < count; i++)
+            {
+                ComputeFrameColors(Samples(frames[i]), width, height, zones[i], rgbw, edgeWeight, quality, colors[i], colorMode, saliency, Previous(i));
+            }
+
+            return Task.CompletedTask;
+        }
+
+        var options = new ParallelOptions { MaxDegreeOfParallelism = workers, CancellationToken = cancellationToken };
+        return Task.Run(
+            () => Parallel.For(0, count, options, i => ComputeFrameColors(Samples(frames[i]), width, height, zones[i], rgbw, edgeWeight, quality, colors[i], colorMode, saliency, Previous(i))),
+            cancellationToken);
     }
 
     private static void ComputeFrameColors(
-        byte[] frame,
+        ReadOnlySpan<ushort> frame,
         int width,
         int height,
         (int x1, int y1, int x2, int y2)[] zones,
         bool rgbw,
-        byte[] output)
+        double edgeWeight,
+        ZoneQuality quality,
+        byte[] output,
+        ZoneColorMode colorMode = ZoneColorMode.Edge,
+        ZoneSaliency saliency = ZoneSaliency.Off,
+        ReadOnlySpan<ushort> previous = default)
     {
         int bytesPerLed = rgbw ? 4 : 3;
 
@@ -549,43 +4087,83 @@ public sealed class AmbilightInProcessExtractor
                 continue;
             }
 
-            // Extract edge-dominant color (matching Rust implementation)
-            var (rOut, gOut, bOut) = ExtractEdgeDominantColor(frame, width, height, x1, y1, x2, y2);
-            byte wOut = 0;
-
-            if (rgbw)
+            // Extract edge-dominant color (matching Rust implementation), or just the mean in fast mode
+            var (step, meanOnly) = Sampling(quality);
+            var (rOut, gOut, bOut) = colorMode switch
             {
-                // Same logic as Rust: extract white component as min(r,g,b).
-                byte wComp = Math.Min(rOut, Math.Min(gOut, bOut));
-                rOut = (byte)(rOut - wComp);
-                gOut = (byte)(gOut - wComp);
-                bOut = (byte)(bOut - wComp);
-                wOut = wComp;
-            }
+                ZoneColorMode.Dominant => ExtractDominantColor(frame, width * 3, x1, y1, x2, y2, step),
+                ZoneColorMode.Median => ExtractMedianColor(frame, width * 3, x1, y1, x2, y2, step),
+                _ when saliency != ZoneSaliency.Off => ExtractSalientColor(frame, previous, width * 3, x1, y1, x2, y2, step, saliency),
+                _ when meanOnly || colorMode == ZoneColorMode.Mean => ExtractMeanColor(frame, width * 3, x1, y1, x2, y2, step),
+                _ => ExtractEdgeDominantColor(frame, width * 3, x1, y1, x2, y2, edgeWeight, step)
+            };
+            StoreZoneColor(output, i, rOut, gOut, bOut, rgbw);
+        }
+    }
 
-            int outBase = i * bytesPerLed;
-            output[outBase] = rOut;
-            output[outBase + 1] = gOut;
-            output[outBase + 2] = bOut;
-            if (rgbw)
-            {
-                output[outBase + 3] = wOut;
-            }
+    /// <summary>Pixel step, and whether edge weighting is skipped, for each <see cref="ZoneQuality"/>.</summary>
+    private static (int step, bool meanOnly) Sampling(ZoneQuality quality) => quality switch
+    {
+        ZoneQuality.Fast => (FastSampleStep, true),
+        ZoneQuality.Balanced => (2, false),
+        _ => (1, false)
+    };
+
+    private static void StoreZoneColor(byte[] output, int zone, byte r, byte g, byte b, bool rgbw)
+    {
+        byte w = 0;
+        if (rgbw)
+        {
+            // Same logic as Rust: extract white component as min(r,g,b).
+            w = Math.Min(r, Math.Min(g, b));
+            r = (byte)(r - w);
+            g = (byte)(g - w);
+            b = (byte)(b - w);
+        }
+
+        int outBase = zone * (rgbw ? 4 : 3);
+        output[outBase] = r;
+        output[outBase + 1] = g;
+        output[outBase + 2] = b;
+        if (rgbw)
+        {
+            output[outBase + 3] = w;
+        }
+    }
+
+    // Per-thread scratch for ExtractEdgeDominantColor, grown to the largest zone seen, so zone work allocates nothing
+    // per frame however many frames the workers go through.
+    [ThreadStatic]
+    private static float[]? _grayScratch;
+
+    [ThreadStatic]
+    private static float[]? _edgeScratch;
+
+    private static float[] Scratch(ref float[]? buffer, int size)
+    {
+        if (buffer == null || buffer.Length < size)
+        {
+            buffer = new float[size];
         }
+
+        return buffer;
     }
 
     /// <summary>
     /// Extract color from a zone using edge detection + center weighting, matching the Rust extractor.
     /// Uses Sobel edge detection (simpler than Canny but similar results) combined with Gaussian center weighting.
+    /// With a <paramref name="step"/> above 1, only every step-th pixel in each direction is weighted and sampled.
+    /// Reads the rgb48le frame in place, <paramref name="stride"/> samples per row, and rounds to 8 bits only at the end.
     /// </summary>
     private static (byte r, byte g, byte b) ExtractEdgeDominantColor(
-        byte[] frame,
-        int frameWidth,
-        int frameHeight,
+        ReadOnlySpan<ushort> frame,
+        int stride,
         int x1,
         int y1,
         int x2,
-        int y2)
+        int y2,
+        double edgeWeightShare = 0.7,
+        int step = 1)
     {
         int w = x2 - x1;
         int h = y2 - y1;
@@ -595,17 +4173,25 @@ public sealed class AmbilightInProcessExtractor
             return (0, 0, 0);
         }
 
-        // Compute grayscale and Sobel edge strength for the ROI
-        var edgeStrength = new float[h, w];
-        float maxEdge = 0.0f;
-
+        // Grayscale approximation once per pixel (0.299*R + 0.587*G + 0.114*B); the Sobel kernel reads it 9 times.
+        var gray = Scratch(ref _grayScratch, w * h);
         for (int yy = 0; yy < h; yy++)
         {
+            var row = frame.Slice((y1 + yy) * stride + x1 * 3, w * 3);
             for (int xx = 0; xx < w; xx++)
             {
-                int fx = x1 + xx;
-                int fy = y1 + yy;
+                gray[yy * w + xx] = row[xx * 3] * 0.299f + row[xx * 3 + 1] * 0.587f + row[xx * 3 + 2] * 0.114f;
+            }
+        }
+
+        // Compute Sobel edge strength for the ROI
+        var edgeStrength = Scratch(ref _edgeScratch, w * h);
+        float maxEdge = 0.0f;
 
+        for (int yy = 0; yy < h; yy += step)
+        {
+            for (int xx = 0; xx < w; xx += step)
+            {
                 // Sobel operators for edge detection
                 float gx = 0.0f;
                 float gy = 0.0f;
@@ -615,25 +4201,22 @@ public sealed class AmbilightInProcessExtractor
                 {
                     for (int dx = -1; dx <= 1; dx++)
                     {
-                        int nx = Math.Clamp(fx + dx, x1, x2 - 1);
-                        int ny = Math.Clamp(fy + dy, y1, y2 - 1);
-                        int idx = (ny * frameWidth + nx) * 3;
-
-                        // Grayscale approximation: 0.299*R + 0.587*G + 0.114*B
-                        float gray = frame[idx] * 0.299f + frame[idx + 1] * 0.587f + frame[idx + 2] * 0.114f;
+                        int nx = Math.Clamp(xx + dx, 0, w - 1);
+                        int ny = Math.Clamp(yy + dy, 0, h - 1);
+                        float g = gray[ny * w + nx];
 
                         // Sobel X kernel: [-1 0 1; -2 0 2; -1 0 1]
-                        if (dx == -1) gx -= gray * (dy == 0 ? 2.0f : 1.0f);
-                        else if (dx == 1) gx += gray * (dy == 0 ? 2.0f : 1.0f);
+                        if (dx == -1) gx -= g * (dy == 0 ? 2.0f : 1.0f);
+                        else if (dx == 1) gx += g * (dy == 0 ? 2.0f : 1.0f);
 
                         // Sobel Y kernel: [-1 -2 -1; 0 0 0; 1 2 1]
-                        if (dy == -1) gy -= gray * (dx == 0 ? 2.0f : 1.0f);
-                        else if (dy == 1) gy += gray * (dx == 0 ? 2.0f : 1.0f);
+                        if (dy == -1) gy -= g * (dx == 0 ? 2.0f : 1.0f);
+                        else if (dy == 1) gy += g * (dx == 0 ? 2.0f : 1.0f);
                     }
                 }
 
                 float magnitude = MathF.Sqrt(gx * gx + gy * gy);
-                edgeStrength[yy, xx] = magnitude;
+                edgeStrength[yy * w + xx] = magnitude;
                 maxEdge = Math.Max(maxEdge, magnitude);
             }
         }
@@ -641,11 +4224,11 @@ public sealed class AmbilightInProcessExtractor
         // Normalize edge strengths to 0-1 range
         if (maxEdge > 0.0f)
         {
-            for (int yy = 0; yy < h; yy++)
+            for (int yy = 0; yy < h; yy += step)
             {
-                for (int xx = 0; xx < w; xx++)
+                for (int xx = 0; xx < w; xx += step)
                 {
-                    edgeStrength[yy, xx] /= maxEdge;
+                    edgeStrength[yy * w + xx] /= maxEdge;
                 }
             }
         }
@@ -660,12 +4243,12 @@ public sealed class AmbilightInProcessExtractor
         double sigma = Math.Max(minSize / 4.0, 1.0);
         double sigmaSq2 = 2.0 * sigma * sigma;
 
-        for (int yy = 0; yy < h; yy++)
+        for (int yy = 0; yy < h; yy += step)
         {
-            for (int xx = 0; xx < w; xx++)
+            for (int xx = 0; xx < w; xx += step)
             {
                 // Edge weight (0-1)
-                double edgeWeight = edgeStrength[yy, xx];
+                double edgeWeight = edgeStrength[yy * w + xx];
 
                 // Center weight (Gaussian)
                 double dx = xx - centerX;
@@ -673,16 +4256,14 @@ public sealed class AmbilightInProcessExtractor
                 double distSq = dx * dx + dy * dy;
                 double centerWeight = Math.Exp(-distSq / sigmaSq2);
 
-                // Combined: 70% edge, 30% center (matching Rust implementation)
-                double weight = Math.Max(edgeWeight * 0.7 + centerWeight * 0.3, 0.01);
+                // Combined: 70% edge, 30% center by default (matching Rust implementation)
+                double weight = Math.Max(edgeWeight * edgeWeightShare + centerWeight * (1.0 - edgeWeightShare), 0.01);
 
-                int fx = x1 + xx;
-                int fy = y1 + yy;
-                int idx = (fy * frameWidth + fx) * 3;
+                int idx = (y1 + yy) * stride + (x1 + xx) * 3;
 
-                byte r = frame[idx];
-                byte g = frame[idx + 1];
-                byte b = frame[idx + 2];
+                ushort r = frame[idx];
+                ushort g = frame[idx + 1];
+                ushort b = frame[idx + 2];
 
                 rSum += r * weight;
                 gSum += g * weight;
@@ -694,9 +4275,9 @@ public sealed class AmbilightInProcessExtractor
         if (totalWeight > 0.0)
         {
             return (
-                (byte)Math.Clamp((int)Math.Round(rSum / totalWeight), 0, 255),
-                (byte)Math.Clamp((int)Math.Round(gSum / totalWeight), 0, 255),
-                (byte)Math.Clamp((int)Math.Round(bSum / totalWeight), 0, 255)
+                (byte)Math.Clamp((int)Math.Round(rSum / totalWeight / 257.0), 0, 255),
+                (byte)Math.Clamp((int)Math.Round(gSum / totalWeight / 257.0), 0, 255),
+                (byte)Math.Clamp((int)Math.Round(bSum / totalWeight / 257.0), 0, 255)
             );
         }
 
@@ -707,7 +4288,7 @@ public sealed class AmbilightInProcessExtractor
         {
             for (int xx = x1; xx < x2; xx++)
             {
-                int idx = (yy * frameWidth + xx) * 3;
+                int idx = yy * stride + xx * 3;
                 rAvg += frame[idx];
                 gAvg += frame[idx + 1];
                 bAvg += frame[idx + 2];
@@ -718,13 +4299,322 @@ public sealed class AmbilightInProcessExtractor
         if (count > 0)
         {
             return (
-                (byte)(rAvg / count),
-                (byte)(gAvg / count),
-                (byte)(bAvg / count)
+                (byte)(rAvg / count / 257),
+                (byte)(gAvg / count / 257),
+                (byte)(bAvg / count / 257)
             );
         }
 
         return (0, 0, 0);
     }
+
+    // Fast mode samples a 1-in-16 grid. Sampling starts at the zone's corner, so no zone comes up empty.
+    private const int FastSampleStep = 4;
+
+    /// <summary>
+    /// Plain mean color of a zone, sampling every <paramref name="step"/>-th pixel in each direction of the rgb48le frame
+    /// (<paramref name="stride"/> samples per row).
+    /// </summary>
+    private static (byte r, byte g, byte b) ExtractMeanColor(ReadOnlySpan<ushort> frame, int stride, int x1, int y1, int x2, int y2, int step)
+    {
+        long rSum = 0, gSum = 0, bSum = 0;
+        int count = 0;
+        for (int yy = y1; yy < y2; yy += step)
+        {
+            for (int xx = x1; xx < x2; xx += step)
+            {
+                int idx = yy * stride + xx * 3;
+                rSum += frame[idx];
+                gSum += frame[idx + 1];
+                bSum += frame[idx + 2];
+                count++;
+            }
+        }
+
+        if (count == 0)
+        {
+            return (0, 0, 0);
+        }
+
+        return ((byte)(rSum / count / 257), (byte)(gSum / count / 257), (byte)(bSum / count / 257));
+    }
+
+    // Weight of a pixel whose luma stands out by the full range, against 1 for one that does not stand out at all.
+    private const double SaliencyGain = 8.0;
+
+    /// <summary>
+    /// Mean color of a zone with each pixel (every <paramref name="step"/>-th in each direction) weighted by how much it
+    /// stands out: its luma distance from the zone's mean luma for contrast, or from the same pixel of
+    /// <paramref name="previous"/> for motion. Without a previous frame (the first one decoded), motion weighs all
+    /// pixels the same.
+    /// </summary>
+    private static (byte r, byte g, byte b) ExtractSalientColor(ReadOnlySpan<ushort> frame, ReadOnlySpan<ushort> previous, int stride, int x1, int y1, int x2, int y2, int step, ZoneSaliency saliency)
+    {
+        bool motion = saliency == ZoneSaliency.Motion;
+        bool hasPrevious = previous.Length == frame.Length;
+        double meanLuma = 0;
+        if (!motion)
+        {
+            int samples = 0;
+            for (int yy = y1; yy < y2; yy += step)
+            {
+                for (int xx = x1; xx < x2; xx += step)
+                {
+                    meanLuma += Luma(frame, yy * stride + xx * 3);
+                    samples++;
+                }
+            }
+
+            meanLuma = samples > 0 ? meanLuma / samples : 0;
+        }
+
+        double rSum = 0, gSum = 0, bSum = 0, weights = 0;
+        for (int yy = y1; yy < y2; yy += step)
+        {
+            for (int xx = x1; xx < x2; xx += step)
+            {
+                int idx = yy * stride + xx * 3;
+                double distance = motion
+                    ? hasPrevious ? Math.Abs(Luma(frame, idx) - Luma(previous, idx)) : 0
+                    : Math.Abs(Luma(frame, idx) - meanLuma);
+                double weight = 1.0 + SaliencyGain * distance / 65535.0;
+                rSum += frame[idx] * weight;
+                gSum += frame[idx + 1] * weight;
+                bSum += frame[idx + 2] * weight;
+                weights += weight;
+            }
+        }
+
+        if (weights <= 0)
+        {
+            return (0, 0, 0);
+        }
+
+        return ((byte)(rSum / weights / 257), (byte)(gSum / weights / 257), (byte)(bSum / weights / 257));
+
+        static int Luma(ReadOnlySpan<ushort> samples, int idx) => (54 * samples[idx] + 183 * samples[idx + 1] + 19 * samples[idx + 2]) >> 8;
+    }
+
+    // Dominant color bins: 3 bits per channel, coarse enough that one subject's shading falls into one or two bins.
+    private const int DominantBits = 3;
+    private const int DominantBins = 1 << (3 * DominantBits);
+
+    [ThreadStatic]
+    private static long[]? _dominantScratch;
+
+    [ThreadStatic]
+    private static int[]? _medianScratch;
+
+    /// <summary>
+    /// Most common color of a zone: pixels (every <paramref name="step"/>-th in each direction) are counted into a
+    /// coarse RGB histogram, and the mean of the pixels in the fullest bin is returned, so a strongly colored subject
+    /// keeps its own color instead of being averaged with everything around it.
+    /// </summary>
+    private static (byte r, byte g, byte b) ExtractDominantColor(ReadOnlySpan<ushort> frame, int stride, int x1, int y1, int x2, int y2, int step)
+    {
+        // Per bin: count, then the r, g and b sums.
+        var bins = _dominantScratch ??= new long[DominantBins * 4];
+        Array.Clear(bins);
+        const int shift = 16 - DominantBits;
+        for (int yy = y1; yy < y2; yy += step)
+        {
+            for (int xx = x1; xx < x2; xx += step)
+            {
+                int idx = yy * stride + xx * 3;
+                int bin = 4 * (((frame[idx] >> shift) << (2 * DominantBits)) | ((frame[idx + 1] >> shift) << DominantBits) | (frame[idx + 2] >> shift));
+                bins[bin]++;
+                bins[bin + 1] += frame[idx];
+                bins[bin + 2] += frame[idx + 1];
+                bins[bin + 3] += frame[idx + 2];
+            }
+        }
+
+        int best = 0;
+        for (int bin = 4; bin < bins.Length; bin += 4)
+        {
+            if (bins[bin] > bins[best])
+            {
+                best = bin;
+            }
+        }
+
+        long count = bins[best];
+        if (count == 0)
+        {
+            return (0, 0, 0);
+        }
+
+        return ((byte)(bins[best + 1] / count / 257), (byte)(bins[best + 2] / count / 257), (byte)(bins[best + 3] / count / 257));
+    }
+
+    /// <summary>
+    /// Per-channel median of a zone (every <paramref name="step"/>-th pixel in each direction), from 8-bit histograms.
+    /// Unlike the mean, a few very bright or dark pixels do not pull it.
+    /// </summary>
+    private static (byte r, byte g, byte b) ExtractMedianColor(ReadOnlySpan<ushort> frame, int stride, int x1, int y1, int x2, int y2, int step)
+    {
+        var histograms = _medianScratch ??= new int[3 * 256];
+        Array.Clear(histograms);
+        int count = 0;
+        for (int yy = y1; yy < y2; yy += step)
+        {
+            for (int xx = x1; xx < x2; xx += step)
+            {
+                int idx = yy * stride + xx * 3;
+                histograms[frame[idx] >> 8]++;
+                histograms[256 + (frame[idx + 1] >> 8)]++;
+                histograms[512 + (frame[idx + 2] >> 8)]++;
+                count++;
+            }
+        }
+
+        if (count == 0)
+        {
+            return (0, 0, 0);
+        }
+
+        return (Median(0), Median(256), Median(512));
+
+        byte Median(int offset)
+        {
+            int below = 0;
+            for (int v = 0; v < 256; v++)
+            {
+                below += histograms[offset + v];
+                if (below * 2 >= count)
+                {
+                    return (byte)v;
+                }
+            }
+
+            return 255;
+        }
+    }
+}
+
+/// <summary>
+/// Outcome of <see cref="AmbilightInProcessExtractor.ExtractAsync"/>.
+/// </summary>
+public enum AmbilightExtractionResult
+{
+    Success,
+    Failed,
+
+    /// <summary>No usable video (only attached pictures) or shorter than <see cref="PluginConfiguration.MinExtractDurationSeconds"/>.</summary>
+    NotExtractable,
+
+    /// <summary>The existing output was extracted from the same file with the same settings; nothing was done.</summary>
+    UpToDate,
+
+    /// <summary>
+    /// Stopped part way (server shutdown): the frames decoded so far were written as a playable binary, marked partial
+    /// in its parameters sidecar, and the next run picks up where this one stopped.
+    /// </summary>
+    Partial
+}
+
+/// <summary>
+/// Result of <see cref="AmbilightInProcessExtractor.TuneFromFrameAsync"/>.
+/// </summary>
+public class AmbilightTuneReport
+{
+    public string VideoPath { get; set; } = string.Empty;
+    public double TimestampSeconds { get; set; }
+    public string PreviewFolder { get; set; } = string.Empty;
+    public List<AmbilightTuneCandidate> Candidates { get; set; } = new();
+    public AmbilightTuneCandidate? Suggested { get; set; }
+}
+
+/// <summary>
+/// Result of <see cref="AmbilightInProcessExtractor.VerifyAsync"/>. <c>Verdict</c> is "ok", "stale" (plays, but no
+/// longer fits the video or settings) or "corrupt" (cannot be played as it is); <c>Problems</c> says why.
+/// </summary>
+public class AmbilightVerifyReport
+{
+    public string VideoPath { get; set; } = string.Empty;
+    public string BinaryPath { get; set; } = string.Empty;
+    public string Verdict { get; set; } = "ok";
+    public List<string> Problems { get; set; } = new();
+    public AmbilightBinaryError Error { get; set; }
+    public int FrameCount { get; set; }
+    public double Fps { get; set; }
+    public double CoveredSeconds { get; set; }
+    public double VideoSeconds { get; set; } // 0 when ffprobe could not tell
+    public bool? ParametersMatch { get; set; } // null when the binary has no parameters sidecar
+    public List<AmbilightSpotCheck> SpotChecks { get; set; } = new();
+
+    internal void Corrupt(string problem)
+    {
+        Verdict = "corrupt";
+        Problems.Add(problem);
+    }
+
+    internal void Stale(string problem)
+    {
+        if (Verdict == "ok")
+        {
+            Verdict = "stale";
+        }
+
+        Problems.Add(problem);
+    }
+}
+
+/// <summary>
+/// Result of <see cref="AmbilightInProcessExtractor.EstimateAsync"/>. <c>Seconds</c> is the predicted time of a
+/// single-pass extraction (segmented runs are usually faster), null without a benchmark.
+/// </summary>
+public class AmbilightEstimate
+{
+    public string VideoPath { get; set; } = string.Empty;
+    public int Width { get; set; }
+    public int Height { get; set; }
+    public double Fps { get; set; }
+    public double VideoSeconds { get; set; }
+    public int LedCount { get; set; }
+    public ulong Frames { get; set; }
+    public int BytesPerFrame { get; set; } // timestamp + zone colors
+    public long Bytes { get; set; }
+    public int Segments { get; set; }
+    public string? Decoder { get; set; }
+    public ulong BenchmarkFrames { get; set; }
+    public double? BenchmarkFps { get; set; } // frames per second through decoding and zone computation
+    public double? Seconds { get; set; }
+    public List<string> Problems { get; set; } = new();
+}
+
+/// <summary>
+/// Result of <see cref="AmbilightExtractorService.EstimatePendingAsync"/>: the estimates of all pending items and their sums.
+/// </summary>
+public class AmbilightLibraryEstimate
+{
+    public int Items { get; set; }
+    public ulong Frames { get; set; }
+    public long Bytes { get; set; }
+    public double VideoSeconds { get; set; }
+    public List<AmbilightEstimate> Estimates { get; set; } = new();
+}
+
+/// <summary>
+/// One frame of a verify spot check: stored zone colors against ones recomputed from the video.
+/// </summary>
+public class AmbilightSpotCheck
+{
+    public double Seconds { get; set; }
+    public int Frame { get; set; }
+    public double MeanError { get; set; } // mean difference per channel, 0-255
+    public bool Passed { get; set; }
 }
 
+/// <summary>
+/// One zone depth / edge weight combination evaluated by the tuner.
+/// </summary>
+public class AmbilightTuneCandidate
+{
+    public double DepthFactor { get; set; } // AmbilightZoneDepthFactor
+    public double EdgeWeight { get; set; } // AmbilightEdgeWeight
+    public double EdgeError { get; set; } // mean RGB distance to the picture edge (lower = more faithful)
+    public double Saturation { get; set; } // mean saturation of the LED colors (0-1)
+    public double Score { get; set; }
+    public string PreviewPath { get; set; } = string.Empty;
+}
@@ -7,12 +7,18 @@
 // (at your option) any later version.
 
 using System;
+using System.Collections.Concurrent;
 using System.Collections.Generic;
+using System.Diagnostics;
+using System.Globalization;
 using System.IO;
 using System.Linq;
 using System.Net;
+using System.Net.Http;
 using System.Net.Sockets;
+using System.Text.Json;
 using System.Threading;
+using System.Threading.Channels;
 using System.Threading.Tasks;
 using Jellyfin.Plugin.Ambilight;
 using Microsoft.Extensions.Logging;
@@ -22,7 +28,7 @@ namespace Jellyfin.Plugin.Ambilight.Services;
 
 /// <summary>
 /// In-process implementation of the ambilight-player logic in C#.
-/// Reads AMb2 binaries and streams frames over UDP to WLED, applying the same
+/// Reads AMb2 and AMb3 binaries and streams frames over UDP to WLED (or ESPHome, see <see cref="AmbilightUdpSink"/>), applying the same
 /// gamma/saturation/brightness/smoothing logic as the Rust player.
 /// </summary>
 public sealed class AmbilightInProcessPlayer : IDisposable
@@ -38,24 +44,313 @@ public sealed class AmbilightInProcessPlayer : IDisposable
     private readonly object _stateLock = new();
     private bool _isPaused;
     private double? _pendingSeekSeconds;
+    private DateTime _pendingSeekUtc;
+
+    // Client playback speed (RATE); frame timing is scaled by it.
+    private double _playbackRate = 1.0;
+
+    // Latest position heartbeat from Jellyfin (see ReportPosition), consumed by PlayAsync for drift correction.
+    private double? _heartbeatSeconds;
+    private DateTime _heartbeatUtc;
+
+    // When the last heartbeat or seek arrived; used to detect a client that stopped reporting.
+    private DateTime _lastContactUtc = DateTime.UtcNow;
+    private const double HeartbeatLossFadeSeconds = 2.0;
+
+    // Drift correction: heartbeats are smoothed, and the player clock is slewed by at most ±2% so the
+    // smoothed drift converges over about DriftConvergeSeconds. Larger offsets are jumped instead.
+    private const double MaxClockSlew = 0.02;
+    private const double DriftConvergeSeconds = 10.0;
+    private const double DriftSmoothing = 0.3;
+    private const double DriftResyncSeconds = 2.0;
+
+    // Phase-locked players credit at most this much load/seek latency against the reference epoch.
+    private const double MaxLaunchDelaySeconds = 10.0;
+
+    // LOAD/UNLOAD requests picked up by RunAsync; _loadSignal wakes it while idle.
+    private PendingLoad? _pendingLoad;
+    private bool _unloadRequested;
+    private readonly SemaphoreSlim _loadSignal = new(0);
+
+    // Resident players that receive no LOAD for this long exit on their own.
+    private static readonly TimeSpan ResidentIdleTimeout = TimeSpan.FromMinutes(30);
+
+    private readonly AmbilightBinaryCache? _binaryCache;
+
+    // LED count the target reports over WLED's JSON API (null if it is not WLED or did not answer).
+    private int? _deviceLedCount;
+    private static readonly HttpClient WledHttp = new() { Timeout = TimeSpan.FromSeconds(2) };
+
+    // Smoothed one-way network latency to the target, probed over WLED's JSON API (see ProbeLatencyAsync).
+    private double? _networkLatencySeconds;
+    private static readonly TimeSpan LatencyProbeInterval = TimeSpan.FromMinutes(1);
+
+    // Static-scene fade: output within this per-channel distance of the frame that started the static
+    // period counts as unchanged; once faded, brightness ramps down over StaticFadeRampSeconds.
+    private const int StaticSceneTolerance = 6;
+    private const double StaticFadeRampSeconds = 30.0;
+
+    // Pause modes (see PauseFadeFactor): how fast "dim" reaches its level, and one "breathe" cycle.
+    private const double PauseDimRampSeconds = 1.0;
+    private const double PauseBreathePeriodSeconds = 6.0;
+
+    // Frames are processed this far ahead of their due time (twice the smoothed processing time plus a margin, see
+    // FrameSender) so they are ready when the sender puts them on the wire.
+    private const double MinProcessingLeadMs = 1.0;
+    private const double MaxProcessingLeadMs = 20.0;
+
+    // Night schedule (see NightBrightnessFactor): brightness eases to and from the night level over this long.
+    private const double NightRampMinutes = 10.0;
+
+    // Post-playback ambient (see AmbientAsync): per-LED colors averaged over about the last
+    // AmbientWindowSeconds of the item, blended in over AmbientBlendSeconds, then drifting once around
+    // the strip per AmbientDriftPeriodSeconds with a slow swell.
+    private const double AmbientWindowSeconds = 120.0;
+    private const double AmbientBlendSeconds = 3.0;
+    private const double AmbientDriftPeriodSeconds = 180.0;
+    private const double AmbientSwellPeriodSeconds = 20.0;
+    private static readonly TimeSpan AmbientFrameInterval = TimeSpan.FromMilliseconds(50);
+    private float[]? _ambientColors;
+
+    // Stall watchdog (see WatchdogAsync): how often it checks, how often it repeats its frame, and the "dim" level.
+    private static readonly TimeSpan WatchdogPollInterval = TimeSpan.FromMilliseconds(500);
+    private static readonly TimeSpan WatchdogRepeatInterval = TimeSpan.FromSeconds(1);
+    private const float WatchdogDimFactor = 0.2f;
+
+    // Gapless playback: items queued with Queue (NEXT), a delayed unload that a LOAD can still cancel,
+    // and the last frame (and layout) sent so the next item can crossfade from it.
+    private readonly Queue<string> _playlist = new();
+
+    // Chapters of the item playing, from its chapters sidecar (empty for remote binaries and videos without any).
+    private List<AmbilightChapter> _chapters = new();
+    private DateTime? _unloadAtUtc;
+    private byte[]? _lastFrame;
+    private byte[]? _lastSentFrame;
+    private int _lastLedCount;
+    private int _lastBytesPerLed;
 
-    public AmbilightInProcessPlayer(ILogger logger, PluginConfiguration config)
+    /// <summary>
+    /// How long a finished or stopped item keeps its last frame before going dark, so the next
+    /// episode (Jellyfin autoplay) can take over without a black gap.
+    /// </summary>
+    public static readonly TimeSpan HandoverGrace = TimeSpan.FromSeconds(5);
+
+    /// <summary>Range accepted by <see cref="SetPlaybackRate"/>.</summary>
+    public const double MinPlaybackRate = 0.25;
+    public const double MaxPlaybackRate = 4.0;
+
+    // Which player currently owns each target (see TargetKeyFor). The newest Start wins; an older player for the
+    // same target (e.g. a stuck session) sees it lost the claim and exits without blanking the LEDs.
+    // An identify pattern being shown holds the claim with its CancellationTokenSource, which a claiming player cancels.
+    private static readonly ConcurrentDictionary<string, object> TargetOwners = new(StringComparer.OrdinalIgnoreCase);
+    private string? _targetKey;
+
+    // Snapshot reported by GetStatus; mutated by RunAsync under _stateLock.
+    private readonly AmbilightPlayerStatus _status = new();
+
+    // Timings of the item being played, reported by FinishRun when it ends.
+    private AmbilightRunStats? _currentRun;
+
+    // Runtime tuning overrides (see SetParameter), applied on top of the plugin configuration.
+    private readonly Dictionary<string, double> _overrides = new(StringComparer.OrdinalIgnoreCase);
+
+    /// <summary>
+    /// Parameter names accepted by <see cref="SetParameter"/>.
+    /// </summary>
+    public static readonly IReadOnlyList<string> TunableParameters = new[]
+    {
+        "brightness", "saturation", "smooth", "gamma",
+        "gamma_red", "gamma_green", "gamma_blue",
+        "red_boost", "green_boost", "blue_boost",
+        "min_brightness", "color_temp", "decay", "av_offset_ms"
+    };
+
+    public AmbilightInProcessPlayer(ILogger logger, PluginConfiguration config, AmbilightBinaryCache? binaryCache = null)
     {
         _logger = logger;
         _config = config;
+        _binaryCache = binaryCache;
     }
 
-    public void Start(string sessionId, string binPath, DeviceMapping mapping, double startSeconds, CancellationTokenSource? loadingEffectCts = null)
+    /// <summary>
+    /// Latest plugin configuration, so tuning saved in the settings page applies to running players.
+    /// Falls back to the snapshot passed to the constructor.
+    /// </summary>
+    private PluginConfiguration Config => Plugin.Instance?.Configuration ?? _config;
+
+    /// <summary>
+    /// Starts playing <paramref name="binPath"/> from <paramref name="startSeconds"/>. <paramref name="referenceEpochUtc"/>
+    /// is when that position was current; players of one session should get the same value so they stay in phase.
+    /// </summary>
+    public void Start(string sessionId, string binPath, DeviceMapping mapping, double startSeconds, CancellationTokenSource? loadingEffectCts = null, DateTime? referenceEpochUtc = null)
     {
         Stop();
-        
-        _loadingEffectCts = loadingEffectCts;
+
+        Mapping = mapping;
+        lock (_stateLock)
+        {
+            _status.SessionId = sessionId;
+            _status.Host = mapping.Host;
+            _status.Port = mapping.Port;
+            _status.FramesSent = 0;
+            _status.BytesSent = 0;
+            _status.SendErrors = 0;
+            _status.FramesDropped = 0;
+            _status.KeepAlivesSent = 0;
+            _status.WatchdogTrips = 0;
+            _status.LastSendUtc = null;
+            _status.PlaybackRate = 1.0;
+            _playbackRate = 1.0;
+        }
+
+        _targetKey = TargetKeyFor(mapping);
+        ClaimTarget(sessionId);
+        QueueLoad(binPath, startSeconds, loadingEffectCts, referenceEpochUtc);
+
         _cts = new CancellationTokenSource();
         var token = _cts.Token;
 
-        _playTask = Task.Run(() => RunAsync(sessionId, binPath, mapping, startSeconds, token), token);
+        _playTask = Task.Run(() => RunAsync(sessionId, mapping, token), token);
+    }
+
+    /// <summary>
+    /// Device mapping the player was started with.
+    /// </summary>
+    public DeviceMapping? Mapping { get; private set; }
+
+    /// <summary>
+    /// True while the player task is alive (playing, or idle in resident mode).
+    /// </summary>
+    public bool IsRunning => _playTask is { IsCompleted: false };
+
+    /// <summary>
+    /// LOAD: switch a running player to another binary at <paramref name="startSeconds"/>, reusing its
+    /// socket and skipping the exit blank so consecutive items hand over without a dark gap.
+    /// Returns false if the player is not running; use <see cref="Start"/> then.
+    /// </summary>
+    public bool Load(string binPath, double startSeconds, CancellationTokenSource? loadingEffectCts = null, DateTime? referenceEpochUtc = null)
+    {
+        if (!IsRunning)
+        {
+            return false;
+        }
+
+        ClaimTarget(GetStatus().SessionId);
+        QueueLoad(binPath, startSeconds, loadingEffectCts, referenceEpochUtc);
+        return true;
+    }
+
+    /// <summary>
+    /// UNLOAD: stop the current item and blank the LEDs, but keep the player and its socket
+    /// around for the next <see cref="Load"/>. With a <paramref name="grace"/> period the current
+    /// frame is held first, and a <see cref="Load"/> arriving in time crossfades instead of going dark.
+    /// </summary>
+    public void Unload(TimeSpan grace = default)
+    {
+        lock (_stateLock)
+        {
+            _pendingLoad = null;
+            _playlist.Clear();
+            _overrides.Clear();
+            if (grace > TimeSpan.Zero)
+            {
+                _unloadAtUtc = DateTime.UtcNow + grace;
+                _isPaused = true;
+            }
+            else
+            {
+                _unloadRequested = true;
+            }
+        }
+
+        _loadSignal.Release();
+    }
+
+    /// <summary>
+    /// NEXT: queue a binary to play from its start once the current one ends, crossfading between them.
+    /// Returns false if the player is not running.
+    /// </summary>
+    public bool Queue(string binPath)
+    {
+        if (!IsRunning)
+        {
+            return false;
+        }
+
+        bool isNext;
+        lock (_stateLock)
+        {
+            _playlist.Enqueue(binPath);
+            isNext = _playlist.Count == 1;
+        }
+
+        // Only the head of the queue is loaded ahead: the cache holds two binaries, so loading a longer queue at once
+        // would evict the next item before it plays. The rest follow one at a time as items load (see RunAsync).
+        if (isNext)
+        {
+            _binaryCache?.Precache(binPath);
+        }
+
+        return true;
+    }
+
+    /// <summary>
+    /// RELOAD: switch to an edited version of this player's mapping (LED counts, direction, color profile...) without
+    /// stopping. The current item is reloaded at the current position and crossfades into the new layout.
+    /// Returns true if the mapping changed. Edits of the target itself (host, port, protocol or segment start)
+    /// are ignored, see <see cref="IsSameTarget"/>; the player has to be restarted for those.
+    /// </summary>
+    public bool UpdateMapping(DeviceMapping mapping)
+    {
+        var current = Mapping;
+        if (current == null || !IsSameTarget(current, mapping))
+        {
+            return false;
+        }
+
+        Mapping = mapping;
+        if (JsonSerializer.Serialize(current) == JsonSerializer.Serialize(mapping))
+        {
+            return false;
+        }
+
+        var status = GetStatus();
+        if (status.State is "playing" or "paused" or "stalled" && !string.IsNullOrEmpty(status.BinPath))
+        {
+            bool paused;
+            lock (_stateLock)
+            {
+                paused = _isPaused;
+            }
+
+            QueueLoad(status.BinPath, status.PositionSeconds, null);
+            if (paused)
+            {
+                SetPaused(true);
+            }
+
+            _logger.LogInformation("[Ambilight] Reloaded mapping for {Target} at {Seconds:F1}s", _targetKey, status.PositionSeconds);
+        }
+
+        return true;
     }
 
+    /// <summary>
+    /// True when both mappings drive the same socket and strip range, so a player can switch between them in place.
+    /// </summary>
+    internal static bool IsSameTarget(DeviceMapping a, DeviceMapping b)
+        => string.Equals(a.Host.Trim(), b.Host.Trim(), StringComparison.OrdinalIgnoreCase)
+            && a.Port == b.Port
+            && AmbilightUdpSink.NormalizeProtocol(a.Protocol) == AmbilightUdpSink.NormalizeProtocol(b.Protocol)
+            && SegmentStartFor(a) == SegmentStartFor(b);
+
+    /// <summary>
+    /// Key a player claims its target under: the fields <see cref="IsSameTarget"/> compares, so two mappings playing
+    /// onto different segments of one controller each keep their own claim.
+    /// </summary>
+    internal static string TargetKeyFor(DeviceMapping mapping)
+        => $"{mapping.Host.Trim()}:{mapping.Port}/{AmbilightUdpSink.NormalizeProtocol(mapping.Protocol)}@{SegmentStartFor(mapping)}";
+
     public void Stop()
     {
         if (_cts == null)
@@ -77,6 +372,64 @@ public sealed class AmbilightInProcessPlayer : IDisposable
             _cts.Dispose();
             _cts = null;
             _playTask = null;
+            ReleaseTarget();
+        }
+    }
+
+    private void QueueLoad(string binPath, double startSeconds, CancellationTokenSource? loadingEffectCts, DateTime? referenceEpochUtc = null)
+    {
+        lock (_stateLock)
+        {
+            _pendingLoad = new PendingLoad(binPath, startSeconds, loadingEffectCts, referenceEpochUtc ?? DateTime.UtcNow);
+            _unloadRequested = false;
+            _unloadAtUtc = null;
+            _isPaused = false;
+            _pendingSeekSeconds = null;
+
+            _status.BinPath = binPath;
+            _status.State = "loading";
+            _status.FrameIndex = 0;
+            _status.FrameCount = 0;
+            _status.DriftSeconds = 0.0;
+            _status.ClockRate = 1.0;
+        }
+
+        _loadSignal.Release();
+    }
+
+    private void ClaimTarget(string sessionId)
+    {
+        if (_targetKey == null)
+        {
+            return;
+        }
+
+        if (TargetOwners.TryGetValue(_targetKey, out var previousOwner) && !ReferenceEquals(previousOwner, this))
+        {
+            if (previousOwner is AmbilightInProcessPlayer previousPlayer)
+            {
+                _logger.LogInformation("[Ambilight] Session {SessionId} takes over {Target} from session {PreviousSession}",
+                    sessionId, _targetKey, previousPlayer.GetStatus().SessionId);
+            }
+            else if (previousOwner is CancellationTokenSource identify)
+            {
+                CancelIdentify(identify);
+            }
+        }
+        TargetOwners[_targetKey] = this;
+    }
+
+    private bool OwnsTarget()
+    {
+        return _targetKey == null || (TargetOwners.TryGetValue(_targetKey, out var owner) && ReferenceEquals(owner, this));
+    }
+
+    private void ReleaseTarget()
+    {
+        if (_targetKey != null)
+        {
+            // Only remove the claim if it is still ours; a newer player may already own the target.
+            TargetOwners.TryRemove(new KeyValuePair<string, object>(_targetKey, this));
         }
     }
 
@@ -97,134 +450,111 @@ public sealed class AmbilightInProcessPlayer : IDisposable
     }
 
     /// <summary>
-    /// Request a seek to the given playback position in seconds.
+    /// Request a seek to the given playback position in seconds, optionally as of <paramref name="atUtc"/>
+    /// (when the position was reported) so phase-locked players land on the same frame.
     /// </summary>
-    public void Seek(double seconds)
+    public void Seek(double seconds, DateTime? atUtc = null)
     {
         lock (_stateLock)
         {
             _pendingSeekSeconds = seconds;
+            _pendingSeekUtc = atUtc ?? DateTime.UtcNow;
+            _lastContactUtc = DateTime.UtcNow;
         }
     }
 
-    private async Task RunAsync(string sessionId, string binPath, DeviceMapping mapping, double startSeconds, CancellationToken cancellationToken)
+    /// <summary>
+    /// Seeks to the start of chapter <paramref name="chapter"/> (1-based) of the item playing. Returns false when it
+    /// has no such chapter.
+    /// </summary>
+    public bool SeekChapter(int chapter)
     {
-        try
+        lock (_stateLock)
         {
-            if (!File.Exists(binPath))
-            {
-                if (_config.Debug)
-                {
-                    _logger.LogInformation("[Ambilight] Binary file not found: {Path}", binPath);
-                }
-                _logger.LogWarning("[Ambilight] AMb2 binary not found for session {SessionId}: {Path}", sessionId, binPath);
-                return;
-            }
-
-            if (_config.Debug)
-            {
-                _logger.LogInformation("[Ambilight] Binary file found: {Path}", binPath);
-            }
-
-            using var fs = File.OpenRead(binPath);
-            using var reader = new BinaryReader(fs);
-
-            // Header
-            var magic = reader.ReadBytes(4);
-            if (magic.Length != 4 || magic[0] != (byte)'A' || magic[1] != (byte)'M' || magic[2] != (byte)'b' || magic[3] != (byte)'2')
+            if (chapter < 1 || chapter > _chapters.Count)
             {
-                _logger.LogWarning("[Ambilight] Invalid AMb2 header in {Path}", binPath);
-                return;
-            }
-
-            var fps = reader.ReadSingle();
-            double fpsD = fps;
-            if (double.IsNaN(fpsD) || fpsD <= 0.001 || fpsD > 300.0)
-            {
-                fpsD = 0.0;
+                return false;
             }
 
-            var topSrc = reader.ReadUInt16();
-            var bottomSrc = reader.ReadUInt16();
-            var leftSrc = reader.ReadUInt16();
-            var rightSrc = reader.ReadUInt16();
-            var fmt = reader.ReadByte();
-            var rgbw = fmt == 1;
-            var bytesPerLed = rgbw ? 4 : 3;
-            var frameSize = (topSrc + bottomSrc + leftSrc + rightSrc) * bytesPerLed;
-
-            // Target counts from mapping (falling back to source counts when unset)
-            int tgtTop = mapping.TopLedCount > 0 ? mapping.TopLedCount : Math.Max(1, (int)topSrc);
-            int tgtBottom = mapping.BottomLedCount > 0 ? mapping.BottomLedCount : Math.Max(1, (int)bottomSrc);
-            int tgtLeft = mapping.LeftLedCount > 0 ? mapping.LeftLedCount : Math.Max(1, (int)leftSrc);
-            int tgtRight = mapping.RightLedCount > 0 ? mapping.RightLedCount : Math.Max(1, (int)rightSrc);
-
-            int totalSrc = topSrc + bottomSrc + leftSrc + rightSrc;
-            if (totalSrc <= 0 && frameSize > 0)
-            {
-                totalSrc = frameSize / bytesPerLed;
-            }
-            int totalTgt = tgtTop + tgtRight + tgtBottom + tgtLeft;
+            Seek(_chapters[chapter - 1].StartSeconds);
+            return true;
+        }
+    }
 
-            if (_config.Debug)
-            {
-                _logger.LogInformation("[Ambilight] Playing {Path} → {Host}:{Port} (src {Src} LEDs → tgt {Tgt} LEDs, rgbw={Rgbw})",
-                    binPath, mapping.Host, mapping.Port, totalSrc, totalTgt, rgbw);
-            }
+    /// <summary>
+    /// RATE: follow a client playing at <paramref name="factor"/>x speed (e.g. 1.25, 1.5). Frame timestamps are
+    /// rescaled on the fly and the rate stays in effect for following items. Returns false if out of range.
+    /// </summary>
+    public bool SetPlaybackRate(double factor)
+    {
+        if (!double.IsFinite(factor) || factor < MinPlaybackRate || factor > MaxPlaybackRate)
+        {
+            return false;
+        }
 
-            var frames = new List<byte[]>();
-            var timestampsUs = new List<ulong>();
+        lock (_stateLock)
+        {
+            _playbackRate = factor;
+            _status.PlaybackRate = factor;
+        }
 
-            while (fs.Position < fs.Length && !cancellationToken.IsCancellationRequested)
-            {
-                var tsBytes = reader.ReadBytes(8);
-                if (tsBytes.Length < 8)
-                {
-                    break;
-                }
-                ulong ts = BitConverter.ToUInt64(tsBytes, 0);
+        return true;
+    }
 
-                var payload = reader.ReadBytes(frameSize);
-                if (payload.Length < frameSize)
-                {
-                    break;
-                }
+    /// <summary>
+    /// Heartbeat: the media position Jellyfin currently reports for the session. Used to estimate and
+    /// slowly correct drift between the player clock and the client.
+    /// </summary>
+    public void ReportPosition(double seconds)
+    {
+        lock (_stateLock)
+        {
+            _heartbeatSeconds = seconds;
+            _heartbeatUtc = DateTime.UtcNow;
+            _lastContactUtc = _heartbeatUtc;
+        }
+    }
 
-                timestampsUs.Add(ts);
-                frames.Add(payload);
-            }
+    /// <summary>
+    /// Override a tuning parameter (e.g. <c>brightness 40</c>, <c>saturation 1.2</c>, <c>smooth 0.2</c>)
+    /// while playing. Takes effect on the next frame and lasts until the player is stopped.
+    /// Returns false for unknown parameter names or non-finite values.
+    /// </summary>
+    public bool SetParameter(string name, double value)
+    {
+        if (!TunableParameters.Contains(name, StringComparer.OrdinalIgnoreCase) || !double.IsFinite(value))
+        {
+            return false;
+        }
 
-            if (frames.Count == 0)
-            {
-                _logger.LogWarning("[Ambilight] No frames in AMb2 file for session {SessionId}", sessionId);
-                return;
-            }
+        lock (_stateLock)
+        {
+            _overrides[name] = value;
+        }
 
-            if (_config.Debug)
-            {
-                _logger.LogInformation("[Ambilight] Binary loaded into memory: {FrameCount} frames", frames.Count);
-            }
+        return true;
+    }
 
-            if (fpsD <= 0.0 && timestampsUs.Count >= 2)
-            {
-                double dtUs = Math.Abs((double)timestampsUs[1] - timestampsUs[0]);
-                if (dtUs > 0.0)
-                {
-                    fpsD = 1e6 / dtUs;
-                }
-                else
-                {
-                    fpsD = 24.0;
-                }
-            }
-            else if (fpsD <= 0.0)
-            {
-                fpsD = 24.0;
-            }
+    /// <summary>
+    /// Current media position, frame index, effective sync lead and send statistics.
+    /// Lets the plugin detect drift and decide whether to resync.
+    /// </summary>
+    public AmbilightPlayerStatus GetStatus()
+    {
+        lock (_stateLock)
+        {
+            return _status.Clone();
+        }
+    }
 
+    private async Task RunAsync(string sessionId, DeviceMapping mapping, CancellationToken cancellationToken)
+    {
+        using var watchdogCts = CancellationTokenSource.CreateLinkedTokenSource(cancellationToken);
+        try
+        {
             // Resolve host to IP so we get a clear error if DNS fails (e.g. .lan not resolvable in Docker).
             IPAddress? targetIp = null;
-            if (IPAddress.TryParse(mapping.Host, out var parsedIp))
+            if (AmbilightUdpSink.TryParseAddress(mapping.Host, out var parsedIp))
             {
                 targetIp = parsedIp;
             }
@@ -232,16 +562,7 @@ public sealed class AmbilightInProcessPlayer : IDisposable
             {
                 try
                 {
-                    var addresses = Dns.GetHostAddresses(mapping.Host);
-                    foreach (var addr in addresses)
-                    {
-                        if (addr.AddressFamily == AddressFamily.InterNetwork)
-                        {
-                            targetIp = addr;
-                            break;
-                        }
-                    }
-                    targetIp ??= addresses.Length > 0 ? addresses[0] : null;
+                    targetIp = AmbilightUdpSink.ResolveHost(mapping.Host);
                 }
                 catch (SocketException ex)
                 {
@@ -256,368 +577,2331 @@ public sealed class AmbilightInProcessPlayer : IDisposable
                 return;
             }
 
-            using var udp = new UdpClient();
-            udp.Connect(targetIp, mapping.Port);
+            int segmentStart = SegmentStartFor(mapping);
+            if (mapping.SegmentStart > 0 && segmentStart == 0)
+            {
+                _logger.LogWarning("[Ambilight] Segment start {Start} for {Host} needs the WLED realtime protocol; playing from LED 0", mapping.SegmentStart, mapping.Host);
+            }
+
+            using var sink = new AmbilightUdpSink(targetIp, mapping.Port, mapping.Protocol, segmentStart, mapping.Host, _logger, Config.UdpDscp);
+            _ = Task.Run(() => WatchdogAsync(sink, watchdogCts.Token), CancellationToken.None);
 
             if (_config.Debug)
             {
-                _logger.LogInformation("[Ambilight] Connected to WLED at {Host}:{Port}", mapping.Host, mapping.Port);
+                _logger.LogInformation("[Ambilight] Connected to WLED at {Host}:{Port} (protocol {Protocol})", mapping.Host, mapping.Port, sink.Protocol);
+            }
+
+            _deviceLedCount = await QueryWledLedCountAsync(mapping.Host, cancellationToken).ConfigureAwait(false);
+            int configuredLeds = StripLedCount(mapping);
+            lock (_stateLock)
+            {
+                _status.DeviceLedCount = _deviceLedCount;
             }
 
-            double baseSyncLead = _config.AmbilightSyncLeadSeconds;
-            double effectiveStart = Math.Max(0.0, startSeconds + baseSyncLead);
-            ulong startTsUs = (ulong)(effectiveStart * 1_000_000.0);
-            int startFrame = 0;
-            while (startFrame < timestampsUs.Count && timestampsUs[startFrame] < startTsUs)
+            if (_deviceLedCount is { } deviceLeds && segmentStart + configuredLeds > deviceLeds)
             {
-                startFrame++;
+                _logger.LogWarning(
+                    "[Ambilight] {Host} reports {DeviceLeds} LEDs but the mapping configures {ConfiguredLeds} from LED {SegmentStart}; {Action}",
+                    mapping.Host,
+                    deviceLeds,
+                    configuredLeds,
+                    segmentStart,
+                    Config.ClampToDeviceLedCount ? "scaling the layout down to fit" : "the last side will stay dark");
             }
-            int frameIndex = Math.Min(startFrame, frames.Count - 1);
 
-            var startInstant = DateTime.UtcNow;
+            if (_deviceLedCount != null)
+            {
+                _ = Task.Run(() => ProbeLatencyAsync(mapping.Host, cancellationToken), cancellationToken);
+            }
 
-            // Smoothing: configured directly in seconds. 0 = no smoothing (per-frame colors).
-            float smoothSeconds = (float)_config.AmbilightSmoothSeconds;
-            bool noSmoothing = smoothSeconds <= 0.0f;
-            float smoothTau = noSmoothing ? 0.0f : ClampF(smoothSeconds, 0.001f, 5.0f);
+            var idleSince = DateTime.UtcNow;
+            byte[]? fadeFrom = null;
+            while (!cancellationToken.IsCancellationRequested)
+            {
+                PendingLoad? load;
+                lock (_stateLock)
+                {
+                    load = _pendingLoad;
+                    _pendingLoad = null;
+                    _unloadRequested = false;
+                }
 
-            var emaAcc = (float[]?)null;
+                if (load == null)
+                {
+                    // Resident players idle here between items; others exit after their first item.
+                    if (!Config.ResidentPlayers || DateTime.UtcNow - idleSince > ResidentIdleTimeout)
+                    {
+                        break;
+                    }
 
-            float gammaBase = (float)_config.AmbilightGamma;
-            float saturation = (float)_config.AmbilightSaturation;
-            float brightnessTarget = (float)_config.AmbilightBrightnessTarget;
-            float gammaRed = (float)_config.AmbilightGammaRed;
-            float gammaGreen = (float)_config.AmbilightGammaGreen;
-            float gammaBlue = (float)_config.AmbilightGammaBlue;
-            float redBoost = (float)_config.AmbilightRedBoost;
-            float greenBoost = (float)_config.AmbilightGreenBoost;
-            float blueBoost = (float)_config.AmbilightBlueBoost;
-            float minLedBrightness = (float)_config.AmbilightMinLedBrightness;
-            int inputPosition = mapping.InputPosition;
+                    lock (_stateLock)
+                    {
+                        _status.State = "idle";
+                    }
 
-            int rotLeds = totalTgt > 0 ? Math.Abs(inputPosition) % totalTgt : 0;
+                    await _loadSignal.WaitAsync(TimeSpan.FromSeconds(5), cancellationToken).ConfigureAwait(false);
+                    continue;
+                }
 
-            TimeSpan elapsedBase = TimeSpan.Zero;
-            bool lastPaused = false;
+                _loadingEffectCts = load.LoadingEffectCts;
+                var binary = await LoadBinaryAsync(sessionId, load.BinPath, cancellationToken).ConfigureAwait(false);
 
-            // Stop loading effect now that we're about to start broadcasting
-            if (_loadingEffectCts != null)
-            {
-                try
+                // Load the next queued item in the background while this one plays, so its handover is instant too.
+                string? following;
+                lock (_stateLock)
                 {
-                    _loadingEffectCts.Cancel();
-                    _loadingEffectCts.Dispose();
-                    _loadingEffectCts = null;
-                    if (_config.Debug)
-                    {
-                        _logger.LogInformation("[Ambilight] Stopped loading effect, starting ambilight broadcast");
-                    }
+                    following = _playlist.Count > 0 ? _playlist.Peek() : null;
                 }
-                catch
+
+                if (binary != null && following != null)
                 {
-                    // Ignore cancellation errors
+                    _binaryCache?.Precache(following);
                 }
-            }
 
-            while (!cancellationToken.IsCancellationRequested && frameIndex < frames.Count)
-            {
-                // Apply pending seek / pause state from PlaybackService
-                bool pausedNow;
-                double? seekSec;
-                lock (_stateLock)
+                PlaybackEnd end;
+                try
+                {
+                    end = binary == null
+                        ? PlaybackEnd.Finished
+                        : await PlayAsync(sessionId, sink, binary, Mapping ?? mapping, load.StartSeconds, load.ReferenceUtc, fadeFrom, cancellationToken).ConfigureAwait(false);
+                }
+                finally
                 {
-                    pausedNow = _isPaused;
-                    seekSec = _pendingSeekSeconds;
-                    _pendingSeekSeconds = null;
+                    // Streamed binaries hold their file open, also when stopped mid-item; cached ones are shared and stay loaded.
+                    if (binary is { IsStreaming: true })
+                    {
+                        binary.Dispose();
+                    }
                 }
 
-                if (seekSec.HasValue)
+                fadeFrom = null;
+
+                if (end == PlaybackEnd.Finished && binary != null)
                 {
-                    var targetUs = (ulong)(Math.Max(0.0, seekSec.Value) * 1_000_000.0);
-                    int targetFrame = 0;
-                    while (targetFrame < timestampsUs.Count && timestampsUs[targetFrame] < targetUs)
+                    // NEXT: continue straight into the queued item.
+                    string? next = null;
+                    lock (_stateLock)
                     {
-                        targetFrame++;
+                        if (_playlist.Count > 0)
+                        {
+                            next = _playlist.Dequeue();
+                        }
                     }
-                    frameIndex = Math.Min(targetFrame, frames.Count - 1);
-                    startFrame = frameIndex;
-                    startInstant = DateTime.UtcNow;
-                    elapsedBase = TimeSpan.Zero;
-                    if (_config.Debug)
+
+                    if (next != null)
                     {
-                        _logger.LogInformation("[Ambilight] Seek to {Seconds:F3}s → frame {Frame}", seekSec.Value, frameIndex);
+                        QueueLoad(next, 0.0, null);
                     }
-                    else
+                    else if (Config.PostPlaybackAmbient && Config.ResidentPlayers)
                     {
-                        _logger.LogDebug("[Ambilight] In-process SEEK to {Seconds:F3}s → frame {Frame}", seekSec.Value, frameIndex);
+                        // Fill the time after the credits (e.g. the "up next" screen) with a gentle ambient light.
+                        end = await AmbientAsync(sink, cancellationToken).ConfigureAwait(false);
                     }
-                }
 
-                if (pausedNow && !lastPaused)
-                {
-                    // Pause: freeze the current ambilight frame and stop advancing time.
-                    // We do NOT blank the LEDs here so the last video frame stays visible.
-                    elapsedBase += DateTime.UtcNow - startInstant;
-                    if (_config.Debug)
+                    // Otherwise hold the last frame briefly so autoplay of the next episode can LOAD it.
+                    if (next != null || (end == PlaybackEnd.Finished && Config.ResidentPlayers && await WaitForLoadAsync(HandoverGrace, cancellationToken).ConfigureAwait(false)))
                     {
-                        _logger.LogInformation("[Ambilight] Pause detected – holding current frame");
+                        fadeFrom = _lastFrame;
+                        continue;
                     }
-                    else
-                    {
-                        _logger.LogDebug("[Ambilight] In-process player paused");
+                }
+
+                if (end == PlaybackEnd.Reloaded)
+                {
+                    fadeFrom = _lastFrame;
+                    continue;
+                }
+
+                if (end == PlaybackEnd.Superseded)
+                {
+                    break;
+                }
+
+                // Blank on exit (finished, stopped or unloaded)
+                if (binary != null)
+                {
+                    await BlankAsync(sink, cancellationToken).ConfigureAwait(false);
+                }
+
+                if (!Config.ResidentPlayers)
+                {
+                    break;
+                }
+
+                idleSince = DateTime.UtcNow;
+            }
+        }
+        catch (Exception ex)
+        {
+            if (!cancellationToken.IsCancellationRequested)
+            {
+                _logger.LogError(ex, "[Ambilight] In-process player error for session {SessionId}", sessionId);
+            }
+
+            // Stopped mid-item (cancellation surfaces as an exception from the frame delays): still report the run.
+            FinishRun(PlaybackEnd.Stopped);
+        }
+        finally
+        {
+            watchdogCts.Cancel();
+
+            // Left on its own (finished, superseded or idle too long): give up the target. When stopped, Stop releases
+            // it, and a restart of this player may already have claimed it again.
+            if (!cancellationToken.IsCancellationRequested)
+            {
+                ReleaseTarget();
+            }
+        }
+    }
+
+    /// <summary>
+    /// Asks the target for its LED count via WLED's <c>/json/info</c>. Returns null for non-WLED targets or on any error.
+    /// </summary>
+    private async Task<int?> QueryWledLedCountAsync(string host, CancellationToken cancellationToken)
+    {
+        try
+        {
+            using var response = await WledHttp.GetAsync($"http://{AmbilightUdpSink.UrlHost(host)}/json/info", cancellationToken).ConfigureAwait(false);
+            if (!response.IsSuccessStatusCode)
+            {
+                return null;
+            }
+
+            await using var stream = await response.Content.ReadAsStreamAsync(cancellationToken).ConfigureAwait(false);
+            using var doc = await JsonDocument.ParseAsync(stream, cancellationToken: cancellationToken).ConfigureAwait(false);
+            if (doc.RootElement.TryGetProperty("leds", out var leds) && leds.TryGetProperty("count", out var count) && count.TryGetInt32(out var ledCount) && ledCount > 0)
+            {
+                if (_config.Debug)
+                {
+                    _logger.LogInformation("[Ambilight] {Host} reports {Count} LEDs", host, ledCount);
+                }
+
+                return ledCount;
+            }
+        }
+        catch (Exception ex) when (ex is HttpRequestException or TaskCanceledException or JsonException)
+        {
+            if (cancellationToken.IsCancellationRequested)
+            {
+                throw;
+            }
+
+            _logger.LogDebug("[Ambilight] No WLED JSON API at {Host}: {Message}", host, ex.Message);
+        }
+
+        return null;
+    }
+
+    /// <summary>
+    /// Measures the round trip to the target's JSON API at startup and every minute while automatic latency
+    /// compensation is enabled. Half of the best of three round trips, smoothed, is added to the sync lead.
+    /// </summary>
+    private async Task ProbeLatencyAsync(string host, CancellationToken cancellationToken)
+    {
+        try
+        {
+            while (!cancellationToken.IsCancellationRequested)
+            {
+                if (Config.AutoLatencyCompensation)
+                {
+                    double? bestRtt = null;
+                    for (int i = 0; i < 3; i++)
+                    {
+                        var sw = Stopwatch.StartNew();
+                        try
+                        {
+                            using var response = await WledHttp.GetAsync($"http://{AmbilightUdpSink.UrlHost(host)}/json/info", HttpCompletionOption.ResponseHeadersRead, cancellationToken).ConfigureAwait(false);
+                            sw.Stop();
+                            if (response.IsSuccessStatusCode && (bestRtt == null || sw.Elapsed.TotalSeconds < bestRtt))
+                            {
+                                bestRtt = sw.Elapsed.TotalSeconds;
+                            }
+                        }
+                        catch (Exception ex) when (ex is HttpRequestException || (ex is TaskCanceledException && !cancellationToken.IsCancellationRequested))
+                        {
+                            // Missed probe; keep the previous estimate.
+                        }
+                    }
+
+                    if (bestRtt is { } rtt)
+                    {
+                        double oneWay = rtt / 2.0;
+                        lock (_stateLock)
+                        {
+                            _networkLatencySeconds = _networkLatencySeconds is { } previous ? previous + ((oneWay - previous) * 0.3) : oneWay;
+                            _status.NetworkLatencySeconds = _networkLatencySeconds.Value;
+                        }
+
+                        if (_config.Debug)
+                        {
+                            _logger.LogInformation("[Ambilight] {Host} round trip {Rtt:F1} ms, latency estimate {Latency:F1} ms", host, rtt * 1000.0, _networkLatencySeconds * 1000.0);
+                        }
+                    }
+                }
+
+                await Task.Delay(LatencyProbeInterval, cancellationToken).ConfigureAwait(false);
+            }
+        }
+        catch (OperationCanceledException)
+        {
+            // Player stopped.
+        }
+    }
+
+    /// <summary>
+    /// Waits up to <paramref name="timeout"/> for a LOAD. Returns true if one is pending.
+    /// </summary>
+    private async Task<bool> WaitForLoadAsync(TimeSpan timeout, CancellationToken cancellationToken)
+    {
+        var deadline = DateTime.UtcNow + timeout;
+        while (true)
+        {
+            lock (_stateLock)
+            {
+                if (_pendingLoad != null)
+                {
+                    return true;
+                }
+            }
+
+            var remaining = deadline - DateTime.UtcNow;
+            if (remaining <= TimeSpan.Zero)
+            {
+                return false;
+            }
+
+            await _loadSignal.WaitAsync(remaining, cancellationToken).ConfigureAwait(false);
+        }
+    }
+
+    /// <summary>
+    /// After an item finishes, shows the average colors of its last minutes slowly drifting around the strip
+    /// until a LOAD arrives (<see cref="PlaybackEnd.Reloaded"/>), the player is unloaded or
+    /// <see cref="PluginConfiguration.PostPlaybackAmbientMinutes"/> runs out (<see cref="PlaybackEnd.Unloaded"/>),
+    /// or another player takes over the target (<see cref="PlaybackEnd.Superseded"/>). The delayed unload
+    /// Jellyfin's stop report schedules at the end of an item does not end it.
+    /// </summary>
+    private async Task<PlaybackEnd> AmbientAsync(AmbilightUdpSink sink, CancellationToken cancellationToken)
+    {
+        var colors = _ambientColors;
+        int totalLeds = _lastLedCount;
+        int bytesPerLed = _lastBytesPerLed;
+        if (colors == null || totalLeds <= 0 || bytesPerLed <= 0 || colors.Length != totalLeds * bytesPerLed)
+        {
+            return PlaybackEnd.Finished;
+        }
+
+        var mapping = Mapping;
+        int rotLeds = mapping != null ? Math.Abs(mapping.InputPosition) % totalLeds : 0;
+        bool reverse = mapping is { Reverse: true } && totalLeds > 1;
+        var calibration = mapping != null ? AmbilightLedCalibration.TryLoad(mapping.CalibrationPath, _logger) : null;
+
+        var config = Config;
+        float brightness = ClampF((float)(config.PostPlaybackAmbientBrightness / 100.0), 0.0f, 1.0f);
+        double limitSeconds = config.PostPlaybackAmbientMinutes * 60.0;
+        var blendFrom = _lastFrame;
+        var startUtc = DateTime.UtcNow;
+        lock (_stateLock)
+        {
+            _status.State = "ambient";
+        }
+
+        if (_config.Debug)
+        {
+            _logger.LogInformation("[Ambilight] Post-playback ambient on {Target}", _targetKey);
+        }
+
+        var outBuffers = new[] { new byte[colors.Length], new byte[colors.Length] };
+        var sendBuffers = new[] { new byte[colors.Length], new byte[colors.Length] };
+        int bufferSlot = 0;
+        while (true)
+        {
+            lock (_stateLock)
+            {
+                if (_pendingLoad != null)
+                {
+                    return PlaybackEnd.Reloaded;
+                }
+
+                if (_unloadRequested)
+                {
+                    return PlaybackEnd.Unloaded;
+                }
+            }
+
+            if (!OwnsTarget())
+            {
+                return PlaybackEnd.Superseded;
+            }
+
+            double elapsed = (DateTime.UtcNow - startUtc).TotalSeconds;
+            if (limitSeconds > 0.0 && elapsed >= limitSeconds)
+            {
+                return PlaybackEnd.Unloaded;
+            }
+
+            // Sample the averaged colors at a fractional offset so they glide around the strip.
+            double shift = elapsed / AmbientDriftPeriodSeconds * totalLeds;
+            int whole = (int)Math.Floor(shift);
+            float frac = (float)(shift - whole);
+            float swell = brightness * NightBrightnessFactor(DateTime.Now) * (0.85f + (0.15f * MathF.Cos((float)(2.0 * Math.PI * elapsed / AmbientSwellPeriodSeconds))));
+            bufferSlot ^= 1;
+            var outFrame = outBuffers[bufferSlot];
+            float blend = blendFrom != null && blendFrom.Length == outFrame.Length
+                ? (float)Math.Min(1.0, elapsed / AmbientBlendSeconds)
+                : 1.0f;
+            for (int t = 0; t < totalLeds; t++)
+            {
+                int a = ((t + whole) % totalLeds) * bytesPerLed;
+                int b = ((t + whole + 1) % totalLeds) * bytesPerLed;
+                for (int c = 0; c < bytesPerLed; c++)
+                {
+                    float value = (colors[a + c] + ((colors[b + c] - colors[a + c]) * frac)) * swell;
+                    if (blend < 1.0f)
+                    {
+                        value = blendFrom![(t * bytesPerLed) + c] + ((value - blendFrom[(t * bytesPerLed) + c]) * blend);
+                    }
+
+                    outFrame[(t * bytesPerLed) + c] = (byte)Math.Clamp((int)MathF.Round(value), 0, 255);
+                }
+            }
+
+            byte[] frameToSend = PrepareOutputFrame(outFrame, rotLeds, totalLeds, bytesPerLed, reverse, 1.0f, calibration, sendBuffers[bufferSlot]);
+
+            _lastFrame = outFrame;
+            _lastSentFrame = frameToSend;
+            try
+            {
+                await sink.SendFrameAsync(frameToSend, totalLeds, bytesPerLed).ConfigureAwait(false);
+            }
+            catch (Exception ex) when (ex is not OperationCanceledException)
+            {
+                _logger.LogDebug(ex, "[Ambilight] Failed to send ambient frame");
+            }
+
+            await _loadSignal.WaitAsync(AmbientFrameInterval, cancellationToken).ConfigureAwait(false);
+        }
+    }
+
+    /// <summary>
+    /// Runs next to the playback loop. If the player is playing but nothing has gone out for
+    /// <see cref="PluginConfiguration.WatchdogSeconds"/> (a stalled disk read, CPU starvation), blanks or dims the
+    /// LEDs instead of leaving them frozen, and keeps doing so until the loop sends again.
+    /// </summary>
+    private async Task WatchdogAsync(AmbilightUdpSink sink, CancellationToken cancellationToken)
+    {
+        DateTime? trippedAtUtc = null;
+        var lastWatchdogSendUtc = DateTime.MinValue;
+        while (!cancellationToken.IsCancellationRequested)
+        {
+            try
+            {
+                await Task.Delay(WatchdogPollInterval, cancellationToken).ConfigureAwait(false);
+            }
+            catch (OperationCanceledException)
+            {
+                return;
+            }
+
+            var config = Config;
+            bool dim = string.Equals(config.WatchdogAction?.Trim(), "dim", StringComparison.OrdinalIgnoreCase);
+            string state;
+            DateTime? lastSendUtc;
+            lock (_stateLock)
+            {
+                state = _status.State;
+                lastSendUtc = _status.LastSendUtc;
+            }
+
+            var now = DateTime.UtcNow;
+            if (trippedAtUtc.HasValue && (state != "playing" || (lastSendUtc.HasValue && lastSendUtc.Value > trippedAtUtc.Value)))
+            {
+                _logger.LogInformation("[Ambilight] Player for {Target} is sending again after {Seconds:F1}s", _targetKey, (now - trippedAtUtc.Value).TotalSeconds);
+                trippedAtUtc = null;
+                continue;
+            }
+
+            if (config.WatchdogSeconds <= 0 || state != "playing" || !lastSendUtc.HasValue)
+            {
+                continue;
+            }
+
+            if (!trippedAtUtc.HasValue)
+            {
+                if ((now - lastSendUtc.Value).TotalSeconds < config.WatchdogSeconds)
+                {
+                    continue;
+                }
+
+                trippedAtUtc = now;
+                lock (_stateLock)
+                {
+                    _status.WatchdogTrips++;
+                }
+
+                _logger.LogWarning("[Ambilight] No frame sent to {Target} for {Seconds:F1}s; {Action} the LEDs until playback recovers",
+                    _targetKey, (now - lastSendUtc.Value).TotalSeconds, dim ? "dimming" : "blanking");
+            }
+            else if (now - lastWatchdogSendUtc < WatchdogRepeatInterval)
+            {
+                continue;
+            }
+
+            // Repeated while stalled so WLED stays in realtime mode instead of falling back to its own effect.
+            var lastSent = _lastSentFrame;
+            int ledCount = _lastLedCount;
+            int bytesPerLed = _lastBytesPerLed;
+            if (lastSent == null || ledCount <= 0 || bytesPerLed <= 0)
+            {
+                continue;
+            }
+
+            var frame = dim ? ScaleFrame(lastSent, WatchdogDimFactor) : new byte[lastSent.Length];
+            try
+            {
+                await sink.SendFrameAsync(frame, ledCount, bytesPerLed).ConfigureAwait(false);
+                lastWatchdogSendUtc = now;
+            }
+            catch (Exception ex) when (ex is not OperationCanceledException)
+            {
+                // The socket may already be closed if the player is exiting.
+                _logger.LogDebug(ex, "[Ambilight] Watchdog could not send to {Target}", _targetKey);
+            }
+        }
+    }
+
+    private async Task BlankAsync(AmbilightUdpSink sink, CancellationToken cancellationToken)
+    {
+        // Once another player owns the target, its frames are on the LEDs; neither fade them nor blank over them.
+        if (_lastLedCount <= 0 || _lastBytesPerLed <= 0 || !OwnsTarget())
+        {
+            return;
+        }
+
+        // Fade the last frame out first; on shutdown (cancelled) go dark at once.
+        double fadeOutSeconds = Config.FadeOutSeconds;
+        if (_lastSentFrame is { } last && fadeOutSeconds > 0.0 && !cancellationToken.IsCancellationRequested)
+        {
+            var fadeStart = DateTime.UtcNow;
+            try
+            {
+                double elapsed;
+                while ((elapsed = (DateTime.UtcNow - fadeStart).TotalSeconds) < fadeOutSeconds)
+                {
+                    if (!OwnsTarget())
+                    {
+                        return;
+                    }
+
+                    float factor = FadeEnvelope(fadeOutSeconds - elapsed, fadeOutSeconds);
+                    await sink.SendFrameAsync(ScaleFrame(last, factor), _lastLedCount, _lastBytesPerLed).ConfigureAwait(false);
+                    await Task.Delay(20, cancellationToken).ConfigureAwait(false);
+                }
+            }
+            catch (Exception ex) when (ex is not OperationCanceledException)
+            {
+                _logger.LogDebug(ex, "[Ambilight] Failed to send fade-out frame");
+            }
+            catch (OperationCanceledException)
+            {
+                // Shutting down; blank below.
+            }
+        }
+
+        // Repeated because UDP may drop a packet and a lost blank would leave the LEDs lit.
+        var zeroes = new byte[_lastLedCount * _lastBytesPerLed];
+        for (int i = 0; i < 3; i++)
+        {
+            try
+            {
+                await sink.SendFrameAsync(zeroes, _lastLedCount, _lastBytesPerLed).ConfigureAwait(false);
+            }
+            catch
+            {
+                break;
+            }
+            await Task.Delay(20, cancellationToken).ConfigureAwait(false);
+        }
+    }
+
+    private async Task<AmbilightBinary?> LoadBinaryAsync(string sessionId, string binPath, CancellationToken cancellationToken)
+    {
+        if (!AmbilightHttpRangeStream.IsRemote(binPath) && !File.Exists(binPath))
+        {
+            if (_config.Debug)
+            {
+                _logger.LogInformation("[Ambilight] Binary file not found: {Path}", binPath);
+            }
+            _logger.LogWarning("[Ambilight] Ambilight binary not found for session {SessionId}: {Path}", sessionId, binPath);
+            return null;
+        }
+
+        if (_config.Debug)
+        {
+            _logger.LogInformation("[Ambilight] Binary file found: {Path}", binPath);
+        }
+
+        AmbilightBinary binary;
+        try
+        {
+            if (Config.StreamBinaries)
+            {
+                binary = await Task.Run(() => AmbilightBinary.OpenStreaming(binPath, cancellationToken), cancellationToken).ConfigureAwait(false);
+            }
+            else
+            {
+                binary = _binaryCache != null
+                    ? await _binaryCache.GetAsync(binPath, cancellationToken).ConfigureAwait(false)
+                    : AmbilightBinary.Load(binPath, cancellationToken);
+            }
+        }
+        catch (Exception ex) when (ex is InvalidDataException or IOException or HttpRequestException)
+        {
+            // Invalid files, and for remote binaries missing files, unreachable servers or servers without range support.
+            _logger.LogWarning("[Ambilight] {Message}", ex.Message);
+            return null;
+        }
+
+        if (binary.FrameCount == 0)
+        {
+            _logger.LogWarning("[Ambilight] No frames in ambilight binary for session {SessionId}", sessionId);
+            binary.Dispose();
+            return null;
+        }
+
+        if (_config.Debug)
+        {
+            _logger.LogInformation("[Ambilight] Binary {How}: {FrameCount} frames", binary.IsStreaming ? "opened for streaming" : "loaded into memory", binary.FrameCount);
+        }
+
+        var chapters = AmbilightHttpRangeStream.IsRemote(binPath) ? new List<AmbilightChapter>() : AmbilightStorageService.ReadChapters(binPath);
+        if (binary.IsStreaming)
+        {
+            // Pre-warm: looking up each chapter start pages in the part of the mapped file a chapter skip lands on.
+            foreach (var chapter in chapters)
+            {
+                binary.FindFrameIndex(chapter.StartSeconds);
+            }
+        }
+
+        lock (_stateLock)
+        {
+            _chapters = chapters;
+            _status.ChapterCount = chapters.Count;
+        }
+
+        return binary;
+    }
+
+    private async Task<PlaybackEnd> PlayAsync(string sessionId, AmbilightUdpSink sink, AmbilightBinary binary, DeviceMapping mapping, double startSeconds, DateTime referenceUtc, byte[]? fadeFrom, CancellationToken cancellationToken)
+    {
+        double fpsD = binary.Fps;
+        var rgbw = binary.Rgbw;
+        // Output is always 8 bits per channel; 16-bit payloads are processed at full precision and dithered down.
+        // RGB files can be sent as RGBW, with the white channel derived from the final colors (see RgbwConversion).
+        int srcBytesPerLed = binary.Channels;
+        var rgbwConversion = rgbw ? "off" : (mapping.RgbwConversion ?? "off").Trim().ToLowerInvariant();
+        if (rgbwConversion is not ("min" or "luma" or "calibrated"))
+        {
+            rgbwConversion = "off";
+        }
+
+        var bytesPerLed = rgbwConversion == "off" ? srcBytesPerLed : 4;
+        var whitePoint = ParseWhitePoint(mapping.RgbwWhitePoint);
+        bool highDepth = binary.Is16Bit;
+        var raw = new float[binary.LedCount * binary.Channels];
+        var ditherError = highDepth ? Array.Empty<float>() : null;
+
+        // Target counts from mapping (falling back to source counts when none is set). A side set to 0 has no
+        // strip (e.g. no bottom LEDs on a wall-mounted TV); the other sides still take their own edge of the picture.
+        bool layoutSet = mapping.TopLedCount > 0 || mapping.BottomLedCount > 0 || mapping.LeftLedCount > 0 || mapping.RightLedCount > 0;
+        int tgtTop = layoutSet ? Math.Max(0, mapping.TopLedCount) : binary.TopCount;
+        int tgtBottom = layoutSet ? Math.Max(0, mapping.BottomLedCount) : binary.BottomCount;
+        int tgtLeft = layoutSet ? Math.Max(0, mapping.LeftLedCount) : binary.LeftCount;
+        int tgtRight = layoutSet ? Math.Max(0, mapping.RightLedCount) : binary.RightCount;
+
+        // LEDs in the corner brackets are part of the strip; they stay dark unless the file has dedicated corner zones.
+        var cornerGaps = ParseCornerGaps(mapping.CornerGapLeds);
+        int gapLeds = cornerGaps.Sum();
+
+        int totalSrc = binary.LedCount;
+        int totalTgt = tgtTop + tgtRight + tgtBottom + tgtLeft + gapLeds;
+
+        // WLED silently drops LEDs past the end of the strip; shrink every side proportionally instead.
+        // With a segment start only the LEDs after it are available.
+        int availableLeds = _deviceLedCount is { } deviceLeds ? deviceLeds - SegmentStartFor(mapping) : int.MaxValue;
+        if (totalTgt > availableLeds && availableLeds > gapLeds && Config.ClampToDeviceLedCount)
+        {
+            double scale = (double)(availableLeds - gapLeds) / (totalTgt - gapLeds);
+            tgtTop = tgtTop > 0 ? Math.Max(1, (int)(tgtTop * scale)) : 0;
+            tgtRight = tgtRight > 0 ? Math.Max(1, (int)(tgtRight * scale)) : 0;
+            tgtBottom = tgtBottom > 0 ? Math.Max(1, (int)(tgtBottom * scale)) : 0;
+            tgtLeft = tgtLeft > 0 ? Math.Max(1, (int)(tgtLeft * scale)) : 0;
+            totalTgt = tgtTop + tgtRight + tgtBottom + tgtLeft + gapLeds;
+        }
+
+        // Source LED(s) and blend factor for every target LED; with identical layouts this is the identity.
+        bool linearResample = !string.Equals(Config.AmbilightResampleMode, "nearest", StringComparison.OrdinalIgnoreCase);
+        if (totalTgt == 0)
+        {
+            _logger.LogWarning("[Ambilight] Mapping for {Host} has no LEDs on any side; nothing to play", mapping.Host);
+            return PlaybackEnd.Stopped;
+        }
+
+        var (resampleA, resampleB, resampleF) = BuildResampleMap(binary, tgtTop, tgtRight, tgtBottom, tgtLeft, cornerGaps, linearResample);
+
+        if (_config.Debug)
+        {
+            _logger.LogInformation("[Ambilight] Playing {Path} → {Host}:{Port} (src {Src} LEDs → tgt {Tgt} LEDs, rgbw={Rgbw}, rgbw conversion={Conversion}, 16-bit={HighDepth})",
+                binary.Path, mapping.Host, mapping.Port, totalSrc, totalTgt, rgbw, rgbwConversion, highDepth);
+        }
+
+        // Sync lead compensates plugin latency; the device's A/V offset delays the LEDs to match a slow display.
+        double syncLead = ReadSyncLead(mapping);
+        double effectiveStart = Math.Max(0.0, startSeconds + syncLead);
+        int startFrame = binary.FindFrameIndex(effectiveStart);
+        int frameIndex = startFrame;
+
+        // Timestamp of startFrame, kept so a streamed binary is not read back at the start frame on every frame.
+        ulong baseTs = startFrame < binary.FrameCount ? binary.TimestampUs(startFrame) : 0;
+
+        // Chapters started by the current position; advanced as frames play, looked up again after a seek.
+        List<AmbilightChapter> chapters;
+        int chapterCursor = -1;
+
+        lock (_stateLock)
+        {
+            chapters = _chapters;
+            _status.State = "playing";
+            _status.FrameCount = binary.FrameCount;
+            _status.SyncLeadSeconds = syncLead;
+        }
+
+        var playStartUtc = DateTime.UtcNow;
+        _ambientColors = null;
+        float ambientAlpha = (float)(1.0 / Math.Max(1.0, fpsD * AmbientWindowSeconds));
+        double crossfadeSeconds = Math.Max(0.0, Config.GaplessCrossfadeSeconds);
+
+        // Fade-in envelope from black, started by the first frame sent of an item (unless crossfading) and by seeks.
+        bool fadeInPending = fadeFrom == null;
+        DateTime? fadeInStartUtc = null;
+
+        var emaAcc = (float[]?)null;
+        var emaLab = (float[]?)null; // OKLab copy of the RGB accumulator while perceptual smoothing is on
+
+        int inputPosition = mapping.InputPosition;
+
+        int rotLeds = totalTgt > 0 ? Math.Abs(inputPosition) % totalTgt : 0;
+        bool reverse = mapping.Reverse && totalTgt > 1;
+
+        // Output buffers are reused from frame to frame, three of each: one being written, one waiting for the sender
+        // and one on the wire, so the frames published in _lastFrame and _lastSentFrame (read by the watchdog, the pause
+        // handling and the next item's crossfade) are not overwritten while in use. The static-scene anchor keeps its own copy.
+        var outBuffers = new[] { new byte[totalTgt * bytesPerLed], new byte[totalTgt * bytesPerLed], new byte[totalTgt * bytesPerLed] };
+        var sendBuffers = new[] { new byte[totalTgt * bytesPerLed], new byte[totalTgt * bytesPerLed], new byte[totalTgt * bytesPerLed] };
+        int bufferSlot = 0;
+
+        // The faded frames sent while paused get their own set, so they never overwrite the held frame they are made from.
+        var pauseBuffers = new[] { new byte[totalTgt * bytesPerLed], new byte[totalTgt * bytesPerLed], new byte[totalTgt * bytesPerLed] };
+        int pauseSlot = 0;
+
+        // Player clock: media seconds elapsed since startFrame, advanced by wall time scaled by the drift slew.
+        // Phase-locked players measure wall time from the reference epoch shared by the session's players and
+        // start with the time spent loading already elapsed; otherwise the clock starts when playback does.
+        double mediaElapsed = 0.0;
+        double smoothedDrift = 0.0;
+        double clockRate = 1.0;
+        double speed;
+        bool lastPaused = false;
+        bool phaseLock = Config.PhaseLockPlayers;
+        var clock = new AmbilightReferenceClock(phaseLock ? referenceUtc : DateTime.UtcNow);
+        double lastTick = clock.Now;
+        var run = new AmbilightRunStats(sessionId, _targetKey ?? mapping.Host, binary.Path);
+        _currentRun = run;
+        var sender = new FrameSender(this, sink, totalTgt, bytesPerLed, run, cancellationToken);
+        await using var senderScope = sender.ConfigureAwait(false);
+        double processingLeadMs = MaxProcessingLeadMs;
+        var lut = AmbilightCubeLut.TryLoad(mapping.LutPath, _logger);
+        var calibration = AmbilightLedCalibration.TryLoad(mapping.CalibrationPath, _logger);
+
+        lock (_stateLock)
+        {
+            _heartbeatSeconds = null;
+            _lastContactUtc = DateTime.UtcNow;
+            speed = _playbackRate;
+        }
+
+        if (phaseLock)
+        {
+            mediaElapsed = Math.Clamp(lastTick, 0.0, MaxLaunchDelaySeconds) * speed;
+        }
+
+        // Heartbeat loss: when the client stopped reporting, and how the LEDs react (freewheel, hold, fade).
+        DateTime? contactLostUtc = null;
+
+        // When the current pause started, for the pause mode's dim/fade/breathe envelope.
+        var pausedSinceUtc = DateTime.UtcNow;
+        byte[]? ambientFrame = null;
+
+        // Stop loading effect now that we're about to start broadcasting
+        if (_loadingEffectCts != null)
+        {
+            try
+            {
+                _loadingEffectCts.Cancel();
+                _loadingEffectCts.Dispose();
+                _loadingEffectCts = null;
+                if (_config.Debug)
+                {
+                    _logger.LogInformation("[Ambilight] Stopped loading effect, starting ambilight broadcast");
+                }
+            }
+            catch
+            {
+                // Ignore cancellation errors
+            }
+        }
+
+        // Transfer curves as lookup tables (see AmbilightGammaTable); the per-channel input curves are rebuilt when
+        // their gamma changes, the adaptive output curve comes from the shared tables.
+        AmbilightGammaTable? redCurve = null;
+        AmbilightGammaTable? greenCurve = null;
+        AmbilightGammaTable? blueCurve = null;
+
+        // Static-scene fade state: the output that started the current static period and when.
+        byte[]? staticAnchor = null;
+        var staticSinceUtc = DateTime.UtcNow;
+        float lastStaticFade = 1.0f;
+
+        // Keep-alive: the bytes last put on the wire and when, re-sent when nothing new goes out for a while.
+        byte[]? lastSentFrame = null;
+        var lastSentUtc = DateTime.UtcNow;
+        int lastProcessedIndex = -1;
+
+        // End credits (see AmbilightCreditsMode): how far the output has eased into the credits look, and for the
+        // ambient mode the long average of the output before them.
+        var creditsMode = binary.HasFrameInfo ? (Config.AmbilightCreditsMode ?? "off").Trim().ToLowerInvariant() : "off";
+        float creditsBlend = 0.0f;
+        float[]? creditsAverage = null;
+
+        var end = PlaybackEnd.Finished;
+
+        while (!cancellationToken.IsCancellationRequested && frameIndex < binary.FrameCount)
+        {
+            if (!OwnsTarget())
+            {
+                // A newer player took over this WLED; stop quietly so frames don't interleave.
+                _logger.LogInformation("[Ambilight] Another player took over {Target}; stopping session {SessionId}", _targetKey, sessionId);
+                end = PlaybackEnd.Superseded;
+                break;
+            }
+
+            lock (_stateLock)
+            {
+                if (_pendingLoad != null)
+                {
+                    end = PlaybackEnd.Reloaded;
+                }
+                else if (_unloadRequested || (_unloadAtUtc.HasValue && DateTime.UtcNow >= _unloadAtUtc.Value))
+                {
+                    end = PlaybackEnd.Unloaded;
+                }
+            }
+
+            if (end != PlaybackEnd.Finished)
+            {
+                break;
+            }
+
+            // Apply pending seek / pause state from PlaybackService
+            bool pausedNow;
+            double? seekSec;
+            double? heartbeat;
+            DateTime heartbeatUtc;
+            double newSpeed;
+            DateTime lastContactUtc;
+            DateTime seekUtc;
+            lock (_stateLock)
+            {
+                newSpeed = _playbackRate;
+                lastContactUtc = _lastContactUtc;
+                pausedNow = _isPaused;
+                seekSec = _pendingSeekSeconds;
+                seekUtc = _pendingSeekUtc;
+                _pendingSeekSeconds = null;
+                heartbeat = _heartbeatSeconds;
+                heartbeatUtc = _heartbeatUtc;
+                _heartbeatSeconds = null;
+            }
+
+            // Advance the clock for the interval just ended, only if we were playing through it.
+            var now = DateTime.UtcNow;
+            double tick = clock.Now;
+            if (!lastPaused)
+            {
+                mediaElapsed += (tick - lastTick) * clockRate * speed;
+            }
+            lastTick = tick;
+            speed = newSpeed;
+
+            // Missing heartbeats: unless set to freewheel, hold the clock (and optionally fade out) until the client
+            // reports again, then jump to where it is.
+            var config = Config;
+            var lossAction = (config.HeartbeatLossAction ?? "freewheel").Trim().ToLowerInvariant();
+            bool contactLost = lossAction != "freewheel" && !pausedNow && config.HeartbeatTimeoutSeconds > 0
+                && (now - lastContactUtc).TotalSeconds > config.HeartbeatTimeoutSeconds;
+            if (contactLost && contactLostUtc == null)
+            {
+                contactLostUtc = now;
+                _logger.LogInformation("[Ambilight] No progress from session {SessionId} for {Seconds:F0}s; {Action} until it resumes",
+                    sessionId, (now - lastContactUtc).TotalSeconds, lossAction == "fade" ? "fading out" : "holding");
+            }
+            else if (!contactLost && contactLostUtc != null)
+            {
+                contactLostUtc = null;
+                if (heartbeat.HasValue && !seekSec.HasValue)
+                {
+                    seekSec = heartbeat.Value + (now - heartbeatUtc).TotalSeconds * speed;
+                    seekUtc = now;
+                    _logger.LogInformation("[Ambilight] Progress from session {SessionId} resumed; resyncing to {Seconds:F1}s", sessionId, seekSec.Value);
+                }
+            }
+
+            // A changed lead (SET av_offset_ms) shifts the clock instead of jumping frames: later frames wait, earlier ones catch up.
+            double newLead = ReadSyncLead(mapping);
+            if (newLead != syncLead)
+            {
+                mediaElapsed += newLead - syncLead;
+                syncLead = newLead;
+                lock (_stateLock)
+                {
+                    _status.SyncLeadSeconds = syncLead;
+                }
+            }
+
+            if (heartbeat.HasValue && !pausedNow && !seekSec.HasValue && Config.DriftCorrection)
+            {
+                // Positive drift = LEDs ahead of the client.
+                double reported = heartbeat.Value + (now - heartbeatUtc).TotalSeconds * speed;
+                double playerPosition = (baseTs / 1e6) + mediaElapsed - syncLead;
+                double drift = playerPosition - reported;
+                if (Math.Abs(drift) > DriftResyncSeconds)
+                {
+                    _logger.LogInformation("[Ambilight] Drift of {Drift:F2}s for session {SessionId}; resyncing to {Seconds:F1}s", drift, sessionId, reported);
+                    seekSec = reported;
+                    seekUtc = now;
+                }
+                else
+                {
+                    smoothedDrift += (drift - smoothedDrift) * DriftSmoothing;
+                    clockRate = 1.0 - Math.Clamp(smoothedDrift / DriftConvergeSeconds, -MaxClockSlew, MaxClockSlew);
+                    if (_config.Debug)
+                    {
+                        _logger.LogInformation("[Ambilight] Drift {Drift:F3}s (smoothed {Smoothed:F3}s), clock rate {Rate:F3}", drift, smoothedDrift, clockRate);
+                    }
+                }
+
+                lock (_stateLock)
+                {
+                    _status.DriftSeconds = drift;
+                    _status.ClockRate = clockRate;
+                }
+            }
+
+            if (seekSec.HasValue)
+            {
+                // Seek targets are media positions; frames are looked up sync-lead ahead, as at start.
+                frameIndex = binary.FindFrameIndex(Math.Max(0.0, seekSec.Value + syncLead));
+                startFrame = frameIndex;
+                baseTs = startFrame < binary.FrameCount ? binary.TimestampUs(startFrame) : 0;
+                chapterCursor = -1;
+                // Phase-locked: count from when the position was reported, so every player of the session agrees.
+                mediaElapsed = phaseLock && !pausedNow ? Math.Clamp(tick - clock.ToClockSeconds(seekUtc), 0.0, MaxLaunchDelaySeconds) * speed : 0.0;
+                smoothedDrift = 0.0;
+                clockRate = 1.0;
+                fadeInPending = true;
+                if (_config.Debug)
+                {
+                    _logger.LogInformation("[Ambilight] Seek to {Seconds:F3}s → frame {Frame}", seekSec.Value, frameIndex);
+                }
+                else
+                {
+                    _logger.LogDebug("[Ambilight] In-process SEEK to {Seconds:F3}s → frame {Frame}", seekSec.Value, frameIndex);
+                }
+            }
+
+            if (pausedNow && !lastPaused)
+            {
+                // Pause: freeze the current ambilight frame and stop advancing time.
+                // The pause mode decides whether it stays as it is, dims, fades out or breathes.
+                pausedSinceUtc = DateTime.UtcNow;
+                ambientFrame = null;
+                if (_config.Debug)
+                {
+                    _logger.LogInformation("[Ambilight] Pause detected – holding current frame");
+                }
+                else
+                {
+                    _logger.LogDebug("[Ambilight] In-process player paused");
+                }
+            }
+            if (!pausedNow && lastPaused)
+            {
+                if (_config.Debug)
+                {
+                    _logger.LogInformation("[Ambilight] Resume detected – resuming broadcast");
+                }
+                else
+                {
+                    _logger.LogDebug("[Ambilight] In-process player resumed");
+                }
+            }
+            lastPaused = pausedNow || contactLost;
+
+            lock (_stateLock)
+            {
+                _status.State = pausedNow ? "paused" : (contactLost ? "stalled" : "playing");
+            }
+
+            if (pausedNow || contactLost)
+            {
+                // While paused we simply sleep; WLED keeps displaying the last frame,
+                // unless it has been static long enough to fade (paused menus left open) or contact is lost with fade selected.
+                float pausedFade = StaticFadeFactor(DateTime.UtcNow - staticSinceUtc) * NightBrightnessFactor(DateTime.Now);
+                if (contactLost && lossAction == "fade" && contactLostUtc is { } lostSince)
+                {
+                    pausedFade = Math.Min(pausedFade, (float)Math.Max(0.0, 1.0 - ((DateTime.UtcNow - lostSince).TotalSeconds / HeartbeatLossFadeSeconds)));
+                }
+
+                var pauseMode = pausedNow ? (Config.PauseMode ?? "hold").Trim().ToLowerInvariant() : "hold";
+                if (pauseMode != "hold")
+                {
+                    pausedFade = Math.Min(pausedFade, PauseFadeFactor(pauseMode, DateTime.UtcNow - pausedSinceUtc));
+                }
+
+                if (pauseMode == "breathe" && _lastFrame != null)
+                {
+                    ambientFrame ??= AverageColorFrame(_lastFrame, totalTgt, bytesPerLed);
+                }
+
+                var heldFrame = pauseMode == "breathe" ? ambientFrame : _lastFrame;
+                if (heldFrame != null && (pausedFade < 1.0f || lastStaticFade < 1.0f))
+                {
+                    lastStaticFade = pausedFade;
+                    pauseSlot = (pauseSlot + 1) % pauseBuffers.Length;
+                    var faded = PrepareOutputFrame(heldFrame, rotLeds, totalTgt, bytesPerLed, reverse, pausedFade, calibration, pauseBuffers[pauseSlot]);
+                    lastSentFrame = faded;
+                    lastSentUtc = DateTime.UtcNow;
+                    try
+                    {
+                        await sender.SendAsync(faded, Stopwatch.GetTimestamp(), 0.0, frameIndex, binary.FrameCount, cancellationToken).ConfigureAwait(false);
+                    }
+                    catch (OperationCanceledException)
+                    {
+                        break;
+                    }
+                }
+                else if (KeepAliveDue(lastSentFrame, lastSentUtc))
+                {
+                    lastSentUtc = DateTime.UtcNow;
+                    await SendKeepAliveAsync(sink, lastSentFrame!, totalTgt, bytesPerLed).ConfigureAwait(false);
+                }
+
+                try
+                {
+                    await Task.Delay(80, cancellationToken).ConfigureAwait(false);
+                }
+                catch (OperationCanceledException)
+                {
+                    break;
+                }
+                continue;
+            }
+
+            if (!binary.IsFrameReady(frameIndex))
+            {
+                // Remote binary and the frame's block is still being fetched: hold the last frame rather than wait on
+                // the network. The clock runs on, so the frames that went late meanwhile are dropped below.
+                if (KeepAliveDue(lastSentFrame, lastSentUtc))
+                {
+                    lastSentUtc = DateTime.UtcNow;
+                    await SendKeepAliveAsync(sink, lastSentFrame!, totalTgt, bytesPerLed).ConfigureAwait(false);
+                }
+
+                try
+                {
+                    await Task.Delay(RemoteFetchPollInterval, cancellationToken).ConfigureAwait(false);
+                }
+                catch (OperationCanceledException)
+                {
+                    break;
+                }
+                continue;
+            }
+
+            ulong frameTs = frameIndex < binary.FrameCount ? binary.TimestampUs(frameIndex) : 0;
+            var frameTargetUs = frameTs > baseTs ? frameTs - baseTs : 0UL;
+            ulong elapsedUs = (ulong)(mediaElapsed * 1_000_000.0);
+            long dueTimestamp = Stopwatch.GetTimestamp();
+            if (elapsedUs < frameTargetUs)
+            {
+                var sleepUs = (frameTargetUs - elapsedUs) / (clockRate * speed);
+                var sleepMs = (int)Math.Max(0, sleepUs / 1000.0);
+                dueTimestamp += (long)(sleepUs * Stopwatch.Frequency / 1e6);
+
+                // Sparse frames (still shots the extractor stretched, low-fps files): refresh WLED in between
+                // and go round the loop again, which also picks up seeks and pauses sooner.
+                double keepAliveMs = Config.KeepAliveSeconds * 1000.0;
+                if (keepAliveMs > 0 && lastSentFrame != null && sleepMs > keepAliveMs)
+                {
+                    var untilKeepAlive = (int)Math.Max(0, keepAliveMs - (DateTime.UtcNow - lastSentUtc).TotalMilliseconds);
+                    await Task.Delay(untilKeepAlive, cancellationToken).ConfigureAwait(false);
+                    lastSentUtc = DateTime.UtcNow;
+                    await SendKeepAliveAsync(sink, lastSentFrame, totalTgt, bytesPerLed).ConfigureAwait(false);
+                    continue;
+                }
+
+                // Wake up early enough to process the frame before it is due; the sender waits out the rest.
+                int earlyMs = (int)(sleepMs - processingLeadMs);
+                if (earlyMs > 0)
+                {
+                    await Task.Delay(earlyMs, cancellationToken).ConfigureAwait(false);
+                }
+            }
+            else if (frameIndex + 1 < binary.FrameCount)
+            {
+                // Behind schedule (after a long stall, a seek or a slow send): skip to the newest frame that is due
+                // instead of sending the backlog back-to-back, which strobes on the strip.
+                double behindElapsed = mediaElapsed + ((clock.Now - lastTick) * clockRate * speed);
+                ulong behindUs = (ulong)(behindElapsed * 1_000_000.0);
+                int skipped = 0;
+                while (frameIndex + 1 < binary.FrameCount && binary.IsFrameReady(frameIndex + 1)
+                    && binary.TimestampUs(frameIndex + 1) > baseTs && binary.TimestampUs(frameIndex + 1) - baseTs <= behindUs)
+                {
+                    frameIndex++;
+                    skipped++;
+                }
+
+                if (skipped > 0)
+                {
+                    frameTs = binary.TimestampUs(frameIndex);
+                    lock (_stateLock)
+                    {
+                        _status.FramesDropped += skipped;
+                    }
+
+                    run.FramesDropped += skipped;
+
+                    if (_config.Debug)
+                    {
+                        _logger.LogInformation("[Ambilight] {Skipped} late frame(s) dropped, continuing at frame {Frame}", skipped, frameIndex);
+                    }
+                }
+            }
+
+            long processingStart = Stopwatch.GetTimestamp();
+            binary.DecodeFrame(frameIndex, raw);
+
+            // Tuning is re-read every frame so settings changes and SetParameter apply immediately.
+            var tuning = ReadTuning(mapping);
+
+            // Smoothing: configured directly in seconds. 0 = no smoothing (per-frame colors).
+            bool noSmoothing = tuning.SmoothSeconds <= 0.0f;
+            float smoothTau = noSmoothing ? 0.0f : ClampF(tuning.SmoothSeconds, 0.001f, 5.0f);
+            float gammaBase = tuning.Gamma;
+            float gammaRed = tuning.GammaRed;
+            float gammaGreen = tuning.GammaGreen;
+            float gammaBlue = tuning.GammaBlue;
+            float redBoost = tuning.RedBoost;
+            float greenBoost = tuning.GreenBoost;
+            float blueBoost = tuning.BlueBoost;
+            var whiteBalance = WhiteBalanceGains(tuning.ColorTemperatureK);
+
+            // avg luminance, stored per frame by extractors that were asked to
+            if (!binary.TryGetLuminance(frameIndex, out float avgLum))
+            {
+                float sumLum = 0f;
+                int countPix = 0;
+                int idx = 0;
+                while (idx + 2 < raw.Length)
+                {
+                    float r = raw[idx];
+                    float g = raw[idx + 1];
+                    float b = raw[idx + 2];
+                    float lum = 0.2126f * r + 0.7152f * g + 0.0722f * b;
+                    sumLum += lum;
+                    countPix++;
+                    idx += srcBytesPerLed;
+                }
+
+                avgLum = countPix > 0 ? sumLum / countPix : 0f;
+            }
+            float gammaAdj = ClampF(gammaBase * (1.0f - (avgLum / 255.0f) * 0.6f), 1.0f, 3.0f);
+            float invGamma = 1.0f / gammaAdj;
+            if (redCurve == null || redCurve.Exponent != gammaRed)
+            {
+                redCurve = new AmbilightGammaTable(gammaRed);
+            }
+
+            if (greenCurve == null || greenCurve.Exponent != gammaGreen)
+            {
+                greenCurve = new AmbilightGammaTable(gammaGreen);
+            }
+
+            if (blueCurve == null || blueCurve.Exponent != gammaBlue)
+            {
+                blueCurve = new AmbilightGammaTable(gammaBlue);
+            }
+
+            var outputCurve = AmbilightGammaTable.Shared(invGamma);
+
+            float frameDtS;
+            if (frameIndex == 0)
+            {
+                frameDtS = (float)(1.0 / fpsD);
+            }
+            else
+            {
+                // Measured from the previous frame actually processed, so dropped frames don't slow the smoothing down.
+                int prevIndex = lastProcessedIndex >= 0 && lastProcessedIndex < frameIndex ? lastProcessedIndex : frameIndex - 1;
+                double prevUs = prevIndex < binary.FrameCount ? binary.TimestampUs(prevIndex) : 0;
+                double curUs = frameIndex < binary.FrameCount ? binary.TimestampUs(frameIndex) : 0;
+                double dt = (curUs - prevUs) / 1e6;
+                frameDtS = dt > 0.0 ? (float)dt : (float)(1.0 / fpsD);
+            }
+
+            // Smoothing is a wall-clock time constant, so faster playback means shorter real frame intervals.
+            frameDtS /= (float)speed;
+            float k = noSmoothing
+                ? 1.0f  // no EMA: use the current frame only
+                : 1.0f - (float)Math.Exp(-frameDtS / smoothTau);
+
+            // Optional separate time constant for LEDs getting darker (fast attack, gentle fade to black).
+            float kDecay = tuning.DecaySeconds > 0.0f
+                ? 1.0f - (float)Math.Exp(-frameDtS / ClampF(tuning.DecaySeconds, 0.001f, 10.0f))
+                : k;
+
+            // A cut flagged by the extractor, here or in a frame skipped since the last one processed, starts the new
+            // shot's colors without blending the old ones in.
+            if (binary.HasFrameInfo && Config.AmbilightSceneCutReset && SceneCutSince(binary, lastProcessedIndex, frameIndex))
+            {
+                k = 1.0f;
+                kDecay = 1.0f;
+            }
+
+            if (emaAcc == null)
+            {
+                emaAcc = new float[totalTgt * bytesPerLed];
+                for (int t = 0; t < totalTgt; t++)
+                {
+                    if (resampleA[t] < 0)
+                    {
+                        continue; // corner gap
+                    }
+
+                    for (int b = 0; b < srcBytesPerLed; b++)
+                    {
+                        emaAcc[t * bytesPerLed + b] = Resample(raw, resampleA[t], resampleB[t], resampleF[t], srcBytesPerLed, b);
+                    }
+                }
+            }
+
+            var acc = emaAcc!;
+            bool perceptual = Config.AmbilightPerceptualColor;
+            if (!perceptual)
+            {
+                emaLab = null;
+            }
+            else if (emaLab == null || emaLab.Length != totalTgt * 3)
+            {
+                // Start from the current RGB state so switching modes mid-playback doesn't jump.
+                emaLab = new float[totalTgt * 3];
+                for (int t = 0; t < totalTgt; t++)
+                {
+                    int ab = t * bytesPerLed;
+                    var lab = AmbilightOklab.FromSrgb(acc[ab] / 255.0f, acc[ab + 1] / 255.0f, acc[ab + 2] / 255.0f);
+                    emaLab[t * 3] = lab.L;
+                    emaLab[t * 3 + 1] = lab.A;
+                    emaLab[t * 3 + 2] = lab.B;
+                }
+            }
+            bufferSlot = (bufferSlot + 1) % outBuffers.Length;
+            var outFrame = outBuffers[bufferSlot];
+            Array.Clear(outFrame);
+            if (ditherError != null && ditherError.Length != outFrame.Length)
+            {
+                ditherError = new float[outFrame.Length];
+            }
+
+            float sUser = ClampF(tuning.Saturation, 0.0f, 5.0f);
+            float gUser = Math.Max(0.01f, tuning.Gamma);
+            float bTarget = Math.Max(1.0f, tuning.BrightnessTarget);
+            float minB = Math.Max(0.0f, tuning.MinLedBrightness);
+
+            float brightnessFactor = 1.0f;
+            if (avgLum > 1.0f)
+            {
+                float factor = (bTarget / avgLum) * 0.7f + 0.3f;
+                brightnessFactor = ClampF(factor, 0.05f, 2.5f);
+            }
+
+            // Audio pulse: frames among the film's loudest brighten by up to the configured share, smoothed like the colors.
+            float audioPulse = 1.0f;
+            if (Config.AmbilightAudioPulse > 0 && binary.TryGetAudioEnergy(frameIndex, out float energy))
+            {
+                audioPulse += ClampF(Config.AmbilightAudioPulse / 100.0f, 0.0f, 1.0f) * Math.Max(0.0f, (energy / 255.0f - AudioPulseFloor) / (1.0f - AudioPulseFloor));
+            }
+
+            for (int t = 0; t < totalTgt; t++)
+            {
+                int srcA = resampleA[t];
+                if (srcA < 0)
+                {
+                    continue; // corner gap: stays black
+                }
+
+                int srcB = resampleB[t];
+                float srcF = resampleF[t];
+
+                float rU = Resample(raw, srcA, srcB, srcF, srcBytesPerLed, 0);
+                float gU = Resample(raw, srcA, srcB, srcF, srcBytesPerLed, 1);
+                float bU = Resample(raw, srcA, srcB, srcF, srcBytesPerLed, 2);
+
+                float rN = ClampF(rU / 255.0f, 0.0f, 1.0f);
+                float gN = ClampF(gU / 255.0f, 0.0f, 1.0f);
+                float bN = ClampF(bU / 255.0f, 0.0f, 1.0f);
+
+                float rLin = redCurve.Apply(rN);
+                float gLin = greenCurve.Apply(gN);
+                float bLin = blueCurve.Apply(bN);
+
+                float rSat, gSat, bSat;
+                if (perceptual)
+                {
+                    // Scale chroma in OKLab: hue and lightness stay put.
+                    var lab = AmbilightOklab.FromSrgb(rLin, gLin, bLin);
+                    (rSat, gSat, bSat) = AmbilightOklab.ToSrgb(lab.L, lab.A * sUser, lab.B * sUser);
+                }
+                else
+                {
+                    float avgIntensity = (rLin + gLin + bLin) / 3.0f;
+                    rSat = avgIntensity + (rLin - avgIntensity) * sUser;
+                    gSat = avgIntensity + (gLin - avgIntensity) * sUser;
+                    bSat = avgIntensity + (bLin - avgIntensity) * sUser;
+                }
+
+                float rG = outputCurve.Apply(rSat);
+                float gG = outputCurve.Apply(gSat);
+                float bG = outputCurve.Apply(bSat);
+
+                float brightnessFactorAdj = ClampF(brightnessFactor, 0.3f, 1.8f) * audioPulse;
+                float rF = rG * brightnessFactorAdj * 255.0f;
+                float gF = gG * brightnessFactorAdj * 255.0f;
+                float bF = bG * brightnessFactorAdj * 255.0f;
+
+                int @base = t * bytesPerLed;
+                float kLed;
+                if (emaLab != null)
+                {
+                    // Blend in OKLab so fades keep their hue (red fades to dark red, not brown), then mirror to RGB.
+                    int lb = t * 3;
+                    var target = AmbilightOklab.FromSrgb(rF / 255.0f, gF / 255.0f, bF / 255.0f);
+                    kLed = target.L < emaLab[lb] ? kDecay : k;
+                    emaLab[lb] = emaLab[lb] * (1.0f - kLed) + target.L * kLed;
+                    emaLab[lb + 1] = emaLab[lb + 1] * (1.0f - kLed) + target.A * kLed;
+                    emaLab[lb + 2] = emaLab[lb + 2] * (1.0f - kLed) + target.B * kLed;
+                    var smoothed = AmbilightOklab.ToSrgb(emaLab[lb], emaLab[lb + 1], emaLab[lb + 2]);
+                    acc[@base] = smoothed.R * 255.0f;
+                    acc[@base + 1] = smoothed.G * 255.0f;
+                    acc[@base + 2] = smoothed.B * 255.0f;
+                }
+                else
+                {
+                    // One factor per LED, chosen by whether it is getting darker, so all channels move together.
+                    float lumTarget = 0.2126f * rF + 0.7152f * gF + 0.0722f * bF;
+                    float lumAcc = 0.2126f * acc[@base] + 0.7152f * acc[@base + 1] + 0.0722f * acc[@base + 2];
+                    kLed = lumTarget < lumAcc ? kDecay : k;
+                    acc[@base] = acc[@base] * (1.0f - kLed) + rF * kLed;
+                    acc[@base + 1] = acc[@base + 1] * (1.0f - kLed) + gF * kLed;
+                    acc[@base + 2] = acc[@base + 2] * (1.0f - kLed) + bF * kLed;
+                }
+
+                // Match Rust: round smoothed accumulator before min clamp and output (avoids truncation bias / blue tint).
+                // 16-bit sources keep the fraction so dithering can carry it into the 8-bit output.
+                float rOut = highDepth ? acc[@base] : MathF.Round(acc[@base]);
+                float gOut = highDepth ? acc[@base + 1] : MathF.Round(acc[@base + 1]);
+                float bOut = highDepth ? acc[@base + 2] : MathF.Round(acc[@base + 2]);
+
+                float minR = minB * redBoost;
+                float minG = minB * greenBoost;
+                float minBB = minB * blueBoost;
+
+                if (rOut > 0.0f && rOut < minR) rOut = minR;
+                if (gOut > 0.0f && gOut < minG) gOut = minG;
+                if (bOut > 0.0f && bOut < minBB) bOut = minBB;
+
+                float lumLed = 0.2126f * rOut + 0.7152f * gOut + 0.0722f * bOut;
+                if (lumLed < minB * 0.5f)
+                {
+                    rOut = 0.0f;
+                    gOut = 0.0f;
+                    bOut = 0.0f;
+                }
+
+                rOut *= whiteBalance.R;
+                gOut *= whiteBalance.G;
+                bOut *= whiteBalance.B;
+
+                // Calibration LUT last, on the final color; black stays black so "off" LEDs are not lit by the LUT.
+                if (lut != null && (rOut > 0.0f || gOut > 0.0f || bOut > 0.0f))
+                {
+                    lut.Apply(ref rOut, ref gOut, ref bOut);
+                }
+
+                float wOut = rgbwConversion == "off" ? 0.0f : ExtractWhite(rgbwConversion, whitePoint, ref rOut, ref gOut, ref bOut);
+
+                // Round before cast to byte to match Rust (truncation was darkening and boosting blue floor)
+                // Send RGB order - WLED handles color order remapping based on its own configuration
+                outFrame[@base] = ToByte(rOut, ditherError, @base);
+                outFrame[@base + 1] = ToByte(gOut, ditherError, @base + 1);
+                outFrame[@base + 2] = ToByte(bOut, ditherError, @base + 2);
+
+                if (srcBytesPerLed == 4)
+                {
+                    float wVal = Resample(raw, srcA, srcB, srcF, srcBytesPerLed, 3);
+                    acc[@base + 3] = acc[@base + 3] * (1.0f - kLed) + wVal * kLed;
+                    outFrame[@base + 3] = ToByte(acc[@base + 3], ditherError, @base + 3);
+                }
+                else if (bytesPerLed == 4)
+                {
+                    outFrame[@base + 3] = ToByte(wOut, ditherError, @base + 3);
+                }
+            }
+
+            // End credits flagged by the extractor: ease towards dimmed output or, in ambient mode, towards the dimmed
+            // average of what played before them, instead of strobing with white text on black.
+            if (creditsMode != "off")
+            {
+                bool inCredits = binary.IsCredits(frameIndex);
+                if (creditsMode == "ambient" && !inCredits)
+                {
+                    creditsAverage ??= outFrame.Select(v => (float)v).ToArray();
+                    for (int i = 0; i < outFrame.Length && i < creditsAverage.Length; i++)
+                    {
+                        creditsAverage[i] += (outFrame[i] - creditsAverage[i]) * ambientAlpha;
                     }
                 }
-                if (!pausedNow && lastPaused)
+
+                creditsBlend = ClampF(creditsBlend + ((inCredits ? 1.0f : -1.0f) * frameDtS / CreditsRampSeconds), 0.0f, 1.0f);
+                if (creditsBlend > 0.0f)
                 {
-                    startInstant = DateTime.UtcNow;
-                    if (_config.Debug)
+                    float dim = ClampF((float)(Config.AmbilightCreditsBrightness / 100.0), 0.0f, 1.0f);
+                    for (int i = 0; i < outFrame.Length; i++)
+                    {
+                        float source = creditsMode == "ambient" && creditsAverage != null && i < creditsAverage.Length ? creditsAverage[i] : outFrame[i];
+                        outFrame[i] = (byte)Math.Clamp((int)Math.Round(outFrame[i] + ((source * dim) - outFrame[i]) * creditsBlend), 0, 255);
+                    }
+                }
+            }
+
+            // Gapless handover: blend from the previous item's last frame over the crossfade window.
+            if (fadeFrom != null)
+            {
+                double t = crossfadeSeconds > 0.0 ? (DateTime.UtcNow - playStartUtc).TotalSeconds / crossfadeSeconds : 1.0;
+                if (t < 1.0 && fadeFrom.Length == outFrame.Length)
+                {
+                    for (int i = 0; i < outFrame.Length; i++)
+                    {
+                        outFrame[i] = (byte)Math.Clamp((int)Math.Round(fadeFrom[i] + ((outFrame[i] - fadeFrom[i]) * t)), 0, 255);
+                    }
+                }
+                else
+                {
+                    fadeFrom = null;
+                }
+            }
+
+            lastProcessedIndex = frameIndex;
+            _lastFrame = outFrame;
+            _lastLedCount = totalTgt;
+            _lastBytesPerLed = bytesPerLed;
+
+            // Long average of the output, for the ambient light after the item (see AmbientAsync).
+            if (Config.PostPlaybackAmbient)
+            {
+                if (_ambientColors == null || _ambientColors.Length != outFrame.Length)
+                {
+                    _ambientColors = new float[outFrame.Length];
+                    for (int i = 0; i < outFrame.Length; i++)
+                    {
+                        _ambientColors[i] = outFrame[i];
+                    }
+                }
+                else
+                {
+                    for (int i = 0; i < outFrame.Length; i++)
+                    {
+                        _ambientColors[i] += (outFrame[i] - _ambientColors[i]) * ambientAlpha;
+                    }
+                }
+            }
+
+            // Anti-glare: dim output that has barely changed for a long time; any real change restores it at once.
+            if (staticAnchor == null || !IsNearlySameFrame(staticAnchor, outFrame))
+            {
+                staticAnchor ??= new byte[outFrame.Length];
+                Buffer.BlockCopy(outFrame, 0, staticAnchor, 0, outFrame.Length);
+                staticSinceUtc = DateTime.UtcNow;
+            }
+            lastStaticFade = StaticFadeFactor(DateTime.UtcNow - staticSinceUtc) * NightBrightnessFactor(DateTime.Now);
+
+            float envelope = 1.0f;
+            if (fadeInPending)
+            {
+                fadeInStartUtc = DateTime.UtcNow;
+                fadeInPending = false;
+            }
+
+            if (fadeInStartUtc is { } fadeInStart)
+            {
+                envelope = FadeEnvelope((DateTime.UtcNow - fadeInStart).TotalSeconds, Config.FadeInSeconds);
+                if (envelope >= 1.0f)
+                {
+                    fadeInStartUtc = null;
+                }
+            }
+
+            float outputScale = lastStaticFade * envelope;
+            byte[] frameToSend = PrepareOutputFrame(outFrame, rotLeds, totalTgt, bytesPerLed, reverse, outputScale, calibration, sendBuffers[bufferSlot]);
+
+            double processingMs = Stopwatch.GetElapsedTime(processingStart).TotalMilliseconds;
+            processingLeadMs = Math.Clamp((0.9 * processingLeadMs) + (0.1 * ((2.0 * processingMs) + MinProcessingLeadMs)), MinProcessingLeadMs, MaxProcessingLeadMs);
+            lastSentFrame = frameToSend;
+            lastSentUtc = DateTime.UtcNow;
+            await sender.SendAsync(frameToSend, dueTimestamp, processingMs / 1000.0, frameIndex, binary.FrameCount, cancellationToken).ConfigureAwait(false);
+
+            lock (_stateLock)
+            {
+                _status.FrameIndex = frameIndex;
+                _status.FrameTimestampSeconds = frameTs / 1e6;
+                _status.PositionSeconds = Math.Max(0.0, frameTs / 1e6 - syncLead);
+                double position = _status.PositionSeconds;
+                if (chapterCursor < 0)
+                {
+                    chapterCursor = ChaptersStartedBy(chapters, position);
+                }
+
+                while (chapterCursor < chapters.Count && chapters[chapterCursor].StartSeconds <= position)
+                {
+                    chapterCursor++;
+                }
+
+                _status.Chapter = chapterCursor;
+            }
+
+            frameIndex++;
+        }
+
+        await sender.CompleteAsync().ConfigureAwait(false);
+
+        if (end == PlaybackEnd.Finished && cancellationToken.IsCancellationRequested)
+        {
+            end = PlaybackEnd.Stopped;
+        }
+
+        lock (_stateLock)
+        {
+            _status.State = end switch
+            {
+                PlaybackEnd.Superseded => "superseded",
+                PlaybackEnd.Stopped => "stopped",
+                PlaybackEnd.Unloaded => "idle",
+                PlaybackEnd.Reloaded => "loading",
+                _ => "finished"
+            };
+        }
+
+        FinishRun(end);
+        return end;
+    }
+
+    /// <summary>
+    /// Logs the statistics of the item that just ended, keeps them in the status and, when enabled,
+    /// writes them as JSON to <c>&lt;data folder&gt;/reports/</c>.
+    /// </summary>
+    private void FinishRun(PlaybackEnd end)
+    {
+        var run = _currentRun;
+        _currentRun = null;
+        if (run == null)
+        {
+            return;
+        }
+
+        var report = run.ToReport(end.ToString().ToLowerInvariant());
+        lock (_stateLock)
+        {
+            _status.LastRun = report;
+        }
+
+        _logger.LogInformation(
+            "[Ambilight] Run summary for {Target} ({End}): {Sent} frames sent, {Dropped} dropped, {KeepAlives} keep-alives, {Errors} send errors; scheduling error avg {Avg:F1} ms, p99 {P99:F1} ms, max {Max:F1} ms; processing avg {Processing:F2} ms",
+            report.Target, report.End, report.FramesSent, report.FramesDropped, report.KeepAlivesSent, report.SendErrors,
+            report.AvgSchedulingErrorMs, report.P99SchedulingErrorMs, report.MaxSchedulingErrorMs, report.AvgProcessingMs);
+
+        var config = Config;
+        if (!config.WriteRunReports)
+        {
+            return;
+        }
+
+        try
+        {
+            var dataFolder = string.IsNullOrWhiteSpace(config.AmbilightDataFolder) ? "/data/ambilight" : config.AmbilightDataFolder.Trim();
+            var reportsFolder = Path.Combine(dataFolder, "reports");
+            Directory.CreateDirectory(reportsFolder);
+            var target = string.Concat(report.Target.Select(c => char.IsLetterOrDigit(c) ? c : '_'));
+            var reportPath = Path.Combine(reportsFolder, $"{report.EndedUtc:yyyyMMdd-HHmmss}-{target}.json");
+            File.WriteAllText(reportPath, JsonSerializer.Serialize(report, new JsonSerializerOptions { WriteIndented = true }));
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException)
+        {
+            _logger.LogWarning(ex, "[Ambilight] Could not write run report");
+        }
+    }
+
+    /// <summary>
+    /// Effective lead in seconds: the configured sync lead plus the measured network latency (when enabled),
+    /// minus the device's A/V offset (mapping value or SET override).
+    /// </summary>
+    private double ReadSyncLead(DeviceMapping mapping)
+    {
+        var config = Config;
+        double avOffsetMs = mapping.AvOffsetMs;
+        double networkLatency = 0.0;
+        lock (_stateLock)
+        {
+            if (_overrides.TryGetValue("av_offset_ms", out var overrideMs))
+            {
+                avOffsetMs = overrideMs;
+            }
+
+            if (config.AutoLatencyCompensation && _networkLatencySeconds is { } latency)
+            {
+                networkLatency = latency;
+            }
+        }
+
+        return config.AmbilightSyncLeadSeconds + networkLatency - avOffsetMs / 1000.0;
+    }
+
+    private Tuning ReadTuning(DeviceMapping mapping)
+    {
+        var config = Config;
+        var t = new Tuning
+        {
+            BrightnessTarget = (float)(mapping.BrightnessTarget ?? config.AmbilightBrightnessTarget),
+            Saturation = (float)(mapping.Saturation ?? config.AmbilightSaturation),
+            SmoothSeconds = (float)config.AmbilightSmoothSeconds,
+            Gamma = (float)(mapping.Gamma ?? config.AmbilightGamma),
+            GammaRed = (float)config.AmbilightGammaRed,
+            GammaGreen = (float)config.AmbilightGammaGreen,
+            GammaBlue = (float)config.AmbilightGammaBlue,
+            RedBoost = (float)config.AmbilightRedBoost,
+            GreenBoost = (float)config.AmbilightGreenBoost,
+            BlueBoost = (float)config.AmbilightBlueBoost,
+            MinLedBrightness = (float)config.AmbilightMinLedBrightness,
+            ColorTemperatureK = (float)(mapping.ColorTemperatureK ?? config.AmbilightColorTemperatureK),
+            DecaySeconds = (float)config.AmbilightDecaySeconds
+        };
+
+        lock (_stateLock)
+        {
+            foreach (var (name, value) in _overrides)
+            {
+                switch (name.ToLowerInvariant())
+                {
+                    case "brightness": t.BrightnessTarget = (float)value; break;
+                    case "saturation": t.Saturation = (float)value; break;
+                    case "smooth": t.SmoothSeconds = (float)value; break;
+                    case "gamma": t.Gamma = (float)value; break;
+                    case "gamma_red": t.GammaRed = (float)value; break;
+                    case "gamma_green": t.GammaGreen = (float)value; break;
+                    case "gamma_blue": t.GammaBlue = (float)value; break;
+                    case "red_boost": t.RedBoost = (float)value; break;
+                    case "green_boost": t.GreenBoost = (float)value; break;
+                    case "blue_boost": t.BlueBoost = (float)value; break;
+                    case "min_brightness": t.MinLedBrightness = (float)value; break;
+                    case "color_temp": t.ColorTemperatureK = (float)value; break;
+                    case "decay": t.DecaySeconds = (float)value; break;
+                }
+            }
+        }
+
+        return t;
+    }
+
+    /// <summary>
+    /// Sending side of <see cref="PlayAsync"/>: frames processed ahead of time are handed over through a one-frame
+    /// channel, and a separate task puts each on the wire at its due time, so the time a frame takes to process no
+    /// longer delays it.
+    /// </summary>
+    private sealed class FrameSender : IAsyncDisposable
+    {
+        private readonly AmbilightInProcessPlayer _player;
+        private readonly AmbilightUdpSink _sink;
+        private readonly int _ledCount;
+        private readonly int _bytesPerLed;
+        private readonly AmbilightRunStats _run;
+        private readonly Channel<OutgoingFrame> _channel = Channel.CreateBounded<OutgoingFrame>(
+            new BoundedChannelOptions(1) { SingleReader = true, SingleWriter = true });
+        private readonly CancellationTokenSource _cts;
+        private readonly Task _task;
+        private long _lastSendTimestamp;
+
+        public FrameSender(AmbilightInProcessPlayer player, AmbilightUdpSink sink, int ledCount, int bytesPerLed, AmbilightRunStats run, CancellationToken cancellationToken)
+        {
+            _player = player;
+            _sink = sink;
+            _ledCount = ledCount;
+            _bytesPerLed = bytesPerLed;
+            _run = run;
+            _cts = CancellationTokenSource.CreateLinkedTokenSource(cancellationToken);
+            _task = Task.Run(() => RunAsync(_cts.Token));
+        }
+
+        /// <summary>
+        /// Queues <paramref name="frame"/> to go out at <paramref name="dueTimestamp"/> (a <see cref="Stopwatch"/>
+        /// timestamp). Waits while the previous frame is still queued.
+        /// </summary>
+        public ValueTask SendAsync(byte[] frame, long dueTimestamp, double processingSeconds, int frameIndex, int frameCount, CancellationToken cancellationToken)
+        {
+            return _channel.Writer.WriteAsync(new OutgoingFrame(frame, dueTimestamp, processingSeconds, frameIndex, frameCount), cancellationToken);
+        }
+
+        /// <summary>
+        /// Sends what is still queued and waits for the sender to finish.
+        /// </summary>
+        public async Task CompleteAsync()
+        {
+            _channel.Writer.TryComplete();
+            try
+            {
+                await _task.ConfigureAwait(false);
+            }
+            catch (OperationCanceledException)
+            {
+                // Stopped while waiting for a frame's due time.
+            }
+        }
+
+        public async ValueTask DisposeAsync()
+        {
+            // Leaving PlayAsync early (an exception): drop anything still queued.
+            _cts.Cancel();
+            await CompleteAsync().ConfigureAwait(false);
+            _cts.Dispose();
+        }
+
+        private async Task RunAsync(CancellationToken cancellationToken)
+        {
+            await foreach (var item in _channel.Reader.ReadAllAsync(cancellationToken).ConfigureAwait(false))
+            {
+                var wait = Stopwatch.GetElapsedTime(Stopwatch.GetTimestamp(), item.DueTimestamp);
+
+                // Pacing: never put two packets on the wire closer than the configured spacing.
+                double minSpacingMs = _player.Config.MinPacketIntervalMs;
+                if (minSpacingMs > 0 && _lastSendTimestamp != 0)
+                {
+                    var spacingWait = TimeSpan.FromMilliseconds(minSpacingMs) - Stopwatch.GetElapsedTime(_lastSendTimestamp);
+                    if (spacingWait > wait)
+                    {
+                        wait = spacingWait;
+                    }
+                }
+
+                if (wait > TimeSpan.Zero)
+                {
+                    await Task.Delay(wait, cancellationToken).ConfigureAwait(false);
+                }
+
+                _lastSendTimestamp = Stopwatch.GetTimestamp();
+
+                try
+                {
+                    _player._lastSentFrame = item.Frame;
+                    await _sink.SendFrameAsync(item.Frame, _ledCount, _bytesPerLed).ConfigureAwait(false);
+
+                    // Scheduling error in wall-clock seconds: how late (or early) this frame went out relative to its due time.
+                    _run.RecordSend(Stopwatch.GetElapsedTime(item.DueTimestamp).TotalSeconds, item.ProcessingSeconds);
+                    lock (_player._stateLock)
+                    {
+                        _player._status.FramesSent++;
+                        _player._status.BytesSent += item.Frame.Length;
+                        _player._status.LastSendUtc = DateTime.UtcNow;
+                    }
+
+                    if (_player._config.Debug && item.FrameIndex > 0 && item.FrameIndex % 100 == 0)
                     {
-                        _logger.LogInformation("[Ambilight] Resume detected – resuming broadcast");
+                        _player._logger.LogInformation("[Ambilight] Broadcast: frame {FrameIndex}/{TotalFrames}", item.FrameIndex, item.FrameCount);
                     }
-                    else
+                }
+                catch (Exception ex)
+                {
+                    _player._logger.LogDebug(ex, "[Ambilight] Failed to send frame {Index}", item.FrameIndex);
+                    _run.SendErrors++;
+                    lock (_player._stateLock)
                     {
-                        _logger.LogDebug("[Ambilight] In-process player resumed");
+                        _player._status.SendErrors++;
                     }
                 }
-                lastPaused = pausedNow;
+            }
+        }
+    }
+
+    private readonly record struct OutgoingFrame(byte[] Frame, long DueTimestamp, double ProcessingSeconds, int FrameIndex, int FrameCount);
+
+    private sealed record PendingLoad(string BinPath, double StartSeconds, CancellationTokenSource? LoadingEffectCts, DateTime ReferenceUtc);
+
+    /// <summary>
+    /// Why <see cref="PlayAsync"/> returned.
+    /// </summary>
+    private enum PlaybackEnd
+    {
+        Finished,
+        Stopped,
+        Superseded,
+        Reloaded,
+        Unloaded
+    }
+
+    /// <summary>
+    /// Visual tuning values in effect for a single frame.
+    /// </summary>
+    private struct Tuning
+    {
+        public float BrightnessTarget;
+        public float Saturation;
+        public float SmoothSeconds;
+        public float Gamma;
+        public float GammaRed;
+        public float GammaGreen;
+        public float GammaBlue;
+        public float RedBoost;
+        public float GreenBoost;
+        public float BlueBoost;
+        public float MinLedBrightness;
+        public float ColorTemperatureK;
+        public float DecaySeconds;
+    }
+
+    /// <summary>
+    /// Per-channel white balance gains for a color temperature, relative to 6500 K so that 6500 is neutral.
+    /// Uses Tanner Helland's blackbody approximation; gains are normalized so the strongest channel is 1
+    /// (the correction only ever dims channels, never clips).
+    /// </summary>
+    private static (float R, float G, float B) WhiteBalanceGains(float kelvin)
+    {
+        if (!float.IsFinite(kelvin) || Math.Abs(kelvin - 6500.0f) < 1.0f)
+        {
+            return (1.0f, 1.0f, 1.0f);
+        }
+
+        var target = BlackbodyRgb(ClampF(kelvin, 1000.0f, 40000.0f));
+        var neutral = BlackbodyRgb(6500.0f);
+        float r = target.R / neutral.R;
+        float g = target.G / neutral.G;
+        float b = target.B / neutral.B;
+        float max = Math.Max(r, Math.Max(g, b));
+        return (r / max, g / max, b / max);
+    }
+
+    private static (float R, float G, float B) BlackbodyRgb(float kelvin)
+    {
+        double t = kelvin / 100.0;
+        double r = t <= 66.0 ? 255.0 : 329.698727446 * Math.Pow(t - 60.0, -0.1332047592);
+        double g = t <= 66.0
+            ? 99.4708025861 * Math.Log(t) - 161.1195681661
+            : 288.1221695283 * Math.Pow(t - 60.0, -0.0755148492);
+        double b = t >= 66.0 ? 255.0 : t <= 19.0 ? 0.0 : 138.5177312231 * Math.Log(t - 10.0) - 305.0447927307;
+
+        // Floor at 1 so very warm targets still leave a trace of blue instead of dividing by zero.
+        return ((float)Math.Clamp(r, 1.0, 255.0), (float)Math.Clamp(g, 1.0, 255.0), (float)Math.Clamp(b, 1.0, 255.0));
+    }
+
+    /// <summary>
+    /// For each target LED, the two source LEDs it is taken from and the blend factor between them. Sides are
+    /// resampled separately (top, right, bottom, left, the extractor's order) with the first and last LED of each
+    /// side aligned to the source's, so corners stay corners, and sides the target lacks are simply skipped.
+    /// Without <paramref name="linear"/> each LED takes the nearest zone of its side. Only when the file has no
+    /// LEDs on a side the target has does it fall back to stretching the whole strip.
+    /// Corner gap LEDs (after top, right, bottom and left) are marked with source -1, unless the file has dedicated
+    /// corner zones, which are then resampled onto them like a side.
+    /// </summary>
+    private static (int[] A, int[] B, float[] F) BuildResampleMap(AmbilightBinary binary, int tgtTop, int tgtRight, int tgtBottom, int tgtLeft, int[] cornerGaps, bool linear)
+    {
+        int totalSrc = binary.SideLedCount;
+        int pictureLeds = tgtTop + tgtRight + tgtBottom + tgtLeft;
+        int totalTgt = pictureLeds + cornerGaps.Sum();
+        var a = new int[totalTgt];
+        var b = new int[totalTgt];
+        var f = new float[totalTgt];
+
+        var srcSides = new[] { binary.TopCount, binary.RightCount, binary.BottomCount, binary.LeftCount };
+        var tgtSides = new[] { tgtTop, tgtRight, tgtBottom, tgtLeft };
+        bool perSide = srcSides.Sum() == totalSrc;
+        for (int side = 0; perSide && side < 4; side++)
+        {
+            perSide = tgtSides[side] == 0 || srcSides[side] > 0;
+        }
+
+        int srcStart = 0;
+        int tgtStart = 0;
+        int picture = 0;
+        for (int side = 0; side < 4; side++)
+        {
+            int src = srcSides[side];
+            int tgt = tgtSides[side];
+            for (int i = 0; i < tgt; i++, picture++)
+            {
+                if (!perSide)
+                {
+                    a[tgtStart + i] = b[tgtStart + i] = pictureLeds > 0 ? (picture * totalSrc) / pictureLeds : 0;
+                    continue;
+                }
+
+                double pos = tgt > 1 ? (double)i * (src - 1) / (tgt - 1) : (src - 1) / 2.0;
+                if (!linear)
+                {
+                    pos = Math.Round(pos);
+                }
+
+                int i0 = Math.Min((int)pos, src - 1);
+                int i1 = Math.Min(i0 + 1, src - 1);
+                a[tgtStart + i] = srcStart + i0;
+                b[tgtStart + i] = srcStart + i1;
+                f[tgtStart + i] = (float)(pos - i0);
+            }
+
+            srcStart += src;
+            tgtStart += tgt;
+            int corner = binary.CornerZones;
+            for (int g = 0; g < cornerGaps[side]; g++, tgtStart++)
+            {
+                if (corner == 0)
+                {
+                    a[tgtStart] = b[tgtStart] = -1;
+                    continue;
+                }
+
+                double pos = cornerGaps[side] > 1 ? (double)g * (corner - 1) / (cornerGaps[side] - 1) : (corner - 1) / 2.0;
+                if (!linear)
+                {
+                    pos = Math.Round(pos);
+                }
+
+                int i0 = Math.Min((int)pos, corner - 1);
+                int cornerStart = totalSrc + (side * corner);
+                a[tgtStart] = cornerStart + i0;
+                b[tgtStart] = cornerStart + Math.Min(i0 + 1, corner - 1);
+                f[tgtStart] = (float)(pos - i0);
+            }
+        }
+
+        return (a, b, f);
+    }
+
+    // Time the output takes to ease into and out of the credits look.
+    private const float CreditsRampSeconds = 3.0f;
+
+    // Audio energy (0-1 of the film's loud moments) above which the audio pulse starts to brighten the LEDs.
+    private const float AudioPulseFloor = 0.7f;
+
+    // How often the playback loop checks whether a remote block it is waiting for has arrived.
+    private static readonly TimeSpan RemoteFetchPollInterval = TimeSpan.FromMilliseconds(20);
+
+    // Frames looked back over for a skipped scene cut; a seek jumps much further and should not read the frames between.
+    private const int SceneCutLookback = 32;
+
+    /// <summary>Whether a frame after <paramref name="after"/> up to and including <paramref name="index"/> is a scene cut.</summary>
+    private static bool SceneCutSince(AmbilightBinary binary, int after, int index)
+    {
+        int first = after >= 0 && after < index ? Math.Max(after + 1, index - SceneCutLookback) : index;
+        for (int i = first; i <= index && i < binary.FrameCount; i++)
+        {
+            if (binary.IsSceneCut(i))
+            {
+                return true;
+            }
+        }
+
+        return false;
+    }
+
+    /// <summary>
+    /// LEDs on the strip a mapping drives: the four sides plus the corner gaps between them.
+    /// </summary>
+    internal static int StripLedCount(DeviceMapping mapping)
+        => mapping.TopLedCount + mapping.BottomLedCount + mapping.LeftLedCount + mapping.RightLedCount + ParseCornerGaps(mapping.CornerGapLeds).Sum();
+
+    /// <summary>
+    /// Parses a mapping's corner gaps: one number for every corner, or four for the corners after the top, right,
+    /// bottom and left sides (top-right, bottom-right, bottom-left, top-left). Invalid entries count as 0.
+    /// </summary>
+    internal static int[] ParseCornerGaps(string? text)
+    {
+        var gaps = new int[4];
+        var parts = (text ?? string.Empty).Split(',', StringSplitOptions.TrimEntries | StringSplitOptions.RemoveEmptyEntries);
+        for (int i = 0; i < 4; i++)
+        {
+            var part = parts.Length == 1 ? parts[0] : (i < parts.Length ? parts[i] : null);
+            if (part != null && int.TryParse(part, NumberStyles.None, CultureInfo.InvariantCulture, out var n))
+            {
+                gaps[i] = Math.Min(n, 100);
+            }
+        }
+
+        return gaps;
+    }
+
+    private static float Resample(float[] raw, int a, int b, float f, int channels, int channel)
+    {
+        float va = raw[(a * channels) + channel];
+        return f > 0.0f ? va + ((raw[(b * channels) + channel] - va) * f) : va;
+    }
+
+    /// <summary>
+    /// Moves the white part of an RGB color (0–255) to a white channel and returns it, for sending RGB files to
+    /// RGBW strips. "min" takes the common minimum of the channels; "luma" takes the gray component's share of
+    /// the color's luminance, so saturated colors keep more on the RGB LEDs; "calibrated" uses the color of the
+    /// strip's white LED (<paramref name="whitePoint"/>, 0–1 per channel), so a warm or cool white does not shift hue.
+    /// </summary>
+    private static float ExtractWhite(string mode, (float R, float G, float B) whitePoint, ref float r, ref float g, ref float b)
+    {
+        float min = Math.Max(0.0f, Math.Min(r, Math.Min(g, b)));
+        if (min <= 0.0f)
+        {
+            return 0.0f;
+        }
+
+        float w;
+        switch (mode)
+        {
+            case "luma":
+                float luma = 0.2126f * r + 0.7152f * g + 0.0722f * b;
+                w = luma > 0.0f ? Math.Min(min, min * min / luma) : 0.0f;
+                break;
+            case "calibrated":
+                w = Math.Min(r / whitePoint.R, Math.Min(g / whitePoint.G, b / whitePoint.B));
+                w = Math.Clamp(w, 0.0f, 255.0f);
+                r -= w * whitePoint.R;
+                g -= w * whitePoint.G;
+                b -= w * whitePoint.B;
+                return w;
+            default:
+                w = min;
+                break;
+        }
 
-                if (pausedNow)
-                {
-                    // While paused we simply sleep; WLED keeps displaying the last frame.
-                    try
-                    {
-                        await Task.Delay(80, cancellationToken).ConfigureAwait(false);
-                    }
-                    catch (OperationCanceledException)
-                    {
-                        break;
-                    }
-                    continue;
-                }
+        r -= w;
+        g -= w;
+        b -= w;
+        return w;
+    }
 
-                ulong frameTs = frameIndex < timestampsUs.Count ? timestampsUs[frameIndex] : 0;
-                ulong baseTs = startFrame < timestampsUs.Count ? timestampsUs[startFrame] : 0;
-                var frameTargetUs = frameTs > baseTs ? frameTs - baseTs : 0UL;
-                var elapsed = elapsedBase + (DateTime.UtcNow - startInstant);
-                ulong elapsedUs = (ulong)(elapsed.TotalSeconds * 1_000_000.0);
-                if (elapsedUs < frameTargetUs)
-                {
-                    var sleepUs = frameTargetUs - elapsedUs;
-                    var sleepMs = (int)Math.Max(0, sleepUs / 1000UL);
-                    if (sleepMs > 0)
-                    {
-                        await Task.Delay(sleepMs, cancellationToken).ConfigureAwait(false);
-                    }
-                }
+    /// <summary>
+    /// Parses the "r,g,b" (0–255) color of a strip's white LED into 0–1 factors; pure white when empty or invalid.
+    /// </summary>
+    private static (float R, float G, float B) ParseWhitePoint(string? text)
+    {
+        var parts = (text ?? string.Empty).Split(',', StringSplitOptions.TrimEntries);
+        if (parts.Length == 3
+            && float.TryParse(parts[0], NumberStyles.Float, CultureInfo.InvariantCulture, out var r)
+            && float.TryParse(parts[1], NumberStyles.Float, CultureInfo.InvariantCulture, out var g)
+            && float.TryParse(parts[2], NumberStyles.Float, CultureInfo.InvariantCulture, out var b)
+            && r > 0.0f && g > 0.0f && b > 0.0f)
+        {
+            float max = Math.Max(r, Math.Max(g, b));
+            return (r / max, g / max, b / max);
+        }
 
-                var raw = frames[frameIndex];
+        return (1.0f, 1.0f, 1.0f);
+    }
 
-                // avg luminance
-                float sumLum = 0f;
-                int countPix = 0;
-                int idx = 0;
-                while (idx + 2 < raw.Length)
-                {
-                    float r = raw[idx];
-                    float g = raw[idx + 1];
-                    float b = raw[idx + 2];
-                    float lum = 0.2126f * r + 0.7152f * g + 0.0722f * b;
-                    sumLum += lum;
-                    countPix++;
-                    idx += bytesPerLed;
-                }
-                float avgLum = countPix > 0 ? sumLum / countPix : 0f;
-                float gammaAdj = ClampF(gammaBase * (1.0f - (avgLum / 255.0f) * 0.6f), 1.0f, 3.0f);
-                float invGamma = 1.0f / gammaAdj;
+    private static float ClampF(float v, float lo, float hi)
+    {
+        if (float.IsNaN(v)) return lo;
+        if (v < lo) return lo;
+        if (v > hi) return hi;
+        return v;
+    }
 
-                float frameDtS;
-                if (frameIndex == 0)
-                {
-                    frameDtS = (float)(1.0 / fpsD);
-                }
-                else
-                {
-                    double prevUs = frameIndex > 0 && frameIndex - 1 < timestampsUs.Count ? timestampsUs[frameIndex - 1] : 0;
-                    double curUs = frameIndex < timestampsUs.Count ? timestampsUs[frameIndex] : 0;
-                    double dt = (curUs - prevUs) / 1e6;
-                    frameDtS = dt > 0.0 ? (float)dt : (float)(1.0 / fpsD);
-                }
-                float k = noSmoothing
-                    ? 1.0f  // no EMA: use the current frame only
-                    : 1.0f - (float)Math.Exp(-frameDtS / smoothTau);
 
-                if (emaAcc == null)
-                {
-                    emaAcc = new float[totalTgt * bytesPerLed];
-                    for (int t = 0; t < totalTgt; t++)
-                    {
-                        int srcIdx = totalTgt > 0 ? (t * totalSrc) / totalTgt : 0;
-                        int sb = srcIdx * bytesPerLed;
-                        for (int b = 0; b < bytesPerLed; b++)
-                        {
-                            emaAcc[t * bytesPerLed + b] = raw[sb + b];
-                        }
-                    }
-                }
+    /// <summary>
+    /// Rounds a 0–255 channel value to a byte. With an error buffer (16-bit sources) the rounding error is
+    /// carried into the next frame for the same channel (temporal error diffusion), so slow gradients
+    /// average out to the true value instead of stepping between 8-bit levels.
+    /// </summary>
+    private static byte ToByte(float value, float[]? error, int index)
+    {
+        if (error == null)
+        {
+            return (byte)Math.Clamp((int)Math.Round(value), 0, 255);
+        }
 
-                var acc = emaAcc!;
-                var outFrame = new byte[totalTgt * bytesPerLed];
+        float target = value + error[index];
+        int quantized = Math.Clamp((int)MathF.Round(target), 0, 255);
+        error[index] = Math.Clamp(target - quantized, -0.5f, 0.5f);
+        return (byte)quantized;
+    }
 
-                float sUser = ClampF(saturation, 0.0f, 5.0f);
-                float gUser = Math.Max(0.01f, (float)_config.AmbilightGamma);
-                float bTarget = Math.Max(1.0f, brightnessTarget);
-                float minB = Math.Max(0.0f, minLedBrightness);
+    /// <summary>
+    /// Brightness factor for output that has been static for <paramref name="staticFor"/>: 1 until the configured
+    /// number of minutes, then ramping down to the configured floor.
+    /// </summary>
+    private float StaticFadeFactor(TimeSpan staticFor)
+    {
+        var config = Config;
+        if (config.StaticSceneFadeMinutes <= 0.0)
+        {
+            return 1.0f;
+        }
 
-                float brightnessFactor = 1.0f;
-                if (avgLum > 1.0f)
-                {
-                    float factor = (bTarget / avgLum) * 0.7f + 0.3f;
-                    brightnessFactor = ClampF(factor, 0.05f, 2.5f);
-                }
+        double over = staticFor.TotalSeconds - (config.StaticSceneFadeMinutes * 60.0);
+        if (over <= 0.0)
+        {
+            return 1.0f;
+        }
 
-                for (int t = 0; t < totalTgt; t++)
-                {
-                    int srcIdx = totalTgt > 0 ? (t * totalSrc) / totalTgt : 0;
-                    int sb = srcIdx * bytesPerLed;
+        float floor = ClampF((float)(config.StaticSceneFadeBrightness / 100.0), 0.0f, 1.0f);
+        float t = (float)Math.Min(1.0, over / StaticFadeRampSeconds);
+        return 1.0f - ((1.0f - floor) * t);
+    }
 
-                    float rU = raw[sb];
-                    float gU = raw[sb + 1];
-                    float bU = raw[sb + 2];
+    /// <summary>
+    /// Brightness cap at local time <paramref name="localNow"/> from the night schedule: the configured night level
+    /// inside the window, easing in and out over <see cref="NightRampMinutes"/>, and 1 outside it or when unset.
+    /// </summary>
+    private float NightBrightnessFactor(DateTime localNow)
+    {
+        var config = Config;
+        float level = ClampF((float)(config.NightBrightness / 100.0), 0.0f, 1.0f);
+        if (level >= 1.0f
+            || !TimeSpan.TryParse(config.NightStart, CultureInfo.InvariantCulture, out var start)
+            || !TimeSpan.TryParse(config.NightEnd, CultureInfo.InvariantCulture, out var end))
+        {
+            return 1.0f;
+        }
 
-                    float rN = ClampF(rU / 255.0f, 0.0f, 1.0f);
-                    float gN = ClampF(gU / 255.0f, 0.0f, 1.0f);
-                    float bN = ClampF(bU / 255.0f, 0.0f, 1.0f);
+        const double day = 24.0 * 60.0;
+        double length = ((end.TotalMinutes - start.TotalMinutes) % day + day) % day;
+        double into = ((localNow.TimeOfDay.TotalMinutes - start.TotalMinutes) % day + day) % day;
+        if (length <= 0.0 || into >= length)
+        {
+            return 1.0f;
+        }
 
-                    float rLin = (float)MathF.Pow(rN, gammaRed);
-                    float gLin = (float)MathF.Pow(gN, gammaGreen);
-                    float bLin = (float)MathF.Pow(bN, gammaBlue);
+        float t = (float)Math.Min(1.0, Math.Min(into, length - into) / NightRampMinutes);
+        return 1.0f - ((1.0f - level) * t);
+    }
 
-                    float avgIntensity = (rLin + gLin + bLin) / 3.0f;
-                    float rSat = avgIntensity + (rLin - avgIntensity) * sUser;
-                    float gSat = avgIntensity + (gLin - avgIntensity) * sUser;
-                    float bSat = avgIntensity + (bLin - avgIntensity) * sUser;
-
-                    float rG = ClampF((float)MathF.Pow(rSat, invGamma), 0.0f, 1.0f);
-                    float gG = ClampF((float)MathF.Pow(gSat, invGamma), 0.0f, 1.0f);
-                    float bG = ClampF((float)MathF.Pow(bSat, invGamma), 0.0f, 1.0f);
-
-                    float brightnessFactorAdj = ClampF(brightnessFactor, 0.3f, 1.8f);
-                    float rF = rG * brightnessFactorAdj * 255.0f;
-                    float gF = gG * brightnessFactorAdj * 255.0f;
-                    float bF = bG * brightnessFactorAdj * 255.0f;
-
-                    int @base = t * bytesPerLed;
-                    acc[@base] = acc[@base] * (1.0f - k) + rF * k;
-                    acc[@base + 1] = acc[@base + 1] * (1.0f - k) + gF * k;
-                    acc[@base + 2] = acc[@base + 2] * (1.0f - k) + bF * k;
-
-                    // Match Rust: round smoothed accumulator before min clamp and output (avoids truncation bias / blue tint)
-                    float rOut = MathF.Round(acc[@base]);
-                    float gOut = MathF.Round(acc[@base + 1]);
-                    float bOut = MathF.Round(acc[@base + 2]);
-
-                    float minR = minB * redBoost;
-                    float minG = minB * greenBoost;
-                    float minBB = minB * blueBoost;
-
-                    if (rOut > 0.0f && rOut < minR) rOut = minR;
-                    if (gOut > 0.0f && gOut < minG) gOut = minG;
-                    if (bOut > 0.0f && bOut < minBB) bOut = minBB;
-
-                    float lumLed = 0.2126f * rOut + 0.7152f * gOut + 0.0722f * bOut;
-                    if (lumLed < minB * 0.5f)
-                    {
-                        rOut = 0.0f;
-                        gOut = 0.0f;
-                        bOut = 0.0f;
-                    }
+    /// <summary>
+    /// Fade-in/out envelope: brightness factor <paramref name="elapsed"/> seconds into a fade up from black lasting
+    /// <paramref name="duration"/>, shaped by <see cref="PluginConfiguration.FadeCurve"/>. Fade-outs pass the remaining time.
+    /// </summary>
+    private float FadeEnvelope(double elapsed, double duration)
+    {
+        if (duration <= 0.0)
+        {
+            return 1.0f;
+        }
 
-                    // Round before cast to byte to match Rust (truncation was darkening and boosting blue floor)
-                    // Send RGB order - WLED handles color order remapping based on its own configuration
-                    outFrame[@base] = (byte)Math.Clamp((int)Math.Round(rOut), 0, 255);
-                    outFrame[@base + 1] = (byte)Math.Clamp((int)Math.Round(gOut), 0, 255);
-                    outFrame[@base + 2] = (byte)Math.Clamp((int)Math.Round(bOut), 0, 255);
+        float t = ClampF((float)(elapsed / duration), 0.0f, 1.0f);
+        return (Config.FadeCurve ?? "smooth").Trim().ToLowerInvariant() switch
+        {
+            "linear" => t,
+            "perceptual" => t * t,
+            _ => t * t * (3.0f - (2.0f * t))
+        };
+    }
 
-                    if (bytesPerLed == 4)
-                    {
-                        int srcWIdx = srcIdx * bytesPerLed + 3;
-                        float wVal = raw[srcWIdx];
-                        acc[@base + 3] = acc[@base + 3] * (1.0f - k) + wVal * k;
-                        outFrame[@base + 3] = (byte)Math.Clamp((int)Math.Round(acc[@base + 3]), 0, 255);
-                    }
-                }
+    /// <summary>
+    /// Brightness factor of a paused frame <paramref name="pausedFor"/> into the pause, for the dim, fade and
+    /// breathe pause modes. Dimming eases in over a second so a quick pause/resume does not flash.
+    /// </summary>
+    private float PauseFadeFactor(string pauseMode, TimeSpan pausedFor)
+    {
+        var config = Config;
+        float level = ClampF((float)(config.PauseDimPercent / 100.0), 0.0f, 1.0f);
+        double seconds = Math.Max(0.0, pausedFor.TotalSeconds);
+        switch (pauseMode)
+        {
+            case "dim":
+                return 1.0f - ((1.0f - level) * (float)Math.Min(1.0, seconds / PauseDimRampSeconds));
+            case "fade":
+                return config.PauseFadeSeconds > 0.0 ? (float)Math.Max(0.0, 1.0 - (seconds / config.PauseFadeSeconds)) : 0.0f;
+            case "breathe":
+                // Starts at the full level and pulses between a quarter of it and all of it.
+                float pulse = 0.625f + (0.375f * MathF.Cos((float)(2.0 * Math.PI * seconds / PauseBreathePeriodSeconds)));
+                return level * pulse;
+            default:
+                return 1.0f;
+        }
+    }
 
-                byte[] frameToSend = outFrame;
-                if (rotLeds > 0)
-                {
-                    frameToSend = RotateLedFrame(outFrame, rotLeds, totalTgt, bytesPerLed);
-                }
+    /// <summary>
+    /// A frame with every LED set to the average color of <paramref name="frame"/>, for the breathe pause mode.
+    /// </summary>
+    private static byte[] AverageColorFrame(byte[] frame, int ledCount, int bytesPerLed)
+    {
+        var sums = new long[bytesPerLed];
+        int leds = Math.Min(ledCount, frame.Length / bytesPerLed);
+        for (int i = 0; i < leds; i++)
+        {
+            for (int c = 0; c < bytesPerLed; c++)
+            {
+                sums[c] += frame[(i * bytesPerLed) + c];
+            }
+        }
 
-                try
-                {
-                    await udp.SendAsync(frameToSend, frameToSend.Length).ConfigureAwait(false);
-                    if (_config.Debug && frameIndex > 0 && frameIndex % 100 == 0)
-                    {
-                        _logger.LogInformation("[Ambilight] Broadcast: frame {FrameIndex}/{TotalFrames}", frameIndex, frames.Count);
-                    }
-                }
-                catch (Exception ex)
-                {
-                    _logger.LogDebug(ex, "[Ambilight] Failed to send frame {Index}", frameIndex);
-                }
+        var result = new byte[frame.Length];
+        for (int i = 0; i < leds; i++)
+        {
+            for (int c = 0; c < bytesPerLed; c++)
+            {
+                result[(i * bytesPerLed) + c] = (byte)(sums[c] / Math.Max(1, leds));
+            }
+        }
+
+        return result;
+    }
+
+    private bool KeepAliveDue(byte[]? lastSent, DateTime lastSentUtc)
+    {
+        double interval = Config.KeepAliveSeconds;
+        return lastSent != null && interval > 0.0 && (DateTime.UtcNow - lastSentUtc).TotalSeconds >= interval;
+    }
+
+    /// <summary>
+    /// Re-sends already prepared bytes so WLED stays in realtime mode; not counted as a sent frame.
+    /// </summary>
+    private async Task SendKeepAliveAsync(AmbilightUdpSink sink, byte[] frame, int ledCount, int bytesPerLed)
+    {
+        try
+        {
+            await sink.SendFrameAsync(frame, ledCount, bytesPerLed).ConfigureAwait(false);
+            lock (_stateLock)
+            {
+                _status.KeepAlivesSent++;
+                _status.LastSendUtc = DateTime.UtcNow;
+            }
 
-                frameIndex++;
+            if (_currentRun != null)
+            {
+                _currentRun.KeepAlivesSent++;
             }
+        }
+        catch (Exception ex) when (ex is not OperationCanceledException)
+        {
+            _logger.LogDebug(ex, "[Ambilight] Failed to send keep-alive frame");
+        }
+    }
+
+    private static bool IsNearlySameFrame(byte[] a, byte[] b)
+    {
+        if (a.Length != b.Length)
+        {
+            return false;
+        }
 
-            // Blank on exit
-            if (totalTgt > 0 && bytesPerLed > 0)
+        for (int i = 0; i < a.Length; i++)
+        {
+            if (Math.Abs(a[i] - b[i]) > StaticSceneTolerance)
             {
-                var zeroes = new byte[totalTgt * bytesPerLed];
-                for (int i = 0; i < 3; i++)
-                {
-                    try
-                    {
-                        await udp.SendAsync(zeroes, zeroes.Length).ConfigureAwait(false);
-                    }
-                    catch
-                    {
-                        break;
-                    }
-                    await Task.Delay(20, cancellationToken).ConfigureAwait(false);
-                }
+                return false;
             }
         }
-        catch (Exception ex)
+
+        return true;
+    }
+
+    /// <summary>
+    /// Number of <paramref name="chapters"/> (sorted by start) that have started by <paramref name="positionSeconds"/>.
+    /// </summary>
+    private static int ChaptersStartedBy(List<AmbilightChapter> chapters, double positionSeconds)
+    {
+        int lo = 0;
+        int hi = chapters.Count;
+        while (lo < hi)
         {
-            if (!cancellationToken.IsCancellationRequested)
+            int mid = (lo + hi) / 2;
+            if (chapters[mid].StartSeconds <= positionSeconds)
             {
-                _logger.LogError(ex, "[Ambilight] In-process player error for session {SessionId}", sessionId);
+                lo = mid + 1;
+            }
+            else
+            {
+                hi = mid;
             }
         }
+
+        return lo;
     }
 
-    private static float ClampF(float v, float lo, float hi)
+    /// <summary>
+    /// Turns a clockwise frame into what goes on the wire: rotated into strip order, scaled by <paramref name="scale"/>
+    /// and calibrated, written to <paramref name="destination"/> (or <paramref name="frame"/> itself when nothing applies).
+    /// </summary>
+    private static byte[] PrepareOutputFrame(byte[] frame, int rotationLeds, int totalLeds, int bytesPerLed, bool reverse, float scale, AmbilightLedCalibration? calibration, byte[] destination)
     {
-        if (float.IsNaN(v)) return lo;
-        if (v < lo) return lo;
-        if (v > hi) return hi;
-        return v;
+        byte[] output = rotationLeds > 0 || reverse ? RotateLedFrame(frame, rotationLeds, totalLeds, bytesPerLed, reverse, destination) : frame;
+        if (scale < 1.0f)
+        {
+            output = ScaleFrame(output, scale, destination);
+        }
+
+        // Per-LED gains are for physical LEDs, so they go on after the rotation to the strip's input.
+        if (calibration != null)
+        {
+            output = calibration.Apply(output, totalLeds, bytesPerLed, destination);
+        }
+
+        return output;
     }
 
+    /// <summary>
+    /// <paramref name="frame"/> with every channel scaled by <paramref name="factor"/>, written to
+    /// <paramref name="destination"/> (which may be <paramref name="frame"/> itself) or a new array.
+    /// </summary>
+    private static byte[] ScaleFrame(byte[] frame, float factor, byte[]? destination = null)
+    {
+        var scaled = destination ?? new byte[frame.Length];
+        for (int i = 0; i < frame.Length; i++)
+        {
+            scaled[i] = (byte)MathF.Round(frame[i] * factor);
+        }
+
+        return scaled;
+    }
 
-    private static byte[] RotateLedFrame(byte[] frame, int rotationLeds, int totalLeds, int bytesPerLed)
+    /// <summary>
+    /// Reorders a clockwise frame into strip order: strip LED 0 sits at <paramref name="rotationLeds"/>, and a
+    /// <paramref name="reverse"/> strip continues counter-clockwise from there.
+    /// </summary>
+    private static byte[] RotateLedFrame(byte[] frame, int rotationLeds, int totalLeds, int bytesPerLed, bool reverse = false, byte[]? destination = null)
     {
-        if (rotationLeds == 0 || totalLeds == 0)
+        if ((rotationLeds == 0 && !reverse) || totalLeds == 0)
         {
             return frame;
         }
 
-        var rotated = new byte[frame.Length];
+        // destination must not be frame: LEDs are moved, not updated in place.
+        var rotated = destination ?? new byte[frame.Length];
 
         for (int i = 0; i < totalLeds; i++)
         {
             int dstStart = i * bytesPerLed;
-            int srcLed = (i + rotationLeds) % totalLeds;
+            int srcLed = reverse ? (rotationLeds - i + totalLeds) % totalLeds : (i + rotationLeds) % totalLeds;
             int srcStart = srcLed * bytesPerLed;
 
             Buffer.BlockCopy(frame, srcStart, rotated, dstStart, bytesPerLed);
@@ -626,21 +2910,24 @@ public sealed class AmbilightInProcessPlayer : IDisposable
         return rotated;
     }
 
+    /// <summary>
+    /// First strip LED the mapping plays onto. Only the WLED realtime protocol can address an offset, so it is 0 otherwise.
+    /// </summary>
+    internal static int SegmentStartFor(DeviceMapping mapping)
+        => AmbilightUdpSink.SupportsLedOffset(mapping.Protocol) ? Math.Max(0, mapping.SegmentStart) : 0;
+
     /// <summary>
     /// Send a loading effect to WLED: rotating ochre segment around the LED strip.
     /// Returns a cancellation task that should be cancelled when loading completes or fails.
     /// </summary>
-    public static Task SendLoadingEffectAsync(string host, int port, int totalLeds, ILogger logger, CancellationToken cancellationToken)
+    public static Task SendLoadingEffectAsync(string host, int port, int totalLeds, ILogger logger, CancellationToken cancellationToken, string? protocol = null, int ledOffset = 0)
     {
         return Task.Run(async () =>
         {
             try
             {
                 // Parse host to IP
-                if (!IPAddress.TryParse(host, out var targetIp))
-                {
-                    targetIp = Dns.GetHostAddresses(host).FirstOrDefault();
-                }
+                var targetIp = AmbilightUdpSink.ResolveHost(host);
 
                 if (targetIp == null)
                 {
@@ -648,8 +2935,7 @@ public sealed class AmbilightInProcessPlayer : IDisposable
                     return;
                 }
 
-                using var udp = new UdpClient();
-                udp.Connect(targetIp, port);
+                using var sink = new AmbilightUdpSink(targetIp, port, protocol, ledOffset, dscp: Plugin.Instance?.Configuration?.UdpDscp ?? 0);
 
                 // Ochre/amber color (RGB: 204, 119, 34)
                 byte r = 204, g = 119, b = 34;
@@ -676,7 +2962,7 @@ public sealed class AmbilightInProcessPlayer : IDisposable
                         frame[offset + 2] = b;
                     }
 
-                    await udp.SendAsync(frame, frame.Length).ConfigureAwait(false);
+                    await sink.SendFrameAsync(frame, totalLeds, bytesPerLed).ConfigureAwait(false);
                     
                     rotation = (rotation + 1) % totalLeds;
                     await Task.Delay(30, cancellationToken).ConfigureAwait(false); // ~33fps rotation
@@ -697,15 +2983,12 @@ public sealed class AmbilightInProcessPlayer : IDisposable
     /// Send a failure flash effect to WLED: 3 red flashes, then return to original state.
     /// WLED automatically returns to previous state when UDP stream stops.
     /// </summary>
-    public static async Task SendFailureFlashAsync(string host, int port, int totalLeds, ILogger logger)
+    public static async Task SendFailureFlashAsync(string host, int port, int totalLeds, ILogger logger, string? protocol = null, int ledOffset = 0)
     {
         try
         {
             // Parse host to IP
-            if (!IPAddress.TryParse(host, out var targetIp))
-            {
-                targetIp = Dns.GetHostAddresses(host).FirstOrDefault();
-            }
+            var targetIp = AmbilightUdpSink.ResolveHost(host);
 
             if (targetIp == null)
             {
@@ -713,8 +2996,7 @@ public sealed class AmbilightInProcessPlayer : IDisposable
                 return;
             }
 
-            using var udp = new UdpClient();
-            udp.Connect(targetIp, port);
+            using var sink = new AmbilightUdpSink(targetIp, port, protocol, ledOffset, dscp: Plugin.Instance?.Configuration?.UdpDscp ?? 0);
 
             int bytesPerLed = 3; // RGB
             var redFrame = new byte[totalLeds * bytesPerLed];
@@ -733,9 +3015,9 @@ public sealed class AmbilightInProcessPlayer : IDisposable
             // Flash 3 times (red on, black off)
             for (int flash = 0; flash < 3; flash++)
             {
-                await udp.SendAsync(redFrame, redFrame.Length).ConfigureAwait(false);
+                await sink.SendFrameAsync(redFrame, totalLeds, bytesPerLed).ConfigureAwait(false);
                 await Task.Delay(150).ConfigureAwait(false);
-                await udp.SendAsync(blackFrame, blackFrame.Length).ConfigureAwait(false);
+                await sink.SendFrameAsync(blackFrame, totalLeds, bytesPerLed).ConfigureAwait(false);
                 await Task.Delay(150).ConfigureAwait(false);
             }
 
@@ -747,5 +3029,173 @@ public sealed class AmbilightInProcessPlayer : IDisposable
             logger.LogWarning(ex, "[Ambilight] Error during failure flash effect");
         }
     }
+
+    /// <summary>
+    /// Identify pattern for checking a mapping before watching anything: every side lit in its own color
+    /// (top red, right green, bottom blue, left white, corner gaps dark), then a single dot running clockwise
+    /// from the first top LED. Sent through the mapping's input position, direction and segment start, so a
+    /// wrong setting shows up as colors on the wrong side or a dot starting in the wrong place.
+    /// Returns false without sending when a player is playing on the target. The pattern claims the target while it
+    /// runs, so an idle or ambient player gives it up and a player starting meanwhile stops the pattern.
+    /// </summary>
+    public static bool TrySendIdentifyPattern(DeviceMapping mapping, ILogger logger, CancellationToken cancellationToken = default)
+    {
+        var targetKey = TargetKeyFor(mapping);
+        if (TargetOwners.TryGetValue(targetKey, out var owner) && owner is AmbilightInProcessPlayer player
+            && player.GetStatus().State is "loading" or "playing" or "paused" or "stalled")
+        {
+            return false;
+        }
+
+        var identifyCts = CancellationTokenSource.CreateLinkedTokenSource(cancellationToken);
+        if (TargetOwners.TryGetValue(targetKey, out var previous) && previous is CancellationTokenSource previousIdentify)
+        {
+            CancelIdentify(previousIdentify);
+        }
+
+        TargetOwners[targetKey] = identifyCts;
+        var token = identifyCts.Token;
+        _ = Task.Run(async () =>
+        {
+            try
+            {
+                var targetIp = AmbilightUdpSink.ResolveHost(mapping.Host);
+
+                if (targetIp == null)
+                {
+                    logger.LogWarning("[Ambilight] Cannot send identify pattern: no IP for host {Host}", mapping.Host);
+                    return;
+                }
+
+                using var sink = new AmbilightUdpSink(targetIp, mapping.Port, mapping.Protocol, SegmentStartFor(mapping), dscp: Plugin.Instance?.Configuration?.UdpDscp ?? 0);
+
+                var gaps = ParseCornerGaps(mapping.CornerGapLeds);
+                var sides = new[] { mapping.TopLedCount, mapping.RightLedCount, mapping.BottomLedCount, mapping.LeftLedCount }
+                    .Select(n => Math.Max(0, n)).ToArray();
+                int totalLeds = sides.Sum() + gaps.Sum();
+                if (totalLeds == 0)
+                {
+                    return;
+                }
+
+                bool rgbw = !string.Equals(mapping.RgbwConversion ?? "off", "off", StringComparison.OrdinalIgnoreCase);
+                int bytesPerLed = rgbw ? 4 : 3;
+                int rotation = Math.Abs(mapping.InputPosition) % totalLeds;
+                bool reverse = mapping.Reverse && totalLeds > 1;
+
+                // Clockwise layout as in playback: each side followed by the gap at its trailing corner.
+                var sideColors = new (byte R, byte G, byte B)[] { (255, 0, 0), (0, 255, 0), (0, 0, 255), (255, 255, 255) };
+                var sidesFrame = new byte[totalLeds * bytesPerLed];
+                var pictureLeds = new List<int>();
+                int led = 0;
+                for (int side = 0; side < 4; side++)
+                {
+                    for (int i = 0; i < sides[side]; i++, led++)
+                    {
+                        var (r, g, b) = sideColors[side];
+                        if (rgbw && side == 3)
+                        {
+                            sidesFrame[(led * 4) + 3] = 255;
+                        }
+                        else
+                        {
+                            sidesFrame[led * bytesPerLed] = r;
+                            sidesFrame[(led * bytesPerLed) + 1] = g;
+                            sidesFrame[(led * bytesPerLed) + 2] = b;
+                        }
+
+                        pictureLeds.Add(led);
+                    }
+
+                    led += gaps[side];
+                }
+
+                logger.LogInformation("[Ambilight] Showing identify pattern on {Host}:{Port} ({Leds} LEDs)", mapping.Host, mapping.Port, totalLeds);
+
+                // Hold the side colors for a few seconds; resend so WLED stays in realtime mode.
+                var strip = RotateLedFrame(sidesFrame, rotation, totalLeds, bytesPerLed, reverse);
+                for (int i = 0; i < 40; i++)
+                {
+                    await sink.SendFrameAsync(strip, totalLeds, bytesPerLed).ConfigureAwait(false);
+                    await Task.Delay(100, token).ConfigureAwait(false);
+                }
+
+                // One lap of a single dot, about 8 seconds whatever the strip length.
+                int stepMs = Math.Clamp(8000 / Math.Max(1, pictureLeds.Count), 10, 100);
+                foreach (var dotLed in pictureLeds)
+                {
+                    var dotFrame = new byte[totalLeds * bytesPerLed];
+                    dotFrame[dotLed * bytesPerLed] = 255;
+                    dotFrame[(dotLed * bytesPerLed) + 1] = 255;
+                    dotFrame[(dotLed * bytesPerLed) + 2] = 255;
+                    strip = RotateLedFrame(dotFrame, rotation, totalLeds, bytesPerLed, reverse);
+                    await sink.SendFrameAsync(strip, totalLeds, bytesPerLed).ConfigureAwait(false);
+                    await Task.Delay(stepMs, token).ConfigureAwait(false);
+                }
+
+                // As with the failure flash, WLED returns to its previous state when the stream stops.
+            }
+            catch (OperationCanceledException)
+            {
+                // Shutting down, or a player took over the target
+            }
+            catch (Exception ex)
+            {
+                logger.LogWarning(ex, "[Ambilight] Error during identify pattern");
+            }
+            finally
+            {
+                TargetOwners.TryRemove(new KeyValuePair<string, object>(targetKey, identifyCts));
+                identifyCts.Dispose();
+            }
+        }, CancellationToken.None);
+
+        return true;
+    }
+
+    private static void CancelIdentify(CancellationTokenSource identify)
+    {
+        try
+        {
+            identify.Cancel();
+        }
+        catch (ObjectDisposedException)
+        {
+            // The pattern has just finished.
+        }
+    }
 }
 
+/// <summary>
+/// Point-in-time state of an in-process player, as returned by <see cref="AmbilightInProcessPlayer.GetStatus"/>.
+/// </summary>
+public class AmbilightPlayerStatus
+{
+    public string SessionId { get; set; } = string.Empty;
+    public string Host { get; set; } = string.Empty;
+    public int Port { get; set; }
+    public string BinPath { get; set; } = string.Empty;
+    public string State { get; set; } = "idle"; // idle, loading, playing, paused, stalled (no heartbeats), finished, ambient, stopped, superseded
+    public double PositionSeconds { get; set; } // media position of the last frame sent (timestamp minus sync lead)
+    public double FrameTimestampSeconds { get; set; }
+    public int FrameIndex { get; set; }
+    public int FrameCount { get; set; }
+    public int Chapter { get; set; } // 1-based chapter of PositionSeconds; 0 before the first or without chapters
+    public int ChapterCount { get; set; }
+    public double SyncLeadSeconds { get; set; }
+    public double DriftSeconds { get; set; } // player position minus reported client position at the last heartbeat
+    public double ClockRate { get; set; } = 1.0; // current drift-correction slew (0.98–1.02)
+    public double PlaybackRate { get; set; } = 1.0; // client playback speed set with RATE
+    public int? DeviceLedCount { get; set; } // LED count reported by WLED's JSON API, if available
+    public double NetworkLatencySeconds { get; set; } // smoothed one-way latency from the JSON API probe (0 until measured)
+    public long FramesSent { get; set; }
+    public long BytesSent { get; set; }
+    public long SendErrors { get; set; }
+    public long FramesDropped { get; set; } // late frames skipped instead of being sent in a burst
+    public long KeepAlivesSent { get; set; } // re-sends of the last frame while nothing new was due
+    public long WatchdogTrips { get; set; } // times the stall watchdog blanked or dimmed the LEDs
+    public DateTime? LastSendUtc { get; set; }
+    public AmbilightRunReport? LastRun { get; set; } // statistics of the last item this player finished or stopped
+
+    public AmbilightPlayerStatus Clone() => (AmbilightPlayerStatus)MemberwiseClone();
+}
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (C) 2026 Jellyfin Ambilight Contributors
+// This file is part of Jellyfin Ambilight Plugin.
+// Jellyfin Ambilight Plugin is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+using System;
+using System.Collections.Concurrent;
+using System.Collections.Generic;
+using System.Globalization;
+using System.IO;
+using Microsoft.Extensions.Logging;
+
+namespace Jellyfin.Plugin.Ambilight.Services;
+
+/// <summary>
+/// Per-LED gains for a physical strip, e.g. to lift a segment that is dimmer near a power injection point.
+/// One line per LED or LED range, indexed from the strip's data input:
+/// <c>120-135 1.15 1.1 1.2</c> (LED or range, then red, green and blue gains; commas also work, <c>#</c> starts a comment).
+/// LEDs not listed keep gain 1.
+/// </summary>
+public sealed class AmbilightLedCalibration
+{
+    private const int MaxLeds = 10000;
+    private const float MaxGain = 4.0f;
+
+    private static readonly ConcurrentDictionary<string, (DateTime WriteTimeUtc, AmbilightLedCalibration Map)> Cache = new(StringComparer.Ordinal);
+
+    // RGB gain triples in physical LED order.
+    private readonly float[] _gains;
+
+    private AmbilightLedCalibration(float[] gains)
+    {
+        _gains = gains;
+    }
+
+    public int LedCount => _gains.Length / 3;
+
+    /// <summary>
+    /// Loads <paramref name="path"/>, reusing the parsed map while the file is unchanged. Returns null (and logs)
+    /// when the path is empty, missing or invalid, so playback continues uncalibrated.
+    /// </summary>
+    public static AmbilightLedCalibration? TryLoad(string? path, ILogger logger)
+    {
+        if (string.IsNullOrWhiteSpace(path))
+        {
+            return null;
+        }
+
+        path = path.Trim();
+        try
+        {
+            var writeTime = File.GetLastWriteTimeUtc(path);
+            if (Cache.TryGetValue(path, out var cached) && cached.WriteTimeUtc == writeTime)
+            {
+                return cached.Map;
+            }
+
+            var map = Parse(File.ReadLines(path), path);
+            Cache[path] = (writeTime, map);
+            logger.LogInformation("[Ambilight] Loaded LED calibration for {Count} LEDs from {Path}", map.LedCount, path);
+            return map;
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException or InvalidDataException)
+        {
+            logger.LogWarning("[Ambilight] Ignoring LED calibration {Path}: {Message}", path, ex.Message);
+            return null;
+        }
+    }
+
+    /// <summary>
+    /// Parses the text of a calibration file. Throws <see cref="InvalidDataException"/> on errors.
+    /// </summary>
+    public static AmbilightLedCalibration Parse(IEnumerable<string> lines, string name)
+    {
+        var entries = new List<(int First, int Last, float R, float G, float B)>();
+        int count = 0;
+        int lineNumber = 0;
+        foreach (var rawLine in lines)
+        {
+            lineNumber++;
+            var hash = rawLine.IndexOf('#', StringComparison.Ordinal);
+            var line = (hash >= 0 ? rawLine[..hash] : rawLine).Trim();
+            if (line.Length == 0)
+            {
+                continue;
+            }
+
+            var parts = line.Split(new[] { ' ', '\t', ',', ';' }, StringSplitOptions.RemoveEmptyEntries);
+            if (parts.Length != 4)
+            {
+                throw new InvalidDataException($"{name}:{lineNumber}: expected '<led>[-<led>] <r> <g> <b>'");
+            }
+
+            var range = parts[0].Split('-');
+            if (range.Length > 2
+                || !int.TryParse(range[0], NumberStyles.None, CultureInfo.InvariantCulture, out var first)
+                || !int.TryParse(range[^1], NumberStyles.None, CultureInfo.InvariantCulture, out var last)
+                || last < first || last >= MaxLeds)
+            {
+                throw new InvalidDataException($"{name}:{lineNumber}: invalid LED or range '{parts[0]}'");
+            }
+
+            var gains = new float[3];
+            for (int c = 0; c < 3; c++)
+            {
+                if (!float.TryParse(parts[c + 1], NumberStyles.Float, CultureInfo.InvariantCulture, out gains[c]) || !float.IsFinite(gains[c]) || gains[c] < 0.0f)
+                {
+                    throw new InvalidDataException($"{name}:{lineNumber}: invalid gain '{parts[c + 1]}'");
+                }
+            }
+
+            entries.Add((first, last, Math.Min(gains[0], MaxGain), Math.Min(gains[1], MaxGain), Math.Min(gains[2], MaxGain)));
+            count = Math.Max(count, last + 1);
+        }
+
+        var table = new float[count * 3];
+        Array.Fill(table, 1.0f);
+        foreach (var (first, last, r, g, b) in entries)
+        {
+            for (int led = first; led <= last; led++)
+            {
+                table[led * 3] = r;
+                table[led * 3 + 1] = g;
+                table[led * 3 + 2] = b;
+            }
+        }
+
+        return new AmbilightLedCalibration(table);
+    }
+
+    /// <summary>
+    /// Returns <paramref name="frame"/> (physical LED order) with each LED's gains applied to its RGB bytes, written to
+    /// <paramref name="destination"/> (which may be <paramref name="frame"/> itself) or a new array.
+    /// The white channel of RGBW frames is left as is.
+    /// </summary>
+    public byte[] Apply(byte[] frame, int totalLeds, int bytesPerLed, byte[]? destination = null)
+    {
+        var result = destination ?? new byte[frame.Length];
+        if (!ReferenceEquals(result, frame))
+        {
+            Buffer.BlockCopy(frame, 0, result, 0, frame.Length);
+        }
+
+        int leds = Math.Min(totalLeds, LedCount);
+        for (int led = 0; led < leds; led++)
+        {
+            int o = led * bytesPerLed;
+            for (int c = 0; c < 3; c++)
+            {
+                result[o + c] = (byte)Math.Clamp((int)MathF.Round(frame[o + c] * _gains[led * 3 + c]), 0, 255);
+            }
+        }
+
+        return result;
+    }
+}
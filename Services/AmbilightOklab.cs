@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (C) 2026 Jellyfin Ambilight Contributors
+// This file is part of Jellyfin Ambilight Plugin.
+// Jellyfin Ambilight Plugin is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+using System;
+
+namespace Jellyfin.Plugin.Ambilight.Services;
+
+/// <summary>
+/// Conversions between sRGB (0–1) and Björn Ottosson's OKLab. Blending and scaling chroma in OKLab keeps hue
+/// and perceived lightness stable, so a fading red stays red instead of drifting through brown.
+/// Values above 1 (boosted colors) are allowed on the way in and out; negatives are clamped to 0.
+/// </summary>
+public static class AmbilightOklab
+{
+    public static (float L, float A, float B) FromSrgb(float r, float g, float b)
+    {
+        float lr = ToLinear(r);
+        float lg = ToLinear(g);
+        float lb = ToLinear(b);
+
+        float l = MathF.Cbrt((0.4122214708f * lr) + (0.5363325363f * lg) + (0.0514459929f * lb));
+        float m = MathF.Cbrt((0.2119034982f * lr) + (0.6806995451f * lg) + (0.1073969566f * lb));
+        float s = MathF.Cbrt((0.0883024619f * lr) + (0.2817188376f * lg) + (0.6299787005f * lb));
+
+        return (
+            (0.2104542553f * l) + (0.7936177850f * m) - (0.0040720468f * s),
+            (1.9779984951f * l) - (2.4285922050f * m) + (0.4505937099f * s),
+            (0.0259040371f * l) + (0.7827717662f * m) - (0.8086757660f * s));
+    }
+
+    public static (float R, float G, float B) ToSrgb(float lightness, float a, float b)
+    {
+        float l = lightness + (0.3963377774f * a) + (0.2158037573f * b);
+        float m = lightness - (0.1055613458f * a) - (0.0638541728f * b);
+        float s = lightness - (0.0894841775f * a) - (1.2914855480f * b);
+        l = l * l * l;
+        m = m * m * m;
+        s = s * s * s;
+
+        return (
+            FromLinear((4.0767416621f * l) - (3.3077115913f * m) + (0.2309699292f * s)),
+            FromLinear((-1.2684380046f * l) + (2.6097574011f * m) - (0.3413193965f * s)),
+            FromLinear((-0.0041960863f * l) - (0.7034186147f * m) + (1.7076147010f * s)));
+    }
+
+    private static float ToLinear(float v)
+    {
+        if (!(v > 0.0f))
+        {
+            return 0.0f;
+        }
+
+        return v <= 0.04045f ? v / 12.92f : MathF.Pow((v + 0.055f) / 1.055f, 2.4f);
+    }
+
+    private static float FromLinear(float v)
+    {
+        if (!(v > 0.0f))
+        {
+            return 0.0f;
+        }
+
+        return v <= 0.0031308f ? v * 12.92f : (1.055f * MathF.Pow(v, 1.0f / 2.4f)) - 0.055f;
+    }
+}
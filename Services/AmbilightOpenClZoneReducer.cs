@@ -0,0 +1,525 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (C) 2026 Jellyfin Ambilight Contributors
+// This file is part of Jellyfin Ambilight Plugin.
+// Jellyfin Ambilight Plugin is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+using System;
+using System.Runtime.InteropServices;
+using System.Text;
+
+namespace Jellyfin.Plugin.Ambilight.Services;
+
+/// <summary>
+/// Computes LED zone colors for a batch of rgb48le frames with OpenCL, the same edge and center weighting as the
+/// in-process extractor (or its plain mean), one work-group per frame and zone. The OpenCL runtime is loaded at run
+/// time from the system (Intel, AMD or NVIDIA ICD), so nothing is shipped with the plugin and servers without one
+/// simply keep computing on the CPU. Results can differ from the CPU path by one step per channel (float vs double
+/// precision and rounding).
+/// </summary>
+internal sealed class AmbilightOpenClZoneReducer : IDisposable
+{
+    private const int WorkGroupSize = 64;
+
+    private const string KernelSource = @"
+#define WG 64
+
+float gray_at(__global const ushort* f, int width, int x, int y)
+{
+    int i = (y * width + x) * 3;
+    return f[i] * 0.299f + f[i + 1] * 0.587f + f[i + 2] * 0.114f;
+}
+
+float sobel(__global const ushort* f, int width, int x1, int y1, int x2, int y2, int fx, int fy)
+{
+    float gx = 0.0f;
+    float gy = 0.0f;
+    for (int dy = -1; dy <= 1; dy++)
+    {
+        for (int dx = -1; dx <= 1; dx++)
+        {
+            float g = gray_at(f, width, clamp(fx + dx, x1, x2 - 1), clamp(fy + dy, y1, y2 - 1));
+            gx += g * dx * (dy == 0 ? 2.0f : 1.0f);
+            gy += g * dy * (dx == 0 ? 2.0f : 1.0f);
+        }
+    }
+    return sqrt(gx * gx + gy * gy);
+}
+
+__kernel void zone_colors(
+    __global const ushort* frames,
+    __global const int* zones,
+    int zone_count,
+    int width,
+    int height,
+    float edge_share,
+    int step,
+    int mean_only,
+    __global uchar* colors)
+{
+    int zone = get_group_id(0);
+    int frame = get_group_id(1);
+    int lid = get_local_id(0);
+    __local float sw_[WG];
+    __local float sr_[WG];
+    __local float sg_[WG];
+    __local float sb_[WG];
+
+    __global const ushort* f = frames + (size_t)frame * width * height * 3;
+    __global uchar* o = colors + ((size_t)frame * zone_count + zone) * 3;
+    int x1 = zones[zone * 4];
+    int y1 = zones[zone * 4 + 1];
+    int x2 = zones[zone * 4 + 2];
+    int y2 = zones[zone * 4 + 3];
+    int w = x2 - x1;
+    int h = y2 - y1;
+    if (w <= 0 || h <= 0)
+    {
+        if (lid == 0)
+        {
+            o[0] = 0;
+            o[1] = 0;
+            o[2] = 0;
+        }
+        return;
+    }
+
+    int cols = (w + step - 1) / step;
+    int n = cols * ((h + step - 1) / step);
+
+    float max_edge = 0.0f;
+    if (!mean_only)
+    {
+        float m = 0.0f;
+        for (int k = lid; k < n; k += WG)
+        {
+            m = fmax(m, sobel(f, width, x1, y1, x2, y2, x1 + (k % cols) * step, y1 + (k / cols) * step));
+        }
+        sw_[lid] = m;
+        barrier(CLK_LOCAL_MEM_FENCE);
+        for (int s = WG / 2; s > 0; s >>= 1)
+        {
+            if (lid < s)
+            {
+                sw_[lid] = fmax(sw_[lid], sw_[lid + s]);
+            }
+            barrier(CLK_LOCAL_MEM_FENCE);
+        }
+        max_edge = sw_[0];
+        barrier(CLK_LOCAL_MEM_FENCE);
+    }
+
+    float cx = (float)(w / 2);
+    float cy = (float)(h / 2);
+    float sigma = fmax(min(w, h) / 4.0f, 1.0f);
+    float sigma_sq2 = 2.0f * sigma * sigma;
+
+    float tw = 0.0f;
+    float tr = 0.0f;
+    float tg = 0.0f;
+    float tb = 0.0f;
+    for (int k = lid; k < n; k += WG)
+    {
+        int xx = (k % cols) * step;
+        int yy = (k / cols) * step;
+        int fx = x1 + xx;
+        int fy = y1 + yy;
+        float weight = 1.0f;
+        if (!mean_only)
+        {
+            float edge = max_edge > 0.0f ? sobel(f, width, x1, y1, x2, y2, fx, fy) / max_edge : 0.0f;
+            float dx = xx - cx;
+            float dy = yy - cy;
+            float center = exp(-(dx * dx + dy * dy) / sigma_sq2);
+            weight = fmax(edge * edge_share + center * (1.0f - edge_share), 0.01f);
+        }
+        int i = (fy * width + fx) * 3;
+        tr += f[i] * weight;
+        tg += f[i + 1] * weight;
+        tb += f[i + 2] * weight;
+        tw += weight;
+    }
+
+    sw_[lid] = tw;
+    sr_[lid] = tr;
+    sg_[lid] = tg;
+    sb_[lid] = tb;
+    barrier(CLK_LOCAL_MEM_FENCE);
+    for (int s = WG / 2; s > 0; s >>= 1)
+    {
+        if (lid < s)
+        {
+            sw_[lid] += sw_[lid + s];
+            sr_[lid] += sr_[lid + s];
+            sg_[lid] += sg_[lid + s];
+            sb_[lid] += sb_[lid + s];
+        }
+        barrier(CLK_LOCAL_MEM_FENCE);
+    }
+
+    if (lid == 0)
+    {
+        float total = sw_[0];
+        if (mean_only)
+        {
+            o[0] = (uchar)clamp((int)(sr_[0] / total / 257.0f), 0, 255);
+            o[1] = (uchar)clamp((int)(sg_[0] / total / 257.0f), 0, 255);
+            o[2] = (uchar)clamp((int)(sb_[0] / total / 257.0f), 0, 255);
+        }
+        else
+        {
+            o[0] = (uchar)clamp((int)round(sr_[0] / total / 257.0f), 0, 255);
+            o[1] = (uchar)clamp((int)round(sg_[0] / total / 257.0f), 0, 255);
+            o[2] = (uchar)clamp((int)round(sb_[0] / total / 257.0f), 0, 255);
+        }
+    }
+}
+";
+
+    private const ulong DeviceTypeGpu = 1 << 2;
+    private const ulong MemReadOnly = 1 << 2;
+    private const ulong MemWriteOnly = 1 << 1;
+    private const uint ProgramBuildLog = 0x1183;
+    private const uint DeviceName = 0x102B;
+
+    private readonly OpenCl _cl;
+    private readonly int _width;
+    private readonly int _height;
+    private readonly int _zoneCount;
+    private readonly int _maxFrames;
+    private IntPtr _context;
+    private IntPtr _queue;
+    private IntPtr _program;
+    private IntPtr _kernel;
+    private IntPtr _frames;
+    private IntPtr _zones;
+    private IntPtr _colors;
+
+    private AmbilightOpenClZoneReducer(OpenCl cl, int width, int height, int zoneCount, int maxFrames)
+    {
+        _cl = cl;
+        _width = width;
+        _height = height;
+        _zoneCount = zoneCount;
+        _maxFrames = maxFrames;
+    }
+
+    /// <summary>Name of the GPU the kernel runs on.</summary>
+    public string Device { get; private set; } = string.Empty;
+
+    /// <summary>
+    /// Sets up the first OpenCL GPU for frames of <paramref name="width"/> x <paramref name="height"/>, the given zones
+    /// (already clamped to the frame) and batches of up to <paramref name="maxFrames"/> frames. Returns null with the
+    /// reason in <paramref name="error"/> when there is no OpenCL runtime or GPU, or the kernel does not build.
+    /// </summary>
+    public static AmbilightOpenClZoneReducer? TryCreate(
+        int width,
+        int height,
+        (int x1, int y1, int x2, int y2)[] zones,
+        int maxFrames,
+        out string error)
+    {
+        var cl = OpenCl.Load();
+        if (cl == null)
+        {
+            error = "no OpenCL runtime found (libOpenCL)";
+            return null;
+        }
+
+        var reducer = new AmbilightOpenClZoneReducer(cl, width, height, zones.Length, maxFrames);
+        try
+        {
+            error = reducer.Initialize(zones) ?? string.Empty;
+            if (error.Length == 0)
+            {
+                return reducer;
+            }
+        }
+        catch (Exception ex) when (ex is OpenClException or MarshalDirectiveException or EntryPointNotFoundException)
+        {
+            error = ex.Message;
+        }
+
+        reducer.Dispose();
+        return null;
+    }
+
+    private string? Initialize((int x1, int y1, int x2, int y2)[] zones)
+    {
+        Check(_cl.GetPlatformIDs(0, null, out uint platformCount), "clGetPlatformIDs");
+        if (platformCount == 0)
+        {
+            return "no OpenCL platforms";
+        }
+
+        var platforms = new IntPtr[platformCount];
+        Check(_cl.GetPlatformIDs(platformCount, platforms, out _), "clGetPlatformIDs");
+        IntPtr device = IntPtr.Zero;
+        foreach (var platform in platforms)
+        {
+            var devices = new IntPtr[1];
+            if (_cl.GetDeviceIDs(platform, DeviceTypeGpu, 1, devices, out uint deviceCount) == 0 && deviceCount > 0)
+            {
+                device = devices[0];
+                break;
+            }
+        }
+
+        if (device == IntPtr.Zero)
+        {
+            return "no OpenCL GPU device";
+        }
+
+        var nameBuffer = new byte[256];
+        if (_cl.GetDeviceInfo(device, DeviceName, (UIntPtr)nameBuffer.Length, nameBuffer, out _) == 0)
+        {
+            Device = Encoding.ASCII.GetString(nameBuffer).TrimEnd('\0').Trim();
+        }
+
+        int err;
+        _context = _cl.CreateContext(IntPtr.Zero, 1, new[] { device }, IntPtr.Zero, IntPtr.Zero, out err);
+        Check(err, "clCreateContext");
+        _queue = _cl.CreateCommandQueue(_context, device, 0, out err);
+        Check(err, "clCreateCommandQueue");
+        _program = _cl.CreateProgramWithSource(_context, 1, new[] { KernelSource }, IntPtr.Zero, out err);
+        Check(err, "clCreateProgramWithSource");
+        if (_cl.BuildProgram(_program, 1, new[] { device }, null, IntPtr.Zero, IntPtr.Zero) != 0)
+        {
+            var log = new byte[8192];
+            _cl.GetProgramBuildInfo(_program, device, ProgramBuildLog, (UIntPtr)log.Length, log, out _);
+            return "kernel build failed: " + Encoding.ASCII.GetString(log).TrimEnd('\0').Trim();
+        }
+
+        _kernel = _cl.CreateKernel(_program, "zone_colors", out err);
+        Check(err, "clCreateKernel");
+
+        var zoneData = PackZones(zones);
+        _frames = _cl.CreateBuffer(_context, MemReadOnly, (UIntPtr)((long)_maxFrames * FrameSize), IntPtr.Zero, out err);
+        Check(err, "clCreateBuffer");
+        _zones = _cl.CreateBuffer(_context, MemReadOnly, (UIntPtr)(zoneData.Length * sizeof(int)), IntPtr.Zero, out err);
+        Check(err, "clCreateBuffer");
+        _colors = _cl.CreateBuffer(_context, MemWriteOnly, (UIntPtr)((long)_maxFrames * _zoneCount * 3), IntPtr.Zero, out err);
+        Check(err, "clCreateBuffer");
+        Check(_cl.EnqueueWriteBufferInt(_queue, _zones, 1, UIntPtr.Zero, (UIntPtr)(zoneData.Length * sizeof(int)), zoneData, 0, IntPtr.Zero, IntPtr.Zero), "clEnqueueWriteBuffer");
+
+        Check(_cl.SetKernelArgPtr(_kernel, 0, (UIntPtr)IntPtr.Size, ref _frames), "clSetKernelArg");
+        Check(_cl.SetKernelArgPtr(_kernel, 1, (UIntPtr)IntPtr.Size, ref _zones), "clSetKernelArg");
+        int zoneCount = _zoneCount;
+        Check(_cl.SetKernelArgInt(_kernel, 2, (UIntPtr)sizeof(int), ref zoneCount), "clSetKernelArg");
+        int width = _width;
+        Check(_cl.SetKernelArgInt(_kernel, 3, (UIntPtr)sizeof(int), ref width), "clSetKernelArg");
+        int height = _height;
+        Check(_cl.SetKernelArgInt(_kernel, 4, (UIntPtr)sizeof(int), ref height), "clSetKernelArg");
+        Check(_cl.SetKernelArgPtr(_kernel, 8, (UIntPtr)IntPtr.Size, ref _colors), "clSetKernelArg");
+        return null;
+    }
+
+    // rgb48le: three 16-bit samples per pixel.
+    private int FrameSize => _width * _height * 6;
+
+    private static int[] PackZones((int x1, int y1, int x2, int y2)[] zones)
+    {
+        var zoneData = new int[zones.Length * 4];
+        for (int i = 0; i < zones.Length; i++)
+        {
+            zoneData[i * 4] = zones[i].x1;
+            zoneData[i * 4 + 1] = zones[i].y1;
+            zoneData[i * 4 + 2] = zones[i].x2;
+            zoneData[i * 4 + 3] = zones[i].y2;
+        }
+
+        return zoneData;
+    }
+
+    /// <summary>
+    /// Replaces the zones (already clamped to the frame) for the following batches, as when the picture area inside
+    /// black bars changes. There must be as many as the reducer was created with.
+    /// </summary>
+    public void SetZones((int x1, int y1, int x2, int y2)[] zones)
+    {
+        if (zones.Length != _zoneCount)
+        {
+            throw new ArgumentException($"expected {_zoneCount} zones, got {zones.Length}", nameof(zones));
+        }
+
+        var zoneData = PackZones(zones);
+        Check(_cl.EnqueueWriteBufferInt(_queue, _zones, 1, UIntPtr.Zero, (UIntPtr)(zoneData.Length * sizeof(int)), zoneData, 0, IntPtr.Zero, IntPtr.Zero), "clEnqueueWriteBuffer");
+    }
+
+    /// <summary>
+    /// Computes the RGB color of every zone of <paramref name="count"/> frames from <paramref name="offset"/> on into
+    /// <paramref name="rgb"/>, frame after frame, three bytes per zone. <paramref name="step"/> samples every step-th
+    /// pixel in each direction; <paramref name="meanOnly"/> skips the edge and center weighting.
+    /// </summary>
+    public void Compute(byte[][] frames, int offset, int count, double edgeShare, int step, bool meanOnly, byte[] rgb)
+    {
+        if (count > _maxFrames)
+        {
+            throw new ArgumentOutOfRangeException(nameof(count));
+        }
+
+        for (int i = 0; i < count; i++)
+        {
+            Check(_cl.EnqueueWriteBuffer(_queue, _frames, 1, (UIntPtr)((long)i * FrameSize), (UIntPtr)FrameSize, frames[offset + i], 0, IntPtr.Zero, IntPtr.Zero), "clEnqueueWriteBuffer");
+        }
+
+        float share = (float)edgeShare;
+        Check(_cl.SetKernelArgFloat(_kernel, 5, (UIntPtr)sizeof(float), ref share), "clSetKernelArg");
+        Check(_cl.SetKernelArgInt(_kernel, 6, (UIntPtr)sizeof(int), ref step), "clSetKernelArg");
+        int mean = meanOnly ? 1 : 0;
+        Check(_cl.SetKernelArgInt(_kernel, 7, (UIntPtr)sizeof(int), ref mean), "clSetKernelArg");
+
+        var global = new[] { (UIntPtr)(_zoneCount * WorkGroupSize), (UIntPtr)count };
+        var local = new[] { (UIntPtr)WorkGroupSize, (UIntPtr)1 };
+        Check(_cl.EnqueueNDRangeKernel(_queue, _kernel, 2, IntPtr.Zero, global, local, 0, IntPtr.Zero, IntPtr.Zero), "clEnqueueNDRangeKernel");
+        Check(_cl.EnqueueReadBuffer(_queue, _colors, 1, UIntPtr.Zero, (UIntPtr)(count * _zoneCount * 3), rgb, 0, IntPtr.Zero, IntPtr.Zero), "clEnqueueReadBuffer");
+    }
+
+    public void Dispose()
+    {
+        Release(ref _colors, _cl.ReleaseMemObject);
+        Release(ref _zones, _cl.ReleaseMemObject);
+        Release(ref _frames, _cl.ReleaseMemObject);
+        Release(ref _kernel, _cl.ReleaseKernel);
+        Release(ref _program, _cl.ReleaseProgram);
+        Release(ref _queue, _cl.ReleaseCommandQueue);
+        Release(ref _context, _cl.ReleaseContext);
+    }
+
+    private static void Release(ref IntPtr handle, OpenCl.ReleaseFn release)
+    {
+        if (handle != IntPtr.Zero)
+        {
+            release(handle);
+            handle = IntPtr.Zero;
+        }
+    }
+
+    private static void Check(int status, string call)
+    {
+        if (status != 0)
+        {
+            throw new OpenClException($"{call} failed with OpenCL error {status}");
+        }
+    }
+
+    internal sealed class OpenClException : Exception
+    {
+        public OpenClException(string message)
+            : base(message)
+        {
+        }
+    }
+
+    /// <summary>
+    /// The OpenCL 1.2 entry points used here, bound from the system library with <see cref="NativeLibrary"/> so the
+    /// plugin loads fine where there is none.
+    /// </summary>
+    private sealed class OpenCl
+    {
+        private static readonly Lazy<OpenCl?> Instance = new(Create);
+
+        public delegate int GetPlatformIDsFn(uint numEntries, IntPtr[]? platforms, out uint numPlatforms);
+        public delegate int GetDeviceIDsFn(IntPtr platform, ulong deviceType, uint numEntries, IntPtr[]? devices, out uint numDevices);
+        public delegate int GetDeviceInfoFn(IntPtr device, uint paramName, UIntPtr size, byte[] value, out UIntPtr sizeRet);
+        public delegate IntPtr CreateContextFn(IntPtr properties, uint numDevices, IntPtr[] devices, IntPtr notify, IntPtr userData, out int errcode);
+        public delegate IntPtr CreateCommandQueueFn(IntPtr context, IntPtr device, ulong properties, out int errcode);
+        public delegate IntPtr CreateProgramWithSourceFn(IntPtr context, uint count, [MarshalAs(UnmanagedType.LPArray, ArraySubType = UnmanagedType.LPStr)] string[] strings, IntPtr lengths, out int errcode);
+        public delegate int BuildProgramFn(IntPtr program, uint numDevices, IntPtr[] devices, [MarshalAs(UnmanagedType.LPStr)] string? options, IntPtr notify, IntPtr userData);
+        public delegate int GetProgramBuildInfoFn(IntPtr program, IntPtr device, uint paramName, UIntPtr size, byte[] value, out UIntPtr sizeRet);
+        public delegate IntPtr CreateKernelFn(IntPtr program, [MarshalAs(UnmanagedType.LPStr)] string name, out int errcode);
+        public delegate IntPtr CreateBufferFn(IntPtr context, ulong flags, UIntPtr size, IntPtr hostPtr, out int errcode);
+        public delegate int SetKernelArgPtrFn(IntPtr kernel, uint index, UIntPtr size, ref IntPtr value);
+        public delegate int SetKernelArgIntFn(IntPtr kernel, uint index, UIntPtr size, ref int value);
+        public delegate int SetKernelArgFloatFn(IntPtr kernel, uint index, UIntPtr size, ref float value);
+        public delegate int EnqueueWriteBufferFn(IntPtr queue, IntPtr buffer, uint blocking, UIntPtr offset, UIntPtr size, byte[] ptr, uint numEvents, IntPtr waitList, IntPtr evt);
+        public delegate int EnqueueWriteBufferIntFn(IntPtr queue, IntPtr buffer, uint blocking, UIntPtr offset, UIntPtr size, int[] ptr, uint numEvents, IntPtr waitList, IntPtr evt);
+        public delegate int EnqueueReadBufferFn(IntPtr queue, IntPtr buffer, uint blocking, UIntPtr offset, UIntPtr size, byte[] ptr, uint numEvents, IntPtr waitList, IntPtr evt);
+        public delegate int EnqueueNDRangeKernelFn(IntPtr queue, IntPtr kernel, uint workDim, IntPtr globalOffset, UIntPtr[] globalSize, UIntPtr[] localSize, uint numEvents, IntPtr waitList, IntPtr evt);
+        public delegate int ReleaseFn(IntPtr handle);
+
+        public GetPlatformIDsFn GetPlatformIDs { get; private init; } = null!;
+        public GetDeviceIDsFn GetDeviceIDs { get; private init; } = null!;
+        public GetDeviceInfoFn GetDeviceInfo { get; private init; } = null!;
+        public CreateContextFn CreateContext { get; private init; } = null!;
+        public CreateCommandQueueFn CreateCommandQueue { get; private init; } = null!;
+        public CreateProgramWithSourceFn CreateProgramWithSource { get; private init; } = null!;
+        public BuildProgramFn BuildProgram { get; private init; } = null!;
+        public GetProgramBuildInfoFn GetProgramBuildInfo { get; private init; } = null!;
+        public CreateKernelFn CreateKernel { get; private init; } = null!;
+        public CreateBufferFn CreateBuffer { get; private init; } = null!;
+        public SetKernelArgPtrFn SetKernelArgPtr { get; private init; } = null!;
+        public SetKernelArgIntFn SetKernelArgInt { get; private init; } = null!;
+        public SetKernelArgFloatFn SetKernelArgFloat { get; private init; } = null!;
+        public EnqueueWriteBufferFn EnqueueWriteBuffer { get; private init; } = null!;
+        public EnqueueWriteBufferIntFn EnqueueWriteBufferInt { get; private init; } = null!;
+        public EnqueueReadBufferFn EnqueueReadBuffer { get; private init; } = null!;
+        public EnqueueNDRangeKernelFn EnqueueNDRangeKernel { get; private init; } = null!;
+        public ReleaseFn ReleaseMemObject { get; private init; } = null!;
+        public ReleaseFn ReleaseKernel { get; private init; } = null!;
+        public ReleaseFn ReleaseProgram { get; private init; } = null!;
+        public ReleaseFn ReleaseCommandQueue { get; private init; } = null!;
+        public ReleaseFn ReleaseContext { get; private init; } = null!;
+
+        public static OpenCl? Load() => Instance.Value;
+
+        private static OpenCl? Create()
+        {
+            // Distributions ship the ICD loader as libOpenCL.so.1; the unversioned name needs the -dev package.
+            string[] names = OperatingSystem.IsWindows()
+                ? new[] { "OpenCL.dll" }
+                : OperatingSystem.IsMacOS()
+                    ? new[] { "/System/Library/Frameworks/OpenCL.framework/OpenCL" }
+                    : new[] { "libOpenCL.so.1", "libOpenCL.so" };
+
+            foreach (var name in names)
+            {
+                if (!NativeLibrary.TryLoad(name, out var lib))
+                {
+                    continue;
+                }
+
+                try
+                {
+                    return new OpenCl
+                    {
+                        GetPlatformIDs = Bind<GetPlatformIDsFn>(lib, "clGetPlatformIDs"),
+                        GetDeviceIDs = Bind<GetDeviceIDsFn>(lib, "clGetDeviceIDs"),
+                        GetDeviceInfo = Bind<GetDeviceInfoFn>(lib, "clGetDeviceInfo"),
+                        CreateContext = Bind<CreateContextFn>(lib, "clCreateContext"),
+                        CreateCommandQueue = Bind<CreateCommandQueueFn>(lib, "clCreateCommandQueue"),
+                        CreateProgramWithSource = Bind<CreateProgramWithSourceFn>(lib, "clCreateProgramWithSource"),
+                        BuildProgram = Bind<BuildProgramFn>(lib, "clBuildProgram"),
+                        GetProgramBuildInfo = Bind<GetProgramBuildInfoFn>(lib, "clGetProgramBuildInfo"),
+                        CreateKernel = Bind<CreateKernelFn>(lib, "clCreateKernel"),
+                        CreateBuffer = Bind<CreateBufferFn>(lib, "clCreateBuffer"),
+                        SetKernelArgPtr = Bind<SetKernelArgPtrFn>(lib, "clSetKernelArg"),
+                        SetKernelArgInt = Bind<SetKernelArgIntFn>(lib, "clSetKernelArg"),
+                        SetKernelArgFloat = Bind<SetKernelArgFloatFn>(lib, "clSetKernelArg"),
+                        EnqueueWriteBuffer = Bind<EnqueueWriteBufferFn>(lib, "clEnqueueWriteBuffer"),
+                        EnqueueWriteBufferInt = Bind<EnqueueWriteBufferIntFn>(lib, "clEnqueueWriteBuffer"),
+                        EnqueueReadBuffer = Bind<EnqueueReadBufferFn>(lib, "clEnqueueReadBuffer"),
+                        EnqueueNDRangeKernel = Bind<EnqueueNDRangeKernelFn>(lib, "clEnqueueNDRangeKernel"),
+                        ReleaseMemObject = Bind<ReleaseFn>(lib, "clReleaseMemObject"),
+                        ReleaseKernel = Bind<ReleaseFn>(lib, "clReleaseKernel"),
+                        ReleaseProgram = Bind<ReleaseFn>(lib, "clReleaseProgram"),
+                        ReleaseCommandQueue = Bind<ReleaseFn>(lib, "clReleaseCommandQueue"),
+                        ReleaseContext = Bind<ReleaseFn>(lib, "clReleaseContext")
+                    };
+                }
+                catch (EntryPointNotFoundException)
+                {
+                    NativeLibrary.Free(lib);
+                }
+            }
+
+            return null;
+        }
+
+        private static T Bind<T>(IntPtr lib, string name)
+            where T : Delegate
+            => Marshal.GetDelegateForFunctionPointer<T>(NativeLibrary.GetExport(lib, name));
+    }
+}
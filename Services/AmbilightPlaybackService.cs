@@ -12,7 +12,10 @@ using System.Diagnostics;
 using System.IO;
 using System.Linq;
 using System.Threading.Tasks;
+using Jellyfin.Data.Enums;
 using Jellyfin.Plugin.Ambilight;
+using MediaBrowser.Controller.Entities;
+using MediaBrowser.Controller.Entities.TV;
 using MediaBrowser.Controller.Library;
 using MediaBrowser.Controller.Session;
 using MediaBrowser.Model.Session;
@@ -34,10 +37,21 @@ public class AmbilightPlaybackService
     private readonly ILibraryManager _libraryManager;
     private readonly AmbilightStorageService _storage;
     private readonly PluginConfiguration _config;
+    private readonly AmbilightBinaryCache _binaryCache;
 
     // In-process C# players, keyed by session Id. Each session can have multiple players for multiple WLED instances.
     private readonly ConcurrentDictionary<string, List<AmbilightInProcessPlayer>> _sessionPlayers = new();
-    private readonly ConcurrentDictionary<string, double> _lastPositionSeconds = new();
+    // Last progress report per session, used to tell seeks apart from normal heartbeats.
+    private readonly ConcurrentDictionary<string, (double Seconds, DateTime Utc, bool Paused)> _lastProgress = new();
+
+    // A report this far from where the client should be by now counts as a seek.
+    private const double SeekThresholdSeconds = 1.0;
+
+    // Players driven by an external timecode source are kept under this prefix instead of a Jellyfin session id.
+    private const string TimecodeSessionPrefix = "timecode:";
+
+    // Session id prefix of calibration players (see StartCalibration); one session per WLED target.
+    private const string CalibrationSessionPrefix = "calibrate:";
     private readonly ConcurrentDictionary<string, CancellationTokenSource> _loadingEffectCancellations = new();
 
     public AmbilightPlaybackService(
@@ -52,6 +66,7 @@ public class AmbilightPlaybackService
         _libraryManager = libraryManager;
         _storage = storage;
             _config = config;
+        _binaryCache = new AmbilightBinaryCache(logger);
     }
 
     /// <summary>
@@ -138,18 +153,20 @@ public class AmbilightPlaybackService
                     session.DeviceName ?? session.DeviceId, targets.Count, wledList);
             }
             
-            if (!File.Exists(binPath))
+            // Without a valid local binary, a configured remote store is read over HTTP instead.
+            var source = _storage.ResolvePlaybackSource(itemIdStr);
+            if (source == null)
             {
                 if (debug)
                 {
-                    _logger.LogInformation("[Ambilight] Skip: binary file not found at {BinPath}", binPath);
+                    _logger.LogInformation("[Ambilight] Skip: binary file missing or invalid at {BinPath}", binPath);
                 }
                 
                 // Show failure flash on all WLED instances
                 foreach (var mapping in targets)
                 {
-                    int ledCount = mapping.TopLedCount + mapping.BottomLedCount + mapping.LeftLedCount + mapping.RightLedCount;
-                    _ = AmbilightInProcessPlayer.SendFailureFlashAsync(mapping.Host, mapping.Port, ledCount, _logger);
+                    int ledCount = AmbilightInProcessPlayer.StripLedCount(mapping);
+                    _ = AmbilightInProcessPlayer.SendFailureFlashAsync(mapping.Host, mapping.Port, ledCount, _logger, mapping.Protocol, AmbilightInProcessPlayer.SegmentStartFor(mapping));
                 }
                 return;
             }
@@ -160,7 +177,7 @@ public class AmbilightPlaybackService
             
             foreach (var mapping in targets)
             {
-                int ledCount = mapping.TopLedCount + mapping.BottomLedCount + mapping.LeftLedCount + mapping.RightLedCount;
+                int ledCount = AmbilightInProcessPlayer.StripLedCount(mapping);
                 
                 if (debug)
                 {
@@ -168,14 +185,21 @@ public class AmbilightPlaybackService
                         mapping.Host, mapping.Port, ledCount);
                 }
                 
-                _ = AmbilightInProcessPlayer.SendLoadingEffectAsync(mapping.Host, mapping.Port, ledCount, _logger, loadingCts.Token);
+                _ = AmbilightInProcessPlayer.SendLoadingEffectAsync(mapping.Host, mapping.Port, ledCount, _logger, loadingCts.Token, mapping.Protocol, AmbilightInProcessPlayer.SegmentStartFor(mapping));
             }
             
             if (debug)
             {
-                _logger.LogInformation("[Ambilight] Binary found at {BinPath}, connecting to {Count} WLED instance(s)", binPath, targets.Count);
+                _logger.LogInformation("[Ambilight] Binary found at {BinPath}, connecting to {Count} WLED instance(s)", source, targets.Count);
             }
 
+            if (source == binPath && _storage.IsTruncated(itemIdStr, out var coveredSeconds))
+            {
+                ReportTruncatedBinary(item, itemIdStr, coveredSeconds);
+            }
+
+            binPath = source;
+
             var startSeconds = (info.PositionTicks ?? 0) / 10_000_000.0;
             
             // Try to start players for all targets (this is async, returns immediately)
@@ -191,10 +215,14 @@ public class AmbilightPlaybackService
                 StopLoadingEffect(session.Id);
                 foreach (var mapping in targets)
                 {
-                    int totalLeds = mapping.TopLedCount + mapping.BottomLedCount + mapping.LeftLedCount + mapping.RightLedCount;
-                    _ = AmbilightInProcessPlayer.SendFailureFlashAsync(mapping.Host, mapping.Port, totalLeds, _logger);
+                    int totalLeds = AmbilightInProcessPlayer.StripLedCount(mapping);
+                    _ = AmbilightInProcessPlayer.SendFailureFlashAsync(mapping.Host, mapping.Port, totalLeds, _logger, mapping.Protocol, AmbilightInProcessPlayer.SegmentStartFor(mapping));
                 }
             }
+            else if (Config.PrecacheNextEpisode && item is Episode episode)
+            {
+                PrecacheNextEpisode(episode);
+            }
         }
         catch (Exception ex)
         {
@@ -202,6 +230,29 @@ public class AmbilightPlaybackService
         }
     }
 
+    /// <summary>
+    /// Logs how much of the item a truncated binary covers and marks the item for re-extraction; playback goes ahead
+    /// with the frames that are there.
+    /// </summary>
+    private void ReportTruncatedBinary(BaseItem item, string itemId, double coveredSeconds)
+    {
+        var covered = TimeSpan.FromSeconds(coveredSeconds);
+        string reason;
+        if (item.RunTimeTicks is long ticks && ticks > 0)
+        {
+            var runtime = TimeSpan.FromTicks(ticks);
+            double percent = Math.Min(100.0, 100.0 * covered.Ticks / ticks);
+            reason = $"Binary is truncated: covers {covered:hh\\:mm\\:ss} of {runtime:hh\\:mm\\:ss} ({percent:F0}%)";
+        }
+        else
+        {
+            reason = $"Binary is truncated: covers {covered:hh\\:mm\\:ss}";
+        }
+
+        _logger.LogWarning("[Ambilight] {Reason} for {ItemName}; playing what is there and queuing the item for re-extraction", reason, item.Name);
+        _storage.MarkForReextraction(itemId, reason);
+    }
+
     public void OnPlaybackStopped(SessionInfo session, PlaybackStopInfo info)
     {
         if (Config.Debug)
@@ -212,51 +263,415 @@ public class AmbilightPlaybackService
         // Stop loading effect if still running
         StopLoadingEffect(session.Id);
         
-        // Stop players
-        StopPlayersForSession(session.Id);
+        // Stop players; resident players just unload and wait for the session's next item
+        if (Config.ResidentPlayers)
+        {
+            UnloadPlayersForSession(session.Id);
+        }
+        else
+        {
+            StopPlayersForSession(session.Id);
+        }
+    }
+
+    /// <summary>
+    /// Releases resident players once Jellyfin drops the session.
+    /// </summary>
+    public void OnSessionEnded(string sessionId)
+    {
+        StopLoadingEffect(sessionId);
+        StopPlayersForSession(sessionId);
+        _lastProgress.TryRemove(sessionId, out _);
     }
 
     public void OnPlaybackProgress(SessionInfo session, PlaybackProgressInfo info)
     {
-        if (!_sessionPlayers.TryGetValue(session.Id, out var players) || players.Count == 0)
+        var positionTicks = info.PositionTicks ?? 0;
+        ApplyPosition(session.Id, positionTicks / 10_000_000.0, info.IsPaused is true);
+    }
+
+    /// <summary>
+    /// Drives players from an external timecode source (SMPTE LTC or MIDI Timecode decoded by a bridge) instead of
+    /// Jellyfin session events. The first call for <paramref name="sourceId"/> starts players for the device's
+    /// mappings; later calls are position reports like Jellyfin's, so small differences are slewed out and jumps
+    /// seek. When the timecode stops, the heartbeat timeout applies. Returns the number of players following it.
+    /// </summary>
+    public int OnTimecode(string sourceId, string deviceName, string binPath, double seconds, bool paused)
+    {
+        var sessionId = TimecodeSessionPrefix + sourceId;
+        if (!_sessionPlayers.TryGetValue(sessionId, out var players) || players.Count == 0
+            || !players.All(p => p.IsRunning && string.Equals(p.GetStatus().BinPath, binPath, StringComparison.Ordinal)))
+        {
+            var targets = ResolveWledTargets(null, deviceName);
+            if (targets.Count == 0)
+            {
+                return 0;
+            }
+
+            _lastProgress.TryRemove(sessionId, out _);
+            if (!StartPlayersForSession(sessionId, binPath, targets, seconds))
+            {
+                return 0;
+            }
+
+            _logger.LogInformation("[Ambilight] Timecode source {Source} started {Count} player(s) for {Device} at {Seconds:F2}s",
+                sourceId, targets.Count, deviceName, seconds);
+        }
+
+        ApplyPosition(sessionId, seconds, paused);
+        return _sessionPlayers.TryGetValue(sessionId, out players) ? players.Count : 0;
+    }
+
+    /// <summary>
+    /// Stops the players started by a timecode source.
+    /// </summary>
+    public void StopTimecode(string sourceId)
+    {
+        var sessionId = TimecodeSessionPrefix + sourceId;
+        StopPlayersForSession(sessionId);
+        _lastProgress.TryRemove(sessionId, out _);
+    }
+
+    private void ApplyPosition(string sessionId, double currSeconds, bool paused)
+    {
+        if (!_sessionPlayers.TryGetValue(sessionId, out var players) || players.Count == 0)
         {
             return;
         }
 
-        foreach (var inProc in players)
+        var now = DateTime.UtcNow;
+
+        // Compare against where the client should be if it kept playing since its last report;
+        // anything close is a heartbeat for drift correction, anything else a seek.
+        bool seeked = false;
+        if (_lastProgress.TryGetValue(sessionId, out var last))
         {
-            var positionTicks = info.PositionTicks ?? 0;
-            var currSeconds = positionTicks / 10_000_000.0;
-            var paused = info.IsPaused is true;
+            var rate = players[0].GetStatus().PlaybackRate;
+            var expected = last.Seconds + (last.Paused ? 0.0 : (now - last.Utc).TotalSeconds * rate);
+            seeked = Math.Abs(currSeconds - expected) > SeekThresholdSeconds;
+        }
+        _lastProgress[sessionId] = (currSeconds, now, paused);
+
+        if (seeked && Config.Debug)
+        {
+            _logger.LogInformation("[Ambilight] Seek detected for session {SessionId} to {Seconds:F1}s", sessionId, currSeconds);
+        }
 
+        foreach (var inProc in players)
+        {
             inProc.SetPaused(paused);
+            if (seeked)
+            {
+                inProc.Seek(currSeconds, now);
+            }
+            else
+            {
+                inProc.ReportPosition(currSeconds);
+            }
+        }
+    }
+
+    /// <summary>
+    /// Applies a runtime tuning override (see <see cref="AmbilightInProcessPlayer.SetParameter"/>) to the players
+    /// of one session, or of every active session when <paramref name="sessionId"/> is empty.
+    /// Returns the number of players updated, or -1 if the parameter is unknown.
+    /// </summary>
+    public int SetParameter(string? sessionId, string name, double value)
+    {
+        if (!AmbilightInProcessPlayer.TunableParameters.Contains(name, StringComparer.OrdinalIgnoreCase))
+        {
+            return -1;
+        }
 
-            var last = _lastPositionSeconds.GetOrAdd(session.Id, currSeconds);
-            _lastPositionSeconds[session.Id] = currSeconds;
+        int updated = 0;
+        foreach (var (id, players) in _sessionPlayers)
+        {
+            if (!string.IsNullOrEmpty(sessionId) && !string.Equals(id, sessionId, StringComparison.Ordinal))
+            {
+                continue;
+            }
 
-            // Detect significant jumps (seek) – keep threshold small so manual skips resync quickly
-            if (Math.Abs(currSeconds - last) > 0.5)
+            foreach (var player in players)
             {
-                if (Config.Debug)
+                if (player.SetParameter(name, value))
+                {
+                    updated++;
+                }
+            }
+        }
+
+        if (Config.Debug)
+        {
+            _logger.LogInformation("[Ambilight] SET {Name} {Value} applied to {Count} player(s)", name, value, updated);
+        }
+
+        return updated;
+    }
+
+    /// <summary>
+    /// NEXT: queues a binary on the players of one session (or every session) to follow the current item
+    /// without a dark gap. Returns the number of players that accepted it.
+    /// </summary>
+    public int QueueNext(string? sessionId, string binPath)
+    {
+        int queued = 0;
+        foreach (var (id, players) in _sessionPlayers)
+        {
+            if (!string.IsNullOrEmpty(sessionId) && !string.Equals(id, sessionId, StringComparison.Ordinal))
+            {
+                continue;
+            }
+
+            foreach (var player in players)
+            {
+                if (player.Queue(binPath))
+                {
+                    queued++;
+                }
+            }
+        }
+
+        if (Config.Debug)
+        {
+            _logger.LogInformation("[Ambilight] NEXT {BinPath} queued on {Count} player(s)", binPath, queued);
+        }
+
+        return queued;
+    }
+
+    /// <summary>
+    /// Pauses or resumes the players of one session (or every session). Jellyfin's next progress report
+    /// for that session takes precedence again.
+    /// </summary>
+    public int SetPaused(string? sessionId, bool paused) => ForEachPlayer(sessionId, p => p.SetPaused(paused));
+
+    /// <summary>
+    /// Seeks the players of one session (or every session) to a media position in seconds.
+    /// </summary>
+    public int Seek(string? sessionId, double seconds) => ForEachPlayer(sessionId, p => p.Seek(seconds));
+
+    /// <summary>
+    /// Seeks the players of one session (or every session) to the start of a chapter (1-based) of their item.
+    /// Returns the number of players whose item has that chapter.
+    /// </summary>
+    public int SeekChapter(string? sessionId, int chapter)
+    {
+        int seeked = 0;
+        ForEachPlayer(sessionId, p =>
+        {
+            if (p.SeekChapter(chapter))
+            {
+                seeked++;
+            }
+        });
+        return seeked;
+    }
+
+    /// <summary>
+    /// Sets the playback speed the players of one session (or every session) follow, e.g. 1.5 for 1.5x.
+    /// Returns the number of players updated, or -1 if the factor is out of range.
+    /// </summary>
+    public int SetPlaybackRate(string? sessionId, double factor)
+    {
+        if (!double.IsFinite(factor) || factor < AmbilightInProcessPlayer.MinPlaybackRate || factor > AmbilightInProcessPlayer.MaxPlaybackRate)
+        {
+            return -1;
+        }
+
+        return ForEachPlayer(sessionId, p => p.SetPlaybackRate(factor));
+    }
+
+    /// <summary>
+    /// Stops the current item and blanks the LEDs; resident players stay ready for the next item.
+    /// </summary>
+    public int Unload(string? sessionId) => ForEachPlayer(sessionId, p => p.Unload());
+
+    /// <summary>
+    /// Plays a static calibration pattern (<c>white</c> or <c>ramp</c>, see <see cref="AmbilightFixtureGenerator"/>)
+    /// on one mapping's target through the normal color pipeline, so tuning overrides sent to the returned session
+    /// with <see cref="SetParameter"/> show up live. Calling again with the same pattern is a heartbeat that keeps
+    /// the session from being treated as a stalled client; another pattern switches in place. Returns null when the
+    /// pattern could not be prepared.
+    /// </summary>
+    public string? StartCalibration(DeviceMapping mapping, string pattern)
+    {
+        var sessionId = CalibrationSessionPrefix + mapping.Host.Trim() + ":" + mapping.Port;
+        int top = Math.Max(0, mapping.TopLedCount);
+        int right = Math.Max(0, mapping.RightLedCount);
+        int bottom = Math.Max(0, mapping.BottomLedCount);
+        int left = Math.Max(0, mapping.LeftLedCount);
+        var binPath = Path.Combine(Path.GetTempPath(), $"ambilight-calibration-{pattern}-{top}x{right}x{bottom}x{left}.bin");
+
+        if (_sessionPlayers.TryGetValue(sessionId, out var players) && players.Count > 0
+            && players.All(p => p.IsRunning && string.Equals(p.GetStatus().BinPath, binPath, StringComparison.Ordinal)))
+        {
+            foreach (var player in players)
+            {
+                player.ReportPosition(player.GetStatus().PositionSeconds);
+            }
+
+            return sessionId;
+        }
+
+        try
+        {
+            if (!File.Exists(binPath))
+            {
+                // One frame per second for an hour: static, small, and long enough for any calibration.
+                var bytes = AmbilightFixtureGenerator.Generate(pattern, 3600, 1, (ushort)top, (ushort)bottom, (ushort)left, (ushort)right, AmbilightBinary.FormatRgb);
+                File.WriteAllBytes(binPath + ".tmp", bytes);
+                File.Move(binPath + ".tmp", binPath, overwrite: true);
+            }
+        }
+        catch (Exception ex) when (ex is ArgumentException or IOException or UnauthorizedAccessException)
+        {
+            _logger.LogWarning("[Ambilight] Cannot prepare calibration pattern {Pattern}: {Message}", pattern, ex.Message);
+            return null;
+        }
+
+        _lastProgress.TryRemove(sessionId, out _);
+        return StartPlayersForSession(sessionId, binPath, new List<DeviceMapping> { mapping }, 0.0) ? sessionId : null;
+    }
+
+    /// <summary>
+    /// Stops the calibration player of a mapping's target, if any.
+    /// </summary>
+    public void StopCalibration(DeviceMapping mapping)
+    {
+        var sessionId = CalibrationSessionPrefix + mapping.Host.Trim() + ":" + mapping.Port;
+        StopPlayersForSession(sessionId);
+        _lastProgress.TryRemove(sessionId, out _);
+    }
+
+    /// <summary>
+    /// Shows the identify pattern on one mapping's target; false when a player is driving it.
+    /// </summary>
+    public bool Identify(DeviceMapping mapping) => AmbilightInProcessPlayer.TrySendIdentifyPattern(mapping, _logger);
+
+    /// <summary>
+    /// Applies saved device mapping changes to running sessions without stopping playback. Players whose target is
+    /// unchanged switch to the edited mapping in place; targets added to a device start at the current position and
+    /// removed ones stop. Tuning values need no reload, players read them every frame. Returns the number of players
+    /// started, stopped or reloaded.
+    /// </summary>
+    public int ReloadConfiguration()
+    {
+        int changed = 0;
+        foreach (var (sessionId, players) in _sessionPlayers.ToArray())
+        {
+            var running = players.Where(p => p.IsRunning && p.Mapping != null).ToList();
+            var current = running.Select(p => p.GetStatus()).FirstOrDefault(s => !string.IsNullOrEmpty(s.BinPath));
+            if (current == null)
+            {
+                continue;
+            }
+
+            // Jellyfin sessions are matched again like at playback start; other sessions (timecode) keep their devices.
+            var session = _sessionManager.Sessions.FirstOrDefault(s => string.Equals(s.Id, sessionId, StringComparison.Ordinal));
+            var targets = session != null
+                ? ResolveWledTargets(session)
+                : running.Select(p => FindSavedMapping(p.Mapping!)).OfType<DeviceMapping>().ToList();
+
+            var next = new List<AmbilightInProcessPlayer>();
+            foreach (var mapping in targets)
+            {
+                var player = running.FirstOrDefault(p => AmbilightInProcessPlayer.IsSameTarget(p.Mapping!, mapping));
+                if (player != null)
+                {
+                    running.Remove(player);
+                    changed += player.UpdateMapping(mapping) ? 1 : 0;
+                    next.Add(player);
+                    continue;
+                }
+
+                var started = new AmbilightInProcessPlayer(_logger, Config, _binaryCache);
+                started.Start(sessionId, current.BinPath, mapping, current.PositionSeconds);
+                if (current.State == "paused")
                 {
-                    _logger.LogInformation("[Ambilight] Seek detected for session {SessionId} to {Seconds:F1}s", session.Id, currSeconds);
+                    started.SetPaused(true);
                 }
-                inProc.Seek(currSeconds);
+
+                next.Add(started);
+                changed++;
+                _logger.LogInformation("[Ambilight] Reload started a player for session {SessionId} → {Host}:{Port}", sessionId, mapping.Host, mapping.Port);
             }
+
+            foreach (var removed in running)
+            {
+                _logger.LogInformation("[Ambilight] Reload stopped the player for session {SessionId} → {Host}:{Port}", sessionId, removed.Mapping!.Host, removed.Mapping.Port);
+                removed.Stop();
+                changed++;
+            }
+
+            _sessionPlayers[sessionId] = next;
         }
+
+        return changed;
+    }
+
+    private DeviceMapping? FindSavedMapping(DeviceMapping mapping)
+        => Config.DeviceMappings.FirstOrDefault(m =>
+            string.Equals(m.DeviceIdentifier, mapping.DeviceIdentifier, StringComparison.OrdinalIgnoreCase)
+            && string.Equals(m.Host.Trim(), mapping.Host.Trim(), StringComparison.OrdinalIgnoreCase)
+            && m.Port == mapping.Port);
+
+    private int ForEachPlayer(string? sessionId, Action<AmbilightInProcessPlayer> action)
+    {
+        int count = 0;
+        foreach (var (id, players) in _sessionPlayers)
+        {
+            if (!string.IsNullOrEmpty(sessionId) && !string.Equals(id, sessionId, StringComparison.Ordinal))
+            {
+                continue;
+            }
+
+            foreach (var player in players)
+            {
+                action(player);
+                count++;
+            }
+        }
+
+        return count;
+    }
+
+    /// <summary>
+    /// Status of every running player, optionally filtered to one session.
+    /// </summary>
+    public List<AmbilightPlayerStatus> GetStatus(string? sessionId = null)
+    {
+        var result = new List<AmbilightPlayerStatus>();
+        foreach (var (id, players) in _sessionPlayers)
+        {
+            if (!string.IsNullOrEmpty(sessionId) && !string.Equals(id, sessionId, StringComparison.Ordinal))
+            {
+                continue;
+            }
+
+            result.AddRange(players.Select(p => p.GetStatus()));
+        }
+
+        return result;
     }
 
     // Device access is now controlled entirely by device mappings
     // Any device with at least one WLED mapping will have ambilight enabled
 
         private List<DeviceMapping> ResolveWledTargets(SessionInfo session)
+        => ResolveWledTargets(session.DeviceId, session.DeviceName);
+
+    /// <summary>
+    /// Device mappings (one per WLED target) that apply to a client, matched by device id first and then by
+    /// device name, the same way playback sessions are matched. Lets external players pick their profile by client name.
+    /// </summary>
+    public List<DeviceMapping> ResolveWledTargets(string? rawDeviceId, string? rawDeviceName)
     {
         var targets = new List<DeviceMapping>();
         var seenTargets = new HashSet<(string host, int port)>();
         
         // Get session identifiers
-        var deviceId = session.DeviceId ?? string.Empty;
-        var deviceName = session.DeviceName ?? string.Empty;
+        var deviceId = rawDeviceId ?? string.Empty;
+        var deviceName = rawDeviceName ?? string.Empty;
         
         // Normalize deviceId by stripping timestamp (Jellyfin web clients use base64(UserAgent|timestamp))
         var normalizedDeviceId = StripDeviceIdTimestamp(deviceId);
@@ -346,21 +761,28 @@ public class AmbilightPlaybackService
 
     private bool StartPlayersForSession(string sessionId, string binPath, List<DeviceMapping> targets, double startSeconds, CancellationTokenSource? loadingEffectCts = null)
     {
+        // One reference epoch for all of the session's players so they schedule frames in phase.
+        var referenceEpoch = DateTime.UtcNow;
         try
         {
+            if (Config.ResidentPlayers && TryLoadResidentPlayers(sessionId, binPath, targets, startSeconds, loadingEffectCts, referenceEpoch))
+            {
+                return true;
+            }
+
             StopPlayersForSession(sessionId);
 
             var players = new List<AmbilightInProcessPlayer>();
             
             foreach (var mapping in targets)
             {
-                var player = new AmbilightInProcessPlayer(_logger, Config);
-                player.Start(sessionId, binPath, mapping, startSeconds, loadingEffectCts);
+                var player = new AmbilightInProcessPlayer(_logger, Config, _binaryCache);
+                player.Start(sessionId, binPath, mapping, startSeconds, loadingEffectCts, referenceEpoch);
                 players.Add(player);
                 
                 if (Config.Debug)
                 {
-                    int totalLeds = mapping.TopLedCount + mapping.BottomLedCount + mapping.LeftLedCount + mapping.RightLedCount;
+                    int totalLeds = AmbilightInProcessPlayer.StripLedCount(mapping);
                     _logger.LogInformation("[Ambilight] Started player for session {SessionId} → {Host}:{Port} ({Leds} LEDs: T{Top} B{Bottom} L{Left} R{Right})", 
                         sessionId, mapping.Host, mapping.Port, totalLeds, 
                         mapping.TopLedCount, mapping.BottomLedCount, mapping.LeftLedCount, mapping.RightLedCount);
@@ -377,6 +799,119 @@ public class AmbilightPlaybackService
         }
     }
 
+    /// <summary>
+    /// Sends LOAD to the session's existing players when they are still running and drive exactly the
+    /// same targets with the same LED layout. Returns false if new players need to be started.
+    /// </summary>
+    private bool TryLoadResidentPlayers(string sessionId, string binPath, List<DeviceMapping> targets, double startSeconds, CancellationTokenSource? loadingEffectCts, DateTime referenceEpoch)
+    {
+        if (!_sessionPlayers.TryGetValue(sessionId, out var players) || players.Count != targets.Count)
+        {
+            return false;
+        }
+
+        var targetKeys = targets.Select(MappingKey).OrderBy(k => k, StringComparer.Ordinal).ToList();
+        var playerKeys = players.Select(p => p.Mapping == null ? string.Empty : MappingKey(p.Mapping)).OrderBy(k => k, StringComparer.Ordinal).ToList();
+        if (!targetKeys.SequenceEqual(playerKeys) || players.Any(p => !p.IsRunning))
+        {
+            return false;
+        }
+
+        foreach (var player in players)
+        {
+            if (!player.Load(binPath, startSeconds, loadingEffectCts, referenceEpoch))
+            {
+                return false;
+            }
+        }
+
+        _lastProgress.TryRemove(sessionId, out _);
+
+        if (Config.Debug)
+        {
+            _logger.LogInformation("[Ambilight] LOAD {BinPath} at {Seconds:F1}s on {Count} resident player(s) for session {SessionId}",
+                binPath, startSeconds, players.Count, sessionId);
+        }
+
+        return true;
+    }
+
+    private static string MappingKey(DeviceMapping m)
+    {
+        return $"{m.Host.Trim()}:{m.Port}/{m.Protocol}/{m.TopLedCount},{m.BottomLedCount},{m.LeftLedCount},{m.RightLedCount}/{m.InputPosition}";
+    }
+
+    private void UnloadPlayersForSession(string sessionId)
+    {
+        if (!_sessionPlayers.TryGetValue(sessionId, out var players) || players.Count == 0)
+        {
+            return;
+        }
+
+        // Hold the last frame for a moment: if autoplay starts the next item, the player crossfades into it.
+        foreach (var player in players)
+        {
+            player.Unload(AmbilightInProcessPlayer.HandoverGrace);
+        }
+
+        if (Config.Debug)
+        {
+            _logger.LogInformation("[Ambilight] UNLOAD {Count} resident player(s) for session {SessionId}", players.Count, sessionId);
+        }
+    }
+
+    /// <summary>
+    /// Loads the binary of the episode after <paramref name="episode"/> (same series, by season and
+    /// episode number) into the cache so a resident player can switch to it without reading the file.
+    /// </summary>
+    private void PrecacheNextEpisode(Episode episode)
+    {
+        try
+        {
+            var series = episode.Series;
+            if (series == null)
+            {
+                return;
+            }
+
+            var query = new InternalItemsQuery
+            {
+                IncludeItemTypes = new[] { BaseItemKind.Episode },
+                Recursive = true,
+                Parent = series
+            };
+
+            var current = (episode.ParentIndexNumber ?? 0, episode.IndexNumber ?? 0);
+            var next = _libraryManager.GetItemList(query)
+                .OfType<Episode>()
+                .Where(e => e.Id != episode.Id && (e.ParentIndexNumber ?? 0, e.IndexNumber ?? 0).CompareTo(current) > 0)
+                .OrderBy(e => e.ParentIndexNumber ?? 0)
+                .ThenBy(e => e.IndexNumber ?? 0)
+                .FirstOrDefault();
+            if (next == null)
+            {
+                return;
+            }
+
+            var nextBinPath = _storage.ResolvePlaybackSource(next.Id.ToString("N"));
+            if (nextBinPath == null)
+            {
+                return;
+            }
+
+            if (Config.Debug)
+            {
+                _logger.LogInformation("[Ambilight] Pre-caching next episode {Name} ({BinPath})", next.Name, nextBinPath);
+            }
+
+            _binaryCache.Precache(nextBinPath);
+        }
+        catch (Exception ex)
+        {
+            _logger.LogWarning(ex, "[Ambilight] Could not pre-cache next episode after {Name}", episode.Name);
+        }
+    }
+
     private void StopLoadingEffect(string sessionId)
     {
         if (_loadingEffectCancellations.TryRemove(sessionId, out var cts))
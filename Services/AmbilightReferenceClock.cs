@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (C) 2026 Jellyfin Ambilight Contributors
+// This file is part of Jellyfin Ambilight Plugin.
+// Jellyfin Ambilight Plugin is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+using System;
+using System.Diagnostics;
+
+namespace Jellyfin.Plugin.Ambilight.Services;
+
+/// <summary>
+/// Seconds elapsed since a reference epoch, read from the monotonic <see cref="Stopwatch"/> so frame
+/// scheduling is smooth, and slewed towards the system clock every few seconds. Players that schedule
+/// against the same epoch therefore stay in phase for hours instead of each drifting with its own timer.
+/// Not thread-safe; each player owns its instance.
+/// </summary>
+public sealed class AmbilightReferenceClock
+{
+    private const double CalibrationIntervalSeconds = 10.0;
+
+    // The Stopwatch is slewed by at most 1000 ppm; larger differences are system clock steps and ignored.
+    private const double MaxRateError = 0.001;
+    private const double StepThresholdSeconds = 1.0;
+
+    private readonly DateTime _epochUtc;
+    private readonly long _startTimestamp;
+    private double _anchorSeconds;
+    private double _anchorMono;
+    private double _rate = 1.0;
+
+    public AmbilightReferenceClock(DateTime epochUtc)
+    {
+        _epochUtc = epochUtc;
+        _startTimestamp = Stopwatch.GetTimestamp();
+        _anchorSeconds = (DateTime.UtcNow - epochUtc).TotalSeconds;
+    }
+
+    public DateTime EpochUtc => _epochUtc;
+
+    /// <summary>
+    /// Estimated difference between the system clock and this clock at the last calibration.
+    /// </summary>
+    public double LastErrorSeconds { get; private set; }
+
+    /// <summary>
+    /// Seconds since the epoch; never goes backwards.
+    /// </summary>
+    public double Now
+    {
+        get
+        {
+            double mono = Stopwatch.GetElapsedTime(_startTimestamp).TotalSeconds;
+            if (mono - _anchorMono >= CalibrationIntervalSeconds)
+            {
+                Calibrate(mono);
+            }
+
+            return _anchorSeconds + ((mono - _anchorMono) * _rate);
+        }
+    }
+
+    /// <summary>
+    /// Seconds since the epoch at a given system time, e.g. when a seek was requested.
+    /// </summary>
+    public double ToClockSeconds(DateTime utc) => (utc - _epochUtc).TotalSeconds;
+
+    private void Calibrate(double mono)
+    {
+        double estimate = _anchorSeconds + ((mono - _anchorMono) * _rate);
+        double error = (DateTime.UtcNow - _epochUtc).TotalSeconds - estimate;
+        LastErrorSeconds = error;
+
+        // Re-anchor so a rate change never makes the clock jump, then aim to absorb the error over a minute.
+        _anchorSeconds = estimate;
+        _anchorMono = mono;
+        _rate = Math.Abs(error) < StepThresholdSeconds
+            ? 1.0 + Math.Clamp(error / (CalibrationIntervalSeconds * 6.0), -MaxRateError, MaxRateError)
+            : 1.0;
+    }
+}
@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (C) 2026 Jellyfin Ambilight Contributors
+// This file is part of Jellyfin Ambilight Plugin.
+// Jellyfin Ambilight Plugin is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+using System;
+using System.Collections.Generic;
+using System.Linq;
+
+namespace Jellyfin.Plugin.Ambilight.Services;
+
+/// <summary>
+/// Summary of one item played by one player, logged when playback of the item ends and kept in
+/// <see cref="AmbilightPlayerStatus.LastRun"/>. Scheduling error is how far from its due time a frame went out.
+/// </summary>
+public sealed class AmbilightRunReport
+{
+    public string SessionId { get; init; } = string.Empty;
+    public string Target { get; init; } = string.Empty;
+    public string BinPath { get; init; } = string.Empty;
+    public string End { get; init; } = string.Empty; // finished, stopped, unloaded, reloaded, superseded
+    public DateTime StartedUtc { get; init; }
+    public DateTime EndedUtc { get; init; }
+    public long FramesSent { get; init; }
+    public long FramesDropped { get; init; }
+    public long KeepAlivesSent { get; init; }
+    public long SendErrors { get; init; }
+    public double AvgSchedulingErrorMs { get; init; }
+    public double P99SchedulingErrorMs { get; init; }
+    public double MaxSchedulingErrorMs { get; init; }
+    public double AvgProcessingMs { get; init; } // decode, color processing and send, per frame
+}
+
+/// <summary>
+/// Collects per-frame timings for an <see cref="AmbilightRunReport"/>. Owned by the playback loop; not thread-safe.
+/// </summary>
+internal sealed class AmbilightRunStats
+{
+    private readonly List<float> _schedulingErrorsMs = new();
+    private double _processingTotalMs;
+
+    public AmbilightRunStats(string sessionId, string target, string binPath)
+    {
+        SessionId = sessionId;
+        Target = target;
+        BinPath = binPath;
+        StartedUtc = DateTime.UtcNow;
+    }
+
+    public string SessionId { get; }
+    public string Target { get; }
+    public string BinPath { get; }
+    public DateTime StartedUtc { get; }
+    public long FramesDropped { get; set; }
+    public long KeepAlivesSent { get; set; }
+    public long SendErrors { get; set; }
+
+    public void RecordSend(double schedulingErrorSeconds, double processingSeconds)
+    {
+        _schedulingErrorsMs.Add((float)Math.Abs(schedulingErrorSeconds * 1000.0));
+        _processingTotalMs += processingSeconds * 1000.0;
+    }
+
+    public AmbilightRunReport ToReport(string end)
+    {
+        int sent = _schedulingErrorsMs.Count;
+        var sorted = _schedulingErrorsMs.OrderBy(e => e).ToList();
+        return new AmbilightRunReport
+        {
+            SessionId = SessionId,
+            Target = Target,
+            BinPath = BinPath,
+            End = end,
+            StartedUtc = StartedUtc,
+            EndedUtc = DateTime.UtcNow,
+            FramesSent = sent,
+            FramesDropped = FramesDropped,
+            KeepAlivesSent = KeepAlivesSent,
+            SendErrors = SendErrors,
+            AvgSchedulingErrorMs = sent > 0 ? sorted.Average(e => (double)e) : 0.0,
+            P99SchedulingErrorMs = sent > 0 ? sorted[Math.Min(sent - 1, (int)Math.Ceiling(sent * 0.99) - 1)] : 0.0,
+            MaxSchedulingErrorMs = sent > 0 ? sorted[sent - 1] : 0.0,
+            AvgProcessingMs = sent > 0 ? _processingTotalMs / sent : 0.0
+        };
+    }
+}
@@ -7,9 +7,11 @@
 // (at your option) any later version.
 
 using System;
+using System.Collections.Concurrent;
 using System.Collections.Generic;
 using System.IO;
 using System.Linq;
+using System.Security.Cryptography;
 using System.Text.Json;
 using Microsoft.Extensions.Logging;
 
@@ -25,6 +27,21 @@ public class AmbilightStorageService
     private readonly ILogger<AmbilightStorageService> _logger;
     private readonly PluginConfiguration _config;
     private readonly Dictionary<string, (ulong current, ulong total)> _extractionProgressCache = new();
+    private readonly ConcurrentDictionary<string, (long Length, DateTime WriteTimeUtc, bool Valid)> _validationCache = new(StringComparer.Ordinal);
+
+    public const string ChecksumExtension = ".sha256";
+
+    /// <summary>Sidecar recording what a binary was extracted from and with which settings.</summary>
+    public const string ParametersExtension = ".params";
+
+    /// <summary>Sidecar with the chapters of the video a binary was extracted from (JSON list of <see cref="AmbilightChapter"/>).</summary>
+    public const string ChaptersExtension = ".chapters";
+
+    /// <summary>
+    /// Sidecar recording the size and write times of a binary and its checksum sidecar when they last matched, so a
+    /// restart does not hash every binary again.
+    /// </summary>
+    public const string VerifiedExtension = ".verified";
 
     public AmbilightStorageService(ILogger<AmbilightStorageService> logger, PluginConfiguration config)
     {
@@ -191,12 +208,265 @@ public class AmbilightStorageService
         return File.Exists(GetBinaryPath(itemId));
     }
 
+    /// <summary>
+    /// Where playback should read an item's binary from: the local file when it is valid, otherwise
+    /// <c>&lt;RemoteBinaryUrl&gt;/&lt;id&gt;.bin</c> when a remote store is configured, otherwise null.
+    /// </summary>
+    public string? ResolvePlaybackSource(string itemId)
+    {
+        if (BinaryIsValid(itemId))
+        {
+            return GetBinaryPath(itemId);
+        }
+
+        var config = Plugin.Instance?.Configuration ?? _config;
+        var remote = config.RemoteBinaryUrl?.Trim();
+        if (!string.IsNullOrEmpty(remote))
+        {
+            return remote.TrimEnd('/') + "/" + itemId + ".bin";
+        }
+
+        return config.PlayTruncatedBinaries && IsTruncated(itemId, out _) ? GetBinaryPath(itemId) : null;
+    }
+
+    /// <summary>
+    /// True when the item's local binary ends mid-frame (interrupted extraction or copy) but has whole frames up
+    /// to there, so it can still play up to <paramref name="coveredSeconds"/>.
+    /// </summary>
+    public bool IsTruncated(string itemId, out double coveredSeconds)
+    {
+        coveredSeconds = 0.0;
+        if (string.IsNullOrEmpty(itemId)) return false;
+
+        var binPath = GetBinaryPath(itemId);
+        return File.Exists(binPath)
+            && AmbilightBinary.Validate(binPath, out _) == AmbilightBinaryError.FrameSizeMismatch
+            && AmbilightBinary.TryReadExtent(binPath, out _, out coveredSeconds, out _);
+    }
+
+    /// <summary>
+    /// Marks an item as pending with <paramref name="reason"/> as its error, so the next extraction run redoes it.
+    /// </summary>
+    public void MarkForReextraction(string itemId, string reason)
+    {
+        var item = GetItem(itemId);
+        if (item == null || (item.ExtractionStatus == "pending" && item.ExtractionError == reason))
+        {
+            return;
+        }
+
+        item.ExtractionStatus = "pending";
+        item.ExtractionError = reason;
+        SaveOrUpdateItem(item);
+    }
+
+    /// <summary>Gets the path of the checksum sidecar written next to a binary: {DataFolder}/{itemId}.bin.sha256</summary>
+    public string GetChecksumPath(string itemId)
+    {
+        return GetBinaryPath(itemId) + ChecksumExtension;
+    }
+
+    /// <summary>
+    /// True when the binary exists and is intact: it must match its <c>.sha256</c> sidecar, or (for files
+    /// extracted before sidecars existed) have a valid header and a whole number of frames.
+    /// Results are cached per file size and write time, so repeated checks only hash a file once, and a match is also
+    /// recorded in a <see cref="VerifiedExtension"/> sidecar, so it survives a restart.
+    /// </summary>
+    public bool BinaryIsValid(string itemId)
+    {
+        if (string.IsNullOrEmpty(itemId)) return false;
+
+        var binPath = GetBinaryPath(itemId);
+        var info = new FileInfo(binPath);
+        if (!info.Exists)
+        {
+            return false;
+        }
+
+        if (_validationCache.TryGetValue(binPath, out var cached) && cached.Length == info.Length && cached.WriteTimeUtc == info.LastWriteTimeUtc)
+        {
+            return cached.Valid;
+        }
+
+        bool valid;
+        string problem;
+        var checksumPath = GetChecksumPath(itemId);
+        if (File.Exists(checksumPath))
+        {
+            var stamp = VerificationStamp(info, checksumPath);
+            if (ReadVerified(binPath) == stamp)
+            {
+                valid = true;
+                problem = string.Empty;
+            }
+            else
+            {
+                var expected = ReadChecksum(checksumPath);
+                valid = expected != null && string.Equals(expected, ComputeChecksum(binPath), StringComparison.OrdinalIgnoreCase);
+                problem = expected == null ? $"unreadable checksum sidecar {checksumPath}" : "contents do not match the checksum sidecar";
+                if (valid)
+                {
+                    WriteVerified(binPath, stamp);
+                }
+            }
+        }
+        else
+        {
+            valid = AmbilightBinary.Validate(binPath, out problem) == AmbilightBinaryError.None;
+        }
+
+        if (!valid)
+        {
+            _logger.LogWarning("[Ambilight] Binary for item {ItemId} is corrupt or incomplete ({Problem}); it will be re-extracted", itemId, problem);
+        }
+
+        _validationCache[binPath] = (info.Length, info.LastWriteTimeUtc, valid);
+        return valid;
+    }
+
+    /// <summary>Deletes an item's binary and its checksum sidecar, if present.</summary>
+    public void DeleteBinary(string itemId)
+    {
+        var binPath = GetBinaryPath(itemId);
+        if (File.Exists(binPath))
+        {
+            File.Delete(binPath);
+        }
+
+        var checksumPath = GetChecksumPath(itemId);
+        if (File.Exists(checksumPath))
+        {
+            File.Delete(checksumPath);
+        }
+
+        var parametersPath = binPath + ParametersExtension;
+        if (File.Exists(parametersPath))
+        {
+            File.Delete(parametersPath);
+        }
+
+        var chaptersPath = binPath + ChaptersExtension;
+        if (File.Exists(chaptersPath))
+        {
+            File.Delete(chaptersPath);
+        }
+
+        File.Delete(binPath + VerifiedExtension);
+        _validationCache.TryRemove(binPath, out _);
+    }
+
+    /// <summary>
+    /// Writes a sha256sum-compatible sidecar ("hex  filename") for a finished binary.
+    /// </summary>
+    public static void WriteChecksum(string binPath, byte[] contents)
+        => WriteChecksumFile(binPath, Convert.ToHexString(SHA256.HashData(contents)));
+
+    /// <summary>
+    /// Writes the checksum sidecar from a SHA-256 hash computed while the binary was written.
+    /// </summary>
+    public static void WriteChecksumForHash(string binPath, byte[] sha256)
+        => WriteChecksumFile(binPath, Convert.ToHexString(sha256));
+
+    /// <summary>
+    /// Writes the checksum sidecar for a binary that is already on disk, hashing the file instead of a buffer.
+    /// </summary>
+    public static void WriteChecksum(string binPath)
+        => WriteChecksumFile(binPath, ComputeChecksum(binPath));
+
+    /// <summary>True when <paramref name="binPath"/> has a checksum sidecar and its contents match it.</summary>
+    public static bool ChecksumMatches(string binPath)
+    {
+        var checksumPath = binPath + ChecksumExtension;
+        if (!File.Exists(binPath) || !File.Exists(checksumPath))
+        {
+            return false;
+        }
+
+        var expected = ReadChecksum(checksumPath);
+        return expected != null && string.Equals(expected, ComputeChecksum(binPath), StringComparison.OrdinalIgnoreCase);
+    }
+
+    private static void WriteChecksumFile(string binPath, string hash)
+    {
+        var tempPath = binPath + ChecksumExtension + ".tmp";
+        File.WriteAllText(tempPath, hash.ToLowerInvariant() + "  " + Path.GetFileName(binPath) + "\n");
+        File.Move(tempPath, binPath + ChecksumExtension, overwrite: true);
+    }
+
+    private static string ComputeChecksum(string path)
+    {
+        using var stream = File.OpenRead(path);
+        return Convert.ToHexString(SHA256.HashData(stream));
+    }
+
+    private static string VerificationStamp(FileInfo binary, string checksumPath)
+        => FormattableString.Invariant($"{binary.Length} {binary.LastWriteTimeUtc.Ticks} {File.GetLastWriteTimeUtc(checksumPath).Ticks}");
+
+    private static string? ReadVerified(string binPath)
+    {
+        try
+        {
+            var path = binPath + VerifiedExtension;
+            return File.Exists(path) ? File.ReadAllText(path).Trim() : null;
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException)
+        {
+            return null;
+        }
+    }
+
+    private void WriteVerified(string binPath, string stamp)
+    {
+        try
+        {
+            File.WriteAllText(binPath + VerifiedExtension, stamp + "\n");
+        }
+        catch (Exception ex) when (ex is IOException or UnauthorizedAccessException)
+        {
+            // Only costs a hash at the next restart.
+            _logger.LogDebug(ex, "[Ambilight] Could not record the verified checksum of {Path}", binPath);
+        }
+    }
+
+    private static string? ReadChecksum(string checksumPath)
+    {
+        try
+        {
+            var text = File.ReadAllText(checksumPath).Trim();
+            var hash = text.Split(' ', 2)[0];
+            return hash.Length == 64 ? hash : null;
+        }
+        catch (IOException)
+        {
+            return null;
+        }
+    }
+
+    /// <summary>
+    /// The chapters sidecar of <paramref name="binPath"/>, in order; empty when the video has no chapters, the binary
+    /// was extracted before chapters were copied, or the sidecar cannot be read.
+    /// </summary>
+    public static List<AmbilightChapter> ReadChapters(string binPath)
+    {
+        try
+        {
+            var chaptersPath = binPath + ChaptersExtension;
+            return File.Exists(chaptersPath)
+                ? JsonSerializer.Deserialize<List<AmbilightChapter>>(File.ReadAllText(chaptersPath))?.OrderBy(c => c.StartSeconds).ToList() ?? new()
+                : new();
+        }
+        catch (Exception ex) when (ex is IOException or JsonException or UnauthorizedAccessException)
+        {
+            return new();
+        }
+    }
+
     public StorageStatistics GetStatistics()
     {
         var items = EnumerateItems().ToList();
         var totalVideos = items.Count;
-        var extracted = items.Count(i => i.ExtractionStatus == "completed" && BinaryExists(i.Id));
-        var failed = items.Count(i => i.ExtractionStatus == "failed");
+        var extracted = items.Count(i => i.ExtractionStatus == "completed" && BinaryIsValid(i.Id));
+        var failed = items.Count(i => i.ExtractionStatus == "failed" || i.ExtractionStatus == "not_extractable");
 
         return new StorageStatistics
         {
@@ -227,6 +497,25 @@ public class AmbilightItem
     public int ExtractionProgress { get; set; } = 0; // 0-100 percentage (deprecated, use frames)
     public ulong ExtractionFramesCurrent { get; set; } = 0; // Current frame count
     public ulong ExtractionFramesTotal { get; set; } = 0; // Total estimated frames
+    public List<AmbilightPictureArea> PictureAreas { get; set; } = new(); // Picture area timeline; empty when black bars were not tracked
+}
+
+/// <summary>A chapter of the source video, copied from its container at extraction.</summary>
+public class AmbilightChapter
+{
+    public double StartSeconds { get; set; }
+    public double EndSeconds { get; set; }
+    public string Title { get; set; } = string.Empty;
+}
+
+/// <summary>Picture area inside black bars from <see cref="StartSeconds"/> on, as fractions of the frame.</summary>
+public class AmbilightPictureArea
+{
+    public double StartSeconds { get; set; }
+    public double Left { get; set; }
+    public double Top { get; set; }
+    public double Right { get; set; } = 1;
+    public double Bottom { get; set; } = 1;
 }
 
 public class StorageStatistics
@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (C) 2026 Jellyfin Ambilight Contributors
+// This file is part of Jellyfin Ambilight Plugin.
+// Jellyfin Ambilight Plugin is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+using System;
+using System.Globalization;
+
+namespace Jellyfin.Plugin.Ambilight.Services;
+
+/// <summary>
+/// SMPTE timecode (as carried by LTC and MIDI Timecode) to media seconds.
+/// </summary>
+public static class AmbilightTimecode
+{
+    /// <summary>
+    /// Parses <c>HH:MM:SS:FF</c> at <paramref name="fps"/> frames per second into seconds. A <c>;</c> or <c>.</c>
+    /// before the frames marks drop-frame timecode (29.97/59.94), where frame labels 0 and 1 (0–3 at 59.94) are
+    /// skipped every minute except every tenth. Returns false for malformed timecode or a frame number out of range.
+    /// </summary>
+    public static bool TryParse(string? timecode, double fps, out double seconds)
+    {
+        seconds = 0.0;
+        if (string.IsNullOrWhiteSpace(timecode) || !double.IsFinite(fps) || fps < 1.0 || fps > 120.0)
+        {
+            return false;
+        }
+
+        var text = timecode.Trim();
+        bool dropFrame = text.Length > 3 && (text[^3] == ';' || text[^3] == '.');
+        var parts = text.Split(':', ';', '.');
+        if (parts.Length != 4)
+        {
+            return false;
+        }
+
+        var values = new int[4];
+        for (int i = 0; i < 4; i++)
+        {
+            if (!int.TryParse(parts[i], NumberStyles.None, CultureInfo.InvariantCulture, out values[i]))
+            {
+                return false;
+            }
+        }
+
+        int hours = values[0], minutes = values[1], secs = values[2], frames = values[3];
+        int nominal = (int)Math.Round(fps);
+        if (minutes > 59 || secs > 59 || frames >= nominal)
+        {
+            return false;
+        }
+
+        long totalMinutes = (hours * 60L) + minutes;
+        long frameNumber = ((totalMinutes * 60L) + secs) * nominal + frames;
+        if (dropFrame)
+        {
+            int dropped = (int)Math.Round(fps * 0.066666);
+            frameNumber -= dropped * (totalMinutes - (totalMinutes / 10));
+        }
+
+        seconds = frameNumber / fps;
+        return true;
+    }
+}
@@ -0,0 +1,541 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (C) 2026 Jellyfin Ambilight Contributors
+// This file is part of Jellyfin Ambilight Plugin.
+// Jellyfin Ambilight Plugin is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+using System;
+using System.Buffers;
+using System.Net;
+using System.Net.Sockets;
+using System.Text;
+using System.Threading;
+using System.Threading.Tasks;
+using Microsoft.Extensions.Logging;
+
+namespace Jellyfin.Plugin.Ambilight.Services;
+
+/// <summary>
+/// UDP output for LED frames. Wraps the socket and frames each LED buffer according to the
+/// protocol selected on the device mapping, so the player does not care whether the
+/// controller runs WLED or ESPHome.
+/// </summary>
+public sealed class AmbilightUdpSink : IDisposable
+{
+    /// <summary>Plain RGB(W) bytes, no header (WLED "UDP raw" / Hyperion, default port 19446).</summary>
+    public const string ProtocolRaw = "raw";
+
+    /// <summary>WLED realtime UDP protocol (DRGB/DRGBW/DNRGB, port 21324). Understood by ESPHome's <c>wled</c> effect.</summary>
+    public const string ProtocolDrgb = "drgb";
+
+    /// <summary>E1.31 / sACN data packets (port 5568). Understood by ESPHome's <c>e131</c> effect.</summary>
+    public const string ProtocolE131 = "e131";
+
+    /// <summary>Adalight framing ("Ada" header + checksum) sent as a UDP datagram, for ESPHome <c>adalight</c> bridges.</summary>
+    public const string ProtocolAdalight = "adalight";
+
+    // WLED realtime protocol ids and per-packet limits.
+    private const byte WledDrgb = 2;
+    private const byte WledDrgbw = 3;
+    private const byte WledDnrgb = 4;
+    private const int DrgbMaxLeds = 490;
+    private const int DrgbwMaxLeds = 367;
+    private const int DnrgbMaxLedsPerPacket = 489;
+
+    // Seconds WLED/ESPHome stay in realtime mode after the last packet.
+    private const byte RealtimeTimeoutSeconds = 2;
+
+    private const int E131HeaderSize = 126;
+    private static readonly byte[] E131SourceName = Encoding.ASCII.GetBytes("Jellyfin Ambilight");
+    private const int E131MaxChannels = 512;
+
+    private static readonly byte[] E131PacketIdentifier =
+    {
+        0x41, 0x53, 0x43, 0x2d, 0x45, 0x31, 0x2e, 0x31, 0x37, 0x00, 0x00, 0x00
+    };
+
+    // WLED reboots (firmware update, power cut) and DHCP renewals must not end playback. Hostnames are looked up
+    // again every ReResolveInterval and the socket moves when the address changes; after ReconnectAfterFailures
+    // sends in a row fail (ICMP port unreachable shows up as ConnectionRefused on a connected socket) the socket
+    // is recreated, after a fresh lookup for hostnames. Frames sent in between are simply lost.
+    private static readonly TimeSpan ReResolveInterval = TimeSpan.FromSeconds(60);
+    private const int ReconnectAfterFailures = 5;
+
+    // IPV6_TCLASS has no SocketOptionName, and its number differs per OS.
+    private const int Ipv6TrafficClassLinux = 67;
+    private const int Ipv6TrafficClassMacOs = 36;
+    private const int Ipv6TrafficClassWindows = 39;
+
+    private readonly string? _host;
+    private readonly int _port;
+    private readonly ILogger? _logger;
+    private readonly int _dscp;
+    private readonly object _connectLock = new();
+    private volatile UdpClient _udp;
+    private IPAddress _address;
+    private int _consecutiveFailures;
+    private int _reconnecting;
+    private long _nextResolveTicks;
+    private bool _disposed;
+    private readonly string _protocol;
+    private readonly int _ledOffset;
+    private readonly byte[] _cid = Guid.NewGuid().ToByteArray();
+    // Frames can come from the playback loop, the watchdog and keep-alives at once, so the sequence is an int bumped
+    // atomically and only its low byte goes on the wire.
+    private int _e131Sequence;
+
+    /// <param name="ledOffset">
+    /// Index of the first strip LED this sink drives, for playing onto a segment of a longer strip.
+    /// Only the WLED realtime protocol can address an offset (via DNRGB); other protocols ignore it.
+    /// </param>
+    /// <param name="host">
+    /// Host name <paramref name="address"/> was resolved from, to look it up again while the sink is open.
+    /// Null (or an IP literal) keeps the address fixed; the socket is still recreated after repeated failures.
+    /// </param>
+    /// <param name="dscp">DSCP code point (0-63) to mark the packets with, 0 = leave the OS default.</param>
+    public AmbilightUdpSink(IPAddress address, int port, string? protocol, int ledOffset = 0, string? host = null, ILogger? logger = null, int dscp = 0)
+    {
+        address = Normalize(address);
+        _address = address;
+        _port = port;
+        _host = string.IsNullOrWhiteSpace(host) || TryParseAddress(host, out _) ? null : host.Trim();
+        _logger = logger;
+        _dscp = Math.Clamp(dscp, 0, 63);
+        _udp = Connect(address, port);
+        _nextResolveTicks = DateTime.UtcNow.Add(ReResolveInterval).Ticks;
+        _protocol = NormalizeProtocol(protocol);
+        _ledOffset = _protocol == ProtocolDrgb ? Math.Clamp(ledOffset, 0, ushort.MaxValue) : 0;
+    }
+
+    public string Protocol => _protocol;
+
+    /// <summary>Address the sink currently sends to.</summary>
+    public IPAddress Address => _address;
+
+    /// <summary>Number of times the socket was recreated or moved to a new address.</summary>
+    public int Reconnects { get; private set; }
+
+    /// <summary>
+    /// Parses an IP literal as typed into a device mapping: surrounding spaces and IPv6 brackets
+    /// (<c>[fd00::12]</c>) are accepted, as is a zone for link-local addresses (<c>fe80::1%eth0</c>).
+    /// </summary>
+    public static bool TryParseAddress(string? host, out IPAddress address)
+    {
+        var text = (host ?? string.Empty).Trim();
+        if (text.Length > 1 && text[0] == '[' && text[^1] == ']')
+        {
+            text = text[1..^1];
+        }
+
+        if (IPAddress.TryParse(text, out var parsed))
+        {
+            address = Normalize(parsed);
+            return true;
+        }
+
+        address = IPAddress.None;
+        return false;
+    }
+
+    /// <summary>
+    /// Address for a device mapping host: the literal itself, or the preferred DNS answer (see <see cref="PickAddress"/>).
+    /// Throws <see cref="SocketException"/> when the name cannot be resolved.
+    /// </summary>
+    public static IPAddress? ResolveHost(string host)
+        => TryParseAddress(host, out var address) ? address : PickAddress(Dns.GetHostAddresses(host.Trim()));
+
+    /// <summary>
+    /// Host part of a URL for a device mapping host, bracketing IPv6 literals (<c>http://[fd00::12]/json</c>).
+    /// </summary>
+    public static string UrlHost(string host)
+    {
+        if (TryParseAddress(host, out var address) && address.AddressFamily == AddressFamily.InterNetworkV6)
+        {
+            return "[" + address.ToString().Replace("%", "%25", StringComparison.Ordinal) + "]";
+        }
+
+        return host.Trim();
+    }
+
+    /// <summary>
+    /// Picks the address to use from a DNS answer: IPv4 when there is one, otherwise the first entry.
+    /// </summary>
+    public static IPAddress? PickAddress(IPAddress[] addresses)
+    {
+        foreach (var address in addresses)
+        {
+            if (address.AddressFamily == AddressFamily.InterNetwork)
+            {
+                return address;
+            }
+        }
+
+        return addresses.Length > 0 ? Normalize(addresses[0]) : null;
+    }
+
+    /// <summary>True when the protocol can place the frame at a non-zero LED offset.</summary>
+    public static bool SupportsLedOffset(string? protocol) => NormalizeProtocol(protocol) == ProtocolDrgb;
+
+    /// <summary>
+    /// Maps user input to one of the supported protocol names, falling back to raw.
+    /// </summary>
+    public static string NormalizeProtocol(string? protocol)
+    {
+        return (protocol ?? string.Empty).Trim().ToLowerInvariant() switch
+        {
+            ProtocolDrgb or "wled" or "dnrgb" => ProtocolDrgb,
+            ProtocolE131 or "e1.31" or "sacn" => ProtocolE131,
+            ProtocolAdalight or "ada" => ProtocolAdalight,
+            _ => ProtocolRaw
+        };
+    }
+
+    /// <summary>
+    /// Sends one LED frame (<paramref name="ledCount"/> LEDs of <paramref name="bytesPerLed"/> bytes each).
+    /// Protocols with per-packet limits split the frame into several datagrams.
+    /// Send errors are rethrown, but a run of them makes the sink reconnect.
+    /// </summary>
+    public async Task SendFrameAsync(byte[] frame, int ledCount, int bytesPerLed)
+    {
+        if (_host != null && DateTime.UtcNow.Ticks >= Interlocked.Read(ref _nextResolveTicks))
+        {
+            Interlocked.Exchange(ref _nextResolveTicks, DateTime.UtcNow.Add(ReResolveInterval).Ticks);
+            _ = ReconnectAsync(force: false);
+        }
+
+        try
+        {
+            await SendFrameCoreAsync(frame, ledCount, bytesPerLed).ConfigureAwait(false);
+            Interlocked.Exchange(ref _consecutiveFailures, 0);
+        }
+        catch (Exception ex) when ((ex is SocketException or ObjectDisposedException) && !_disposed)
+        {
+            if (Interlocked.Increment(ref _consecutiveFailures) >= ReconnectAfterFailures)
+            {
+                Interlocked.Exchange(ref _consecutiveFailures, 0);
+                _logger?.LogWarning("[Ambilight] {Count} sends to {Address}:{Port} failed in a row ({Message}); reconnecting", ReconnectAfterFailures, _address, _port, ex.Message);
+                _ = ReconnectAsync(force: true);
+            }
+
+            throw;
+        }
+    }
+
+    private async Task SendFrameCoreAsync(byte[] frame, int ledCount, int bytesPerLed)
+    {
+        int length = Math.Min(frame.Length, ledCount * bytesPerLed);
+
+        switch (_protocol)
+        {
+            case ProtocolDrgb:
+                await SendWledRealtimeAsync(frame, ledCount, bytesPerLed).ConfigureAwait(false);
+                break;
+
+            case ProtocolE131:
+                await SendE131Async(frame, length, bytesPerLed).ConfigureAwait(false);
+                break;
+
+            case ProtocolAdalight:
+                await SendAdalightAsync(frame, ledCount, bytesPerLed).ConfigureAwait(false);
+                break;
+
+            default:
+                await _udp.SendAsync(frame, length).ConfigureAwait(false);
+                break;
+        }
+    }
+
+    public void Dispose()
+    {
+        lock (_connectLock)
+        {
+            _disposed = true;
+            _udp.Dispose();
+        }
+    }
+
+    // IPv4-mapped IPv6 addresses (::ffff:192.168.1.5) go out over a plain IPv4 socket.
+    private static IPAddress Normalize(IPAddress address)
+        => address.IsIPv4MappedToIPv6 ? address.MapToIPv4() : address;
+
+    private UdpClient Connect(IPAddress address, int port)
+    {
+        // IPv6 targets get a socket on [::], IPv4 targets one on 0.0.0.0; the OS picks the outgoing interface.
+        var udp = new UdpClient(address.AddressFamily);
+        try
+        {
+            udp.Client.Bind(new IPEndPoint(address.AddressFamily == AddressFamily.InterNetworkV6 ? IPAddress.IPv6Any : IPAddress.Any, 0));
+            ApplyDscp(udp.Client);
+            udp.Connect(address, port);
+        }
+        catch
+        {
+            udp.Dispose();
+            throw;
+        }
+
+        return udp;
+    }
+
+    /// <summary>
+    /// Marks outgoing packets with <see cref="_dscp"/> (the upper six bits of the IPv4 TOS / IPv6 traffic class byte),
+    /// so Wi-Fi access points that honor WMM can put LED traffic in the video or voice queue. Best effort: a platform
+    /// that refuses the option only gets a warning.
+    /// </summary>
+    private void ApplyDscp(Socket socket)
+    {
+        if (_dscp == 0)
+        {
+            return;
+        }
+
+        int trafficClass = _dscp << 2;
+        try
+        {
+            if (socket.AddressFamily == AddressFamily.InterNetworkV6)
+            {
+                int option = OperatingSystem.IsWindows() ? Ipv6TrafficClassWindows
+                    : OperatingSystem.IsMacOS() ? Ipv6TrafficClassMacOs
+                    : Ipv6TrafficClassLinux;
+                socket.SetRawSocketOption((int)SocketOptionLevel.IPv6, option, BitConverter.GetBytes(trafficClass));
+            }
+            else
+            {
+                socket.SetSocketOption(SocketOptionLevel.IP, SocketOptionName.TypeOfService, trafficClass);
+            }
+        }
+        catch (Exception ex) when (ex is SocketException or PlatformNotSupportedException)
+        {
+            _logger?.LogWarning("[Ambilight] Cannot set DSCP {Dscp} on the UDP socket: {Message}", _dscp, ex.Message);
+        }
+    }
+
+    /// <summary>
+    /// Looks the host up again (when there is one) and moves the socket if the address changed, or in any case
+    /// when <paramref name="force"/> is set. Runs off the send path; a lookup or reconnect already running wins.
+    /// </summary>
+    private async Task ReconnectAsync(bool force)
+    {
+        if (Interlocked.Exchange(ref _reconnecting, 1) == 1)
+        {
+            return;
+        }
+
+        try
+        {
+            var address = _address;
+            if (_host != null)
+            {
+                try
+                {
+                    address = PickAddress(await Dns.GetHostAddressesAsync(_host).ConfigureAwait(false)) ?? address;
+                }
+                catch (SocketException ex)
+                {
+                    _logger?.LogWarning("[Ambilight] Cannot resolve WLED host \"{Host}\" again: {Message}; keeping {Address}", _host, ex.Message, address);
+                }
+            }
+
+            if (!force && address.Equals(_address))
+            {
+                return;
+            }
+
+            var udp = Connect(address, _port);
+            UdpClient old;
+            lock (_connectLock)
+            {
+                if (_disposed)
+                {
+                    udp.Dispose();
+                    return;
+                }
+
+                old = _udp;
+                _udp = udp;
+                _address = address;
+                Reconnects++;
+            }
+
+            old.Dispose();
+            _logger?.LogInformation("[Ambilight] Reconnected to {Host} at {Address}:{Port}", _host ?? address.ToString(), address, _port);
+        }
+        catch (Exception ex) when (ex is SocketException or ObjectDisposedException)
+        {
+            _logger?.LogWarning("[Ambilight] Reconnecting to {Address}:{Port} failed: {Message}", _address, _port, ex.Message);
+        }
+        finally
+        {
+            Interlocked.Exchange(ref _reconnecting, 0);
+        }
+    }
+
+    private async Task SendWledRealtimeAsync(byte[] frame, int ledCount, int bytesPerLed)
+    {
+        // DRGB/DRGBW always start at LED 0, so a segment offset forces DNRGB.
+        // Packets are built in pooled buffers: a frame goes out many times a second and the sends are awaited,
+        // so nothing is allocated per frame. The watchdog may send concurrently, hence a pool instead of one buffer.
+        if (_ledOffset == 0 && bytesPerLed is 3 or 4 && ledCount <= (bytesPerLed == 4 ? DrgbwMaxLeds : DrgbMaxLeds))
+        {
+            int length = 2 + (ledCount * bytesPerLed);
+            var packet = ArrayPool<byte>.Shared.Rent(length);
+            try
+            {
+                packet[0] = bytesPerLed == 4 ? WledDrgbw : WledDrgb;
+                packet[1] = RealtimeTimeoutSeconds;
+                Buffer.BlockCopy(frame, 0, packet, 2, ledCount * bytesPerLed);
+                await _udp.SendAsync(packet, length).ConfigureAwait(false);
+            }
+            finally
+            {
+                ArrayPool<byte>.Shared.Return(packet);
+            }
+
+            return;
+        }
+
+        // Long strips (or RGBW beyond the DRGBW limit): DNRGB chunks with a start index.
+        // DNRGB has no white channel, so fold W back into RGB.
+        var chunk = ArrayPool<byte>.Shared.Rent(4 + (DnrgbMaxLedsPerPacket * 3));
+        try
+        {
+            for (int start = 0; start < ledCount; start += DnrgbMaxLedsPerPacket)
+            {
+                int count = Math.Min(DnrgbMaxLedsPerPacket, ledCount - start);
+                int index = _ledOffset + start;
+                if (index > ushort.MaxValue)
+                {
+                    break;
+                }
+
+                await _udp.SendAsync(chunk, BuildDnrgbPacket(chunk, frame, start, count, index, bytesPerLed)).ConfigureAwait(false);
+            }
+        }
+        finally
+        {
+            ArrayPool<byte>.Shared.Return(chunk);
+        }
+    }
+
+    private static int BuildDnrgbPacket(byte[] packet, byte[] frame, int start, int count, int index, int bytesPerLed)
+    {
+        packet[0] = WledDnrgb;
+        packet[1] = RealtimeTimeoutSeconds;
+        packet[2] = (byte)(index >> 8);
+        packet[3] = (byte)(index & 0xFF);
+
+        for (int i = 0; i < count; i++)
+        {
+            int src = (start + i) * bytesPerLed;
+            int w = bytesPerLed == 4 ? frame[src + 3] : 0;
+            packet[4 + i * 3] = (byte)Math.Min(255, frame[src] + w);
+            packet[4 + i * 3 + 1] = (byte)Math.Min(255, frame[src + 1] + w);
+            packet[4 + i * 3 + 2] = (byte)Math.Min(255, frame[src + 2] + w);
+        }
+
+        return 4 + (count * 3);
+    }
+
+    private async Task SendE131Async(byte[] frame, int length, int bytesPerLed)
+    {
+        // Never split an LED across universes: 170 RGB or 128 RGBW LEDs per universe.
+        int channelsPerUniverse = (E131MaxChannels / bytesPerLed) * bytesPerLed;
+        ushort universe = 1;
+        byte sequence = (byte)(Interlocked.Increment(ref _e131Sequence) & 0xFF);
+
+        var packet = ArrayPool<byte>.Shared.Rent(E131HeaderSize + channelsPerUniverse);
+        try
+        {
+            for (int offset = 0; offset < length; offset += channelsPerUniverse)
+            {
+                int channels = Math.Min(channelsPerUniverse, length - offset);
+                await _udp.SendAsync(packet, BuildE131Packet(packet, frame, offset, channels, universe, sequence)).ConfigureAwait(false);
+                universe++;
+            }
+        }
+        finally
+        {
+            ArrayPool<byte>.Shared.Return(packet);
+        }
+    }
+
+    private int BuildE131Packet(byte[] p, byte[] frame, int offset, int channels, ushort universe, byte sequence)
+    {
+        int packetLength = E131HeaderSize + channels;
+        Array.Clear(p, 0, E131HeaderSize); // pooled buffer: unset header fields must be zero
+
+        // Root layer
+        p[1] = 0x10; // preamble size
+        Buffer.BlockCopy(E131PacketIdentifier, 0, p, 4, E131PacketIdentifier.Length);
+        WriteFlagsAndLength(p, 16, packetLength - 16);
+        p[21] = 0x04; // VECTOR_ROOT_E131_DATA
+        Buffer.BlockCopy(_cid, 0, p, 22, 16);
+
+        // Framing layer
+        WriteFlagsAndLength(p, 38, packetLength - 38);
+        p[43] = 0x02; // VECTOR_E131_DATA_PACKET
+        Buffer.BlockCopy(E131SourceName, 0, p, 44, Math.Min(E131SourceName.Length, 63));
+        p[108] = 100; // priority
+        p[111] = sequence;
+        p[113] = (byte)(universe >> 8);
+        p[114] = (byte)(universe & 0xFF);
+
+        // DMP layer
+        WriteFlagsAndLength(p, 115, packetLength - 115);
+        p[117] = 0x02; // VECTOR_DMP_SET_PROPERTY
+        p[118] = 0xA1; // address & data type
+        p[122] = 0x01; // address increment
+        int propertyCount = channels + 1;
+        p[123] = (byte)(propertyCount >> 8);
+        p[124] = (byte)(propertyCount & 0xFF);
+        // p[125] = DMX start code 0
+
+        Buffer.BlockCopy(frame, offset, p, E131HeaderSize, channels);
+        return packetLength;
+    }
+
+    private static void WriteFlagsAndLength(byte[] packet, int index, int length)
+    {
+        int value = 0x7000 | (length & 0x0FFF);
+        packet[index] = (byte)(value >> 8);
+        packet[index + 1] = (byte)(value & 0xFF);
+    }
+
+    private async Task SendAdalightAsync(byte[] frame, int ledCount, int bytesPerLed)
+    {
+        int length = 6 + (ledCount * 3);
+        var packet = ArrayPool<byte>.Shared.Rent(length);
+        try
+        {
+            BuildAdalightPacket(packet, frame, ledCount, bytesPerLed);
+            await _udp.SendAsync(packet, length).ConfigureAwait(false);
+        }
+        finally
+        {
+            ArrayPool<byte>.Shared.Return(packet);
+        }
+    }
+
+    private static void BuildAdalightPacket(byte[] packet, byte[] frame, int ledCount, int bytesPerLed)
+    {
+        // Adalight is RGB only; fold W back into RGB like DNRGB.
+        int n = Math.Max(0, ledCount - 1);
+        packet[0] = (byte)'A';
+        packet[1] = (byte)'d';
+        packet[2] = (byte)'a';
+        packet[3] = (byte)(n >> 8);
+        packet[4] = (byte)(n & 0xFF);
+        packet[5] = (byte)(packet[3] ^ packet[4] ^ 0x55);
+
+        for (int i = 0; i < ledCount; i++)
+        {
+            int src = i * bytesPerLed;
+            int w = bytesPerLed == 4 ? frame[src + 3] : 0;
+            packet[6 + i * 3] = (byte)Math.Min(255, frame[src] + w);
+            packet[6 + i * 3 + 1] = (byte)Math.Min(255, frame[src + 1] + w);
+            packet[6 + i * 3 + 2] = (byte)Math.Min(255, frame[src + 2] + w);
+        }
+    }
+}
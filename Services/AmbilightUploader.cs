@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (C) 2026 Jellyfin Ambilight Contributors
+// This file is part of Jellyfin Ambilight Plugin.
+// Jellyfin Ambilight Plugin is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+using System;
+using System.IO;
+using System.Net;
+using System.Net.Http;
+using System.Net.Http.Headers;
+using System.Threading;
+using System.Threading.Tasks;
+using Microsoft.Extensions.Logging;
+
+namespace Jellyfin.Plugin.Ambilight.Services;
+
+/// <summary>
+/// Copies finished binaries (and their checksum sidecar) to a remote store with HTTP PUT, for setups where
+/// extraction runs on a different machine than the Jellyfin server that plays them. Works with WebDAV and any
+/// HTTP endpoint that accepts PUT to <c>&lt;UploadUrl&gt;/&lt;file name&gt;</c>.
+/// </summary>
+public static class AmbilightUploader
+{
+    private const int MaxAttempts = 4;
+
+    private static readonly HttpClient Http = new() { Timeout = TimeSpan.FromMinutes(10) };
+
+    /// <summary>
+    /// Uploads <paramref name="binPath"/> and, if present, its <c>.sha256</c> sidecar. Returns false when an upload
+    /// still fails after retries; transient errors (network, 408, 429, 5xx) are retried with exponential backoff.
+    /// Does nothing and returns true when <see cref="PluginConfiguration.UploadUrl"/> is empty. Never throws for
+    /// cancellation; the caller's extraction has already succeeded.
+    /// </summary>
+    public static async Task<bool> UploadAsync(string binPath, PluginConfiguration config, ILogger logger, CancellationToken cancellationToken)
+    {
+        var baseUrl = config.UploadUrl?.Trim();
+        if (string.IsNullOrEmpty(baseUrl))
+        {
+            return true;
+        }
+
+        var checksumPath = binPath + AmbilightStorageService.ChecksumExtension;
+        try
+        {
+            bool ok = await PutFileAsync(baseUrl, binPath, "application/octet-stream", config, logger, cancellationToken).ConfigureAwait(false);
+            if (ok && File.Exists(checksumPath))
+            {
+                ok = await PutFileAsync(baseUrl, checksumPath, "text/plain", config, logger, cancellationToken).ConfigureAwait(false);
+            }
+
+            return ok;
+        }
+        catch (OperationCanceledException)
+        {
+            // Shutdown during an upload must not turn a finished extraction into a cancelled one.
+            logger.LogInformation("[Ambilight] Upload of {File} cancelled", Path.GetFileName(binPath));
+            return false;
+        }
+    }
+
+    private static async Task<bool> PutFileAsync(string baseUrl, string path, string contentType, PluginConfiguration config, ILogger logger, CancellationToken cancellationToken)
+    {
+        var url = baseUrl.TrimEnd('/') + "/" + Uri.EscapeDataString(Path.GetFileName(path));
+        for (int attempt = 1; attempt <= MaxAttempts; attempt++)
+        {
+            string failure;
+            try
+            {
+                await using var stream = File.OpenRead(path);
+                using var request = new HttpRequestMessage(HttpMethod.Put, url) { Content = new StreamContent(stream) };
+                request.Content.Headers.ContentType = new MediaTypeHeaderValue(contentType);
+                if (!string.IsNullOrWhiteSpace(config.UploadAuthorization))
+                {
+                    request.Headers.TryAddWithoutValidation("Authorization", config.UploadAuthorization.Trim());
+                }
+
+                using var response = await Http.SendAsync(request, cancellationToken).ConfigureAwait(false);
+                if (response.IsSuccessStatusCode)
+                {
+                    if (config.Debug)
+                    {
+                        logger.LogInformation("[Ambilight] Uploaded {File} to {Url}", Path.GetFileName(path), url);
+                    }
+
+                    return true;
+                }
+
+                var status = (int)response.StatusCode;
+                failure = $"HTTP {status} {response.ReasonPhrase}";
+                if (status < 500 && response.StatusCode != HttpStatusCode.RequestTimeout && response.StatusCode != HttpStatusCode.TooManyRequests)
+                {
+                    // Auth, path or method errors will not go away by retrying.
+                    logger.LogWarning("[Ambilight] Upload of {File} to {Url} failed: {Failure}", Path.GetFileName(path), url, failure);
+                    return false;
+                }
+            }
+            catch (HttpRequestException ex)
+            {
+                failure = ex.Message;
+            }
+            catch (Exception ex) when (ex is IOException or UnauthorizedAccessException)
+            {
+                // The local file cannot be read (deleted or replaced meanwhile); retrying will not help.
+                logger.LogWarning("[Ambilight] Upload of {File} to {Url} failed: {Failure}", Path.GetFileName(path), url, ex.Message);
+                return false;
+            }
+            catch (TaskCanceledException) when (!cancellationToken.IsCancellationRequested)
+            {
+                failure = "timed out";
+            }
+
+            if (attempt == MaxAttempts)
+            {
+                logger.LogWarning("[Ambilight] Upload of {File} to {Url} failed after {Attempts} attempts: {Failure}", Path.GetFileName(path), url, MaxAttempts, failure);
+                break;
+            }
+
+            var delay = TimeSpan.FromSeconds(2 << (attempt - 1));
+            logger.LogInformation("[Ambilight] Upload of {File} failed ({Failure}); retrying in {Delay}s", Path.GetFileName(path), failure, delay.TotalSeconds);
+            await Task.Delay(delay, cancellationToken).ConfigureAwait(false);
+        }
+
+        return false;
+    }
+}